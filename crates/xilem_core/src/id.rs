@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::{
+    cell::Cell,
     num::NonZeroU64,
     sync::atomic::{AtomicU64, Ordering},
 };
@@ -14,7 +15,16 @@ pub type IdPath = Vec<Id>;
 
 impl Id {
     /// Allocate a new, unique `Id`.
+    ///
+    /// Within [`IdAllocator::enter`], the id is allocated by that allocator instead.
     pub fn next() -> Id {
+        if let Some(id) = SCOPED_ID_COUNTER.with(|counter| {
+            let id = counter.get()?;
+            counter.set(Some(id.checked_add(1).unwrap()));
+            Some(id)
+        }) {
+            return Id(id);
+        }
         static ID_COUNTER: AtomicU64 = AtomicU64::new(1);
         // Note: we can make the safety argument for the unchecked version.
         Id(NonZeroU64::new(ID_COUNTER.fetch_add(1, Ordering::Relaxed)).unwrap())
@@ -43,6 +53,54 @@ impl Id {
     */
 }
 
+thread_local! {
+    /// The next id of the [`IdAllocator`] which is currently entered on this thread.
+    static SCOPED_ID_COUNTER: Cell<Option<NonZeroU64>> = const { Cell::new(None) };
+}
+
+/// Allocates ids deterministically, independent of other apps or tests (e.g. running in parallel threads).
+///
+/// This is intended for tests (e.g. asserting on serialized element trees), the ids are unique within the allocator,
+/// but may collide with ids allocated by [`Id::next`] outside of it.
+#[derive(Clone, Debug)]
+pub struct IdAllocator {
+    next: NonZeroU64,
+}
+
+impl IdAllocator {
+    /// Creates an allocator, which allocates ids starting with `seed`.
+    pub fn new(seed: NonZeroU64) -> Self {
+        IdAllocator { next: seed }
+    }
+
+    /// Runs `f`, while [`Id::next`] allocates ids with this allocator on the current thread.
+    pub fn enter<R>(&mut self, f: impl FnOnce() -> R) -> R {
+        /// Restores the previously entered allocator, also when `f` panics.
+        struct Guard<'a> {
+            allocator: &'a mut IdAllocator,
+            prev: Option<NonZeroU64>,
+        }
+        impl Drop for Guard<'_> {
+            fn drop(&mut self) {
+                let next = SCOPED_ID_COUNTER.with(|counter| counter.replace(self.prev));
+                self.allocator.next = next.unwrap();
+            }
+        }
+        let prev = SCOPED_ID_COUNTER.with(|counter| counter.replace(Some(self.next)));
+        let _guard = Guard {
+            allocator: self,
+            prev,
+        };
+        f()
+    }
+}
+
+impl Default for IdAllocator {
+    fn default() -> Self {
+        IdAllocator::new(NonZeroU64::MIN)
+    }
+}
+
 // Discussion question: do we need AccessKit integration for id's at the view level, or is
 // that primarily a widget concern? If the former, then we should probably have a feature
 // that enables these conversions.
@@ -22,6 +22,6 @@ mod sequence;
 mod vec_splice;
 mod view;
 
-pub use id::{Id, IdPath};
+pub use id::{Id, IdAllocator, IdPath};
 pub use message::{AsyncWake, MessageResult};
 pub use vec_splice::VecSplice;
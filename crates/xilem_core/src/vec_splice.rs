@@ -66,6 +66,17 @@ impl<'a, 'b, T> VecSplice<'a, 'b, T> {
         }
     }
 
+    /// The `n`-th of the remaining elements after the current index, which weren't mutated or deleted yet.
+    pub fn peek(&self, n: usize) -> Option<&T> {
+        let remaining = self.v.len() - self.ix;
+        if n < remaining {
+            self.v.get(self.ix + n)
+        } else {
+            let idx = self.scratch.len().checked_sub(n - remaining + 1)?;
+            self.scratch.get(idx)
+        }
+    }
+
     pub fn len(&self) -> usize {
         self.ix
     }
@@ -213,5 +213,183 @@ macro_rules! generate_adapt_state_view {
             F: Fn(&mut ParentT) -> &mut ChildT $( $ss )*
         {
         }
+
+        /// Wraps `child`, which is written against its own state `ChildT`, which is accessed in the state `ParentT` via `f`.
+        ///
+        /// This allows writing reusable components, e.g. `adapt_state(|state: &mut AppState| &mut state.form, form_view(&state.form))`.
+        pub fn adapt_state<ParentT, ChildT, V, F>(f: F, child: V) -> AdaptState<ParentT, ChildT, V, F>
+        where
+            F: Fn(&mut ParentT) -> &mut ChildT $( $ss )*,
+        {
+            AdaptState::new(f, child)
+        }
+
+        /// A view that wraps a child view and modifies the state that callbacks have access to,
+        /// when the child state is (still) available, see [`adapt_state_option`].
+        pub struct AdaptStateOption<ParentT, ChildT, V, F = fn(&mut ParentT) -> Option<&mut ChildT>> {
+            f: F,
+            child: V,
+            phantom: std::marker::PhantomData<fn() -> (ParentT, ChildT)>,
+        }
+
+        /// Like [`adapt_state`], but the child state may not be available, e.g. an `Option` field,
+        /// or the fields of a specific enum variant:
+        ///
+        /// ```ignore
+        /// adapt_state_option(
+        ///     |state: &mut AppState| match &mut state.page {
+        ///         Page::Settings(settings) => Some(settings),
+        ///         _ => None,
+        ///     },
+        ///     settings_view(settings),
+        /// )
+        /// ```
+        ///
+        /// When the child state isn't available (anymore) while a message is handled, the message is stale.
+        pub fn adapt_state_option<ParentT, ChildT, V, F>(
+            f: F,
+            child: V,
+        ) -> AdaptStateOption<ParentT, ChildT, V, F>
+        where
+            F: Fn(&mut ParentT) -> Option<&mut ChildT> $( $ss )*,
+        {
+            AdaptStateOption {
+                f,
+                child,
+                phantom: Default::default(),
+            }
+        }
+
+        impl<ParentT, ChildT, A, V, F> $viewtrait<ParentT, A> for AdaptStateOption<ParentT, ChildT, V, F>
+        where
+            V: $viewtrait<ChildT, A>,
+            F: Fn(&mut ParentT) -> Option<&mut ChildT> $( $ss )*,
+        {
+            type State = V::State;
+            type Element = V::Element;
+
+            fn build(&self, cx: &mut $cx) -> ($crate::Id, Self::State, Self::Element) {
+                self.child.build(cx)
+            }
+
+            fn rebuild(
+                &self,
+                cx: &mut $cx,
+                prev: &Self,
+                id: &mut $crate::Id,
+                state: &mut Self::State,
+                element: &mut Self::Element,
+            ) -> $changeflags {
+                self.child.rebuild(cx, &prev.child, id, state, element)
+            }
+
+            fn message(
+                &self,
+                id_path: &[$crate::Id],
+                state: &mut Self::State,
+                message: Box<dyn std::any::Any>,
+                app_state: &mut ParentT,
+            ) -> $crate::MessageResult<A> {
+                match (self.f)(app_state) {
+                    Some(child_state) => self.child.message(id_path, state, message, child_state),
+                    None => $crate::MessageResult::Stale(message),
+                }
+            }
+        }
+
+        impl<ParentT, ChildT, V, F> ViewMarker for AdaptStateOption<ParentT, ChildT, V, F> where
+            F: Fn(&mut ParentT) -> Option<&mut ChildT> $( $ss )*
+        {
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! generate_map_action_view {
+    ($viewtrait:ident, $cx:ty, $changeflags:ty; $($ss:tt)*) => {
+        /// A view that wraps a child view and maps the actions it emits, see [`map_action`].
+        pub struct MapAction<T, ParentA, ChildA, V, F = fn(&mut T, ChildA) -> Option<ParentA>> {
+            f: F,
+            child: V,
+            phantom: std::marker::PhantomData<fn() -> (T, ParentA, ChildA)>,
+        }
+
+        /// Maps the actions of `child` with `f`, which also has access to the app state.
+        ///
+        /// This allows components to emit their own action type, which is translated by the parent,
+        /// or intercepted, when `f` returns `None`:
+        ///
+        /// ```ignore
+        /// enum CounterAction { Increment, Reset }
+        ///
+        /// map_action(counter_view(state.count), |state: &mut AppState, action| match action {
+        ///     CounterAction::Increment => {
+        ///         state.count += 1;
+        ///         None
+        ///     }
+        ///     CounterAction::Reset => Some(AppAction::CounterReset),
+        /// })
+        /// ```
+        pub fn map_action<T, ParentA, ChildA, V, F>(
+            child: V,
+            f: F,
+        ) -> MapAction<T, ParentA, ChildA, V, F>
+        where
+            V: $viewtrait<T, ChildA>,
+            F: Fn(&mut T, ChildA) -> Option<ParentA> $( $ss )*,
+        {
+            MapAction {
+                f,
+                child,
+                phantom: Default::default(),
+            }
+        }
+
+        impl<T, ParentA, ChildA, V, F> $viewtrait<T, ParentA> for MapAction<T, ParentA, ChildA, V, F>
+        where
+            V: $viewtrait<T, ChildA>,
+            F: Fn(&mut T, ChildA) -> Option<ParentA> $( $ss )*,
+        {
+            type State = V::State;
+            type Element = V::Element;
+
+            fn build(&self, cx: &mut $cx) -> ($crate::Id, Self::State, Self::Element) {
+                self.child.build(cx)
+            }
+
+            fn rebuild(
+                &self,
+                cx: &mut $cx,
+                prev: &Self,
+                id: &mut $crate::Id,
+                state: &mut Self::State,
+                element: &mut Self::Element,
+            ) -> $changeflags {
+                self.child.rebuild(cx, &prev.child, id, state, element)
+            }
+
+            fn message(
+                &self,
+                id_path: &[$crate::Id],
+                state: &mut Self::State,
+                message: Box<dyn std::any::Any>,
+                app_state: &mut T,
+            ) -> $crate::MessageResult<ParentA> {
+                match self.child.message(id_path, state, message, app_state) {
+                    $crate::MessageResult::Action(action) => match (self.f)(app_state, action) {
+                        Some(action) => $crate::MessageResult::Action(action),
+                        None => $crate::MessageResult::Nop,
+                    },
+                    $crate::MessageResult::RequestRebuild => $crate::MessageResult::RequestRebuild,
+                    $crate::MessageResult::Nop => $crate::MessageResult::Nop,
+                    $crate::MessageResult::Stale(message) => $crate::MessageResult::Stale(message),
+                }
+            }
+        }
+
+        impl<T, ParentA, ChildA, V, F> ViewMarker for MapAction<T, ParentA, ChildA, V, F> where
+            F: Fn(&mut T, ChildA) -> Option<ParentA> $( $ss )*
+        {
+        }
     };
 }
@@ -1,6 +1,10 @@
 // Copyright 2023 the Xilem Authors.
 // SPDX-License-Identifier: Apache-2.0
 
+/// Generates a view, which is only rebuilt when its data changed.
+///
+/// `$cx` has to provide `fn memoize_invalidated(&self) -> bool`, which tells whether memoized views have to be rebuilt
+/// regardless of their data, e.g. because a value provided by an ancestor (which they may depend on) changed.
 #[macro_export]
 macro_rules! generate_memoize_view {
     ($memoizeview:ident,
@@ -64,7 +68,7 @@ macro_rules! generate_memoize_view {
                 state: &mut Self::State,
                 element: &mut Self::Element,
             ) -> $changeflags {
-                if std::mem::take(&mut state.dirty) || prev.data != self.data {
+                if std::mem::take(&mut state.dirty) || cx.memoize_invalidated() || prev.data != self.data {
                     let view = (self.child_cb)(&self.data);
                     let changed = view.rebuild(cx, &state.view, id, &mut state.view_state, element);
                     state.view = view;
@@ -92,6 +96,11 @@ macro_rules! generate_memoize_view {
         }
 
         /// A static view, all of the content of the `view` should be constant, as this function is only run once
+        ///
+        /// The subtree is neither recreated nor rebuilt afterwards (e.g. for a large SVG logo),
+        /// unless it's explicitly invalidated by a descendant, which returns `MessageResult::RequestRebuild` from `message`,
+        /// or all memoized views are invalidated by the context (`memoize_invalidated`), e.g. because the locale changed,
+        /// as the subtree may contain translated text.
         pub fn $staticviewfunction<V, F>(view: F) -> $memoizeview<(), impl Fn(&()) -> V>
         where
             F: Fn() -> V $( $ss )* + 'static,
@@ -100,6 +109,8 @@ macro_rules! generate_memoize_view {
         }
 
         /// Memoize the view, until the `data` changes (in which case `view` is called again)
+        ///
+        /// Multiple dependencies can be provided as tuple, e.g. `memoize((state.count, state.filter), |&(count, filter)| ...)`.
         pub fn $memoizeviewfunction<D, V, F>(data: D, view: F) -> $memoizeview<D, F>
         where
             F: Fn(&D) -> V $( $ss )*,
@@ -108,3 +119,335 @@ macro_rules! generate_memoize_view {
         }
     };
 }
+
+/// Like [`generate_memoize_view`], but the data is compared via a hash,
+/// for data which is expensive to compare (or which doesn't implement `PartialEq`, e.g. a shared handle to a large `Vec`).
+///
+/// `$cx` has to provide `fn memoize_invalidated(&self) -> bool`, the same as for [`generate_memoize_view`].
+#[macro_export]
+macro_rules! generate_hash_memoize_view {
+    ($hashmemoizeview:ident,
+     $hashmemoizestate:ident,
+     $viewtrait:ident,
+     $viewmarker:ty,
+     $cx:ty,
+     $changeflags:ty,
+     $hashmemoizeviewfunction:ident;
+     $($ss:tt)*
+    ) => {
+        pub struct $hashmemoizeview<D, F> {
+            hash: u64,
+            data: D,
+            child_cb: F,
+        }
+
+        pub struct $hashmemoizestate<T, A, V: $viewtrait<T, A>> {
+            view: V,
+            view_state: V::State,
+            dirty: bool,
+        }
+
+        impl<D, V, F> $hashmemoizeview<D, F>
+        where
+            F: Fn(&D) -> V,
+        {
+            /// Memoize the view with a user-provided `hash` of `data` (e.g. a version counter of the data).
+            pub fn new(hash: u64, data: D, child_cb: F) -> Self {
+                $hashmemoizeview {
+                    hash,
+                    data,
+                    child_cb,
+                }
+            }
+        }
+
+        impl<D, F> $viewmarker for $hashmemoizeview<D, F> {}
+
+        impl<T, A, D, V, F> $viewtrait<T, A> for $hashmemoizeview<D, F>
+        where
+            D: 'static $( $ss )*,
+            V: $viewtrait<T, A>,
+            F: Fn(&D) -> V $( $ss )*,
+        {
+            type State = $hashmemoizestate<T, A, V>;
+
+            type Element = V::Element;
+
+            fn build(&self, cx: &mut $cx) -> ($crate::Id, Self::State, Self::Element) {
+                let view = (self.child_cb)(&self.data);
+                let (id, view_state, element) = view.build(cx);
+                let memoize_state = $hashmemoizestate {
+                    view,
+                    view_state,
+                    dirty: false,
+                };
+                (id, memoize_state, element)
+            }
+
+            fn rebuild(
+                &self,
+                cx: &mut $cx,
+                prev: &Self,
+                id: &mut $crate::Id,
+                state: &mut Self::State,
+                element: &mut Self::Element,
+            ) -> $changeflags {
+                if std::mem::take(&mut state.dirty) || cx.memoize_invalidated() || prev.hash != self.hash {
+                    let view = (self.child_cb)(&self.data);
+                    let changed = view.rebuild(cx, &state.view, id, &mut state.view_state, element);
+                    state.view = view;
+                    changed
+                } else {
+                    <$changeflags>::empty()
+                }
+            }
+
+            fn message(
+                &self,
+                id_path: &[$crate::Id],
+                state: &mut Self::State,
+                event: Box<dyn std::any::Any>,
+                app_state: &mut T,
+            ) -> $crate::MessageResult<A> {
+                let r = state
+                    .view
+                    .message(id_path, &mut state.view_state, event, app_state);
+                if matches!(r, $crate::MessageResult::RequestRebuild) {
+                    state.dirty = true;
+                }
+                r
+            }
+        }
+
+        /// Memoize the view, until the hash of `data` changes (in which case `view` is called again).
+        ///
+        /// Only the hash is compared, so e.g. an `Rc` of a large `Vec` can be used as `data`, without cloning it.
+        /// When hashing the data is expensive as well, a cheaper hash (e.g. a version counter) can be provided via `new`.
+        pub fn $hashmemoizeviewfunction<D, V, F>(data: D, view: F) -> $hashmemoizeview<D, F>
+        where
+            D: std::hash::Hash,
+            F: Fn(&D) -> V $( $ss )*,
+        {
+            use std::hash::{Hash, Hasher};
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            data.hash(&mut hasher);
+            $hashmemoizeview::new(hasher.finish(), data, view)
+        }
+    };
+}
+
+/// Implements the view trait for a shared pointer (`Rc` or `Arc`) to a view,
+/// which skips rebuilding the whole subtree, when the pointer didn't change.
+///
+/// `$cx` has to provide `fn memoize_invalidated(&self) -> bool`, the same as for [`generate_memoize_view`].
+#[macro_export]
+macro_rules! generate_rc_view {
+    ($rcstate:ident,
+     $viewtrait:ident,
+     $viewmarker:ty,
+     $cx:ty,
+     $changeflags:ty,
+     $rc:ident;
+     $($ss:tt)*
+    ) => {
+        pub struct $rcstate<T, A, V: $viewtrait<T, A>> {
+            view_state: V::State,
+            dirty: bool,
+        }
+
+        impl<V> $viewmarker for $rc<V> {}
+
+        /// A shared view is only rebuilt, when it's not the same pointer as the previous view
+        /// (or when memoized views are invalidated by the context),
+        /// so a subtree can be memoized by keeping it in e.g. the app state, and reusing it in the next `app_logic` call.
+        impl<T, A, V> $viewtrait<T, A> for $rc<V>
+        where
+            V: $viewtrait<T, A> $( $ss )*,
+        {
+            type State = $rcstate<T, A, V>;
+
+            type Element = V::Element;
+
+            fn build(&self, cx: &mut $cx) -> ($crate::Id, Self::State, Self::Element) {
+                let (id, view_state, element) = (**self).build(cx);
+                let state = $rcstate {
+                    view_state,
+                    dirty: false,
+                };
+                (id, state, element)
+            }
+
+            fn rebuild(
+                &self,
+                cx: &mut $cx,
+                prev: &Self,
+                id: &mut $crate::Id,
+                state: &mut Self::State,
+                element: &mut Self::Element,
+            ) -> $changeflags {
+                if std::mem::take(&mut state.dirty) || cx.memoize_invalidated() || !$rc::ptr_eq(prev, self) {
+                    (**self).rebuild(cx, prev, id, &mut state.view_state, element)
+                } else {
+                    <$changeflags>::empty()
+                }
+            }
+
+            fn message(
+                &self,
+                id_path: &[$crate::Id],
+                state: &mut Self::State,
+                event: Box<dyn std::any::Any>,
+                app_state: &mut T,
+            ) -> $crate::MessageResult<A> {
+                let r = (**self).message(id_path, &mut state.view_state, event, app_state);
+                if matches!(r, $crate::MessageResult::RequestRebuild) {
+                    state.dirty = true;
+                }
+                r
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use crate::{Id, MessageResult};
+
+    #[derive(Default)]
+    pub struct Cx {
+        invalidated: bool,
+    }
+
+    impl Cx {
+        pub fn memoize_invalidated(&self) -> bool {
+            self.invalidated
+        }
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct ChangeFlags(bool);
+
+    impl ChangeFlags {
+        pub fn empty() -> Self {
+            ChangeFlags(false)
+        }
+    }
+
+    pub trait Element {}
+    impl Element for () {}
+
+    #[allow(unused)]
+    pub trait ViewMarker {}
+
+    crate::generate_view_trait! {View, Element, Cx, ChangeFlags;}
+    crate::generate_memoize_view! {Memoize, MemoizeState, View, ViewMarker, Cx, ChangeFlags, static_view, memoize;}
+
+    /// Counts its rebuilds, and requests a rebuild on each message.
+    pub struct Leaf {
+        rebuilds: Rc<Cell<u32>>,
+    }
+
+    impl View<()> for Leaf {
+        type State = ();
+        type Element = ();
+
+        fn build(&self, _cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+            (Id::next(), (), ())
+        }
+
+        fn rebuild(
+            &self,
+            _cx: &mut Cx,
+            _prev: &Self,
+            _id: &mut Id,
+            _state: &mut Self::State,
+            _element: &mut Self::Element,
+        ) -> ChangeFlags {
+            self.rebuilds.set(self.rebuilds.get() + 1);
+            ChangeFlags(true)
+        }
+
+        fn message(
+            &self,
+            _id_path: &[Id],
+            _state: &mut Self::State,
+            _message: Box<dyn std::any::Any>,
+            _app_state: &mut (),
+        ) -> MessageResult<()> {
+            MessageResult::RequestRebuild
+        }
+    }
+
+    /// A `static_view` of a [`Leaf`], `calls` counts how often the view is created.
+    fn static_leaf(
+        calls: &Rc<Cell<u32>>,
+        rebuilds: &Rc<Cell<u32>>,
+    ) -> Memoize<(), impl Fn(&()) -> Leaf> {
+        let (calls, rebuilds) = (Rc::clone(calls), Rc::clone(rebuilds));
+        static_view(move || {
+            calls.set(calls.get() + 1);
+            Leaf {
+                rebuilds: Rc::clone(&rebuilds),
+            }
+        })
+    }
+
+    #[test]
+    fn static_view_is_only_rebuilt_when_requested() {
+        let (calls, rebuilds) = (Rc::default(), Rc::default());
+        let mut cx = Cx::default();
+        let prev = static_leaf(&calls, &rebuilds);
+        let (mut id, mut state, mut element) = prev.build(&mut cx);
+        assert_eq!((calls.get(), rebuilds.get()), (1, 0));
+
+        // A rebuild of the parent neither recreates nor rebuilds the subtree
+        let next = static_leaf(&calls, &rebuilds);
+        let changed = next.rebuild(&mut cx, &prev, &mut id, &mut state, &mut element);
+        assert_eq!(changed, ChangeFlags::empty());
+        assert_eq!((calls.get(), rebuilds.get()), (1, 0));
+
+        // Until a descendant requests it
+        let result = next.message(&[], &mut state, Box::new(()), &mut ());
+        assert!(matches!(result, MessageResult::RequestRebuild));
+        let prev = next;
+        let next = static_leaf(&calls, &rebuilds);
+        let changed = next.rebuild(&mut cx, &prev, &mut id, &mut state, &mut element);
+        assert_eq!(changed, ChangeFlags(true));
+        assert_eq!((calls.get(), rebuilds.get()), (2, 1));
+
+        // Only once
+        let prev = next;
+        let next = static_leaf(&calls, &rebuilds);
+        let changed = next.rebuild(&mut cx, &prev, &mut id, &mut state, &mut element);
+        assert_eq!(changed, ChangeFlags::empty());
+        assert_eq!((calls.get(), rebuilds.get()), (2, 1));
+    }
+
+    #[test]
+    fn memoized_views_are_rebuilt_when_invalidated() {
+        let (calls, rebuilds) = (Rc::default(), Rc::default());
+        let mut cx = Cx::default();
+        let prev = static_leaf(&calls, &rebuilds);
+        let (mut id, mut state, mut element) = prev.build(&mut cx);
+
+        // e.g. when the locale changed
+        cx.invalidated = true;
+        let next = static_leaf(&calls, &rebuilds);
+        let changed = next.rebuild(&mut cx, &prev, &mut id, &mut state, &mut element);
+        assert_eq!(changed, ChangeFlags(true));
+        assert_eq!((calls.get(), rebuilds.get()), (2, 1));
+
+        // The same for `memoize` with unchanged data
+        let leaf = |_: &u32| Leaf {
+            rebuilds: Rc::clone(&rebuilds),
+        };
+        let prev = memoize(0, leaf);
+        let (mut id, mut state, mut element) = prev.build(&mut cx);
+        let next = memoize(0, leaf);
+        let changed = next.rebuild(&mut cx, &prev, &mut id, &mut state, &mut element);
+        assert_eq!(changed, ChangeFlags(true));
+        assert_eq!(rebuilds.get(), 2);
+    }
+}
@@ -0,0 +1,116 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+/// Create a view which subscribes to an external source of messages (e.g. a WebSocket, a channel, or an `EventSource`).
+///
+/// The messages are sent on the same thread as the view tree runs on (as in `xilem_web`),
+/// the native backend has no way to deliver them to a view yet, so it doesn't provide this view.
+///
+/// Arguments are
+///
+///  - `$subscriptionview` - The name of the view type.
+///  - `$subscriptionfunction` - The name of the function to create the view.
+///  - `$viewtrait`, `$viewmarker`, `$cx`, `$changeflags` - See [`generate_view_trait`](crate::generate_view_trait).
+///  - `$element` - The (invisible) element type of the view.
+///  - `$create_element` - A function `fn(&mut $cx) -> $element`.
+///  - `$create_sender` - A function `fn(&$cx) -> Box<dyn Fn(Box<dyn Any>)>`, which returns a function that
+///    sends a message to the view with the current id path.
+///  - `$optionalaction` - A trait with a method `action(self) -> Option<A>`, for the return type of the handler.
+#[macro_export]
+macro_rules! generate_subscription_view {
+    ($subscriptionview:ident,
+     $subscriptionfunction:ident,
+     $viewtrait:ident,
+     $viewmarker:ty,
+     $cx:ty,
+     $changeflags:ty,
+     $element:ty,
+     $create_element:ident,
+     $create_sender:ident,
+     $optionalaction:ident
+    ) => {
+        /// A view which subscribes to an external source of messages, see
+        #[doc = concat!("[`", stringify!($subscriptionfunction), "`].")]
+        pub struct $subscriptionview<M, S, F> {
+            subscribe: S,
+            handler: F,
+            phantom: std::marker::PhantomData<fn() -> M>,
+        }
+
+        /// Subscribes to an external source of messages `M` (e.g. a WebSocket, a channel, or an `EventSource`),
+        /// `handler` is called with the app state for each message.
+        ///
+        /// `subscribe` is called once, when the view is built, with a function that sends a message to this view
+        /// (it shouldn't be called synchronously within `subscribe`, as the view tree is currently being built).
+        /// It returns a guard, e.g. the connection or a handle to a listener, which is dropped
+        /// (and so should unsubscribe) when this view is removed from the view tree.
+        /// The subscription isn't renewed on rebuilds, so `subscribe` should not depend on the app state,
+        /// different subscriptions can be distinguished with e.g. a `OneOf` view.
+        pub fn $subscriptionfunction<M, S, G, F>(
+            subscribe: S,
+            handler: F,
+        ) -> $subscriptionview<M, S, F>
+        where
+            S: Fn(Box<dyn Fn(M)>) -> G,
+        {
+            $subscriptionview {
+                subscribe,
+                handler,
+                phantom: std::marker::PhantomData,
+            }
+        }
+
+        impl<M, S, F> $viewmarker for $subscriptionview<M, S, F> {}
+
+        impl<T, A, M, S, G, F, O> $viewtrait<T, A> for $subscriptionview<M, S, F>
+        where
+            M: 'static,
+            S: Fn(Box<dyn Fn(M)>) -> G,
+            G: 'static,
+            F: Fn(&mut T, M) -> O,
+            O: $optionalaction<A>,
+        {
+            type State = G;
+
+            type Element = $element;
+
+            fn build(&self, cx: &mut $cx) -> ($crate::Id, Self::State, Self::Element) {
+                let (id, guard) = cx.with_new_id(|cx| {
+                    let send = $create_sender(cx);
+                    (self.subscribe)(Box::new(move |message: M| send(Box::new(message))))
+                });
+                (id, guard, $create_element(cx))
+            }
+
+            fn rebuild(
+                &self,
+                _cx: &mut $cx,
+                _prev: &Self,
+                _id: &mut $crate::Id,
+                _state: &mut Self::State,
+                _element: &mut Self::Element,
+            ) -> $changeflags {
+                <$changeflags>::empty()
+            }
+
+            fn message(
+                &self,
+                id_path: &[$crate::Id],
+                _state: &mut Self::State,
+                message: Box<dyn std::any::Any>,
+                app_state: &mut T,
+            ) -> $crate::MessageResult<A> {
+                match id_path {
+                    [] if message.downcast_ref::<M>().is_some() => {
+                        let message = message.downcast::<M>().unwrap();
+                        match (self.handler)(app_state, *message).action() {
+                            Some(a) => $crate::MessageResult::Action(a),
+                            None => $crate::MessageResult::Nop,
+                        }
+                    }
+                    _ => $crate::MessageResult::Stale(message),
+                }
+            }
+        }
+    };
+}
@@ -0,0 +1,257 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A debug-only accessibility audit of the DOM built by the view tree.
+//!
+//! After an update cycle (throttled), the DOM of the app is walked and common issues that can be
+//! detected automatically are logged as warnings to the console. Each warning contains the id path
+//! of the element views responsible, collected from the `data-debugid` attributes.
+
+use std::{cell::Cell, collections::HashMap, fmt, rc::Rc};
+
+use wasm_bindgen::{JsCast, JsValue};
+
+use crate::{
+    aria::{required_attributes, ID_REFERENCE_ATTRIBUTES},
+    devtools::DEBUG_NAME_ATTRIBUTE,
+    timer::Timeout,
+    HTML_NS,
+};
+
+/// The minimum time in milliseconds between two audits.
+const AUDIT_INTERVAL_MS: f64 = 1000.0;
+
+/// The (hidden) property on an `EventTarget` where the names of the attached listeners are recorded.
+const LISTENERS_PROPERTY: &str = "__xilem_listeners";
+
+/// Records that a listener for `event` was attached to `target`.
+///
+/// The DOM doesn't offer a way to query the event listeners of an element,
+/// so they're tracked here, to be able to tell whether e.g. a clickable element is usable with the keyboard.
+pub(crate) fn register_listener(target: &web_sys::EventTarget, event: &str) {
+    let key = JsValue::from_str(LISTENERS_PROPERTY);
+    let listeners = match js_sys::Reflect::get(target, &key) {
+        Ok(listeners) if listeners.is_instance_of::<js_sys::Array>() => {
+            listeners.unchecked_into::<js_sys::Array>()
+        }
+        _ => {
+            let listeners = js_sys::Array::new();
+            let _ = js_sys::Reflect::set(target, &key, &listeners);
+            listeners
+        }
+    };
+    let event = JsValue::from_str(event);
+    if !listeners.includes(&event, 0) {
+        listeners.push(&event);
+    }
+}
+
+fn has_listener(element: &web_sys::Element, event: &str) -> bool {
+    js_sys::Reflect::get(element, &JsValue::from_str(LISTENERS_PROPERTY))
+        .ok()
+        .and_then(|listeners| listeners.dyn_into::<js_sys::Array>().ok())
+        .is_some_and(|listeners| listeners.includes(&JsValue::from_str(event), 0))
+}
+
+/// Runs the audit at most once every [`AUDIT_INTERVAL_MS`].
+///
+/// Changes within the interval after an audit are covered by a trailing audit at its end.
+#[derive(Default)]
+pub(crate) struct Auditor {
+    /// The time of the last audit, and whether a trailing audit is pending
+    last_audit: Rc<Cell<(Option<f64>, bool)>>,
+    trailing_audit: Option<Timeout>,
+}
+
+impl Auditor {
+    pub(crate) fn maybe_audit(&mut self, root: &web_sys::Node) {
+        let now = js_sys::Date::now();
+        let (last_audit, trailing_pending) = self.last_audit.get();
+        match last_audit.map(|last_audit| now - last_audit) {
+            Some(elapsed) if elapsed < AUDIT_INTERVAL_MS => {
+                if !trailing_pending {
+                    self.last_audit.set((last_audit, true));
+                    let last_audit = Rc::clone(&self.last_audit);
+                    let root = root.clone();
+                    let delay = (AUDIT_INTERVAL_MS - elapsed).ceil() as i32;
+                    self.trailing_audit = Some(Timeout::new(delay, move || {
+                        last_audit.set((Some(js_sys::Date::now()), false));
+                        audit(&root);
+                    }));
+                }
+            }
+            _ => {
+                // A pending trailing audit is superseded by this one
+                self.trailing_audit = None;
+                self.last_audit.set((Some(now), false));
+                audit(root);
+            }
+        }
+    }
+}
+
+enum Issue {
+    ImageWithoutAlt,
+    ButtonWithoutName,
+    NoKeyboardAccess,
+    DuplicateId(String),
+    MissingAriaAttribute {
+        role: String,
+        attribute: &'static str,
+    },
+    MissingReferencedElement {
+        attribute: &'static str,
+        id: String,
+    },
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Issue::ImageWithoutAlt => f.write_str("image without an `alt` attribute"),
+            Issue::ButtonWithoutName => f.write_str(
+                "button without an accessible name (text content, `aria-label` or `title`)",
+            ),
+            Issue::NoKeyboardAccess => f.write_str(
+                "element has a click handler, but no keyboard handler, consider using a <button>",
+            ),
+            Issue::DuplicateId(id) => write!(f, "the id `{id}` is used by multiple elements"),
+            Issue::MissingAriaAttribute { role, attribute } => {
+                write!(f, "the role `{role}` requires the attribute `{attribute}`")
+            }
+            Issue::MissingReferencedElement { attribute, id } => {
+                write!(
+                    f,
+                    "`{attribute}` references the id `{id}`, but there's no such element"
+                )
+            }
+        }
+    }
+}
+
+/// Walks the DOM starting at (and including) `root` and reports every detected issue.
+pub(crate) fn audit(root: &web_sys::Node) {
+    let Some(root) = root.dyn_ref::<web_sys::Element>() else {
+        return;
+    };
+    let mut elements = vec![root.clone()];
+    if let Ok(descendants) = root.query_selector_all("*") {
+        elements.extend(
+            (0..descendants.length())
+                .filter_map(|idx| descendants.get(idx))
+                .filter_map(|node| node.dyn_into::<web_sys::Element>().ok()),
+        );
+    }
+
+    let mut ids: HashMap<String, Vec<&web_sys::Element>> = HashMap::new();
+    for element in &elements {
+        if let Some(id) = element.get_attribute("id") {
+            ids.entry(id).or_default().push(element);
+        }
+        if let Some(issue) = check_element(element) {
+            report(element, &issue);
+        }
+    }
+    for element in &elements {
+        for &attribute in ID_REFERENCE_ATTRIBUTES {
+            let Some(references) = element.get_attribute(attribute) else {
+                continue;
+            };
+            for id in references.split_whitespace() {
+                // The referenced element may be outside of the app (e.g. in a static part of the page)
+                if !ids.contains_key(id)
+                    && root
+                        .owner_document()
+                        .map_or(true, |document| document.get_element_by_id(id).is_none())
+                {
+                    let issue = Issue::MissingReferencedElement {
+                        attribute,
+                        id: id.into(),
+                    };
+                    report(element, &issue);
+                }
+            }
+        }
+    }
+    for (id, elements) in ids {
+        if elements.len() > 1 {
+            let issue = Issue::DuplicateId(id);
+            for element in elements {
+                report(element, &issue);
+            }
+        }
+    }
+}
+
+fn check_element(element: &web_sys::Element) -> Option<Issue> {
+    if element.namespace_uri().as_deref() != Some(HTML_NS) {
+        return None;
+    }
+    if let Some(role) = element.get_attribute("role") {
+        let missing = required_attributes(&role)
+            .iter()
+            .find(|attribute| !element.has_attribute(attribute));
+        if let Some(&attribute) = missing {
+            return Some(Issue::MissingAriaAttribute { role, attribute });
+        }
+    }
+    let has_label = || {
+        element.has_attribute("aria-label")
+            || element.has_attribute("aria-labelledby")
+            || element.has_attribute("title")
+    };
+    match element.local_name().as_str() {
+        "img" if !element.has_attribute("alt") => Some(Issue::ImageWithoutAlt),
+        "button"
+            if element
+                .text_content()
+                .map_or(true, |text| text.trim().is_empty())
+                && !has_label() =>
+        {
+            Some(Issue::ButtonWithoutName)
+        }
+        // These elements are activated with the keyboard by the browser (via a synthetic click event)
+        "a" | "button" | "input" | "select" | "textarea" | "summary" | "option" | "label" => None,
+        _ if has_listener(element, "click")
+            && !has_listener(element, "keydown")
+            && !has_listener(element, "keyup") =>
+        {
+            Some(Issue::NoKeyboardAccess)
+        }
+        _ => None,
+    }
+}
+
+/// The id path of the element views that created `element`.
+fn view_id_path(element: &web_sys::Element) -> Vec<u64> {
+    let mut id_path = vec![];
+    let mut current = Some(element.clone());
+    while let Some(element) = current {
+        if let Some(id) = element
+            .get_attribute("data-debugid")
+            .and_then(|id| id.parse().ok())
+        {
+            id_path.push(id);
+        }
+        current = element.parent_element();
+    }
+    id_path.reverse();
+    id_path
+}
+
+fn report(element: &web_sys::Element, issue: &Issue) {
+    // The name of the closest element named via `debug_id`
+    let debug_name = element
+        .closest(&format!("[{DEBUG_NAME_ATTRIBUTE}]"))
+        .ok()
+        .flatten()
+        .and_then(|named| named.get_attribute(DEBUG_NAME_ATTRIBUTE))
+        .map(|name| format!(" in `{name}`"))
+        .unwrap_or_default();
+    let message = format!(
+        "a11y: <{}>{debug_name} (view id path {:?}): {issue}",
+        element.local_name(),
+        view_id_path(element)
+    );
+    web_sys::console::warn_1(&message.into());
+}
@@ -0,0 +1,432 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{any::Any, marker::PhantomData};
+
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    context::{ChangeFlags, Cx},
+    timer::AnimationFrameLoop,
+    view::{View, ViewMarker},
+    OptionalAction,
+};
+
+/// The timestamp of an animation frame in milliseconds.
+struct AnimateFrameMsg(f64);
+
+/// How the progress of an [`animate`] view is mapped to the interpolated value.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Easing {
+    Linear,
+    EaseIn,
+    EaseOut,
+    #[default]
+    EaseInOut,
+}
+
+impl Easing {
+    /// Maps the linear progress `t` (between `0.0` and `1.0`) to the eased progress.
+    pub fn apply(self, t: f64) -> f64 {
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+        }
+    }
+}
+
+/// How the value of an [`Animate`] view moves towards its target.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Motion {
+    Tween { duration_ms: f64, easing: Easing },
+    Spring { stiffness: f64, damping: f64 },
+}
+
+/// The time step of the spring simulation in seconds.
+const SPRING_STEP: f64 = 1.0 / 240.0;
+/// The maximum time simulated per frame in seconds, so that a spring doesn't jump after e.g. the tab was in the background.
+const SPRING_MAX_FRAME_TIME: f64 = 0.064;
+/// A spring is at rest, when both its distance to the target and its velocity are below this.
+const SPRING_REST_THRESHOLD: f64 = 0.01;
+
+/// Interpolates a value over time, see [`animate`] and [`spring`].
+pub struct Animate<T, A, F, C> {
+    target: f64,
+    initial: Option<f64>,
+    motion: Motion,
+    child_cb: F,
+    on_complete: Option<C>,
+    phantom: PhantomData<fn() -> (T, A)>,
+}
+
+pub struct AnimateState<V, S> {
+    child_id: Id,
+    view: V,
+    view_state: S,
+    value: f64,
+    /// The velocity of a spring in units per second.
+    velocity: f64,
+    from: f64,
+    target: f64,
+    /// The timestamp of the first frame of the current animation.
+    start: Option<f64>,
+    /// The timestamp of the previous frame.
+    last_frame: Option<f64>,
+    frame_loop: Option<AnimationFrameLoop>,
+}
+
+/// Creates the view with `child_cb` with a value, which is interpolated over `duration_ms` (with `easing`),
+/// each time `target` changes, e.g. for animated styles, positions or SVG shapes.
+///
+/// An animation, that is still running when `target` changes, continues from the current value towards the new target.
+/// The view is rebuilt with the current value each animation frame, until the animation completed.
+///
+/// ```ignore
+/// animate(if state.open { 300.0 } else { 0.0 }, 250.0, Easing::EaseOut, |height| {
+///     el::div(content).style("height", px(height))
+/// })
+/// .on_complete(|state: &mut AppState| state.animating = false)
+/// ```
+pub fn animate<T, A, V, F>(
+    target: f64,
+    duration_ms: f64,
+    easing: Easing,
+    child_cb: F,
+) -> Animate<T, A, F, fn(&mut T)>
+where
+    V: View<T, A>,
+    F: Fn(f64) -> V,
+{
+    Animate {
+        target,
+        initial: None,
+        motion: Motion::Tween {
+            duration_ms,
+            easing,
+        },
+        child_cb,
+        on_complete: None,
+        phantom: PhantomData,
+    }
+}
+
+/// Like [`animate`], but the value moves towards `target` like a (damped) spring, instead of over a fixed duration.
+///
+/// When `target` changes while the spring is moving, it keeps its velocity, so interrupted animations look natural.
+/// A higher `stiffness` makes it faster, a higher `damping` reduces the oscillation,
+/// e.g. `spring(target, 170.0, 26.0, ...)` doesn't overshoot noticeably, while `spring(target, 300.0, 10.0, ...)` is bouncy.
+///
+/// ```ignore
+/// spring(if state.expanded { 1.0 } else { 0.8 }, 170.0, 26.0, |scale| {
+///     el::div(content).style("transform", format!("scale({scale})"))
+/// })
+/// ```
+pub fn spring<T, A, V, F>(
+    target: f64,
+    stiffness: f64,
+    damping: f64,
+    child_cb: F,
+) -> Animate<T, A, F, fn(&mut T)>
+where
+    V: View<T, A>,
+    F: Fn(f64) -> V,
+{
+    Animate {
+        target,
+        initial: None,
+        motion: Motion::Spring { stiffness, damping },
+        child_cb,
+        on_complete: None,
+        phantom: PhantomData,
+    }
+}
+
+impl<T, A, F, C> Animate<T, A, F, C> {
+    /// Animates from `value` to the target, when the view is built, instead of starting with the target.
+    pub fn initial(mut self, value: f64) -> Self {
+        self.initial = Some(value);
+        self
+    }
+
+    /// Calls `on_complete`, when an animation reached its target.
+    pub fn on_complete<OA, OC>(self, on_complete: OC) -> Animate<T, A, F, OC>
+    where
+        OA: OptionalAction<A>,
+        OC: Fn(&mut T) -> OA,
+    {
+        Animate {
+            target: self.target,
+            initial: self.initial,
+            motion: self.motion,
+            child_cb: self.child_cb,
+            on_complete: Some(on_complete),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<V, S> AnimateState<V, S> {
+    /// Advances the value to the frame at `timestamp`, and returns whether it reached its target.
+    fn step(&mut self, motion: Motion, timestamp: f64) -> bool {
+        let start = *self.start.get_or_insert(timestamp);
+        let last_frame = self.last_frame.replace(timestamp).unwrap_or(timestamp);
+        match motion {
+            Motion::Tween {
+                duration_ms,
+                easing,
+            } => {
+                let progress = if duration_ms > 0.0 {
+                    ((timestamp - start) / duration_ms).clamp(0.0, 1.0)
+                } else {
+                    1.0
+                };
+                self.value = self.from + (self.target - self.from) * easing.apply(progress);
+                progress >= 1.0
+            }
+            Motion::Spring { stiffness, damping } => {
+                let elapsed = ((timestamp - last_frame) / 1000.0).min(SPRING_MAX_FRAME_TIME);
+                (self.value, self.velocity) = spring_step(
+                    self.value,
+                    self.velocity,
+                    self.target,
+                    stiffness,
+                    damping,
+                    elapsed,
+                );
+                (self.value - self.target).abs() < SPRING_REST_THRESHOLD
+                    && self.velocity.abs() < SPRING_REST_THRESHOLD
+            }
+        }
+    }
+}
+
+/// Simulates a spring moving `value` (with `velocity`) towards `target` for `elapsed` seconds,
+/// and returns the new value and velocity.
+fn spring_step(
+    mut value: f64,
+    mut velocity: f64,
+    target: f64,
+    stiffness: f64,
+    damping: f64,
+    elapsed: f64,
+) -> (f64, f64) {
+    let mut remaining = elapsed;
+    while remaining > 0.0 {
+        let dt = remaining.min(SPRING_STEP);
+        let force = -stiffness * (value - target) - damping * velocity;
+        velocity += force * dt;
+        value += velocity * dt;
+        remaining -= dt;
+    }
+    (value, velocity)
+}
+
+fn animate_frame_loop(cx: &Cx) -> AnimationFrameLoop {
+    let thunk = cx.message_thunk();
+    AnimationFrameLoop::new(move |timestamp| thunk.push_message(AnimateFrameMsg(timestamp)))
+}
+
+impl<T, A, F, C> ViewMarker for Animate<T, A, F, C> {}
+
+impl<T, A, V, F, C, OA> View<T, A> for Animate<T, A, F, C>
+where
+    V: View<T, A>,
+    F: Fn(f64) -> V,
+    OA: OptionalAction<A>,
+    C: Fn(&mut T) -> OA,
+{
+    type State = AnimateState<V, V::State>;
+    type Element = V::Element;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let value = self.initial.unwrap_or(self.target);
+        let (id, (state, element)) = cx.with_new_id(|cx| {
+            let view = (self.child_cb)(value);
+            let (child_id, view_state, element) = view.build(cx);
+            let frame_loop = (value != self.target).then(|| animate_frame_loop(cx));
+            let state = AnimateState {
+                child_id,
+                view,
+                view_state,
+                value,
+                velocity: 0.0,
+                from: value,
+                target: self.target,
+                start: None,
+                last_frame: None,
+                frame_loop,
+            };
+            (state, element)
+        });
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        _prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        cx.with_id(*id, |cx| {
+            if self.target != state.target {
+                state.from = state.value;
+                state.target = self.target;
+                state.start = None;
+                if state.frame_loop.is_none() {
+                    state.last_frame = None;
+                    state.frame_loop = Some(animate_frame_loop(cx));
+                }
+            }
+            let view = (self.child_cb)(state.value);
+            let changed = view.rebuild(
+                cx,
+                &state.view,
+                &mut state.child_id,
+                &mut state.view_state,
+                element,
+            );
+            state.view = view;
+            changed
+        })
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Box<dyn Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        match id_path {
+            [] if message.downcast_ref::<AnimateFrameMsg>().is_some() => {
+                // A frame may still arrive after the animation completed
+                if state.frame_loop.is_none() {
+                    return MessageResult::Nop;
+                }
+                let AnimateFrameMsg(timestamp) = *message.downcast::<AnimateFrameMsg>().unwrap();
+                if !state.step(self.motion, timestamp) {
+                    return MessageResult::RequestRebuild;
+                }
+                state.value = state.target;
+                state.velocity = 0.0;
+                state.frame_loop = None;
+                match self
+                    .on_complete
+                    .as_ref()
+                    .and_then(|f| f(app_state).action())
+                {
+                    Some(a) => MessageResult::Action(a),
+                    None => MessageResult::RequestRebuild,
+                }
+            }
+            [child_id, rest_path @ ..] if *child_id == state.child_id => {
+                state
+                    .view
+                    .message(rest_path, &mut state.view_state, message, app_state)
+            }
+            _ => MessageResult::Stale(message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EASINGS: [Easing; 4] = [
+        Easing::Linear,
+        Easing::EaseIn,
+        Easing::EaseOut,
+        Easing::EaseInOut,
+    ];
+
+    #[test]
+    fn easings_start_and_end_at_the_bounds() {
+        for easing in EASINGS {
+            assert_eq!(easing.apply(0.0), 0.0, "{easing:?}");
+            assert_eq!(easing.apply(1.0), 1.0, "{easing:?}");
+        }
+    }
+
+    #[test]
+    fn easings_are_monotonic() {
+        for easing in EASINGS {
+            let mut prev = easing.apply(0.0);
+            for step in 1..=100 {
+                let eased = easing.apply(step as f64 / 100.0);
+                assert!(eased >= prev, "{easing:?} decreases at {step}%");
+                prev = eased;
+            }
+        }
+    }
+
+    #[test]
+    fn ease_in_out_is_symmetric() {
+        assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+        for step in 0..=50 {
+            let t = step as f64 / 100.0;
+            let sum = Easing::EaseInOut.apply(t) + Easing::EaseInOut.apply(1.0 - t);
+            assert!((sum - 1.0).abs() < 1e-12);
+        }
+        assert!(Easing::EaseIn.apply(0.25) < 0.25);
+        assert!(Easing::EaseOut.apply(0.25) > 0.25);
+    }
+    /// Simulates the spring until it's at rest (or `max_seconds` passed),
+    /// and returns the simulated time and the largest value on the way.
+    fn settle(stiffness: f64, damping: f64, max_seconds: f64) -> (f64, f64) {
+        let (mut value, mut velocity, mut time, mut max) = (0.0, 0.0, 0.0, 0.0_f64);
+        while time < max_seconds {
+            (value, velocity) = spring_step(value, velocity, 1.0, stiffness, damping, 1.0 / 60.0);
+            time += 1.0 / 60.0;
+            max = max.max(value);
+            if (value - 1.0_f64).abs() < SPRING_REST_THRESHOLD
+                && velocity.abs() < SPRING_REST_THRESHOLD
+            {
+                break;
+            }
+        }
+        (time, max)
+    }
+
+    #[test]
+    fn spring_at_rest_stays_at_rest() {
+        assert_eq!(spring_step(1.0, 0.0, 1.0, 170.0, 26.0, 0.5), (1.0, 0.0));
+        assert_eq!(spring_step(0.0, 0.0, 1.0, 170.0, 26.0, 0.0), (0.0, 0.0));
+    }
+
+    #[test]
+    fn spring_settles_at_target() {
+        let (time, max) = settle(170.0, 26.0, 10.0);
+        assert!(time < 2.0, "the spring didn't settle");
+        // Almost critically damped, so it doesn't overshoot noticeably
+        assert!(max < 1.01);
+    }
+
+    #[test]
+    fn bouncy_spring_overshoots() {
+        let (time, max) = settle(300.0, 10.0, 10.0);
+        assert!(time < 10.0, "the spring didn't settle");
+        assert!(max > 1.2);
+    }
+
+    #[test]
+    fn spring_step_is_independent_of_frame_rate() {
+        let once = spring_step(0.0, 0.0, 1.0, 170.0, 26.0, 0.05);
+        let mut split = (0.0, 0.0);
+        for _ in 0..12 {
+            split = spring_step(split.0, split.1, 1.0, 170.0, 26.0, 0.05 / 12.0);
+        }
+        assert!((once.0 - split.0).abs() < 0.01);
+        assert!((once.1 - split.1).abs() < 0.1);
+    }
+}
@@ -0,0 +1,108 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{any::Any, marker::PhantomData};
+
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    context::{ChangeFlags, Cx},
+    timer::AnimationFrameLoop,
+    view::{View, ViewMarker},
+    OptionalAction,
+};
+
+/// The timestamp of an animation frame in milliseconds.
+struct AnimationFrameMsg(f64);
+
+/// Calls a handler before each animation frame, see [`on_animation_frame`].
+///
+/// It doesn't create any visible content, in the DOM it's represented by an (empty) comment node.
+pub struct OnAnimationFrame<T, A, C> {
+    running: bool,
+    handler: C,
+    phantom: PhantomData<fn() -> (T, A)>,
+}
+
+/// Calls `handler` with the timestamp (in milliseconds, see `performance.now()`) before each animation frame,
+/// as long as `running` is `true` and this view is in the view tree, e.g. to advance an animation.
+///
+/// ```ignore
+/// on_animation_frame(state.playing, |state: &mut AppState, timestamp| {
+///     state.angle = (timestamp / 1000.0) % TAU;
+/// })
+/// ```
+pub fn on_animation_frame<T, A, C, OA>(running: bool, handler: C) -> OnAnimationFrame<T, A, C>
+where
+    OA: OptionalAction<A>,
+    C: Fn(&mut T, f64) -> OA,
+{
+    OnAnimationFrame {
+        running,
+        handler,
+        phantom: PhantomData,
+    }
+}
+
+fn animation_frame_loop(running: bool, cx: &Cx) -> Option<AnimationFrameLoop> {
+    running.then(|| {
+        let thunk = cx.message_thunk();
+        AnimationFrameLoop::new(move |timestamp| thunk.push_message(AnimationFrameMsg(timestamp)))
+    })
+}
+
+impl<T, A, C> ViewMarker for OnAnimationFrame<T, A, C> {}
+
+impl<T, A, C, OA> View<T, A> for OnAnimationFrame<T, A, C>
+where
+    OA: OptionalAction<A>,
+    C: Fn(&mut T, f64) -> OA,
+{
+    type State = Option<AnimationFrameLoop>;
+    type Element = web_sys::Comment;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, animation_frame_loop) =
+            cx.with_new_id(|cx| animation_frame_loop(self.running, cx));
+        let el = cx.create_comment("animation frame");
+        (id, animation_frame_loop, el)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        _element: &mut Self::Element,
+    ) -> ChangeFlags {
+        if prev.running != self.running {
+            *state = cx.with_id(*id, |cx| animation_frame_loop(self.running, cx));
+        }
+        ChangeFlags::empty()
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Box<dyn Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        match id_path {
+            [] if message.downcast_ref::<AnimationFrameMsg>().is_some() => {
+                // A frame may still arrive after the loop has been stopped
+                if state.is_none() {
+                    return MessageResult::Nop;
+                }
+                let AnimationFrameMsg(timestamp) =
+                    *message.downcast::<AnimationFrameMsg>().unwrap();
+                match (self.handler)(app_state, timestamp).action() {
+                    Some(a) => MessageResult::Action(a),
+                    None => MessageResult::Nop,
+                }
+            }
+            _ => MessageResult::Stale(message),
+        }
+    }
+}
@@ -1,14 +1,16 @@
 // Copyright 2023 the Druid Authors.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{cell::RefCell, rc::Rc};
+use std::{any::Any, cell::RefCell, rc::Rc};
 
 use crate::{
-    context::Cx,
+    context::{ChangeFlags, Cx},
+    recorder::{MessageRecorder, RecordedMessage},
     view::{DomNode, View},
     Message,
 };
-use xilem_core::{Id, MessageResult};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue, UnwrapThrowExt};
+use xilem_core::{Id, IdAllocator, MessageResult};
 
 /// The type responsible for running your app.
 pub struct App<T, V: View<T>, F: FnMut(&mut T) -> V>(Rc<RefCell<AppInner<T, V, F>>>);
@@ -21,8 +23,38 @@ struct AppInner<T, V: View<T>, F: FnMut(&mut T) -> V> {
     state: Option<V::State>,
     element: Option<V::Element>,
     cx: Cx,
+    /// Called after each rebuild, e.g. to persist the app state.
+    on_rebuild: Option<Box<dyn FnMut()>>,
+    /// Subscribers to the messages and renders of the app, see [`App::on_message`] and [`App::on_render`].
+    on_message: Vec<Box<dyn FnMut(&[Id], &dyn Any)>>,
+    on_render: Vec<Box<dyn FnMut(ChangeFlags)>>,
+    /// Whether the view tree is rebuilt once per animation frame, instead of after each message.
+    batch_rebuilds: bool,
+    rebuild_scheduled: bool,
+    /// Allocates the ids of the view tree instead of the global counter, see [`App::deterministic_ids`].
+    id_allocator: Option<IdAllocator>,
+    #[cfg(feature = "profiling")]
+    profiler: Option<Box<dyn crate::profiler::Profiler>>,
+    #[cfg(feature = "devtools")]
+    devtools: Option<crate::devtools::DevtoolsOutput>,
+    #[cfg(debug_assertions)]
+    a11y_auditor: crate::a11y::Auditor,
 }
 
+/// Where the app state is persisted, see [`App::persist`].
+#[cfg(feature = "persist")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StorageArea {
+    /// `localStorage`, which is retained across browser sessions.
+    Local,
+    /// `sessionStorage`, which is cleared when the page session ends.
+    Session,
+}
+
+/// How long to wait after the last update, before the app state is written to the storage.
+#[cfg(feature = "persist")]
+const PERSIST_DEBOUNCE_MS: i32 = 200;
+
 pub(crate) trait AppRunner {
     fn handle_message(&self, message: Message);
 
@@ -44,15 +76,274 @@ impl<T: 'static, V: View<T> + 'static, F: FnMut(&mut T) -> V + 'static> App<T, V
         app
     }
 
+    /// Exposes a function `name` on `window`, so that the host page (or e.g. a browser extension) can drive the app.
+    ///
+    /// When the function is called from JS, `handler` is called with the app state and the first argument,
+    /// and the app is rebuilt afterwards, the same way as when a message was handled.
+    /// Calls that happen while the app is handling a message (e.g. synchronously from an event handler)
+    /// are deferred to a microtask.
+    ///
+    /// ```ignore
+    /// App::new(AppState::default(), app_logic)
+    ///     .expose_js_api("setUser", |state: &mut AppState, user: JsValue| {
+    ///         state.user = user.as_string();
+    ///     })
+    ///     .run(&document_body());
+    /// ```
+    /// which can be called in JS as `window.setUser("Alice")`.
+    pub fn expose_js_api(self, name: &str, handler: impl Fn(&mut T, JsValue) + 'static) -> Self {
+        let handler = Rc::new(handler);
+        let app = self.clone();
+        let function = Closure::<dyn Fn(JsValue)>::new(move |arg: JsValue| {
+            let Ok(mut inner) = app.0.try_borrow_mut() else {
+                let (app, handler) = (app.clone(), Rc::clone(&handler));
+                wasm_bindgen_futures::spawn_local(async move {
+                    let mut inner = app.0.borrow_mut();
+                    handler(&mut inner.data, arg);
+                    inner.rebuild();
+                });
+                return;
+            };
+            handler(&mut inner.data, arg);
+            inner.rebuild();
+        });
+        let window = web_sys::window().unwrap_throw();
+        js_sys::Reflect::set(&window, &JsValue::from_str(name), function.as_ref()).unwrap_throw();
+        // The function is alive as long as the app, which runs forever
+        function.forget();
+        self
+    }
+
+    /// Persists the app state as JSON in the browser storage `area` under `key`.
+    ///
+    /// The app state is loaded from the storage instead of the initial state, when it was persisted before (and can be deserialized),
+    /// and it's written back after updates, debounced so that e.g. typing doesn't serialize the app state with each keystroke.
+    /// Fields which shouldn't be persisted can be skipped with `#[serde(skip)]`.
+    ///
+    /// ```ignore
+    /// App::new(AppState::default(), app_logic)
+    ///     .persist("my_app", StorageArea::Local)
+    ///     .run(&document_body());
+    /// ```
+    #[cfg(feature = "persist")]
+    pub fn persist(self, key: &str, area: StorageArea) -> Self
+    where
+        T: serde::Serialize + serde::de::DeserializeOwned,
+    {
+        let window = web_sys::window().unwrap_throw();
+        let storage = match area {
+            StorageArea::Local => window.local_storage(),
+            StorageArea::Session => window.session_storage(),
+        };
+        let Some(storage) = storage.ok().flatten() else {
+            web_sys::console::warn_1(
+                &"The storage isn't available, the app state isn't persisted".into(),
+            );
+            return self;
+        };
+        let mut inner = self.0.borrow_mut();
+        if let Some(raw) = storage.get_item(key).unwrap_throw() {
+            match serde_json::from_str(&raw) {
+                Ok(data) => inner.data = data,
+                Err(err) => web_sys::console::warn_1(
+                    &format!("Couldn't load the persisted app state: {err}").into(),
+                ),
+            }
+        }
+        let app = Rc::downgrade(&self.0);
+        let key = key.to_owned();
+        // Whether there's an update, which isn't saved yet
+        let pending = Rc::new(std::cell::Cell::new(false));
+        let save = {
+            let pending = Rc::clone(&pending);
+            Rc::new(move || {
+                let Some(app) = app.upgrade() else {
+                    return;
+                };
+                // When the app is currently handling a message, it's saved after the following rebuild
+                let Ok(inner) = app.try_borrow() else {
+                    return;
+                };
+                pending.set(false);
+                match serde_json::to_string(&inner.data) {
+                    Ok(raw) => storage.set_item(&key, &raw).unwrap_throw(),
+                    Err(err) => web_sys::console::warn_1(
+                        &format!("Couldn't persist the app state: {err}").into(),
+                    ),
+                }
+            })
+        };
+        // The debounced save wouldn't run anymore, when the page is closed, so pending updates are saved right away
+        for (target, event) in [
+            (window.unchecked_ref::<web_sys::EventTarget>(), "pagehide"),
+            (
+                window.document().unwrap_throw().unchecked_ref(),
+                "visibilitychange",
+            ),
+        ] {
+            let (pending, save) = (Rc::clone(&pending), Rc::clone(&save));
+            let listener = gloo::events::EventListener::new(target, event, move |event| {
+                if pending.get() && (event.type_() == "pagehide" || crate::document().hidden()) {
+                    (*save)();
+                }
+            });
+            // The listener is needed as long as the app, which runs forever
+            listener.forget();
+        }
+        let mut debounced_save = None;
+        inner.on_rebuild = Some(Box::new(move || {
+            pending.set(true);
+            let save = Rc::clone(&save);
+            // The previous timeout is cleared
+            debounced_save = Some(crate::timer::Timeout::new(PERSIST_DEBOUNCE_MS, move || {
+                (*save)()
+            }));
+        }));
+        drop(inner);
+        self
+    }
+
+    /// Reports measurements of the app (e.g. how long rebuilding the view tree took) to `profiler`.
+    ///
+    /// ```ignore
+    /// App::new(AppState::default(), app_logic)
+    ///     .profiler(ConsoleProfiler)
+    ///     .run(&document_body());
+    /// ```
+    #[cfg(feature = "profiling")]
+    pub fn profiler(self, profiler: impl crate::profiler::Profiler + 'static) -> Self {
+        self.0.borrow_mut().profiler = Some(Box::new(profiler));
+        self
+    }
+
+    /// Shows the views named with [`debug_id`](crate::interfaces::Element::debug_id) after each pass,
+    /// with what changed when they were rebuilt, e.g. to find out why a subtree was rebuilt.
+    ///
+    /// ```ignore
+    /// App::new(AppState::default(), app_logic)
+    ///     .devtools(DevtoolsOutput::Overlay)
+    ///     .run(&document_body());
+    /// ```
+    #[cfg(feature = "devtools")]
+    pub fn devtools(self, output: crate::devtools::DevtoolsOutput) -> Self {
+        self.0.borrow_mut().devtools = Some(output);
+        self
+    }
+
+    /// Calls `f` with the id path and the body of each message, before it's handled by the view tree.
+    pub fn on_message(self, f: impl FnMut(&[Id], &dyn Any) + 'static) -> Self {
+        self.0.borrow_mut().on_message.push(Box::new(f));
+        self
+    }
+
+    /// Calls `f` after the view tree was built or rebuilt, with what changed.
+    pub fn on_render(self, f: impl FnMut(ChangeFlags) + 'static) -> Self {
+        self.0.borrow_mut().on_render.push(Box::new(f));
+        self
+    }
+
+    /// Records all messages and what changed in the following rebuild into `recorder`, see [`MessageRecorder`].
+    pub fn record(self, recorder: Rc<RefCell<MessageRecorder>>) -> Self {
+        let render_recorder = Rc::clone(&recorder);
+        self.on_message(move |id_path, body| recorder.borrow_mut().record_message(id_path, body))
+            .on_render(move |changed| render_recorder.borrow_mut().record_render(changed))
+    }
+
+    /// Handles the recorded `messages` (which are replayable) in order, each followed by a rebuild.
+    ///
+    /// This has to be called after the app is [run](App::run), usually with the initial app state.
+    pub fn replay(&self, messages: &[RecordedMessage]) {
+        for recorded in messages {
+            if let Some(body) = recorded.recreate_body() {
+                let message = Message {
+                    id_path: recorded.id_path.clone(),
+                    body,
+                };
+                self.handle(message, true);
+            }
+        }
+    }
+
+    /// Restores the scroll positions of the window and of [scroll containers](crate::scroll_container),
+    /// when navigating back or forward in the history (e.g. with a router based on `pushState` or the url hash).
+    ///
+    /// The positions are recorded per history entry (identified via `history.state`), and restored after the view tree
+    /// was rebuilt for the entry that was navigated to, so that the content that is scrolled to exists.
+    /// The positions are kept in memory, so they're not restored after the page was reloaded.
+    pub fn scroll_restoration(self) -> Self {
+        crate::scroll_restoration::enable();
+        let app = self.clone();
+        let listener = gloo::events::EventListener::new(
+            &web_sys::window().unwrap_throw(),
+            "popstate",
+            move |_| {
+                crate::scroll_restoration::navigated();
+                // The positions are restored after the next rebuild, which may not be caused by the app otherwise
+                if let Ok(mut inner) = app.0.try_borrow_mut() {
+                    app.schedule_rebuild(&mut inner);
+                }
+            },
+        );
+        // The listener is alive as long as the app, which runs forever
+        listener.forget();
+        self.on_render(|_| crate::scroll_restoration::restore_pending())
+    }
+
+    /// Rebuilds the view tree once per animation frame, instead of synchronously after each message.
+    ///
+    /// The messages arriving within the same animation frame (e.g. during a storm of `mousemove` events)
+    /// are all handled first, and the view tree is rebuilt only once, right before the next frame is rendered.
+    ///
+    /// Note that the messages after the first one are then handled by the views of the previous rebuild,
+    /// so handlers must not rely on the data they captured still matching the app state
+    /// (e.g. an index into a `Vec`, which may have been removed by a previous message).
+    pub fn batch_rebuilds(self) -> Self {
+        self.0.borrow_mut().batch_rebuilds = true;
+        self
+    }
+
+    /// Allocates the ids of the view tree starting with `seed`, independent of other apps (e.g. running in parallel tests),
+    /// so that they're the same in each run, e.g. for snapshot tests of the element tree.
+    pub fn deterministic_ids(self, seed: std::num::NonZeroU64) -> Self {
+        self.0.borrow_mut().id_allocator = Some(IdAllocator::new(seed));
+        self
+    }
+
+    /// Passes the DOM mutations of each (re)build to `applier`, after they were applied to the DOM,
+    /// e.g. to record or count them in tests, or to mirror the DOM somewhere else, see [`mutations`](crate::mutations).
+    pub fn dom_applier(self, applier: impl crate::mutations::ApplyDomOps + 'static) -> Self {
+        self.0.borrow_mut().cx.set_dom_applier(applier);
+        self
+    }
+
     /// Run the app.
     ///
     /// Because we don't want to block the render thread, we return immediately here. The app is
     /// forgotten, and will continue to respond to events in the background.
     pub fn run(self, root: &web_sys::HtmlElement) {
-        self.0.borrow_mut().ensure_app(root);
+        self.0.borrow_mut().ensure_app(Some(root));
         // Latter may not be necessary, we have an rc loop.
         std::mem::forget(self);
     }
+
+    /// Run the app, with the whole document managed by its root view, a [`document_shell`](crate::document_shell),
+    /// instead of mounting it into a root element.
+    pub fn run_document(self)
+    where
+        V: View<T, Element = web_sys::HtmlHtmlElement>,
+    {
+        self.0.borrow_mut().ensure_app(None);
+        std::mem::forget(self);
+    }
+
+    /// Run the app, with its root view, [`islands`](crate::islands), mounted into multiple existing elements of the document.
+    pub fn run_islands(self)
+    where
+        V: View<T, Element = web_sys::DocumentFragment>,
+    {
+        self.0.borrow_mut().ensure_app(None);
+        std::mem::forget(self);
+    }
 }
 
 impl<T, V: View<T>, F: FnMut(&mut T) -> V> AppInner<T, V, F> {
@@ -66,36 +357,171 @@ impl<T, V: View<T>, F: FnMut(&mut T) -> V> AppInner<T, V, F> {
             state: None,
             element: None,
             cx,
+            on_rebuild: None,
+            on_message: Vec::new(),
+            on_render: Vec::new(),
+            batch_rebuilds: false,
+            rebuild_scheduled: false,
+            id_allocator: None,
+            #[cfg(feature = "profiling")]
+            profiler: None,
+            #[cfg(feature = "devtools")]
+            devtools: None,
+            #[cfg(debug_assertions)]
+            a11y_auditor: Default::default(),
+        }
+    }
+
+    /// Runs `f` with the id allocator of the app, if there's one, see [`App::deterministic_ids`].
+    fn with_id_allocator(&mut self, f: impl FnOnce(&mut Self)) {
+        match self.id_allocator.take() {
+            Some(mut allocator) => {
+                allocator.enter(|| f(self));
+                self.id_allocator = Some(allocator);
+            }
+            None => f(self),
         }
     }
 
-    fn ensure_app(&mut self, root: &web_sys::HtmlElement) {
+    /// Builds the app, and mounts it into `root`, unless it mounts itself, see [`App::run_document`] and [`App::run_islands`].
+    fn ensure_app(&mut self, root: Option<&web_sys::HtmlElement>) {
+        self.with_id_allocator(|inner| inner.build(root));
+    }
+
+    fn build(&mut self, root: Option<&web_sys::HtmlElement>) {
         if self.view.is_none() {
-            let view = (self.app_logic)(&mut self.data);
+            let view = self.app_logic();
+            #[cfg(feature = "profiling")]
+            let start = crate::profiler::now();
             let (id, state, element) = view.build(&mut self.cx);
+            if let Some(root) = root {
+                self.cx.set_root_node(root);
+                self.cx.insert_node(root, element.as_node_ref(), None);
+            }
+            self.cx.flush_dom_ops();
+            #[cfg(feature = "profiling")]
+            if let Some(profiler) = &mut self.profiler {
+                let mutations = self.cx.take_dom_mutations();
+                profiler.build(crate::profiler::now() - start, mutations);
+            }
+            #[cfg(feature = "devtools")]
+            self.show_devtools("build");
+            for on_render in &mut self.on_render {
+                on_render(ChangeFlags::tree_structure());
+            }
             self.view = Some(view);
             self.id = Some(id);
             self.state = Some(state);
-
-            root.append_child(element.as_node_ref()).unwrap();
+            #[cfg(debug_assertions)]
+            self.a11y_auditor.maybe_audit(element.as_node_ref());
             self.element = Some(element);
         }
     }
+
+    fn app_logic(&mut self) -> V {
+        #[cfg(feature = "profiling")]
+        let start = crate::profiler::now();
+        let view = (self.app_logic)(&mut self.data);
+        #[cfg(feature = "profiling")]
+        if let Some(profiler) = &mut self.profiler {
+            profiler.app_logic(crate::profiler::now() - start);
+        }
+        view
+    }
+
+    #[cfg(feature = "devtools")]
+    fn show_devtools(&mut self, pass: &str) {
+        let records = self.cx.take_debug_records();
+        if let Some(output) = self.devtools {
+            output.show(pass, &records);
+        }
+    }
+
+    /// Rebuilds the view tree after the app state was changed.
+    fn rebuild(&mut self) {
+        self.with_id_allocator(Self::rebuild_view);
+    }
+
+    fn rebuild_view(&mut self) {
+        if self.view.is_none() {
+            return;
+        }
+        let new_view = self.app_logic();
+        let view = self.view.as_mut().unwrap();
+        #[cfg(feature = "profiling")]
+        let start = crate::profiler::now();
+        let changed = new_view.rebuild(
+            &mut self.cx,
+            view,
+            self.id.as_mut().unwrap(),
+            self.state.as_mut().unwrap(),
+            self.element.as_mut().unwrap(),
+        );
+        // Not sure we have to do anything on changed, the rebuild
+        // traversal should cause the DOM to update.
+        *view = new_view;
+        self.cx.flush_dom_ops();
+        #[cfg(feature = "profiling")]
+        if let Some(profiler) = &mut self.profiler {
+            let mutations = self.cx.take_dom_mutations();
+            profiler.rebuild(crate::profiler::now() - start, changed, mutations);
+        }
+
+        #[cfg(feature = "devtools")]
+        self.show_devtools("rebuild");
+
+        #[cfg(debug_assertions)]
+        self.a11y_auditor
+            .maybe_audit(self.element.as_ref().unwrap().as_node_ref());
+
+        for on_render in &mut self.on_render {
+            on_render(changed);
+        }
+        if let Some(on_rebuild) = &mut self.on_rebuild {
+            on_rebuild();
+        }
+    }
 }
 
-impl<T: 'static, V: View<T> + 'static, F: FnMut(&mut T) -> V + 'static> AppRunner for App<T, V, F> {
-    // For now we handle the message synchronously, but it would also
-    // make sense to to batch them (for example with requestAnimFrame).
-    fn handle_message(&self, message: Message) {
+impl<T: 'static, V: View<T> + 'static, F: FnMut(&mut T) -> V + 'static> App<T, V, F> {
+    /// Rebuilds the view tree before the next animation frame, unless that's already scheduled.
+    fn schedule_rebuild(&self, inner: &mut AppInner<T, V, F>) {
+        if inner.rebuild_scheduled {
+            return;
+        }
+        inner.rebuild_scheduled = true;
+        let app = self.clone();
+        let callback = Closure::once_into_js(move || {
+            let mut inner = app.0.borrow_mut();
+            inner.rebuild_scheduled = false;
+            inner.rebuild();
+        });
+        web_sys::window()
+            .unwrap_throw()
+            .request_animation_frame(callback.unchecked_ref())
+            .unwrap_throw();
+    }
+
+    /// Handles the message synchronously, rebuilding is batched when enabled via [`App::batch_rebuilds`], unless `sync_rebuild` is set.
+    fn handle(&self, message: Message, sync_rebuild: bool) {
         let mut inner_guard = self.0.borrow_mut();
         let inner = &mut *inner_guard;
         if let Some(view) = &mut inner.view {
+            for on_message in &mut inner.on_message {
+                on_message(&message.id_path, &*message.body);
+            }
+            #[cfg(feature = "profiling")]
+            let start = crate::profiler::now();
             let message_result = view.message(
                 &message.id_path[1..],
                 inner.state.as_mut().unwrap(),
                 message.body,
                 &mut inner.data,
             );
+            #[cfg(feature = "profiling")]
+            if let Some(profiler) = &mut inner.profiler {
+                profiler.message(crate::profiler::now() - start);
+            }
             match message_result {
                 MessageResult::Nop | MessageResult::Action(_) => {
                     // Nothing to do.
@@ -108,19 +534,19 @@ impl<T: 'static, V: View<T> + 'static, F: FnMut(&mut T) -> V + 'static> AppRunne
                 }
             }
 
-            let new_view = (inner.app_logic)(&mut inner.data);
-            let _changed = new_view.rebuild(
-                &mut inner.cx,
-                view,
-                inner.id.as_mut().unwrap(),
-                inner.state.as_mut().unwrap(),
-                inner.element.as_mut().unwrap(),
-            );
-            // Not sure we have to do anything on changed, the rebuild
-            // traversal should cause the DOM to update.
-            *view = new_view;
+            if inner.batch_rebuilds && !sync_rebuild {
+                self.schedule_rebuild(inner);
+            } else {
+                inner.rebuild();
+            }
         }
     }
+}
+
+impl<T: 'static, V: View<T> + 'static, F: FnMut(&mut T) -> V + 'static> AppRunner for App<T, V, F> {
+    fn handle_message(&self, message: Message) {
+        self.handle(message, false);
+    }
 
     fn clone_box(&self) -> Box<dyn AppRunner> {
         Box::new(self.clone())
@@ -0,0 +1,127 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Typed ARIA attributes, see e.g. [`Element::role`](crate::interfaces::Element::role)
+//! and [`Element::aria_expanded`](crate::interfaces::Element::aria_expanded).
+//!
+//! In debug builds, roles which are missing attributes they require (e.g. `role="dialog"` without `aria-modal`),
+//! and references to elements which don't exist (e.g. in `aria-controls`) are reported to the console.
+
+use crate::{AttributeValue, IntoAttributeValue};
+
+macro_rules! roles {
+    ($($(#[$attr:meta])* $variant:ident => $name:literal,)*) => {
+        /// The [ARIA role](https://developer.mozilla.org/en-US/docs/Web/Accessibility/ARIA/Roles) of an element, see [`Element::role`](crate::interfaces::Element::role).
+        ///
+        /// Roles which are implied by an HTML element (e.g. `button` for `<button>`) don't need to be set.
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub enum Role {
+            $($(#[$attr])* $variant,)*
+        }
+
+        impl Role {
+            /// The value of the `role` attribute.
+            pub fn as_str(self) -> &'static str {
+                match self {
+                    $(Role::$variant => $name,)*
+                }
+            }
+        }
+    };
+}
+
+roles! {
+    Alert => "alert",
+    AlertDialog => "alertdialog",
+    Application => "application",
+    Banner => "banner",
+    Button => "button",
+    Checkbox => "checkbox",
+    Combobox => "combobox",
+    Complementary => "complementary",
+    ContentInfo => "contentinfo",
+    Dialog => "dialog",
+    Grid => "grid",
+    GridCell => "gridcell",
+    Group => "group",
+    Heading => "heading",
+    Img => "img",
+    Link => "link",
+    List => "list",
+    Listbox => "listbox",
+    ListItem => "listitem",
+    Log => "log",
+    Main => "main",
+    Menu => "menu",
+    Menubar => "menubar",
+    MenuItem => "menuitem",
+    MenuItemCheckbox => "menuitemcheckbox",
+    MenuItemRadio => "menuitemradio",
+    Navigation => "navigation",
+    /// Removes the implicit semantics of the element, e.g. for a `<table>` used for layout.
+    Presentation => "presentation",
+    Progressbar => "progressbar",
+    Radio => "radio",
+    RadioGroup => "radiogroup",
+    Region => "region",
+    Row => "row",
+    Scrollbar => "scrollbar",
+    Search => "search",
+    Separator => "separator",
+    Slider => "slider",
+    Spinbutton => "spinbutton",
+    Status => "status",
+    Switch => "switch",
+    Tab => "tab",
+    Tablist => "tablist",
+    Tabpanel => "tabpanel",
+    Textbox => "textbox",
+    Timer => "timer",
+    Toolbar => "toolbar",
+    Tooltip => "tooltip",
+    Tree => "tree",
+    TreeItem => "treeitem",
+}
+
+impl IntoAttributeValue for Role {
+    fn into_attr_value(self) -> Option<AttributeValue> {
+        self.as_str().into_attr_value()
+    }
+}
+
+/// The attributes which have to be set for elements with a role (by name), which are checked in debug builds.
+#[cfg(debug_assertions)]
+pub(crate) fn required_attributes(role: &str) -> &'static [&'static str] {
+    match role {
+        "dialog" | "alertdialog" => &["aria-modal"],
+        "checkbox" | "radio" | "switch" | "menuitemcheckbox" | "menuitemradio" => &["aria-checked"],
+        "combobox" => &["aria-expanded", "aria-controls"],
+        "heading" => &["aria-level"],
+        "scrollbar" => &["aria-controls", "aria-valuenow"],
+        "slider" | "spinbutton" => &["aria-valuenow"],
+        "tab" => &["aria-selected"],
+        _ => &[],
+    }
+}
+
+/// The attributes which reference other elements by a (space-separated list of) id, which are checked in debug builds.
+#[cfg(debug_assertions)]
+pub(crate) const ID_REFERENCE_ATTRIBUTES: &[&str] = &[
+    "aria-activedescendant",
+    "aria-controls",
+    "aria-describedby",
+    "aria-details",
+    "aria-errormessage",
+    "aria-flowto",
+    "aria-labelledby",
+    "aria-owns",
+];
+
+/// ARIA states like `aria-expanded` are `"true"` or `"false"`, in contrast to boolean HTML attributes, which are present or absent.
+pub(crate) fn bool_attr_value(value: bool) -> &'static str {
+    if value {
+        "true"
+    } else {
+        "false"
+    }
+}
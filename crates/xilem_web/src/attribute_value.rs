@@ -1,26 +1,93 @@
 type CowStr = std::borrow::Cow<'static, str>;
 
-#[derive(PartialEq, Clone, Debug, PartialOrd)]
+/// The value of an attribute, which is kept typed, so that it's only serialized when it changed.
+#[derive(PartialEq, Clone, Debug)]
 pub enum AttributeValue {
     True, // for the boolean true, this serializes to an empty string (e.g. for <input checked>)
     I32(i32),
     U32(u32),
+    I64(i64),
+    U64(u64),
     F32(f32),
     F64(f64),
+    /// A whitespace separated list of numbers, e.g. for `viewBox` or `points` of SVG elements.
+    Numbers(Vec<f64>),
     String(CowStr),
+    /// A JSON value, e.g. for `data-*` attributes, see [`Element::data_json`](crate::interfaces::Element::data_json).
+    #[cfg(feature = "json")]
+    Json(serde_json::Value),
 }
 
 impl AttributeValue {
     pub fn serialize(&self) -> CowStr {
         match self {
-            AttributeValue::True => "".into(), // empty string is equivalent to a true set attribute
-            AttributeValue::I32(n) => n.to_string().into(),
-            AttributeValue::U32(n) => n.to_string().into(),
-            AttributeValue::F32(n) => n.to_string().into(),
-            AttributeValue::F64(n) => n.to_string().into(),
             AttributeValue::String(s) => s.clone(),
+            value => {
+                let mut buf = String::new();
+                value.serialize_into(&mut buf);
+                buf.into()
+            }
         }
     }
+
+    /// Like [`AttributeValue::serialize`], but numbers are written into `buf` (which is cleared before),
+    /// so that it can be reused to avoid an allocation per attribute.
+    pub fn serialize_into<'a>(&'a self, buf: &'a mut String) -> &'a str {
+        buf.clear();
+        match self {
+            AttributeValue::True => "", // empty string is equivalent to a true set attribute
+            AttributeValue::I32(n) => {
+                buf.push_str(itoa::Buffer::new().format(*n));
+                buf
+            }
+            AttributeValue::U32(n) => {
+                buf.push_str(itoa::Buffer::new().format(*n));
+                buf
+            }
+            AttributeValue::I64(n) => {
+                buf.push_str(itoa::Buffer::new().format(*n));
+                buf
+            }
+            AttributeValue::U64(n) => {
+                buf.push_str(itoa::Buffer::new().format(*n));
+                buf
+            }
+            AttributeValue::F32(n) => {
+                write_float(buf, *n);
+                buf
+            }
+            AttributeValue::F64(n) => {
+                write_float(buf, *n);
+                buf
+            }
+            AttributeValue::Numbers(numbers) => {
+                for (i, n) in numbers.iter().enumerate() {
+                    if i > 0 {
+                        buf.push(' ');
+                    }
+                    write_float(buf, *n);
+                }
+                buf
+            }
+            AttributeValue::String(s) => s,
+            #[cfg(feature = "json")]
+            AttributeValue::Json(value) => {
+                // Serializing a `serde_json::Value` can't fail
+                *buf = value.to_string();
+                buf
+            }
+        }
+    }
+}
+
+/// Integral values are written without a fractional part (e.g. `1` instead of `1.0`), the same as with `to_string()`.
+fn write_float<F: ryu::Float + Into<f64>>(buf: &mut String, n: F) {
+    let n_f64: f64 = n.into();
+    if n_f64.fract() == 0.0 && n_f64.abs() < 1e15 {
+        buf.push_str(itoa::Buffer::new().format(n_f64 as i64));
+    } else {
+        buf.push_str(ryu::Buffer::new().format(n));
+    }
 }
 
 pub trait IntoAttributeValue: Sized {
@@ -37,6 +104,7 @@ impl<T: IntoAttributeValue> IntoAttributeValue for Option<T> {
     }
 }
 
+/// `true` sets the attribute (to an empty string) and `false` removes it, as it's expected by boolean attributes like `disabled`.
 impl IntoAttributeValue for bool {
     fn into_attr_value(self) -> Option<AttributeValue> {
         self.then_some(AttributeValue::True)
@@ -61,6 +129,34 @@ impl IntoAttributeValue for i32 {
     }
 }
 
+macro_rules! impl_into_attr_value_for_int {
+    ($($ty:ty => $variant:ident),*) => {
+        $(
+            impl IntoAttributeValue for $ty {
+                fn into_attr_value(self) -> Option<AttributeValue> {
+                    Some(AttributeValue::$variant(self.into()))
+                }
+            }
+        )*
+    };
+}
+
+impl_into_attr_value_for_int!(
+    i8 => I32, i16 => I32, u8 => U32, u16 => U32, i64 => I64, u64 => U64
+);
+
+impl IntoAttributeValue for usize {
+    fn into_attr_value(self) -> Option<AttributeValue> {
+        Some(AttributeValue::U64(self as u64))
+    }
+}
+
+impl IntoAttributeValue for isize {
+    fn into_attr_value(self) -> Option<AttributeValue> {
+        Some(AttributeValue::I64(self as i64))
+    }
+}
+
 impl IntoAttributeValue for f32 {
     fn into_attr_value(self) -> Option<AttributeValue> {
         Some(AttributeValue::F32(self))
@@ -73,6 +169,18 @@ impl IntoAttributeValue for f64 {
     }
 }
 
+impl IntoAttributeValue for Vec<f64> {
+    fn into_attr_value(self) -> Option<AttributeValue> {
+        Some(AttributeValue::Numbers(self))
+    }
+}
+
+impl<const N: usize> IntoAttributeValue for [f64; N] {
+    fn into_attr_value(self) -> Option<AttributeValue> {
+        Some(AttributeValue::Numbers(self.into()))
+    }
+}
+
 impl IntoAttributeValue for String {
     fn into_attr_value(self) -> Option<AttributeValue> {
         Some(AttributeValue::String(self.into()))
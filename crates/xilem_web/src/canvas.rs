@@ -0,0 +1,343 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{any::Any, cell::RefCell, marker::PhantomData, rc::Rc};
+
+use gloo::events::EventListener;
+use peniko::kurbo::Size;
+use wasm_bindgen::{closure::Closure, JsCast, UnwrapThrowExt};
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    context::{ChangeFlags, Cx, ElementProps},
+    interfaces::sealed::Sealed,
+    timer::AnimationFrameLoop,
+    view::{View, ViewMarker},
+    HTML_NS,
+};
+
+macro_rules! generate_dom_interface_impl {
+    ($dom_interface:ident, ()) => {
+        impl<D, F, T, A> $crate::interfaces::$dom_interface<T, A> for Canvas2d<D, F, T, A>
+        where
+            D: PartialEq + 'static,
+            F: Fn(&web_sys::CanvasRenderingContext2d, Size, &D) + 'static,
+        {
+        }
+    };
+}
+
+/// Sizes the backing store of `canvas` for its size in CSS pixels and the device pixel ratio.
+///
+/// Returns the size in CSS pixels, the device pixel ratio and whether the backing store was resized.
+fn resize_backing_store(canvas: &web_sys::HtmlCanvasElement) -> (Size, f64, bool) {
+    let scale = web_sys::window().unwrap_throw().device_pixel_ratio();
+    let size = Size::new(canvas.client_width() as f64, canvas.client_height() as f64);
+    let (width, height) = (
+        (size.width * scale).round() as u32,
+        (size.height * scale).round() as u32,
+    );
+    // Resizing the backing store clears the canvas (and resets a 2D context), so it's only done when the size changed
+    let resized = canvas.width() != width || canvas.height() != height;
+    if resized {
+        canvas.set_width(width);
+        canvas.set_height(height);
+    }
+    (size, scale, resized)
+}
+
+/// Sent when the canvas has to be drawn again, because its size (or the device pixel ratio) may have changed.
+struct CanvasResized;
+
+/// A `<canvas>` drawn with its 2D rendering context, see [`canvas_2d`].
+pub struct Canvas2d<D, F, T, A = ()> {
+    data: D,
+    draw: F,
+    phantom: PhantomData<fn() -> (T, A)>,
+}
+
+/// A `<canvas>`, which is drawn by `draw` with the 2D rendering context, its size (in CSS pixels) and `data`.
+///
+/// It's drawn again when `data` changed (compared via `PartialEq`, like [`memoize`](crate::memoize)),
+/// and when the window was resized. The backing store of the canvas is sized for the device pixel ratio,
+/// and the context is scaled accordingly, so `draw` can use CSS pixels and is still crisp on high-DPI screens.
+/// The canvas is cleared before each draw.
+///
+/// The size of the canvas is determined by CSS (e.g. `width: 100%; height: 200px`),
+/// so it's drawn the first time after it's laid out.
+///
+/// ```ignore
+/// canvas_2d(state.points.clone(), |ctx, size, points| {
+///     ctx.begin_path();
+///     for point in points {
+///         ctx.line_to(point.x * size.width, point.y * size.height);
+///     }
+///     ctx.stroke();
+/// })
+/// .attr("style", "width: 100%; height: 200px")
+/// ```
+pub fn canvas_2d<D, F, T, A>(data: D, draw: F) -> Canvas2d<D, F, T, A>
+where
+    D: PartialEq + 'static,
+    F: Fn(&web_sys::CanvasRenderingContext2d, Size, &D) + 'static,
+{
+    Canvas2d {
+        data,
+        draw,
+        phantom: PhantomData,
+    }
+}
+
+pub struct Canvas2dState {
+    props: ElementProps,
+    canvas: web_sys::HtmlCanvasElement,
+    context: web_sys::CanvasRenderingContext2d,
+    // Retained, so that the canvas is drawn again when the window is resized, and removed when dropped
+    #[allow(unused)]
+    resize_listener: EventListener,
+}
+
+impl<D, F, T, A> Canvas2d<D, F, T, A>
+where
+    D: PartialEq + 'static,
+    F: Fn(&web_sys::CanvasRenderingContext2d, Size, &D) + 'static,
+{
+    fn draw(&self, state: &Canvas2dState) {
+        let context = &state.context;
+        let (size, scale, _) = resize_backing_store(&state.canvas);
+        context
+            .set_transform(scale, 0.0, 0.0, scale, 0.0, 0.0)
+            .unwrap_throw();
+        context.clear_rect(0.0, 0.0, size.width, size.height);
+        (self.draw)(context, size, &self.data);
+    }
+}
+
+generate_dom_interface_impl!(HtmlCanvasElement, ());
+crate::interfaces::for_all_html_canvas_element_ancestors!(generate_dom_interface_impl, ());
+
+impl<D, F, T, A> ViewMarker for Canvas2d<D, F, T, A> {}
+impl<D, F, T, A> Sealed for Canvas2d<D, F, T, A> {}
+
+impl<D, F, T, A> View<T, A> for Canvas2d<D, F, T, A>
+where
+    D: PartialEq + 'static,
+    F: Fn(&web_sys::CanvasRenderingContext2d, Size, &D) + 'static,
+{
+    type State = Canvas2dState;
+    type Element = web_sys::HtmlCanvasElement;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, (canvas, state)) = cx.with_new_id(|cx| {
+            let (el, props) = cx.build_element(HTML_NS, "canvas");
+            let canvas: web_sys::HtmlCanvasElement = el.unchecked_into();
+            let context = canvas
+                .get_context("2d")
+                .unwrap_throw()
+                .unwrap_throw()
+                .unchecked_into();
+            let thunk = cx.message_thunk();
+            let resize_listener =
+                EventListener::new(&web_sys::window().unwrap_throw(), "resize", move |_| {
+                    thunk.push_message(CanvasResized);
+                });
+            // The canvas is drawn the first time, after it's laid out
+            let thunk = cx.message_thunk();
+            let first_draw = Closure::once_into_js(move || thunk.push_message(CanvasResized));
+            web_sys::window()
+                .unwrap_throw()
+                .request_animation_frame(first_draw.unchecked_ref())
+                .unwrap_throw();
+            let state = Canvas2dState {
+                props,
+                canvas: canvas.clone(),
+                context,
+                resize_listener,
+            };
+            (canvas, state)
+        });
+        (id, state, canvas)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        _id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let mut changed = cx.rebuild_element(element, &mut state.props);
+        if prev.data != self.data {
+            self.draw(state);
+            changed |= ChangeFlags::OTHER_CHANGE;
+        }
+        changed
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Box<dyn Any>,
+        _app_state: &mut T,
+    ) -> MessageResult<A> {
+        match id_path {
+            [] if message.downcast_ref::<CanvasResized>().is_some() => {
+                self.draw(state);
+                MessageResult::Nop
+            }
+            _ => MessageResult::Stale(message),
+        }
+    }
+}
+
+/// The frame which is rendered by [`canvas_surface`].
+#[derive(Clone, Copy, Debug)]
+pub struct SurfaceFrame {
+    /// The timestamp of the animation frame in milliseconds.
+    pub timestamp: f64,
+    /// The size of the backing store (e.g. for configuring a `wgpu::Surface`) in physical pixels.
+    pub width: u32,
+    pub height: u32,
+    /// The device pixel ratio, i.e. physical pixels per CSS pixel.
+    pub scale: f64,
+    /// Whether the backing store was resized since the last frame, which is also the case for the first frame.
+    pub resized: bool,
+}
+
+/// A `<canvas>` rendered by a custom renderer each animation frame, see [`canvas_surface`].
+pub struct CanvasSurface<S, I, R, T, A = ()> {
+    init: I,
+    render: R,
+    phantom: PhantomData<fn() -> (S, T, A)>,
+}
+
+/// A `<canvas>` for custom renderers (e.g. vello via wgpu, or raw WebGL), which own the content of the canvas.
+///
+/// `init` creates the surface (or rendering context) of the renderer from the canvas, when it's built.
+/// `render` is called with it before each animation frame, as long as the view is in the tree,
+/// the backing store of the canvas is sized for its size in CSS pixels and the device pixel ratio before,
+/// which is indicated by [`SurfaceFrame::resized`].
+///
+/// ```ignore
+/// canvas_surface(
+///     |canvas| canvas.get_context("webgl2").unwrap().unwrap().unchecked_into::<WebGl2RenderingContext>(),
+///     move |gl, frame| {
+///         if frame.resized {
+///             gl.viewport(0, 0, frame.width as i32, frame.height as i32);
+///         }
+///         draw_scene(gl, frame.timestamp);
+///     },
+/// )
+/// .attr("style", "width: 100%; height: 100%")
+/// ```
+pub fn canvas_surface<S, I, R, T, A>(init: I, render: R) -> CanvasSurface<S, I, R, T, A>
+where
+    S: 'static,
+    I: Fn(&web_sys::HtmlCanvasElement) -> S,
+    R: Fn(&mut S, &SurfaceFrame) + Clone + 'static,
+{
+    CanvasSurface {
+        init,
+        render,
+        phantom: PhantomData,
+    }
+}
+
+struct Surface<S, R> {
+    surface: S,
+    /// The render callback of the current view.
+    render: R,
+}
+
+pub struct CanvasSurfaceState<S, R> {
+    props: ElementProps,
+    surface: Rc<RefCell<Surface<S, R>>>,
+    // Retained, so that rendering stops when the view is removed
+    #[allow(unused)]
+    animation_frame_loop: AnimationFrameLoop,
+}
+
+macro_rules! generate_surface_dom_interface_impl {
+    ($dom_interface:ident, ()) => {
+        impl<S, I, R, T, A> $crate::interfaces::$dom_interface<T, A>
+            for CanvasSurface<S, I, R, T, A>
+        where
+            S: 'static,
+            I: Fn(&web_sys::HtmlCanvasElement) -> S,
+            R: Fn(&mut S, &SurfaceFrame) + Clone + 'static,
+        {
+        }
+    };
+}
+
+generate_surface_dom_interface_impl!(HtmlCanvasElement, ());
+crate::interfaces::for_all_html_canvas_element_ancestors!(generate_surface_dom_interface_impl, ());
+
+impl<S, I, R, T, A> ViewMarker for CanvasSurface<S, I, R, T, A> {}
+impl<S, I, R, T, A> Sealed for CanvasSurface<S, I, R, T, A> {}
+
+impl<S, I, R, T, A> View<T, A> for CanvasSurface<S, I, R, T, A>
+where
+    S: 'static,
+    I: Fn(&web_sys::HtmlCanvasElement) -> S,
+    R: Fn(&mut S, &SurfaceFrame) + Clone + 'static,
+{
+    type State = CanvasSurfaceState<S, R>;
+    type Element = web_sys::HtmlCanvasElement;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (el, props) = cx.build_element(HTML_NS, "canvas");
+        let canvas: web_sys::HtmlCanvasElement = el.unchecked_into();
+        let surface = Rc::new(RefCell::new(Surface {
+            surface: (self.init)(&canvas),
+            render: self.render.clone(),
+        }));
+        let animation_frame_loop = {
+            let (canvas, surface) = (canvas.clone(), Rc::clone(&surface));
+            AnimationFrameLoop::new(move |timestamp| {
+                let (_, scale, resized) = resize_backing_store(&canvas);
+                let frame = SurfaceFrame {
+                    timestamp,
+                    width: canvas.width(),
+                    height: canvas.height(),
+                    scale,
+                    resized,
+                };
+                let mut surface = surface.borrow_mut();
+                let Surface { surface, render } = &mut *surface;
+                render(surface, &frame);
+            })
+        };
+        let state = CanvasSurfaceState {
+            props,
+            surface,
+            animation_frame_loop,
+        };
+        (Id::next(), state, canvas)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        _prev: &Self,
+        _id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        // The next frame is rendered with the render callback of this view
+        state.surface.borrow_mut().render = self.render.clone();
+        cx.rebuild_element(element, &mut state.props)
+    }
+
+    fn message(
+        &self,
+        _id_path: &[Id],
+        _state: &mut Self::State,
+        message: Box<dyn Any>,
+        _app_state: &mut T,
+    ) -> MessageResult<A> {
+        MessageResult::Stale(message)
+    }
+}
@@ -0,0 +1,515 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Charting primitives built from SVG elements: [`LinearScale`]s, [`axis`] and line, bar and scatter series.
+//!
+//! The data of a series is shared via an `Rc`, and is compared by pointer, so the path of a series is only
+//! generated again when the data (or the scales) actually changed, not when the app is rebuilt for something else.
+//!
+//! ```ignore
+//! let x = LinearScale::fit(state.data.iter().map(|p| p.x), (0.0, 400.0)).nice(8);
+//! let y = LinearScale::fit(state.data.iter().map(|p| p.y), (200.0, 0.0)).nice(5);
+//! svg::g((
+//!     line_series(&state.data, x, y).stroke(Color::BLUE, Stroke::new(2.0)),
+//!     axis(x, AxisSide::Bottom).attr("transform", "translate(0, 200)"),
+//!     axis(y, AxisSide::Left),
+//! ))
+//! ```
+
+use std::{fmt::Write, rc::Rc};
+
+use peniko::kurbo::Point;
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    context::{ChangeFlags, Cx, ElementProps},
+    interfaces::sealed::Sealed,
+    view::{View, ViewMarker},
+    SVG_NS,
+};
+
+/// Maps the values of `domain` linearly to `range` (e.g. pixels), `range` can be inverted (e.g. for a y axis).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LinearScale {
+    pub domain: (f64, f64),
+    pub range: (f64, f64),
+}
+
+impl LinearScale {
+    pub fn new(domain: (f64, f64), range: (f64, f64)) -> Self {
+        LinearScale { domain, range }
+    }
+
+    /// A scale with the smallest domain containing all `values` (`(0.0, 1.0)` when there are none).
+    pub fn fit(values: impl IntoIterator<Item = f64>, range: (f64, f64)) -> Self {
+        let domain = values
+            .into_iter()
+            .filter(|v| v.is_finite())
+            .fold(None, |domain: Option<(f64, f64)>, v| match domain {
+                Some((min, max)) => Some((min.min(v), max.max(v))),
+                None => Some((v, v)),
+            })
+            .unwrap_or((0.0, 1.0));
+        LinearScale { domain, range }
+    }
+
+    /// Extends the domain to multiples of the tick step (see [`LinearScale::ticks`]) for about `count` ticks.
+    pub fn nice(mut self, count: usize) -> Self {
+        let (min, max) = self.domain;
+        if let Some(step) = tick_step(min, max, count) {
+            self.domain = ((min / step).floor() * step, (max / step).ceil() * step);
+        }
+        self
+    }
+
+    pub fn map(&self, value: f64) -> f64 {
+        let (d0, d1) = self.domain;
+        let (r0, r1) = self.range;
+        if d0 == d1 {
+            return (r0 + r1) / 2.0;
+        }
+        r0 + (value - d0) / (d1 - d0) * (r1 - r0)
+    }
+
+    /// The inverse of [`LinearScale::map`], e.g. to map a pointer position into the domain.
+    pub fn invert(&self, value: f64) -> f64 {
+        LinearScale::new(self.range, self.domain).map(value)
+    }
+
+    /// About `count` evenly spaced "round" values (multiples of 1, 2 or 5 times a power of ten) within the domain.
+    pub fn ticks(&self, count: usize) -> Vec<f64> {
+        let (min, max) = self.domain;
+        let (min, max) = (min.min(max), min.max(max));
+        let Some(step) = tick_step(min, max, count) else {
+            return if min == max && min.is_finite() {
+                vec![min]
+            } else {
+                Vec::new()
+            };
+        };
+        // Multiples of the step, so that rounding errors don't accumulate
+        let first = (min / step).ceil() as i64;
+        let last = (max / step).floor() as i64;
+        (first..=last).map(|i| i as f64 * step).collect()
+    }
+}
+
+fn tick_step(min: f64, max: f64, count: usize) -> Option<f64> {
+    let span = (max - min).abs();
+    if count == 0 || span == 0.0 || !span.is_finite() {
+        return None;
+    }
+    let raw_step = span / count as f64;
+    let power = 10f64.powf(raw_step.log10().floor());
+    let error = raw_step / power;
+    let factor = if error >= 50f64.sqrt() {
+        10.0
+    } else if error >= 10f64.sqrt() {
+        5.0
+    } else if error >= 2f64.sqrt() {
+        2.0
+    } else {
+        1.0
+    };
+    Some(factor * power)
+}
+
+/// Formats `value` with as many decimals as necessary for ticks with the distance `step`.
+fn format_tick(value: f64, step: f64) -> String {
+    let decimals = (-step.log10().floor()).max(0.0) as usize;
+    let label = format!("{value:.decimals$}");
+    // Avoid "-0"
+    match label.strip_prefix('-') {
+        Some(rest) if rest.chars().all(|c| c == '0' || c == '.') => rest.into(),
+        _ => label,
+    }
+}
+
+/// Appends `value` rounded to hundredths (which is precise enough for pixels), to keep the path short.
+fn push_number(path: &mut String, value: f64) {
+    let _ = write!(path, "{}", (value * 100.0).round() / 100.0);
+}
+
+fn push_point(path: &mut String, command: char, x: f64, y: f64) {
+    path.push(command);
+    push_number(path, x);
+    path.push(',');
+    push_number(path, y);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum SeriesKind {
+    Line,
+    /// Bars from `y = 0` (or the nearest end of the domain) to the values, centered around the x values.
+    Bar {
+        width: f64,
+    },
+    Scatter {
+        radius: f64,
+    },
+}
+
+/// A data series drawn as a single `<path>`, see [`line_series`], [`bar_series`] and [`scatter_series`].
+///
+/// Its `fill` and `stroke` can be set like for other paths, by default it's filled with black (like any SVG path).
+pub struct Series {
+    kind: SeriesKind,
+    data: Rc<[Point]>,
+    x: LinearScale,
+    y: LinearScale,
+}
+
+/// The points of `data` connected by straight lines, non-finite values interrupt the line.
+pub fn line_series(data: &Rc<[Point]>, x: LinearScale, y: LinearScale) -> Series {
+    Series {
+        kind: SeriesKind::Line,
+        data: Rc::clone(data),
+        x,
+        y,
+    }
+}
+
+/// A bar with the width `width` (in the range of `x`) for each point of `data`.
+pub fn bar_series(data: &Rc<[Point]>, x: LinearScale, y: LinearScale, width: f64) -> Series {
+    Series {
+        kind: SeriesKind::Bar { width },
+        data: Rc::clone(data),
+        x,
+        y,
+    }
+}
+
+/// A circle with the radius `radius` (in pixels) for each point of `data`.
+pub fn scatter_series(data: &Rc<[Point]>, x: LinearScale, y: LinearScale, radius: f64) -> Series {
+    Series {
+        kind: SeriesKind::Scatter { radius },
+        data: Rc::clone(data),
+        x,
+        y,
+    }
+}
+
+impl Series {
+    fn path(&self) -> String {
+        let mut path = String::new();
+        let points = self.data.iter().map(|p| (self.x.map(p.x), self.y.map(p.y)));
+        match self.kind {
+            SeriesKind::Line => {
+                let mut command = 'M';
+                for (x, y) in points {
+                    if x.is_finite() && y.is_finite() {
+                        push_point(&mut path, command, x, y);
+                        command = 'L';
+                    } else {
+                        command = 'M';
+                    }
+                }
+            }
+            SeriesKind::Bar { width } => {
+                let (d0, d1) = self.y.domain;
+                let base = self.y.map(0f64.clamp(d0.min(d1), d0.max(d1)));
+                for (x, y) in points.filter(|(x, y)| x.is_finite() && y.is_finite()) {
+                    push_point(&mut path, 'M', x - width / 2.0, base);
+                    path.push('V');
+                    push_number(&mut path, y);
+                    path.push('h');
+                    push_number(&mut path, width);
+                    path.push('V');
+                    push_number(&mut path, base);
+                    path.push('Z');
+                }
+            }
+            SeriesKind::Scatter { radius } => {
+                for (x, y) in points.filter(|(x, y)| x.is_finite() && y.is_finite()) {
+                    // A circle as two arcs
+                    push_point(&mut path, 'M', x - radius, y);
+                    for dx in [2.0 * radius, -2.0 * radius] {
+                        path.push('a');
+                        push_number(&mut path, radius);
+                        path.push(',');
+                        push_number(&mut path, radius);
+                        path.push_str(",0,1,0,");
+                        push_number(&mut path, dx);
+                        path.push_str(",0");
+                    }
+                }
+            }
+        }
+        path
+    }
+
+    fn changed(&self, prev: &Self) -> bool {
+        !Rc::ptr_eq(&self.data, &prev.data)
+            || self.kind != prev.kind
+            || self.x != prev.x
+            || self.y != prev.y
+    }
+}
+
+macro_rules! generate_dom_interface_impl {
+    ($dom_interface:ident, ($ty_name:ident)) => {
+        impl<T, A> $crate::interfaces::$dom_interface<T, A> for $ty_name {}
+    };
+}
+
+generate_dom_interface_impl!(SvgPathElement, (Series));
+crate::interfaces::for_all_svg_path_element_ancestors!(generate_dom_interface_impl, (Series));
+
+impl ViewMarker for Series {}
+impl Sealed for Series {}
+
+impl<T, A> View<T, A> for Series {
+    type State = ElementProps;
+    type Element = web_sys::Element;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (el, props) = cx.build_element(SVG_NS, "path");
+        // Not a managed attribute, so that the (possibly long) path isn't compared on every rebuild
+        cx.set_attribute(&el, "d", Some(self.path().into()));
+        (Id::next(), props, el)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        _id: &mut Id,
+        props: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let mut changed = cx.rebuild_element(element, props);
+        if self.changed(prev) {
+            cx.set_attribute(element, "d", Some(self.path().into()));
+            changed |= ChangeFlags::ATTRS;
+        }
+        changed
+    }
+
+    fn message(
+        &self,
+        _id_path: &[Id],
+        _state: &mut Self::State,
+        message: Box<dyn std::any::Any>,
+        _app_state: &mut T,
+    ) -> MessageResult<A> {
+        MessageResult::Stale(message)
+    }
+}
+
+/// On which side of the chart an [`axis`] is, which determines the direction of the ticks and the position of the labels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AxisSide {
+    Top,
+    Right,
+    Bottom,
+    Left,
+}
+
+/// An axis drawn as a `<g>` with a `<path>` (the domain line and tick marks) and `<text>` labels, see [`axis`].
+pub struct Axis {
+    scale: LinearScale,
+    side: AxisSide,
+    tick_count: usize,
+}
+
+/// The axis of `scale` at the origin, which can be moved e.g. via the `transform` attribute.
+///
+/// Horizontal axes (`Top` and `Bottom`) use the range of `scale` as x coordinates, vertical axes as y coordinates.
+/// The lines and labels use `currentColor`, so they can be styled via `color`.
+pub fn axis(scale: LinearScale, side: AxisSide) -> Axis {
+    Axis {
+        scale,
+        side,
+        tick_count: 10,
+    }
+}
+
+const TICK_SIZE: f64 = 6.0;
+const LABEL_OFFSET: f64 = 9.0;
+
+impl Axis {
+    /// About `count` ticks (10 by default), see [`LinearScale::ticks`].
+    pub fn tick_count(mut self, count: usize) -> Self {
+        self.tick_count = count;
+        self
+    }
+
+    /// Replaces the children of `g` with the domain line, the tick marks and the labels.
+    fn build_children(&self, cx: &mut Cx, g: &web_sys::Element) {
+        cx.set_text(g, None);
+        let horizontal = matches!(self.side, AxisSide::Top | AxisSide::Bottom);
+        let direction = match self.side {
+            AxisSide::Top | AxisSide::Left => -1.0,
+            AxisSide::Bottom | AxisSide::Right => 1.0,
+        };
+        let ticks = self.scale.ticks(self.tick_count);
+        let step = match ticks.as_slice() {
+            [first, second, ..] => second - first,
+            _ => 1.0,
+        };
+        let (r0, r1) = self.scale.range;
+
+        let mut path = String::new();
+        if horizontal {
+            push_point(&mut path, 'M', r0, 0.0);
+            path.push('H');
+            push_number(&mut path, r1);
+        } else {
+            push_point(&mut path, 'M', 0.0, r0);
+            path.push('V');
+            push_number(&mut path, r1);
+        }
+        for tick in &ticks {
+            let pos = self.scale.map(*tick);
+            if horizontal {
+                push_point(&mut path, 'M', pos, 0.0);
+                path.push('v');
+            } else {
+                push_point(&mut path, 'M', 0.0, pos);
+                path.push('h');
+            }
+            push_number(&mut path, direction * TICK_SIZE);
+        }
+        self.create_child(
+            cx,
+            g,
+            "path",
+            &[("d", &path), ("fill", "none"), ("stroke", "currentColor")],
+        );
+
+        let (anchor, baseline) = match self.side {
+            AxisSide::Top => ("middle", "auto"),
+            AxisSide::Right => ("start", "middle"),
+            AxisSide::Bottom => ("middle", "hanging"),
+            AxisSide::Left => ("end", "middle"),
+        };
+        let offset = (direction * LABEL_OFFSET).to_string();
+        for tick in ticks {
+            let pos = self.scale.map(tick).to_string();
+            let (x, y) = if horizontal {
+                (pos.as_str(), offset.as_str())
+            } else {
+                (offset.as_str(), pos.as_str())
+            };
+            let label = self.create_child(
+                cx,
+                g,
+                "text",
+                &[
+                    ("x", x),
+                    ("y", y),
+                    ("text-anchor", anchor),
+                    ("dominant-baseline", baseline),
+                ],
+            );
+            cx.set_text(&label, Some(format_tick(tick, step).into()));
+        }
+    }
+
+    fn create_child(
+        &self,
+        cx: &mut Cx,
+        g: &web_sys::Element,
+        name: &str,
+        attrs: &[(&'static str, &str)],
+    ) -> web_sys::Element {
+        let child = cx.create_element(SVG_NS, name);
+        for &(name, value) in attrs {
+            cx.set_attribute(&child, name, Some(value.to_owned().into()));
+        }
+        cx.insert_node(g, &child, None);
+        child
+    }
+}
+
+generate_dom_interface_impl!(SvggElement, (Axis));
+crate::interfaces::for_all_svgg_element_ancestors!(generate_dom_interface_impl, (Axis));
+
+impl ViewMarker for Axis {}
+impl Sealed for Axis {}
+
+impl<T, A> View<T, A> for Axis {
+    type State = ElementProps;
+    type Element = web_sys::Element;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (el, props) = cx.build_element(SVG_NS, "g");
+        // The labels are inheriting it
+        cx.set_attribute(&el, "fill", Some("currentColor".into()));
+        self.build_children(cx, &el);
+        (Id::next(), props, el)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        _id: &mut Id,
+        props: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let mut changed = cx.rebuild_element(element, props);
+        if self.scale != prev.scale || self.side != prev.side || self.tick_count != prev.tick_count
+        {
+            self.build_children(cx, element);
+            changed |= ChangeFlags::OTHER_CHANGE;
+        }
+        changed
+    }
+
+    fn message(
+        &self,
+        _id_path: &[Id],
+        _state: &mut Self::State,
+        message: Box<dyn std::any::Any>,
+        _app_state: &mut T,
+    ) -> MessageResult<A> {
+        MessageResult::Stale(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn linear_scale_maps_and_inverts() {
+        let scale = LinearScale::new((0.0, 10.0), (200.0, 0.0));
+        assert_eq!(scale.map(0.0), 200.0);
+        assert_eq!(scale.map(2.5), 150.0);
+        assert_eq!(scale.invert(150.0), 2.5);
+        let fitted = LinearScale::fit([3.0, f64::NAN, -1.0, 7.0], (0.0, 1.0));
+        assert_eq!(fitted.domain, (-1.0, 7.0));
+    }
+
+    #[test]
+    fn ticks_are_round_and_nice_extends_the_domain() {
+        let scale = LinearScale::new((0.3, 9.7), (0.0, 100.0));
+        assert_eq!(scale.ticks(5), vec![2.0, 4.0, 6.0, 8.0]);
+        assert_eq!(scale.nice(5).domain, (0.0, 10.0));
+        let ticks = LinearScale::new((0.0, 0.5), (0.0, 1.0)).ticks(5);
+        let labels: Vec<_> = ticks.iter().map(|t| format_tick(*t, 0.1)).collect();
+        assert_eq!(labels, ["0.0", "0.1", "0.2", "0.3", "0.4", "0.5"]);
+        assert_eq!(format_tick(-0.0001, 1.0), "0");
+    }
+
+    #[test]
+    fn series_paths() {
+        let data: Rc<[Point]> = vec![
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 2.0),
+            Point::new(2.0, f64::NAN),
+            Point::new(3.0, 1.0),
+        ]
+        .into();
+        let x = LinearScale::new((0.0, 3.0), (0.0, 30.0));
+        let y = LinearScale::new((0.0, 2.0), (20.0, 0.0));
+        assert_eq!(line_series(&data, x, y).path(), "M0,20L10,0M30,10");
+        let bars: Rc<[Point]> = vec![Point::new(1.0, 1.0)].into();
+        assert_eq!(bar_series(&bars, x, y, 4.0).path(), "M8,20V10h4V20Z");
+        assert_eq!(
+            scatter_series(&bars, x, y, 2.0).path(),
+            "M8,10a2,2,0,1,0,4,0a2,2,0,1,0,-4,0"
+        );
+    }
+}
@@ -0,0 +1,119 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::marker::PhantomData;
+
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    interfaces::{sealed::Sealed, Element},
+    ChangeFlags, Cx, View, ViewMarker,
+};
+
+type CowStr = std::borrow::Cow<'static, str>;
+
+/// Adds a class to the element when a condition holds, see [`Element::class_if`].
+pub struct ClassIf<E, T, A> {
+    pub(crate) element: E,
+    pub(crate) class: CowStr,
+    pub(crate) condition: bool,
+    pub(crate) phantom: PhantomData<fn() -> (T, A)>,
+}
+
+impl<E, T, A> ViewMarker for ClassIf<E, T, A> {}
+impl<E, T, A> Sealed for ClassIf<E, T, A> {}
+
+impl<E: Element<T, A>, T, A> View<T, A> for ClassIf<E, T, A> {
+    type State = E::State;
+    type Element = E::Element;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        if self.condition {
+            cx.add_class_to_element(&self.class);
+        }
+        self.element.build(cx)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        if self.condition {
+            cx.add_class_to_element(&self.class);
+        }
+        self.element.rebuild(cx, &prev.element, id, state, element)
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Box<dyn std::any::Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        self.element.message(id_path, state, message, app_state)
+    }
+}
+
+crate::interfaces::impl_dom_interfaces_for_ty!(Element, ClassIf);
+
+/// Adds (or removes) a set of classes, see [`Element::classes`] and [`Element::remove_class`].
+pub struct Classes<E, T, A> {
+    pub(crate) element: E,
+    pub(crate) classes: Vec<CowStr>,
+    pub(crate) present: bool,
+    pub(crate) phantom: PhantomData<fn() -> (T, A)>,
+}
+
+impl<E, T, A> Classes<E, T, A> {
+    fn add_to_element(&self, cx: &mut Cx) {
+        for class in &self.classes {
+            if self.present {
+                cx.add_class_to_element(class);
+            } else {
+                cx.remove_class_from_element(class);
+            }
+        }
+    }
+}
+
+impl<E, T, A> ViewMarker for Classes<E, T, A> {}
+impl<E, T, A> Sealed for Classes<E, T, A> {}
+
+impl<E: Element<T, A>, T, A> View<T, A> for Classes<E, T, A> {
+    type State = E::State;
+    type Element = E::Element;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        self.add_to_element(cx);
+        self.element.build(cx)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        self.add_to_element(cx);
+        self.element.rebuild(cx, &prev.element, id, state, element)
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Box<dyn std::any::Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        self.element.message(id_path, state, message, app_state)
+    }
+}
+
+crate::interfaces::impl_dom_interfaces_for_ty!(Element, Classes);
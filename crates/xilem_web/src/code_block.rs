@@ -0,0 +1,609 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A code block with a small hand-written lexer (behind the `code_block` feature), which is only a fallback for a real highlighter.
+
+use std::{any::Any, borrow::Cow};
+
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    context::{ChangeFlags, Cx, ElementProps},
+    interfaces::sealed::Sealed,
+    view::{View, ViewMarker},
+    HTML_NS,
+};
+
+type CowStr = Cow<'static, str>;
+
+/// The lexical rules of a language, which are used by [`code_block`] to highlight keywords, strings, comments and numbers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CodeLanguage {
+    pub keywords: &'static [&'static str],
+    pub line_comment: Option<&'static str>,
+    pub block_comment: Option<(&'static str, &'static str)>,
+    /// The characters which start and end a string.
+    pub quotes: &'static str,
+    /// Whether strings can span multiple lines.
+    pub multiline_strings: bool,
+    /// Whether a `'` is only a quote when it's a char literal (e.g. `'a'`), and not a lifetime (like `'a`) as in Rust.
+    pub lifetimes: bool,
+}
+
+impl CodeLanguage {
+    pub const PLAIN: CodeLanguage = CodeLanguage {
+        keywords: &[],
+        line_comment: None,
+        block_comment: None,
+        quotes: "",
+        multiline_strings: false,
+        lifetimes: false,
+    };
+
+    pub const RUST: CodeLanguage = CodeLanguage {
+        keywords: &[
+            "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+            "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod",
+            "move", "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super",
+            "trait", "true", "type", "unsafe", "use", "where", "while",
+        ],
+        line_comment: Some("//"),
+        block_comment: Some(("/*", "*/")),
+        quotes: "\"'",
+        multiline_strings: true,
+        lifetimes: true,
+    };
+
+    pub const JAVASCRIPT: CodeLanguage = CodeLanguage {
+        keywords: &[
+            "async",
+            "await",
+            "break",
+            "case",
+            "catch",
+            "class",
+            "const",
+            "continue",
+            "default",
+            "delete",
+            "do",
+            "else",
+            "export",
+            "extends",
+            "false",
+            "finally",
+            "for",
+            "from",
+            "function",
+            "if",
+            "import",
+            "in",
+            "instanceof",
+            "interface",
+            "let",
+            "new",
+            "null",
+            "of",
+            "return",
+            "static",
+            "super",
+            "switch",
+            "this",
+            "throw",
+            "true",
+            "try",
+            "type",
+            "typeof",
+            "undefined",
+            "var",
+            "void",
+            "while",
+            "yield",
+        ],
+        line_comment: Some("//"),
+        block_comment: Some(("/*", "*/")),
+        quotes: "\"'`",
+        multiline_strings: false,
+        lifetimes: false,
+    };
+
+    pub const PYTHON: CodeLanguage = CodeLanguage {
+        keywords: &[
+            "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del",
+            "elif", "else", "except", "False", "finally", "for", "from", "global", "if", "import",
+            "in", "is", "lambda", "None", "nonlocal", "not", "or", "pass", "raise", "return",
+            "True", "try", "while", "with", "yield",
+        ],
+        line_comment: Some("#"),
+        block_comment: None,
+        quotes: "\"'",
+        // Triple quoted strings are lexed as empty string followed by a (multi-line) string
+        multiline_strings: true,
+        lifetimes: false,
+    };
+
+    pub const JSON: CodeLanguage = CodeLanguage {
+        keywords: &["false", "null", "true"],
+        line_comment: None,
+        block_comment: None,
+        quotes: "\"",
+        multiline_strings: false,
+        lifetimes: false,
+    };
+
+    /// The language with the name or file extension `name` (e.g. `rust` or `rs`), or [`CodeLanguage::PLAIN`] for unknown languages.
+    pub fn from_name(name: &str) -> CodeLanguage {
+        match name.to_ascii_lowercase().as_str() {
+            "rust" | "rs" => CodeLanguage::RUST,
+            "javascript" | "js" | "jsx" | "typescript" | "ts" | "tsx" => CodeLanguage::JAVASCRIPT,
+            "python" | "py" => CodeLanguage::PYTHON,
+            "json" => CodeLanguage::JSON,
+            _ => CodeLanguage::PLAIN,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum TokenKind {
+    Keyword,
+    String,
+    Comment,
+    Number,
+}
+
+impl TokenKind {
+    fn class(self) -> &'static str {
+        match self {
+            TokenKind::Keyword => "hl-keyword",
+            TokenKind::String => "hl-string",
+            TokenKind::Comment => "hl-comment",
+            TokenKind::Number => "hl-number",
+        }
+    }
+}
+
+/// The state of the lexer at the start or end of a line.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum LexState {
+    #[default]
+    Normal,
+    BlockComment,
+    String(char),
+}
+
+/// A highlighted line, as text with an optional token kind.
+type Tokens<'s> = Vec<(Option<TokenKind>, &'s str)>;
+
+fn push_token<'s>(
+    tokens: &mut Tokens<'s>,
+    line: &'s str,
+    kind: Option<TokenKind>,
+    start: usize,
+    end: usize,
+) {
+    if start == end {
+        return;
+    }
+    // Adjacent tokens of the same kind are merged, so that e.g. plain text is a single text node
+    if let Some((last_kind, last)) = tokens.last_mut() {
+        if *last_kind == kind {
+            let last_start = last.as_ptr() as usize - line.as_ptr() as usize;
+            *last = &line[last_start..end];
+            return;
+        }
+    }
+    tokens.push((kind, &line[start..end]));
+}
+
+/// The end of the string starting at `start` (after the opening quote), i.e. after the closing quote, if it's in this line.
+fn string_end(line: &str, start: usize, quote: char) -> Option<usize> {
+    let mut chars = line[start..].char_indices();
+    while let Some((i, c)) = chars.next() {
+        if c == '\\' {
+            chars.next();
+        } else if c == quote {
+            return Some(start + i + c.len_utf8());
+        }
+    }
+    None
+}
+
+/// Splits `line` into tokens, `state` is the state at the end of the previous line, the state at the end of `line` is returned.
+pub(crate) fn highlight_line<'s>(
+    language: &CodeLanguage,
+    line: &'s str,
+    mut state: LexState,
+) -> (Tokens<'s>, LexState) {
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    while pos < line.len() {
+        match state {
+            LexState::BlockComment => {
+                let (_, end_marker) = language.block_comment.unwrap_or(("", ""));
+                let end = match line[pos..].find(end_marker) {
+                    Some(i) if !end_marker.is_empty() => {
+                        state = LexState::Normal;
+                        pos + i + end_marker.len()
+                    }
+                    _ => line.len(),
+                };
+                push_token(&mut tokens, line, Some(TokenKind::Comment), pos, end);
+                pos = end;
+                continue;
+            }
+            LexState::String(quote) => {
+                let end = match string_end(line, pos, quote) {
+                    Some(end) => {
+                        state = LexState::Normal;
+                        end
+                    }
+                    None => {
+                        if !language.multiline_strings {
+                            state = LexState::Normal;
+                        }
+                        line.len()
+                    }
+                };
+                push_token(&mut tokens, line, Some(TokenKind::String), pos, end);
+                pos = end;
+                continue;
+            }
+            LexState::Normal => {}
+        }
+        let rest = &line[pos..];
+        if language
+            .line_comment
+            .is_some_and(|marker| rest.starts_with(marker))
+        {
+            push_token(&mut tokens, line, Some(TokenKind::Comment), pos, line.len());
+            break;
+        }
+        if let Some((start_marker, _)) = language.block_comment {
+            if rest.starts_with(start_marker) {
+                push_token(
+                    &mut tokens,
+                    line,
+                    Some(TokenKind::Comment),
+                    pos,
+                    pos + start_marker.len(),
+                );
+                pos += start_marker.len();
+                state = LexState::BlockComment;
+                continue;
+            }
+        }
+        let c = rest.chars().next().unwrap_throw();
+        if language.quotes.contains(c) {
+            let is_lifetime = c == '\''
+                && language.lifetimes
+                && !matches!(rest.chars().nth(1), Some('\\'))
+                && rest.chars().nth(2) != Some('\'');
+            if !is_lifetime {
+                push_token(&mut tokens, line, Some(TokenKind::String), pos, pos + 1);
+                pos += 1;
+                state = LexState::String(c);
+                continue;
+            }
+        }
+        let word_end = |is_word: fn(char) -> bool| {
+            rest.char_indices()
+                .find(|(_, c)| !is_word(*c))
+                .map_or(line.len(), |(i, _)| pos + i)
+        };
+        if c.is_ascii_digit() {
+            let end = word_end(|c| c.is_alphanumeric() || c == '_' || c == '.');
+            push_token(&mut tokens, line, Some(TokenKind::Number), pos, end);
+            pos = end;
+        } else if c.is_alphabetic() || c == '_' || c == '$' {
+            let end = word_end(|c| c.is_alphanumeric() || c == '_' || c == '$');
+            let kind = language
+                .keywords
+                .contains(&&line[pos..end])
+                .then_some(TokenKind::Keyword);
+            push_token(&mut tokens, line, kind, pos, end);
+            pos = end;
+        } else {
+            push_token(&mut tokens, line, None, pos, pos + c.len_utf8());
+            pos += c.len_utf8();
+        }
+    }
+    // E.g. a quote at the end of the line
+    if matches!(state, LexState::String(_)) && !language.multiline_strings {
+        state = LexState::Normal;
+    }
+    (tokens, state)
+}
+
+/// The number of lines at the start and at the end, which are equal in `prev` and `next` (and don't overlap).
+pub(crate) fn unchanged_lines<P: AsRef<str>>(prev: &[P], next: &[&str]) -> (usize, usize) {
+    let prefix = prev
+        .iter()
+        .zip(next)
+        .take_while(|(prev, next)| prev.as_ref() == **next)
+        .count();
+    let max_suffix = prev.len().min(next.len()) - prefix;
+    let suffix = prev
+        .iter()
+        .rev()
+        .zip(next.iter().rev())
+        .take(max_suffix)
+        .take_while(|(prev, next)| prev.as_ref() == **next)
+        .count();
+    (prefix, suffix)
+}
+
+/// A highlighted `<pre><code>` block, see [`code_block`].
+pub struct CodeBlock {
+    language: CowStr,
+    source: CowStr,
+}
+
+/// Shows `source` in a `<pre>` (with a `<code class="language-{language}">`), highlighted by the (simple) built-in lexer of the language,
+/// see [`CodeLanguage::from_name`].
+///
+/// The tokens are `<span>`s with the classes `hl-keyword`, `hl-string`, `hl-comment` and `hl-number`,
+/// and each line is a `<span class="line">`, e.g. for line numbers via CSS counters.
+///
+/// When `source` changes, only the changed lines are highlighted and rendered again (and the following lines,
+/// when e.g. the start of a block comment was inserted), so editing a line of a large file is cheap.
+///
+/// The lexer is only meant as a fallback, when a real highlighter (e.g. `syntect`, or highlight.js via a `js_component`)
+/// isn't an option. It knows the keywords, strings, comments and numbers of a few languages, but e.g. not:
+///
+/// - multi-line template literals of JavaScript, they're highlighted per line,
+/// - triple-quoted strings of Python (`"""..."""`),
+/// - raw strings of Rust (`r#"..."#`),
+/// - the keywords of TypeScript, it's highlighted as JavaScript.
+pub fn code_block(language: impl Into<CowStr>, source: impl Into<CowStr>) -> CodeBlock {
+    CodeBlock {
+        language: language.into(),
+        source: source.into(),
+    }
+}
+
+struct Line {
+    text: String,
+    /// The state of the lexer at the start of the line
+    entry: LexState,
+    exit: LexState,
+    element: web_sys::Element,
+}
+
+pub struct CodeBlockState {
+    props: ElementProps,
+    code: web_sys::Element,
+    lines: Vec<Line>,
+}
+
+/// Replaces the content of `element` with the highlighted `text`, and returns the state at the end of the line.
+fn render_line(
+    cx: &mut Cx,
+    language: &CodeLanguage,
+    element: &web_sys::Element,
+    text: &str,
+    entry: LexState,
+) -> LexState {
+    cx.set_text(element, None);
+    let (tokens, exit) = highlight_line(language, text, entry);
+    for (kind, token) in tokens {
+        let node: web_sys::Node = match kind {
+            Some(kind) => {
+                let span = cx.create_element(HTML_NS, "span");
+                cx.set_attribute(&span, "class", Some(kind.class().into()));
+                cx.set_text(&span, Some(token.to_owned().into()));
+                span.into()
+            }
+            None => cx.create_text(token).into(),
+        };
+        cx.insert_node(element, &node, None);
+    }
+    let newline = cx.create_text("\n");
+    cx.insert_node(element, &newline, None);
+    exit
+}
+
+fn create_line(
+    cx: &mut Cx,
+    language: &CodeLanguage,
+    code: &web_sys::Element,
+    before: Option<&web_sys::Node>,
+    text: &str,
+    entry: LexState,
+) -> Line {
+    let element = cx.create_element(HTML_NS, "span");
+    cx.set_attribute(&element, "class", Some("line".into()));
+    let exit = render_line(cx, language, &element, text, entry);
+    cx.insert_node(code, &element, before);
+    Line {
+        text: text.into(),
+        entry,
+        exit,
+        element,
+    }
+}
+
+impl CodeBlock {
+    /// Replaces all lines, e.g. when the language changed.
+    fn render(&self, cx: &mut Cx, state_code: &web_sys::Element) -> Vec<Line> {
+        let language = CodeLanguage::from_name(&self.language);
+        cx.set_text(state_code, None);
+        let class = format!("language-{}", self.language);
+        cx.set_attribute(state_code, "class", Some(class.into()));
+        let mut entry = LexState::Normal;
+        self.source
+            .lines()
+            .map(|text| {
+                let line = create_line(cx, &language, state_code, None, text, entry);
+                entry = line.exit;
+                line
+            })
+            .collect()
+    }
+
+    /// Replaces only the lines which changed, or which are lexed differently now.
+    fn update_lines(&self, cx: &mut Cx, state: &mut CodeBlockState) {
+        let language = CodeLanguage::from_name(&self.language);
+        let next: Vec<&str> = self.source.lines().collect();
+        let prev: Vec<&str> = state.lines.iter().map(|line| line.text.as_str()).collect();
+        let (prefix, suffix) = unchanged_lines(&prev, &next);
+
+        let removed_end = state.lines.len() - suffix;
+        for line in state.lines.drain(prefix..removed_end) {
+            cx.remove_node(&line.element);
+        }
+
+        let mut entry = match prefix {
+            0 => LexState::Normal,
+            _ => state.lines[prefix - 1].exit,
+        };
+        let before: Option<web_sys::Node> = state
+            .lines
+            .get(prefix)
+            .map(|line| line.element.clone().into());
+        let inserted: Vec<Line> = next[prefix..next.len() - suffix]
+            .iter()
+            .map(|text| {
+                let line = create_line(cx, &language, &state.code, before.as_ref(), text, entry);
+                entry = line.exit;
+                line
+            })
+            .collect();
+        let inserted_count = inserted.len();
+        state.lines.splice(prefix..prefix, inserted);
+
+        // The unchanged lines at the end are only lexed again until their state at the start is the same as before
+        for line in &mut state.lines[prefix + inserted_count..] {
+            if line.entry == entry {
+                break;
+            }
+            line.entry = entry;
+            line.exit = render_line(cx, &language, &line.element, &line.text, entry);
+            entry = line.exit;
+        }
+    }
+}
+
+macro_rules! generate_dom_interface_impl {
+    ($dom_interface:ident, ()) => {
+        impl<T, A> $crate::interfaces::$dom_interface<T, A> for CodeBlock {}
+    };
+}
+
+generate_dom_interface_impl!(HtmlPreElement, ());
+crate::interfaces::for_all_html_pre_element_ancestors!(generate_dom_interface_impl, ());
+
+impl ViewMarker for CodeBlock {}
+impl Sealed for CodeBlock {}
+
+impl<T, A> View<T, A> for CodeBlock {
+    type State = CodeBlockState;
+    type Element = web_sys::HtmlPreElement;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (el, props) = cx.build_element(HTML_NS, "pre");
+        let code = cx.create_element(HTML_NS, "code");
+        cx.insert_node(&el, &code, None);
+        let lines = self.render(cx, &code);
+        let state = CodeBlockState { props, code, lines };
+        (Id::next(), state, el.unchecked_into())
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        _id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let mut changed = cx.rebuild_element(element, &mut state.props);
+        if self.language != prev.language {
+            state.lines = self.render(cx, &state.code);
+            changed |= ChangeFlags::OTHER_CHANGE;
+        } else if self.source != prev.source {
+            self.update_lines(cx, state);
+            changed |= ChangeFlags::OTHER_CHANGE;
+        }
+        changed
+    }
+
+    fn message(
+        &self,
+        _id_path: &[Id],
+        _state: &mut Self::State,
+        message: Box<dyn Any>,
+        _app_state: &mut T,
+    ) -> MessageResult<A> {
+        MessageResult::Stale(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds<'s>(tokens: &[(Option<TokenKind>, &'s str)]) -> Vec<(Option<&'static str>, &'s str)> {
+        tokens
+            .iter()
+            .map(|(kind, text)| (kind.map(TokenKind::class), *text))
+            .collect()
+    }
+
+    #[test]
+    fn highlights_rust() {
+        let (tokens, state) = highlight_line(
+            &CodeLanguage::RUST,
+            "fn f<'a>(x: &'a str) -> char { 'x' } // 1",
+            LexState::Normal,
+        );
+        assert_eq!(
+            kinds(&tokens),
+            [
+                (Some("hl-keyword"), "fn"),
+                (None, " f<'a>(x: &'a str) -> char { "),
+                (Some("hl-string"), "'x'"),
+                (None, " } "),
+                (Some("hl-comment"), "// 1"),
+            ]
+        );
+        assert_eq!(state, LexState::Normal);
+    }
+
+    #[test]
+    fn state_continues_over_lines() {
+        let (tokens, state) =
+            highlight_line(&CodeLanguage::RUST, "let x = 10; /* a", LexState::Normal);
+        assert_eq!(
+            kinds(&tokens),
+            [
+                (Some("hl-keyword"), "let"),
+                (None, " x = "),
+                (Some("hl-number"), "10"),
+                (None, "; "),
+                (Some("hl-comment"), "/* a"),
+            ]
+        );
+        assert_eq!(state, LexState::BlockComment);
+        let (tokens, state) = highlight_line(&CodeLanguage::RUST, "b */ \"s\\\"", state);
+        assert_eq!(
+            kinds(&tokens),
+            [
+                (Some("hl-comment"), "b */"),
+                (None, " "),
+                (Some("hl-string"), "\"s\\\""),
+            ]
+        );
+        assert_eq!(state, LexState::String('"'));
+        let (_, state) = highlight_line(&CodeLanguage::JSON, "\"unterminated", LexState::Normal);
+        assert_eq!(state, LexState::Normal);
+    }
+
+    #[test]
+    fn unchanged_lines_at_start_and_end() {
+        assert_eq!(unchanged_lines(&["a", "b", "c"], &["a", "x", "c"]), (1, 1));
+        assert_eq!(unchanged_lines(&["a", "b"], &["a", "b", "b"]), (2, 0));
+        assert_eq!(unchanged_lines(&["a", "a"], &["a"]), (1, 0));
+        assert_eq!(unchanged_lines(&["b"], &["a", "b"]), (0, 1));
+    }
+}
@@ -1,7 +1,7 @@
-use std::any::Any;
+use std::{any::Any, rc::Rc};
 
 use bitflags::bitflags;
-use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use wasm_bindgen::{intern, UnwrapThrowExt};
 use web_sys::Document;
 
 use xilem_core::{Id, IdPath};
@@ -9,36 +9,102 @@ use xilem_core::{Id, IdPath};
 use crate::{
     app::AppRunner,
     diff::{diff_kv_iterables, Diff},
+    mutations::{ApplyDomOps, DomOp, DomOps, NodeId},
+    style::StyleValue,
     vecmap::VecMap,
     view::DomNode,
-    AttributeValue, Message, Pod,
+    AttributeValue, Message, Pod, PropertyValue,
 };
 
 type CowStr = std::borrow::Cow<'static, str>;
 
-fn set_attribute(element: &web_sys::Element, name: &str, value: &str) {
-    // we have to special-case `value` because setting the value using `set_attribute`
-    // doesn't work after the value has been changed.
-    if name == "value" {
-        let element: &web_sys::HtmlInputElement = element.dyn_ref().unwrap_throw();
-        element.set_value(value);
-    } else if name == "checked" {
-        let element: &web_sys::HtmlInputElement = element.dyn_ref().unwrap_throw();
-        element.set_checked(true);
-    } else {
-        element.set_attribute(name, value).unwrap_throw();
+/// Applies the `changes` (a class, and whether it's added or removed) in order to the classes in `base`.
+///
+/// Each class is contained only once, in the order it was first added, so composed views can add the same class,
+/// and later changes win, e.g. an outer view can remove a class which was added by an inner one.
+fn merge_class_list<'a>(base: &'a str, changes: impl Iterator<Item = (&'a str, bool)>) -> String {
+    let mut classes: Vec<&str> = Vec::new();
+    let base = base.split_ascii_whitespace().map(|class| (class, true));
+    for (class_names, present) in base.chain(changes) {
+        for class in class_names.split_ascii_whitespace() {
+            let idx = classes.iter().position(|c| *c == class);
+            match (idx, present) {
+                (None, true) => classes.push(class),
+                (Some(idx), false) => {
+                    classes.remove(idx);
+                }
+                _ => {}
+            }
+        }
     }
+    classes.join(" ")
 }
 
-fn remove_attribute(element: &web_sys::Element, name: &str) {
-    // we have to special-case `checked` because setting the value using `set_attribute`
-    // doesn't work after the value has been changed.
-    if name == "checked" {
-        let element: &web_sys::HtmlInputElement = element.dyn_ref().unwrap_throw();
-        element.set_checked(false);
-    } else {
-        element.remove_attribute(name).unwrap_throw();
+/// Above this number of changed class names, the whole `class` attribute is replaced instead.
+const MAX_CLASS_LIST_DELTA: usize = 4;
+
+/// The class names which are removed and added, when the `class` attribute changes from `old` to `new`.
+fn class_delta<'a>(old: &'a str, new: &'a str) -> (Vec<&'a str>, Vec<&'a str>) {
+    let removed = old
+        .split_ascii_whitespace()
+        .filter(|class| !new.split_ascii_whitespace().any(|c| c == *class))
+        .collect();
+    let added = new
+        .split_ascii_whitespace()
+        .filter(|class| !old.split_ascii_whitespace().any(|c| c == *class))
+        .collect();
+    (removed, added)
+}
+
+/// The `class` attribute, when the managed classes change from `old` to `new`, and the attribute currently is `current`.
+///
+/// Classes in `current` which weren't managed (i.e. not in `old`), like the ones added by other JS, are kept after the `new` ones.
+fn replace_managed_classes(current: &str, old: &str, new: &str) -> String {
+    let mut classes: Vec<&str> = new.split_ascii_whitespace().collect();
+    for class in current.split_ascii_whitespace() {
+        if !old.split_ascii_whitespace().any(|c| c == class) && !classes.contains(&class) {
+            classes.push(class);
+        }
     }
+    classes.join(" ")
+}
+
+/// Updates the `class` attribute, for a few toggled classes via `classList`,
+/// which avoids reparsing the whole class string and keeps classes added outside of xilem (e.g. by transitions).
+fn update_class(ops: &mut DomOps, element: &web_sys::Element, old: &str, new: &str) {
+    let id = ops.node_id(element);
+    let (removed, added) = class_delta(old, new);
+    if removed.len() + added.len() > MAX_CLASS_LIST_DELTA {
+        let current = element.get_attribute("class").unwrap_or_default();
+        ops.push(DomOp::SetAttr {
+            id,
+            name: "class".into(),
+            value: Some(replace_managed_classes(&current, old, new).into()),
+        });
+        return;
+    }
+    for (classes, present) in [(removed, false), (added, true)] {
+        for class in classes {
+            ops.push(DomOp::ToggleClass {
+                id,
+                class: class.to_owned().into(),
+                present,
+            });
+        }
+    }
+}
+
+/// The attributes, properties and inline styles of an element, retained for diffing.
+///
+/// They're only allocated when the element has any modifiers, which keeps the state of plain elements at a single pointer.
+#[derive(Default)]
+pub struct ElementProps(Option<Box<Modifiers>>);
+
+#[derive(Default)]
+struct Modifiers {
+    attributes: VecMap<CowStr, AttributeValue>,
+    properties: VecMap<CowStr, PropertyValue>,
+    styles: VecMap<CowStr, StyleValue>,
 }
 
 // Note: xilem has derive Clone here. Not sure.
@@ -47,7 +113,25 @@ pub struct Cx {
     document: Document,
     // TODO There's likely a cleaner more robust way to propagate the attributes to an element
     pub(crate) current_element_attributes: VecMap<CowStr, AttributeValue>,
+    pub(crate) current_element_properties: VecMap<CowStr, PropertyValue>,
+    pub(crate) current_element_styles: VecMap<CowStr, StyleValue>,
+    /// The classes added (`true`) or removed (`false`) via e.g. [`class_if`](crate::interfaces::Element::class_if)
+    /// and [`classes`](crate::interfaces::Element::classes), which are merged into the `class` attribute.
+    current_element_classes: Vec<(CowStr, bool)>,
+    /// The values provided via [`provide_context`](crate::provide_context) by the ancestors of the current view,
+    /// and whether they changed in the current rebuild.
+    contexts: Vec<(Rc<dyn Any>, bool)>,
+    /// The DOM mutations since the last call of `take_dom_mutations`.
+    #[cfg(feature = "profiling")]
+    dom_mutations: crate::profiler::DomMutations,
+    /// The views named with [`debug_id`](crate::interfaces::Element::debug_id) which were (re)built since the last call of `take_debug_records`.
+    #[cfg(feature = "devtools")]
+    debug_records: Vec<crate::devtools::DebugRecord>,
+    #[cfg(feature = "devtools")]
+    debug_depth: usize,
     app_ref: Option<Box<dyn AppRunner>>,
+    /// The DOM mutations of the current (re)build, which are applied by [`Cx::flush_dom_ops`].
+    dom_ops: DomOps,
 }
 
 pub struct MessageThunk {
@@ -59,7 +143,27 @@ bitflags! {
     #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
     pub struct ChangeFlags: u32 {
         const STRUCTURE = 1;
+        /// Any change other than the structure, it's also contained in each of the more specific flags below.
         const OTHER_CHANGE = 2;
+        /// Attributes (other than `class`) or properties of an element changed.
+        const ATTRS = 4 | Self::OTHER_CHANGE.bits();
+        /// Inline styles of an element changed.
+        const STYLE = 8 | Self::OTHER_CHANGE.bits();
+        /// The classes of an element changed.
+        const CLASS = 16 | Self::OTHER_CHANGE.bits();
+        /// The content of a text node changed.
+        const TEXT = 32 | Self::OTHER_CHANGE.bits();
+        /// Event listeners were (re)attached.
+        const LISTENERS = 64 | Self::OTHER_CHANGE.bits();
+    }
+}
+
+/// The classes are an attribute in the DOM, but have their own flag.
+fn attribute_change_flag(name: &str) -> ChangeFlags {
+    if name == "class" {
+        ChangeFlags::CLASS
+    } else {
+        ChangeFlags::ATTRS
     }
 }
 
@@ -70,6 +174,17 @@ impl Cx {
             document: crate::document(),
             app_ref: None,
             current_element_attributes: Default::default(),
+            current_element_properties: Default::default(),
+            current_element_styles: Default::default(),
+            current_element_classes: Vec::new(),
+            contexts: Vec::new(),
+            #[cfg(feature = "profiling")]
+            dom_mutations: Default::default(),
+            #[cfg(feature = "devtools")]
+            debug_records: Vec::new(),
+            #[cfg(feature = "devtools")]
+            debug_depth: 0,
+            dom_ops: DomOps::default(),
         }
     }
 
@@ -96,6 +211,53 @@ impl Cx {
         result
     }
 
+    /// Run some logic with a context value provided to the views built within `f`, see [`provide_context`](crate::provide_context).
+    ///
+    /// When the value `changed`, memoized views within `f` are rebuilt, as they may depend on it, see [`Cx::memoize_invalidated`].
+    pub(crate) fn with_context<T, F: FnOnce(&mut Cx) -> T>(
+        &mut self,
+        value: Rc<dyn Any>,
+        changed: bool,
+        f: F,
+    ) -> T {
+        self.contexts.push((value, changed));
+        let result = f(self);
+        self.contexts.pop();
+        result
+    }
+
+    /// The value of type `C` provided by the nearest ancestor via [`provide_context`](crate::provide_context).
+    ///
+    /// This is only available while building or rebuilding views, not while handling messages.
+    pub fn context<C: 'static>(&self) -> Option<&C> {
+        self.contexts
+            .iter()
+            .rev()
+            .find_map(|(value, _)| value.downcast_ref())
+    }
+
+    /// Whether memoized views (e.g. [`memoize`](crate::memoize)) have to be rebuilt regardless of their data,
+    /// because a context value provided by an ancestor changed (e.g. the catalog of a [`locale_provider`](crate::i18n::locale_provider)).
+    pub fn memoize_invalidated(&self) -> bool {
+        self.contexts.iter().any(|(_, changed)| *changed)
+    }
+
+    /// The current nesting of the id path and the provided contexts, see [`Cx::recover_from_panic`].
+    pub(crate) fn depth(&self) -> (usize, usize) {
+        (self.id_path.len(), self.contexts.len())
+    }
+
+    /// Restores the id path and the provided contexts to `depth`, and discards the attributes, properties and styles of an element,
+    /// which wasn't (re)built because of a panic.
+    pub(crate) fn recover_from_panic(&mut self, (id_depth, context_depth): (usize, usize)) {
+        self.id_path.truncate(id_depth);
+        self.contexts.truncate(context_depth);
+        self.current_element_attributes.clear();
+        self.current_element_properties.clear();
+        self.current_element_styles.clear();
+        self.current_element_classes.clear();
+    }
+
     /// Allocate a new id and run logic with the new id added to the id path.
     ///
     /// Also an ergonomic helper.
@@ -141,25 +303,162 @@ impl Cx {
         &self.document
     }
 
-    pub(crate) fn build_element(
-        &mut self,
-        ns: &str,
-        name: &str,
-    ) -> (web_sys::Element, VecMap<CowStr, AttributeValue>) {
+    /// The id of `node` in the [`DomOp`]s.
+    pub(crate) fn node_id(&mut self, node: &web_sys::Node) -> NodeId {
+        self.dom_ops.node_id(node)
+    }
+
+    /// Records a DOM mutation, it's applied by [`Cx::flush_dom_ops`] when the (re)build is done.
+    pub(crate) fn push_dom_op(&mut self, op: DomOp) {
+        self.dom_ops.push(op);
+    }
+
+    /// Applies the DOM mutations of the (re)build in one pass, and passes them on to the applier of the app,
+    /// see [`App::dom_applier`](crate::App::dom_applier).
+    pub(crate) fn flush_dom_ops(&mut self) {
+        self.dom_ops.flush();
+    }
+
+    pub(crate) fn set_dom_applier(&mut self, applier: impl ApplyDomOps + 'static) {
+        self.dom_ops.set_applier(applier);
+    }
+
+    /// Marks `root` as the element the app is mounted into, see [`NodeId::ROOT`].
+    pub(crate) fn set_root_node(&mut self, root: &web_sys::Node) {
+        self.dom_ops.set_root(root);
+    }
+
+    pub(crate) fn create_element(&mut self, ns: &'static str, name: &str) -> web_sys::Element {
         let el = self
             .document
-            .create_element_ns(Some(ns), name)
+            .create_element_ns(Some(intern(ns)), intern(name))
             .expect("could not create element");
-        let attributes = self.apply_attributes(&el);
-        (el, attributes)
+        let id = self.dom_ops.new_node_id(&el);
+        self.dom_ops.push(DomOp::CreateElement {
+            id,
+            ns: ns.into(),
+            tag: name.to_owned().into(),
+        });
+        el
+    }
+
+    pub(crate) fn create_text(&mut self, text: &str) -> web_sys::Text {
+        let node = self.document.create_text_node(text);
+        let id = self.dom_ops.new_node_id(&node);
+        self.dom_ops.push(DomOp::CreateText {
+            id,
+            text: text.to_owned().into(),
+        });
+        node
+    }
+
+    pub(crate) fn create_comment(&mut self, text: &str) -> web_sys::Comment {
+        let node = self.document.create_comment(text);
+        let id = self.dom_ops.new_node_id(&node);
+        self.dom_ops.push(DomOp::CreateComment {
+            id,
+            text: text.to_owned().into(),
+        });
+        node
+    }
+
+    /// Inserts `child` in front of `before`, or at the end of `parent`, see [`DomOp::Insert`].
+    pub(crate) fn insert_node(
+        &mut self,
+        parent: &web_sys::Node,
+        child: &web_sys::Node,
+        before: Option<&web_sys::Node>,
+    ) {
+        let op = DomOp::Insert {
+            parent: self.dom_ops.node_id(parent),
+            child: self.dom_ops.node_id(child),
+            before: before.map(|before| self.dom_ops.node_id(before)),
+        };
+        self.dom_ops.push(op);
+    }
+
+    /// Removes `node` from its parent, see [`DomOp::Remove`].
+    pub(crate) fn remove_node(&mut self, node: &web_sys::Node) {
+        let id = self.dom_ops.node_id(node);
+        self.dom_ops.push(DomOp::Remove { id });
+    }
+
+    /// Replaces the child `old` of `parent` with `new`.
+    pub(crate) fn replace_node(
+        &mut self,
+        parent: &web_sys::Node,
+        old: &web_sys::Node,
+        new: &web_sys::Node,
+    ) {
+        self.insert_node(parent, new, Some(old));
+        self.remove_node(old);
+    }
+
+    /// Sets the text of a text node, or replaces the children of an element, see [`DomOp::SetText`].
+    pub(crate) fn set_text(&mut self, node: &web_sys::Node, text: Option<CowStr>) {
+        let id = self.dom_ops.node_id(node);
+        self.dom_ops.push(DomOp::SetText { id, text });
+    }
+
+    /// Sets or removes (with `None`) an attribute of an element, which isn't managed via its props, see [`DomOp::SetAttr`].
+    pub(crate) fn set_attribute(
+        &mut self,
+        element: &web_sys::Element,
+        name: impl Into<CowStr>,
+        value: Option<CowStr>,
+    ) {
+        let id = self.dom_ops.node_id(element);
+        self.dom_ops.push(DomOp::SetAttr {
+            id,
+            name: name.into(),
+            value,
+        });
+    }
+
+    pub(crate) fn build_element(
+        &mut self,
+        ns: &'static str,
+        name: &str,
+    ) -> (web_sys::Element, ElementProps) {
+        let el = self.create_element(ns, name);
+        let props = self.adopt_element(&el);
+        (el, props)
+    }
+
+    /// Applies the modifiers of the current element to an already existing element (e.g. the `<html>` element).
+    pub(crate) fn adopt_element(&mut self, el: &web_sys::Element) -> ElementProps {
+        self.merge_classes();
+        let attributes = self.apply_attributes(el);
+        let properties = self.apply_properties(el);
+        let styles = self.apply_styles(el);
+        if attributes.is_empty() && properties.is_empty() && styles.is_empty() {
+            ElementProps(None)
+        } else {
+            ElementProps(Some(Box::new(Modifiers {
+                attributes,
+                properties,
+                styles,
+            })))
+        }
     }
 
     pub(crate) fn rebuild_element(
         &mut self,
         element: &web_sys::Element,
-        attributes: &mut VecMap<CowStr, AttributeValue>,
+        props: &mut ElementProps,
     ) -> ChangeFlags {
-        self.apply_attribute_changes(element, attributes)
+        self.merge_classes();
+        if props.0.is_none()
+            && self.current_element_attributes.is_empty()
+            && self.current_element_properties.is_empty()
+            && self.current_element_styles.is_empty()
+        {
+            return ChangeFlags::empty();
+        }
+        let modifiers = props.0.get_or_insert_with(Default::default);
+        self.apply_attribute_changes(element, &mut modifiers.attributes)
+            | self.apply_property_changes(element, &mut modifiers.properties)
+            | self.apply_style_changes(element, &mut modifiers.styles)
     }
 
     // TODO Not sure how multiple attribute definitions with the same name should be handled (e.g. `e.attr("class", "a").attr("class", "b")`)
@@ -180,8 +479,19 @@ impl Cx {
     ) -> VecMap<CowStr, AttributeValue> {
         let mut attributes = VecMap::default();
         std::mem::swap(&mut attributes, &mut self.current_element_attributes);
-        for (name, value) in attributes.iter() {
-            set_attribute(element, name, &value.serialize());
+        if !attributes.is_empty() {
+            let id = self.dom_ops.node_id(element);
+            for (name, value) in attributes.iter() {
+                #[cfg(feature = "profiling")]
+                {
+                    self.dom_mutations.attributes += 1;
+                }
+                self.dom_ops.push(DomOp::SetAttr {
+                    id,
+                    name: name.clone(),
+                    value: Some(value.serialize()),
+                });
+            }
         }
         attributes
     }
@@ -194,14 +504,33 @@ impl Cx {
         let mut changed = ChangeFlags::empty();
         // update attributes
         for itm in diff_kv_iterables(&*attributes, &self.current_element_attributes) {
+            #[cfg(feature = "profiling")]
+            {
+                self.dom_mutations.attributes += 1;
+            }
             match itm {
+                Diff::Change(name, value) if name == "class" => {
+                    let old = attributes.get(name).unwrap_throw().serialize();
+                    update_class(&mut self.dom_ops, element, &old, &value.serialize());
+                    changed |= ChangeFlags::CLASS;
+                }
                 Diff::Add(name, value) | Diff::Change(name, value) => {
-                    set_attribute(element, name, &value.serialize());
-                    changed |= ChangeFlags::OTHER_CHANGE;
+                    let id = self.dom_ops.node_id(element);
+                    self.dom_ops.push(DomOp::SetAttr {
+                        id,
+                        name: name.clone(),
+                        value: Some(value.serialize()),
+                    });
+                    changed |= attribute_change_flag(name);
                 }
                 Diff::Remove(name) => {
-                    remove_attribute(element, name);
-                    changed |= ChangeFlags::OTHER_CHANGE;
+                    let id = self.dom_ops.node_id(element);
+                    self.dom_ops.push(DomOp::SetAttr {
+                        id,
+                        name: name.clone(),
+                        value: None,
+                    });
+                    changed |= attribute_change_flag(name);
                 }
             }
         }
@@ -210,6 +539,199 @@ impl Cx {
         changed
     }
 
+    pub(crate) fn add_class_to_element(&mut self, class: &CowStr) {
+        self.current_element_classes.push((class.clone(), true));
+    }
+
+    pub(crate) fn remove_class_from_element(&mut self, class: &CowStr) {
+        self.current_element_classes.push((class.clone(), false));
+    }
+
+    /// Merges the classes added (or removed) via e.g. `class_if` into the `class` attribute, see [`merge_class_list`].
+    ///
+    /// As the attribute is diffed as a whole, a class which is toggled on or off is a single `classList` change,
+    /// and an unchanged set of classes doesn't touch the DOM at all.
+    fn merge_classes(&mut self) {
+        if self.current_element_classes.is_empty() {
+            return;
+        }
+        let base = self
+            .current_element_attributes
+            .get("class")
+            .map(|value| value.serialize().into_owned())
+            .unwrap_or_default();
+        // Classes are added from the outer most view inwards, the inner ones come first, similar as they're written
+        let changes = self
+            .current_element_classes
+            .iter()
+            .rev()
+            .map(|(class, present)| (&**class, *present));
+        let class = merge_class_list(&base, changes);
+        self.current_element_classes.clear();
+        self.current_element_attributes
+            .insert("class".into(), AttributeValue::String(class.into()));
+    }
+
+    // Same as for attributes, the outer most property definition with the same name wins.
+    pub(crate) fn add_prop_to_element(&mut self, name: &CowStr, value: &Option<PropertyValue>) {
+        if let Some(value) = value {
+            if !self.current_element_properties.contains_key(name) {
+                self.current_element_properties
+                    .insert(name.clone(), value.clone());
+            }
+        }
+    }
+
+    pub(crate) fn apply_properties(
+        &mut self,
+        element: &web_sys::Element,
+    ) -> VecMap<CowStr, PropertyValue> {
+        let mut properties = VecMap::default();
+        std::mem::swap(&mut properties, &mut self.current_element_properties);
+        if !properties.is_empty() {
+            let id = self.dom_ops.node_id(element);
+            for (name, value) in properties.iter() {
+                #[cfg(feature = "profiling")]
+                {
+                    self.dom_mutations.properties += 1;
+                }
+                self.dom_ops.push(DomOp::SetProp {
+                    id,
+                    name: name.clone(),
+                    value: Some(value.clone()),
+                });
+            }
+        }
+        properties
+    }
+
+    pub(crate) fn apply_property_changes(
+        &mut self,
+        element: &web_sys::Element,
+        properties: &mut VecMap<CowStr, PropertyValue>,
+    ) -> ChangeFlags {
+        let mut changed = ChangeFlags::empty();
+        for itm in diff_kv_iterables(&*properties, &self.current_element_properties) {
+            #[cfg(feature = "profiling")]
+            {
+                self.dom_mutations.properties += 1;
+            }
+            let id = self.dom_ops.node_id(element);
+            let op = match itm {
+                Diff::Add(name, value) | Diff::Change(name, value) => DomOp::SetProp {
+                    id,
+                    name: name.clone(),
+                    value: Some(value.clone()),
+                },
+                Diff::Remove(name) => DomOp::SetProp {
+                    id,
+                    name: name.clone(),
+                    value: None,
+                },
+            };
+            self.dom_ops.push(op);
+            changed |= ChangeFlags::ATTRS;
+        }
+        std::mem::swap(properties, &mut self.current_element_properties);
+        self.current_element_properties.clear();
+        changed
+    }
+
+    // Same as for attributes, the outer most style definition with the same name wins.
+    pub(crate) fn add_style_to_element(&mut self, name: &CowStr, value: &Option<StyleValue>) {
+        if let Some(value) = value {
+            if !self.current_element_styles.contains_key(name) {
+                self.current_element_styles
+                    .insert(name.clone(), value.clone());
+            }
+        }
+    }
+
+    pub(crate) fn apply_styles(
+        &mut self,
+        element: &web_sys::Element,
+    ) -> VecMap<CowStr, StyleValue> {
+        let mut styles = VecMap::default();
+        std::mem::swap(&mut styles, &mut self.current_element_styles);
+        if !styles.is_empty() {
+            let id = self.dom_ops.node_id(element);
+            for (name, value) in styles.iter() {
+                self.dom_ops.push(DomOp::SetStyle {
+                    id,
+                    name: name.clone(),
+                    value: Some(value.serialize()),
+                });
+            }
+        }
+        styles
+    }
+
+    pub(crate) fn apply_style_changes(
+        &mut self,
+        element: &web_sys::Element,
+        styles: &mut VecMap<CowStr, StyleValue>,
+    ) -> ChangeFlags {
+        let mut changed = ChangeFlags::empty();
+        for itm in diff_kv_iterables(&*styles, &self.current_element_styles) {
+            let id = self.dom_ops.node_id(element);
+            let op = match itm {
+                Diff::Add(name, value) | Diff::Change(name, value) => DomOp::SetStyle {
+                    id,
+                    name: name.clone(),
+                    value: Some(value.serialize()),
+                },
+                Diff::Remove(name) => DomOp::SetStyle {
+                    id,
+                    name: name.clone(),
+                    value: None,
+                },
+            };
+            self.dom_ops.push(op);
+            changed |= ChangeFlags::STYLE;
+        }
+        std::mem::swap(styles, &mut self.current_element_styles);
+        self.current_element_styles.clear();
+        changed
+    }
+
+    #[cfg(feature = "profiling")]
+    pub(crate) fn take_dom_mutations(&mut self) -> crate::profiler::DomMutations {
+        std::mem::take(&mut self.dom_mutations)
+    }
+
+    /// Starts recording a named view, which is (re)built after this, and returns the position of its record.
+    #[cfg(feature = "devtools")]
+    pub(crate) fn begin_debug_record(&mut self) -> usize {
+        self.debug_depth += 1;
+        self.debug_records.len()
+    }
+
+    /// Records the named view, before the records of its descendants.
+    #[cfg(feature = "devtools")]
+    pub(crate) fn end_debug_record(
+        &mut self,
+        index: usize,
+        name: &CowStr,
+        id: Id,
+        element: &impl DomNode,
+        changed: Option<ChangeFlags>,
+    ) {
+        self.debug_depth -= 1;
+        let record = crate::devtools::DebugRecord {
+            name: name.clone(),
+            tag: element.as_node_ref().node_name(),
+            id,
+            depth: self.debug_depth,
+            changed,
+        };
+        self.debug_records.insert(index, record);
+    }
+
+    #[cfg(feature = "devtools")]
+    pub(crate) fn take_debug_records(&mut self) -> Vec<crate::devtools::DebugRecord> {
+        std::mem::take(&mut self.debug_records)
+    }
+
     pub fn message_thunk(&self) -> MessageThunk {
         MessageThunk {
             id_path: self.id_path.clone(),
@@ -235,6 +757,15 @@ impl MessageThunk {
         };
         self.app_ref.handle_message(message);
     }
+
+    /// Like [`push_message`](Self::push_message), but without boxing the already boxed message again.
+    pub(crate) fn push_boxed_message(&self, message_body: Box<dyn Any>) {
+        let message = Message {
+            id_path: self.id_path.clone(),
+            body: message_body,
+        };
+        self.app_ref.handle_message(message);
+    }
 }
 
 impl ChangeFlags {
@@ -242,3 +773,65 @@ impl ChangeFlags {
         Self::STRUCTURE
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{class_delta, merge_class_list, replace_managed_classes, ChangeFlags};
+
+    #[test]
+    fn specific_change_flags_contain_other_change() {
+        for flag in [
+            ChangeFlags::ATTRS,
+            ChangeFlags::STYLE,
+            ChangeFlags::CLASS,
+            ChangeFlags::TEXT,
+            ChangeFlags::LISTENERS,
+        ] {
+            assert!(flag.contains(ChangeFlags::OTHER_CHANGE), "{flag:?}");
+            assert!(!flag.contains(ChangeFlags::STRUCTURE), "{flag:?}");
+        }
+    }
+
+    #[test]
+    fn merge_class_list_dedup_and_order() {
+        let changes = [("b", true), ("a c", true), ("d", true)];
+        assert_eq!(merge_class_list("a  b", changes.into_iter()), "a b c d");
+        assert_eq!(merge_class_list("", [("a", true)].into_iter()), "a");
+    }
+
+    #[test]
+    fn merge_class_list_remove() {
+        let changes = [("c", true), ("a c", false), ("a", true)];
+        assert_eq!(merge_class_list("a b", changes.into_iter()), "b a");
+        assert_eq!(merge_class_list("a", [("b", false)].into_iter()), "a");
+    }
+
+    #[test]
+    fn class_delta_toggled_class() {
+        assert_eq!(class_delta("a b", "a b c"), (vec![], vec!["c"]));
+        assert_eq!(class_delta("a b c", "a c"), (vec!["b"], vec![]));
+        assert_eq!(class_delta("a  b", "b\ta"), (vec![], vec![]));
+    }
+
+    #[test]
+    fn class_delta_replaced_classes() {
+        assert_eq!(
+            class_delta("a b c", "d e f"),
+            (vec!["a", "b", "c"], vec!["d", "e", "f"])
+        );
+        assert_eq!(class_delta("", "a b"), (vec![], vec!["a", "b"]));
+    }
+
+    #[test]
+    fn replace_managed_classes_keeps_foreign_classes() {
+        assert_eq!(
+            replace_managed_classes("a b c x", "a b c", "d e f"),
+            "d e f x"
+        );
+        assert_eq!(
+            replace_managed_classes("x a y b", "a b", "b c d e"),
+            "b c d e x y"
+        );
+        assert_eq!(replace_managed_classes("", "a b", "c d"), "c d");
+    }
+}
@@ -0,0 +1,372 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A custom context menu, which replaces the native one of the browser.
+
+use std::{
+    any::Any,
+    borrow::Cow,
+    cell::RefCell,
+    marker::PhantomData,
+    rc::{Rc, Weak},
+};
+
+use gloo::events::{EventListener, EventListenerOptions, EventListenerPhase};
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    context::{ChangeFlags, Cx, MessageThunk},
+    interfaces::{sealed::Sealed, Element},
+    view::{DomNode, View, ViewMarker},
+    OptionalAction,
+};
+
+type CowStr = Cow<'static, str>;
+
+/// The class of the `<ul>` of the menu, to style it.
+pub const CONTEXT_MENU_CLASS: &str = "xilem-context-menu";
+
+/// The message sent when an item was chosen.
+struct SelectItem(usize);
+
+/// Shows a menu with `items` when the context menu of the element of the child view `V` is requested
+/// (right-click or long-press on touch devices), see [`context_menu`].
+pub struct ContextMenu<V, T, A, F> {
+    child: V,
+    items: Vec<CowStr>,
+    on_select: F,
+    phantom: PhantomData<fn() -> (T, A)>,
+}
+
+/// Replaces the native context menu of the element of `child` with a menu showing `items`.
+///
+/// `on_select` is called with the index of the chosen item.
+/// The menu is appended to the document body at the position of the pointer,
+/// it's navigable with the arrow keys, `Enter`/`Space` chooses an item, and `Escape` or a click outside closes it.
+/// The `<ul>` has the class [`CONTEXT_MENU_CLASS`] and `role="menu"`, the items are `<li role="menuitem">`.
+pub fn context_menu<T, A, F, OA, V>(
+    items: impl IntoIterator<Item = impl Into<CowStr>>,
+    child: V,
+    on_select: F,
+) -> ContextMenu<V, T, A, F>
+where
+    V: Element<T, A>,
+    OA: OptionalAction<A>,
+    F: Fn(&mut T, usize) -> OA,
+{
+    ContextMenu {
+        child,
+        items: items.into_iter().map(Into::into).collect(),
+        on_select,
+        phantom: PhantomData,
+    }
+}
+
+/// The DOM of the opened menu, which is removed when dropped.
+struct OpenMenu {
+    element: web_sys::HtmlElement,
+    #[allow(unused)]
+    listeners: [EventListener; 3],
+}
+
+impl Drop for OpenMenu {
+    fn drop(&mut self) {
+        self.element.remove();
+    }
+}
+
+type SharedMenu = Rc<RefCell<Option<OpenMenu>>>;
+
+/// The listeners only hold a weak reference to the menu, as it owns them.
+type WeakMenu = Weak<RefCell<Option<OpenMenu>>>;
+
+fn close(menu: &SharedMenu) {
+    // The menu is dropped after the borrow ended
+    let open_menu = menu.borrow_mut().take();
+    drop(open_menu);
+}
+
+fn close_weak(menu: &WeakMenu) {
+    if let Some(menu) = menu.upgrade() {
+        close(&menu);
+    }
+}
+
+fn focus_item(menu: &web_sys::HtmlElement, idx: u32) {
+    if let Some(item) = menu.children().item(idx) {
+        let _ = item.unchecked_into::<web_sys::HtmlElement>().focus();
+    }
+}
+
+/// The index of the item that currently has the focus.
+fn focused_item(menu: &web_sys::HtmlElement) -> Option<u32> {
+    let active = crate::document().active_element()?;
+    let items = menu.children();
+    (0..items.length()).find(|&idx| items.item(idx).as_ref() == Some(&active))
+}
+
+fn select(menu: &WeakMenu, thunk: &MessageThunk, idx: u32) {
+    close_weak(menu);
+    thunk.push_message(SelectItem(idx as usize));
+}
+
+fn open(items: &[CowStr], x: i32, y: i32, menu: &SharedMenu, thunk: &Rc<MessageThunk>) -> OpenMenu {
+    let document = crate::document();
+    let element: web_sys::HtmlElement = document
+        .create_element("ul")
+        .unwrap_throw()
+        .unchecked_into();
+    element.set_class_name(CONTEXT_MENU_CLASS);
+    element.set_attribute("role", "menu").unwrap_throw();
+    element
+        .set_attribute(
+            "style",
+            &format!("position: fixed; left: {x}px; top: {y}px; z-index: 1000;"),
+        )
+        .unwrap_throw();
+    for (idx, label) in items.iter().enumerate() {
+        let item = document.create_element("li").unwrap_throw();
+        item.set_attribute("role", "menuitem").unwrap_throw();
+        item.set_attribute("tabindex", "-1").unwrap_throw();
+        item.set_attribute("data-index", &idx.to_string())
+            .unwrap_throw();
+        item.set_text_content(Some(label));
+        element.append_child(&item).unwrap_throw();
+    }
+    crate::document_body().append_child(&element).unwrap_throw();
+
+    let click_listener = {
+        let (menu, thunk) = (Rc::downgrade(menu), Rc::clone(thunk));
+        EventListener::new(&element, "click", move |event| {
+            let idx = event
+                .target()
+                .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+                .and_then(|target| target.closest("li").ok().flatten())
+                .and_then(|item| item.get_attribute("data-index"))
+                .and_then(|idx| idx.parse().ok());
+            if let Some(idx) = idx {
+                select(&menu, &thunk, idx);
+            }
+        })
+    };
+    let keydown_listener = {
+        let (menu, thunk) = (Rc::downgrade(menu), Rc::clone(thunk));
+        let el = element.clone();
+        let item_count = items.len() as u32;
+        EventListener::new_with_options(
+            &element,
+            "keydown",
+            EventListenerOptions::enable_prevent_default(),
+            move |event| {
+                let event: &web_sys::KeyboardEvent = event.unchecked_ref();
+                if item_count == 0 {
+                    close_weak(&menu);
+                    return;
+                }
+                let focused = focused_item(&el);
+                match event.key().as_str() {
+                    "ArrowDown" => {
+                        focus_item(&el, focused.map_or(0, |idx| (idx + 1) % item_count));
+                    }
+                    "ArrowUp" => {
+                        let last = item_count - 1;
+                        focus_item(
+                            &el,
+                            focused.map_or(last, |idx| idx.checked_sub(1).unwrap_or(last)),
+                        );
+                    }
+                    "Home" => focus_item(&el, 0),
+                    "End" => focus_item(&el, item_count - 1),
+                    "Enter" | " " => {
+                        if let Some(idx) = focused {
+                            select(&menu, &thunk, idx);
+                        }
+                    }
+                    "Escape" | "Tab" => close_weak(&menu),
+                    _ => return,
+                }
+                event.prevent_default();
+            },
+        )
+    };
+    // Close the menu, when clicked somewhere else
+    let outside_listener = {
+        let menu = Rc::downgrade(menu);
+        let el = element.clone();
+        EventListener::new_with_options(
+            &document,
+            "pointerdown",
+            EventListenerOptions {
+                phase: EventListenerPhase::Capture,
+                passive: true,
+            },
+            move |event| {
+                let target = event
+                    .target()
+                    .and_then(|target| target.dyn_into::<web_sys::Node>().ok());
+                if !el.contains(target.as_ref()) {
+                    close_weak(&menu);
+                }
+            },
+        )
+    };
+    focus_item(&element, 0);
+    OpenMenu {
+        element,
+        listeners: [click_listener, keydown_listener, outside_listener],
+    }
+}
+
+fn create_contextmenu_listener(
+    node: &web_sys::Node,
+    items: &Rc<RefCell<Vec<CowStr>>>,
+    menu: &SharedMenu,
+    cx: &Cx,
+) -> EventListener {
+    #[cfg(debug_assertions)]
+    crate::a11y::register_listener(node, "contextmenu");
+    let thunk = Rc::new(cx.message_thunk());
+    let (items, menu) = (Rc::downgrade(items), Rc::downgrade(menu));
+    EventListener::new_with_options(
+        node,
+        "contextmenu",
+        EventListenerOptions::enable_prevent_default(),
+        move |event| {
+            let (Some(items), Some(menu)) = (items.upgrade(), menu.upgrade()) else {
+                return;
+            };
+            let event: &web_sys::MouseEvent = event.unchecked_ref();
+            event.prevent_default();
+            close(&menu);
+            let open_menu = open(
+                &items.borrow(),
+                event.client_x(),
+                event.client_y(),
+                &menu,
+                &thunk,
+            );
+            *menu.borrow_mut() = Some(open_menu);
+        },
+    )
+}
+
+pub struct ContextMenuState<S> {
+    #[allow(unused)]
+    listener: EventListener,
+    items: Rc<RefCell<Vec<CowStr>>>,
+    menu: SharedMenu,
+    child_id: Id,
+    child_state: S,
+}
+
+impl<S> Drop for ContextMenuState<S> {
+    fn drop(&mut self) {
+        // An open menu is in the document body, outside of the element of the view
+        close(&self.menu);
+    }
+}
+
+crate::interfaces::impl_dom_interfaces_for_ty!(
+    Element,
+    ContextMenu,
+    vars: <F, OA,>,
+    vars_on_ty: <F,>,
+    bounds: {
+        OA: OptionalAction<A>,
+        F: Fn(&mut T, usize) -> OA,
+    }
+);
+
+impl<V, T, A, F> ViewMarker for ContextMenu<V, T, A, F> {}
+impl<V, T, A, F> Sealed for ContextMenu<V, T, A, F> {}
+
+impl<V, T, A, F, OA> View<T, A> for ContextMenu<V, T, A, F>
+where
+    V: View<T, A>,
+    OA: OptionalAction<A>,
+    F: Fn(&mut T, usize) -> OA,
+{
+    type State = ContextMenuState<V::State>;
+    type Element = V::Element;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, (element, state)) = cx.with_new_id(|cx| {
+            let (child_id, child_state, element) = self.child.build(cx);
+            let items = Rc::new(RefCell::new(self.items.clone()));
+            let menu = SharedMenu::default();
+            let listener = create_contextmenu_listener(element.as_node_ref(), &items, &menu, cx);
+            let state = ContextMenuState {
+                listener,
+                items,
+                menu,
+                child_id,
+                child_state,
+            };
+            (element, state)
+        });
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        cx.with_id(*id, |cx| {
+            let prev_child_id = state.child_id;
+            let mut changed = self.child.rebuild(
+                cx,
+                &prev.child,
+                &mut state.child_id,
+                &mut state.child_state,
+                element,
+            );
+            if state.child_id != prev_child_id {
+                changed |= ChangeFlags::OTHER_CHANGE;
+            }
+            if prev.items != self.items {
+                // The indices of an open menu would be stale otherwise
+                close(&state.menu);
+                *state.items.borrow_mut() = self.items.clone();
+            }
+            if changed.contains(ChangeFlags::STRUCTURE) {
+                close(&state.menu);
+                state.listener = create_contextmenu_listener(
+                    element.as_node_ref(),
+                    &state.items,
+                    &state.menu,
+                    cx,
+                );
+                changed |= ChangeFlags::LISTENERS;
+            }
+            changed
+        })
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Box<dyn Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        match id_path {
+            [] if message.downcast_ref::<SelectItem>().is_some() => {
+                let SelectItem(idx) = *message.downcast::<SelectItem>().unwrap();
+                match (self.on_select)(app_state, idx).action() {
+                    Some(a) => MessageResult::Action(a),
+                    None => MessageResult::Nop,
+                }
+            }
+            [child_id, rest_path @ ..] if *child_id == state.child_id => {
+                self.child
+                    .message(rest_path, &mut state.child_state, message, app_state)
+            }
+            _ => MessageResult::Stale(message),
+        }
+    }
+}
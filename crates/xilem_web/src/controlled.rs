@@ -0,0 +1,402 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Controlled form inputs, i.e. the value shown in the DOM always reflects the app state.
+//!
+//! After each rebuild, the current value of the DOM element is compared with the value of the view
+//! (not the previous view), so the DOM is reset to the app state, even when a change by the user was rejected
+//! (e.g. with validation, by not updating the state in the callback).
+
+use std::{borrow::Cow, marker::PhantomData};
+
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    context::{ChangeFlags, Cx, ElementProps},
+    events::{create_event_listener, Delivery, Dispatch},
+    interfaces::sealed::Sealed,
+    mutations::DomOp,
+    view::{View, ViewMarker},
+    IntoAttributeValue, OptionalAction, PropertyValue, HTML_NS,
+};
+
+type CowStr = Cow<'static, str>;
+
+macro_rules! generate_dom_interface_impl {
+    ($dom_interface:ident, ($ty_name:ident, $msg_ty:ty)) => {
+        impl<T, A, F, OA> $crate::interfaces::$dom_interface<T, A> for $ty_name<T, A, F>
+        where
+            F: Fn(&mut T, $msg_ty) -> OA,
+            OA: OptionalAction<A>,
+        {
+        }
+    };
+}
+
+/// The state of the controlled input views.
+pub struct ControlledState {
+    props: ElementProps,
+    #[allow(unused)]
+    listener: gloo::events::EventListener,
+}
+
+fn build_controlled<E: JsCast>(
+    cx: &mut Cx,
+    tag_name: &str,
+    event: &'static str,
+) -> (Id, ControlledState, E) {
+    let (el, props) = cx.build_element(HTML_NS, tag_name);
+    let (id, listener) = cx.with_new_id(|cx| {
+        create_event_listener::<web_sys::Event>(
+            &el,
+            event,
+            Default::default(),
+            Delivery::Immediate,
+            Dispatch::default(),
+            cx,
+        )
+    });
+
+    // Set the id used internally to the `data-debugid` attribute.
+    // This allows the user to see if an element has been re-created or only altered.
+    #[cfg(debug_assertions)]
+    cx.set_attribute(&el, "data-debugid", Some(id.to_raw().to_string().into()));
+
+    (id, ControlledState { props, listener }, el.unchecked_into())
+}
+
+/// Sets the state of the element, which is controlled by the view, in order with the other DOM mutations.
+fn set_prop(cx: &mut Cx, element: &web_sys::Element, name: &'static str, value: PropertyValue) {
+    let id = cx.node_id(element);
+    cx.push_dom_op(DomOp::SetProp {
+        id,
+        name: name.into(),
+        value: Some(value),
+    });
+}
+
+/// Whether the DOM has to be reset to the value of the view.
+///
+/// It's compared with the current value in the DOM, not with the previous view, so a change by the user,
+/// which was rejected by the app (i.e. the view didn't change), is reverted as well.
+fn needs_sync<D: PartialEq<V> + ?Sized, V: ?Sized>(dom: &D, view: &V) -> bool {
+    dom != view
+}
+
+fn action<A>(action: impl OptionalAction<A>) -> MessageResult<A> {
+    match action.action() {
+        Some(a) => MessageResult::Action(a),
+        None => MessageResult::Nop,
+    }
+}
+
+/// A controlled `<input>` for text, see [`text_input`].
+pub struct TextInput<T, A, F> {
+    value: CowStr,
+    on_input: F,
+    phantom: PhantomData<fn() -> (T, A)>,
+}
+
+/// A text `<input>` which always shows `value`, `on_input` is called with the new value, each time it's changed by the user.
+///
+/// Attributes like `type` (e.g. `"password"`) or `placeholder` can be added with the usual modifiers.
+pub fn text_input<T, A, F, OA>(value: impl Into<CowStr>, on_input: F) -> TextInput<T, A, F>
+where
+    F: Fn(&mut T, String) -> OA,
+    OA: OptionalAction<A>,
+{
+    TextInput {
+        value: value.into(),
+        on_input,
+        phantom: PhantomData,
+    }
+}
+
+generate_dom_interface_impl!(HtmlInputElement, (TextInput, String));
+crate::interfaces::for_all_html_input_element_ancestors!(
+    generate_dom_interface_impl,
+    (TextInput, String)
+);
+
+impl<T, A, F> ViewMarker for TextInput<T, A, F> {}
+impl<T, A, F> Sealed for TextInput<T, A, F> {}
+
+impl<T, A, F, OA> View<T, A> for TextInput<T, A, F>
+where
+    F: Fn(&mut T, String) -> OA,
+    OA: OptionalAction<A>,
+{
+    type State = ControlledState;
+    type Element = web_sys::HtmlInputElement;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, state, el) = build_controlled::<web_sys::HtmlInputElement>(cx, "input", "input");
+        set_prop(cx, &el, "value", PropertyValue::String(self.value.clone()));
+        (id, state, el)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        _prev: &Self,
+        _id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let mut changed = cx.rebuild_element(element, &mut state.props);
+        // Only set when different, otherwise the cursor position would be lost
+        if needs_sync(&element.value(), &self.value) {
+            set_prop(
+                cx,
+                element,
+                "value",
+                PropertyValue::String(self.value.clone()),
+            );
+            changed |= ChangeFlags::ATTRS;
+        }
+        changed
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        _state: &mut Self::State,
+        message: Box<dyn std::any::Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        match id_path {
+            [] if message.downcast_ref::<web_sys::Event>().is_some() => {
+                let event = message.downcast::<web_sys::Event>().unwrap();
+                let input: web_sys::HtmlInputElement =
+                    event.target().unwrap_throw().unchecked_into();
+                action((self.on_input)(app_state, input.value()))
+            }
+            _ => MessageResult::Stale(message),
+        }
+    }
+}
+
+/// A controlled `<input type="checkbox">`, see [`checkbox`].
+pub struct Checkbox<T, A, F> {
+    checked: bool,
+    on_change: F,
+    phantom: PhantomData<fn() -> (T, A)>,
+}
+
+/// A checkbox which is checked when `checked` is `true`, `on_change` is called with the new value when it's toggled by the user.
+pub fn checkbox<T, A, F, OA>(checked: bool, on_change: F) -> Checkbox<T, A, F>
+where
+    F: Fn(&mut T, bool) -> OA,
+    OA: OptionalAction<A>,
+{
+    Checkbox {
+        checked,
+        on_change,
+        phantom: PhantomData,
+    }
+}
+
+generate_dom_interface_impl!(HtmlInputElement, (Checkbox, bool));
+crate::interfaces::for_all_html_input_element_ancestors!(
+    generate_dom_interface_impl,
+    (Checkbox, bool)
+);
+
+impl<T, A, F> ViewMarker for Checkbox<T, A, F> {}
+impl<T, A, F> Sealed for Checkbox<T, A, F> {}
+
+impl<T, A, F, OA> View<T, A> for Checkbox<T, A, F>
+where
+    F: Fn(&mut T, bool) -> OA,
+    OA: OptionalAction<A>,
+{
+    type State = ControlledState;
+    type Element = web_sys::HtmlInputElement;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        cx.add_attr_to_element(&"type".into(), &"checkbox".into_attr_value());
+        let (id, state, el) = build_controlled::<web_sys::HtmlInputElement>(cx, "input", "change");
+        set_prop(cx, &el, "checked", PropertyValue::Bool(self.checked));
+        (id, state, el)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        _prev: &Self,
+        _id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        cx.add_attr_to_element(&"type".into(), &"checkbox".into_attr_value());
+        let mut changed = cx.rebuild_element(element, &mut state.props);
+        if needs_sync(&element.checked(), &self.checked) {
+            set_prop(cx, element, "checked", PropertyValue::Bool(self.checked));
+            changed |= ChangeFlags::ATTRS;
+        }
+        changed
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        _state: &mut Self::State,
+        message: Box<dyn std::any::Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        match id_path {
+            [] if message.downcast_ref::<web_sys::Event>().is_some() => {
+                let event = message.downcast::<web_sys::Event>().unwrap();
+                let input: web_sys::HtmlInputElement =
+                    event.target().unwrap_throw().unchecked_into();
+                action((self.on_change)(app_state, input.checked()))
+            }
+            _ => MessageResult::Stale(message),
+        }
+    }
+}
+
+/// A controlled `<select>`, see [`select`].
+pub struct Select<T, A, F> {
+    options: Vec<CowStr>,
+    selected: Option<usize>,
+    on_change: F,
+    phantom: PhantomData<fn() -> (T, A)>,
+}
+
+/// A `<select>` with an `<option>` for each label in `options`, the option at index `selected` is selected.
+///
+/// `on_change` is called with the index of the option the user selected.
+pub fn select<T, A, F, OA>(
+    options: impl IntoIterator<Item = impl Into<CowStr>>,
+    selected: Option<usize>,
+    on_change: F,
+) -> Select<T, A, F>
+where
+    F: Fn(&mut T, usize) -> OA,
+    OA: OptionalAction<A>,
+{
+    Select {
+        options: options.into_iter().map(Into::into).collect(),
+        selected,
+        on_change,
+        phantom: PhantomData,
+    }
+}
+
+impl<T, A, F> Select<T, A, F> {
+    fn append_options(&self, cx: &mut Cx, element: &web_sys::HtmlSelectElement) {
+        for label in &self.options {
+            let option = cx.create_element(HTML_NS, "option");
+            cx.set_text(&option, Some(label.clone()));
+            cx.insert_node(element, &option, None);
+        }
+    }
+
+    fn selected_index(&self) -> i32 {
+        self.selected.map_or(-1, |idx| idx as i32)
+    }
+}
+
+generate_dom_interface_impl!(HtmlSelectElement, (Select, usize));
+crate::interfaces::for_all_html_select_element_ancestors!(
+    generate_dom_interface_impl,
+    (Select, usize)
+);
+
+impl<T, A, F> ViewMarker for Select<T, A, F> {}
+impl<T, A, F> Sealed for Select<T, A, F> {}
+
+impl<T, A, F, OA> View<T, A> for Select<T, A, F>
+where
+    F: Fn(&mut T, usize) -> OA,
+    OA: OptionalAction<A>,
+{
+    type State = ControlledState;
+    type Element = web_sys::HtmlSelectElement;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, state, el) =
+            build_controlled::<web_sys::HtmlSelectElement>(cx, "select", "change");
+        self.append_options(cx, &el);
+        // After the options, so that the index refers to them
+        set_prop(
+            cx,
+            &el,
+            "selectedIndex",
+            PropertyValue::I32(self.selected_index()),
+        );
+        (id, state, el)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        _id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let mut changed = cx.rebuild_element(element, &mut state.props);
+        let options_changed = prev.options != self.options;
+        if options_changed {
+            cx.set_text(element, None);
+            self.append_options(cx, element);
+            // The labels of the options are the text content of the select
+            changed |= ChangeFlags::TEXT;
+        }
+        if options_changed || needs_sync(&element.selected_index(), &self.selected_index()) {
+            set_prop(
+                cx,
+                element,
+                "selectedIndex",
+                PropertyValue::I32(self.selected_index()),
+            );
+            changed |= ChangeFlags::ATTRS;
+        }
+        changed
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        _state: &mut Self::State,
+        message: Box<dyn std::any::Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        match id_path {
+            [] if message.downcast_ref::<web_sys::Event>().is_some() => {
+                let event = message.downcast::<web_sys::Event>().unwrap();
+                let select: web_sys::HtmlSelectElement =
+                    event.target().unwrap_throw().unchecked_into();
+                match usize::try_from(select.selected_index()) {
+                    Ok(idx) => action((self.on_change)(app_state, idx)),
+                    Err(_) => MessageResult::Nop,
+                }
+            }
+            _ => MessageResult::Stale(message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::needs_sync;
+
+    #[test]
+    fn rejected_changes_are_reverted() {
+        // The user typed a character, which was rejected by the app, so the view still has the previous value
+        let view_value: std::borrow::Cow<'static, str> = "abc".into();
+        let mut dom_value = String::from("abcd");
+        assert!(needs_sync(&dom_value, &view_value));
+        dom_value = view_value.to_string();
+        assert!(!needs_sync(&dom_value, &view_value));
+
+        // The same for checkboxes and the selected index
+        assert!(needs_sync(&true, &false));
+        assert!(!needs_sync(&false, &false));
+        assert!(needs_sync(&2, &-1));
+        assert!(!needs_sync(&1, &1));
+    }
+}
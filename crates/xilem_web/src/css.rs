@@ -0,0 +1,98 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+};
+
+use wasm_bindgen::UnwrapThrowExt;
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    interfaces::{sealed::Sealed, Element},
+    ChangeFlags, Cx, View, ViewMarker,
+};
+
+type CowStr = std::borrow::Cow<'static, str>;
+
+/// Styles the element and its descendants with a scoped stylesheet, see [`Element::css`].
+pub struct Css<E, T, A> {
+    pub(crate) element: E,
+    pub(crate) stylesheet: CowStr,
+    pub(crate) phantom: PhantomData<fn() -> (T, A)>,
+}
+
+pub struct CssState<S> {
+    child_state: S,
+    /// The class which scopes the stylesheet to the element.
+    class: CowStr,
+}
+
+/// Injects the stylesheet into the document `<head>`, unless it's already there,
+/// and returns the class of the element it's scoped to.
+///
+/// The class is derived from a hash of the stylesheet, so each distinct stylesheet is only injected once,
+/// regardless of how many elements use it.
+fn inject_stylesheet(cx: &Cx, stylesheet: &str) -> CowStr {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    stylesheet.hash(&mut hasher);
+    let class = format!("xilem-css-{:x}", hasher.finish());
+    let document = cx.document();
+    if document.get_element_by_id(&class).is_none() {
+        let style = document.create_element("style").unwrap_throw();
+        style.set_id(&class);
+        // CSS nesting scopes all rules to the element (and its descendants), `&` refers to the element itself
+        style.set_text_content(Some(&format!(".{class} {{\n{stylesheet}\n}}")));
+        document
+            .head()
+            .unwrap_throw()
+            .append_child(&style)
+            .unwrap_throw();
+    }
+    class.into()
+}
+
+impl<E, T, A> ViewMarker for Css<E, T, A> {}
+impl<E, T, A> Sealed for Css<E, T, A> {}
+
+impl<E: Element<T, A>, T, A> View<T, A> for Css<E, T, A> {
+    type State = CssState<E::State>;
+    type Element = E::Element;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let class = inject_stylesheet(cx, &self.stylesheet);
+        cx.add_class_to_element(&class);
+        let (id, child_state, element) = self.element.build(cx);
+        (id, CssState { child_state, class }, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        if prev.stylesheet != self.stylesheet {
+            state.class = inject_stylesheet(cx, &self.stylesheet);
+        }
+        cx.add_class_to_element(&state.class);
+        self.element
+            .rebuild(cx, &prev.element, id, &mut state.child_state, element)
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Box<dyn std::any::Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        self.element
+            .message(id_path, &mut state.child_state, message, app_state)
+    }
+}
+
+crate::interfaces::impl_dom_interfaces_for_ty!(Element, Css);
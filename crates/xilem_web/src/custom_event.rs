@@ -0,0 +1,131 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{borrow::Cow, marker::PhantomData};
+
+use wasm_bindgen::{JsCast, JsValue, UnwrapThrowExt};
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    interfaces::{sealed::Sealed, Element},
+    timer::Timeout,
+    view::DomNode,
+    ChangeFlags, Cx, View, ViewMarker,
+};
+
+/// Dispatches a `CustomEvent` from the element whenever `detail` changes, see [`Element::emit_custom_event`].
+pub struct EmitCustomEvent<E, T, A> {
+    element: E,
+    name: Cow<'static, str>,
+    detail: Option<serde_json::Value>,
+    phantom: PhantomData<fn() -> (T, A)>,
+}
+
+impl<E, T, A> EmitCustomEvent<E, T, A> {
+    pub fn new(element: E, name: Cow<'static, str>, detail: &impl serde::Serialize) -> Self {
+        let detail = match serde_json::to_value(detail) {
+            Ok(detail) => Some(detail),
+            Err(err) => {
+                web_sys::console::error_1(
+                    &format!("Couldn't serialize the detail of the `{name}` event: {err}").into(),
+                );
+                None
+            }
+        };
+        EmitCustomEvent {
+            element,
+            name,
+            detail,
+            phantom: PhantomData,
+        }
+    }
+}
+
+pub struct EmitCustomEventState<S> {
+    child_state: S,
+    #[allow(unused)]
+    pending_event: Option<Timeout>,
+}
+
+/// Converts a JSON value to the equivalent JS value (also used for the props of [`js_component`](crate::js_component)).
+pub(crate) fn json_to_js_value(value: &serde_json::Value) -> JsValue {
+    js_sys::JSON::parse(&value.to_string()).unwrap_throw()
+}
+
+/// The event only reaches listeners of ancestors when the element is in the document,
+/// so it's dispatched after the current (re)build.
+fn schedule_event(
+    el: &web_sys::Element,
+    name: &str,
+    detail: Option<&serde_json::Value>,
+) -> Timeout {
+    let el = el.clone();
+    let name = name.to_owned();
+    let detail = detail.map_or(JsValue::NULL, json_to_js_value);
+    Timeout::new(0, move || {
+        let mut init = web_sys::CustomEventInit::new();
+        init.bubbles(true).composed(true).detail(&detail);
+        let event = web_sys::CustomEvent::new_with_event_init_dict(&name, &init).unwrap_throw();
+        let _ = el.dispatch_event(&event);
+    })
+}
+
+impl<E, T, A> ViewMarker for EmitCustomEvent<E, T, A> {}
+impl<E, T, A> Sealed for EmitCustomEvent<E, T, A> {}
+
+impl<E: Element<T, A>, T, A> View<T, A> for EmitCustomEvent<E, T, A> {
+    type State = EmitCustomEventState<E::State>;
+    type Element = E::Element;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, child_state, element) = self.element.build(cx);
+        let pending_event = schedule_event(
+            element.as_node_ref().unchecked_ref(),
+            &self.name,
+            self.detail.as_ref(),
+        );
+        let state = EmitCustomEventState {
+            child_state,
+            pending_event: Some(pending_event),
+        };
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let changed = self
+            .element
+            .rebuild(cx, &prev.element, id, &mut state.child_state, element);
+        // Listeners of a recreated element haven't seen the current detail yet
+        if self.name != prev.name
+            || self.detail != prev.detail
+            || changed.contains(ChangeFlags::STRUCTURE)
+        {
+            state.pending_event = Some(schedule_event(
+                element.as_node_ref().unchecked_ref(),
+                &self.name,
+                self.detail.as_ref(),
+            ));
+        }
+        changed
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Box<dyn std::any::Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        self.element
+            .message(id_path, &mut state.child_state, message, app_state)
+    }
+}
+
+crate::interfaces::impl_dom_interfaces_for_ty!(Element, EmitCustomEvent);
@@ -0,0 +1,170 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Inspecting the view tree of an [`App`](crate::App), see [`Element::debug_id`] and `App::devtools`
+//! (which requires the `devtools` feature).
+
+use std::marker::PhantomData;
+
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    interfaces::{sealed::Sealed, Element},
+    ChangeFlags, Cx, IntoAttributeValue, View, ViewMarker,
+};
+
+#[cfg(feature = "devtools")]
+use wasm_bindgen::UnwrapThrowExt;
+
+type CowStr = std::borrow::Cow<'static, str>;
+
+/// The attribute with the name of an element given via [`Element::debug_id`].
+///
+/// It's separate from `data-debugid`, which contains the (numeric) id of the element view in debug builds.
+pub(crate) const DEBUG_NAME_ATTRIBUTE: &str = "data-debugname";
+
+/// Names the element for debugging, see [`Element::debug_id`].
+pub struct DebugId<E, T, A> {
+    pub(crate) element: E,
+    pub(crate) name: CowStr,
+    pub(crate) phantom: PhantomData<fn() -> (T, A)>,
+}
+
+impl<E, T, A> ViewMarker for DebugId<E, T, A> {}
+impl<E, T, A> Sealed for DebugId<E, T, A> {}
+
+impl<E: Element<T, A>, T, A> View<T, A> for DebugId<E, T, A> {
+    type State = E::State;
+    type Element = E::Element;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        cx.add_attr_to_element(
+            &DEBUG_NAME_ATTRIBUTE.into(),
+            &self.name.clone().into_attr_value(),
+        );
+        #[cfg(feature = "devtools")]
+        let index = cx.begin_debug_record();
+        let (id, state, element) = self.element.build(cx);
+        #[cfg(feature = "devtools")]
+        cx.end_debug_record(index, &self.name, id, &element, None);
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        cx.add_attr_to_element(
+            &DEBUG_NAME_ATTRIBUTE.into(),
+            &self.name.clone().into_attr_value(),
+        );
+        #[cfg(feature = "devtools")]
+        let index = cx.begin_debug_record();
+        let changed = self.element.rebuild(cx, &prev.element, id, state, element);
+        #[cfg(feature = "devtools")]
+        cx.end_debug_record(index, &self.name, *id, element, Some(changed));
+        changed
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Box<dyn std::any::Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        self.element.message(id_path, state, message, app_state)
+    }
+}
+
+crate::interfaces::impl_dom_interfaces_for_ty!(Element, DebugId);
+
+/// A view named with [`Element::debug_id`], as it was (re)built in the last pass of the app.
+#[cfg(feature = "devtools")]
+#[derive(Clone, Debug)]
+pub struct DebugRecord {
+    pub name: CowStr,
+    /// The tag name of the element, e.g. `DIV`.
+    pub tag: String,
+    pub id: Id,
+    /// The number of named ancestors.
+    pub depth: usize,
+    /// What changed when the view was rebuilt, or `None` when it was built (again).
+    pub changed: Option<ChangeFlags>,
+}
+
+#[cfg(feature = "devtools")]
+impl std::fmt::Display for DebugRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} <{}> {:?}: ", self.name, self.tag, self.id)?;
+        match self.changed {
+            None => write!(f, "built"),
+            Some(changed) if changed.is_empty() => write!(f, "unchanged"),
+            Some(changed) => write!(f, "{changed:?}"),
+        }
+    }
+}
+
+/// Where the view tree is shown after each pass, see `App::devtools`.
+#[cfg(feature = "devtools")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DevtoolsOutput {
+    /// Nested (collapsed) groups in the browser console.
+    Console,
+    /// A panel in the corner of the page.
+    Overlay,
+}
+
+#[cfg(feature = "devtools")]
+const OVERLAY_ID: &str = "xilem-devtools";
+
+#[cfg(feature = "devtools")]
+impl DevtoolsOutput {
+    pub(crate) fn show(self, pass: &str, records: &[DebugRecord]) {
+        match self {
+            DevtoolsOutput::Console => {
+                web_sys::console::group_collapsed_1(&format!("xilem {pass}").into());
+                let mut open_groups = 0;
+                for record in records {
+                    while open_groups > record.depth {
+                        web_sys::console::group_end();
+                        open_groups -= 1;
+                    }
+                    web_sys::console::group_collapsed_1(&record.to_string().into());
+                    open_groups += 1;
+                }
+                for _ in 0..=open_groups {
+                    web_sys::console::group_end();
+                }
+            }
+            DevtoolsOutput::Overlay => {
+                let document = crate::document();
+                let overlay = document.get_element_by_id(OVERLAY_ID).unwrap_or_else(|| {
+                    let overlay = document.create_element("pre").unwrap_throw();
+                    overlay.set_id(OVERLAY_ID);
+                    overlay
+                        .set_attribute(
+                            "style",
+                            "position: fixed; right: 0; bottom: 0; z-index: 2147483647; margin: 0; \
+                             padding: 0.5em; max-height: 50vh; overflow: auto; pointer-events: none; \
+                             font-size: 11px; background: rgba(0, 0, 0, 0.75); color: white;",
+                        )
+                        .unwrap_throw();
+                    document.body().unwrap_throw().append_child(&overlay).unwrap_throw();
+                    overlay
+                });
+                let mut text = format!("xilem {pass}\n");
+                for record in records {
+                    text.extend(std::iter::repeat("  ").take(record.depth + 1));
+                    text.push_str(&record.to_string());
+                    text.push('\n');
+                }
+                overlay.set_text_content(Some(&text));
+            }
+        }
+    }
+}
@@ -0,0 +1,194 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::marker::PhantomData;
+
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    context::{ChangeFlags, Cx, ElementProps},
+    elements::ChildrenSplice,
+    interfaces::sealed::Sealed,
+    view::{View, ViewMarker},
+    Pod, ViewSequence,
+};
+
+macro_rules! generate_dom_interface_impl {
+    ($dom_interface:ident, ()) => {
+        impl<T, A, H, B> $crate::interfaces::$dom_interface<T, A> for DocumentShell<T, A, H, B>
+        where
+            H: ViewSequence<T, A>,
+            B: ViewSequence<T, A>,
+        {
+        }
+    };
+}
+
+/// The whole document, with the children of `<head>` and `<body>`, see [`document_shell`].
+pub struct DocumentShell<T, A, H, B> {
+    head: H,
+    body: B,
+    phantom: PhantomData<fn() -> (T, A)>,
+}
+
+/// The root view of an app managing the whole document (see [`App::run_document`](crate::App::run_document)),
+/// `head` is appended to the static content of the `<head>` (e.g. stylesheets), and `body` replaces the content of the `<body>`.
+///
+/// Modifiers apply to the `<html>` element.
+///
+/// ```ignore
+/// App::new(state, |state| {
+///     document_shell(
+///         (document_title(state.title.clone()), meta("description", state.summary.clone())),
+///         (el::header(nav(state)), el::main(page(state))),
+///     )
+///     .attr("lang", state.locale.clone())
+/// })
+/// .run_document();
+/// ```
+pub fn document_shell<T, A, H, B>(head: H, body: B) -> DocumentShell<T, A, H, B>
+where
+    H: ViewSequence<T, A>,
+    B: ViewSequence<T, A>,
+{
+    DocumentShell {
+        head,
+        body,
+        phantom: PhantomData,
+    }
+}
+
+/// The children of the `<head>` or the `<body>`.
+struct ShellPart<S> {
+    parent: web_sys::HtmlElement,
+    /// Marks the start of the children, so that the range stays correct, when others (e.g. scripts) insert nodes in front of it
+    start: web_sys::Comment,
+    /// Marks the end of the children, so that nodes appended by others (e.g. [`meta`](crate::head::meta)) are not in between
+    end: web_sys::Comment,
+    children_states: S,
+    child_elements: Vec<Pod>,
+    scratch: Vec<Pod>,
+}
+
+impl<S> ShellPart<S> {
+    fn build<T, A, V>(children: &V, cx: &mut Cx, parent: web_sys::HtmlElement) -> Self
+    where
+        V: ViewSequence<T, A, State = S>,
+    {
+        let start = cx.create_comment("xilem-document-shell-start");
+        let end = cx.create_comment("xilem-document-shell-end");
+        cx.insert_node(&parent, &start, None);
+        cx.insert_node(&parent, &end, None);
+        let mut child_elements = vec![];
+        let mut scratch = vec![];
+        let mut splice = ChildrenSplice::new_in_range(
+            &mut child_elements,
+            &mut scratch,
+            &parent,
+            Some(start.as_ref()),
+            Some(end.as_ref()),
+        );
+        let children_states = children.build(cx, &mut splice);
+        debug_assert!(scratch.is_empty());
+        ShellPart {
+            parent,
+            start,
+            end,
+            children_states,
+            child_elements,
+            scratch,
+        }
+    }
+
+    fn rebuild<T, A, V>(&mut self, children: &V, prev: &V, cx: &mut Cx) -> ChangeFlags
+    where
+        V: ViewSequence<T, A, State = S>,
+    {
+        let mut splice = ChildrenSplice::new_in_range(
+            &mut self.child_elements,
+            &mut self.scratch,
+            &self.parent,
+            Some(self.start.as_ref()),
+            Some(self.end.as_ref()),
+        );
+        let changed = children.rebuild(cx, prev, &mut self.children_states, &mut splice);
+        debug_assert!(self.scratch.is_empty());
+        changed
+    }
+}
+
+pub struct DocumentShellState<HS, BS> {
+    props: ElementProps,
+    head: ShellPart<HS>,
+    body: ShellPart<BS>,
+}
+
+generate_dom_interface_impl!(HtmlHtmlElement, ());
+crate::interfaces::for_all_html_html_element_ancestors!(generate_dom_interface_impl, ());
+
+impl<T, A, H, B> ViewMarker for DocumentShell<T, A, H, B> {}
+impl<T, A, H, B> Sealed for DocumentShell<T, A, H, B> {}
+
+impl<T, A, H, B> View<T, A> for DocumentShell<T, A, H, B>
+where
+    H: ViewSequence<T, A>,
+    B: ViewSequence<T, A>,
+{
+    type State = DocumentShellState<H::State, B::State>;
+    type Element = web_sys::HtmlHtmlElement;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let document = cx.document().clone();
+        let html: web_sys::HtmlHtmlElement =
+            document.document_element().unwrap_throw().unchecked_into();
+        let props = cx.adopt_element(&html);
+        let head = document.head().unwrap_throw();
+        let body = document.body().unwrap_throw();
+        // There's no hydration, so e.g. server-rendered content is replaced
+        cx.set_text(&body, None);
+        let (id, (head, body)) = cx.with_new_id(|cx| {
+            let head = ShellPart::build(&self.head, cx, head.into());
+            let body = ShellPart::build(&self.body, cx, body);
+            (head, body)
+        });
+        (id, DocumentShellState { props, head, body }, html)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let mut changed = cx.rebuild_element(element, &mut state.props);
+        changed |= cx.with_id(*id, |cx| {
+            state.head.rebuild(&self.head, &prev.head, cx)
+                | state.body.rebuild(&self.body, &prev.body, cx)
+        });
+        // The structure of the children is handled by the `ChildrenSplice`s already
+        changed.remove(ChangeFlags::STRUCTURE);
+        changed
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Box<dyn std::any::Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        match self
+            .head
+            .message(id_path, &mut state.head.children_states, message, app_state)
+        {
+            MessageResult::Stale(message) => {
+                self.body
+                    .message(id_path, &mut state.body.children_states, message, app_state)
+            }
+            result => result,
+        }
+    }
+}
@@ -0,0 +1,285 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Typed drag and drop, see [`Element::draggable`] and [`Element::drop_target`].
+
+use std::{any::Any, borrow::Cow, marker::PhantomData, rc::Rc};
+
+use gloo::events::{EventListener, EventListenerOptions};
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    context::{ChangeFlags, Cx, MessageThunk},
+    form::{read_file, FormFile},
+    interfaces::{sealed::Sealed, Element},
+    view::{DomNode, View, ViewMarker},
+    IntoAttributeValue, OptionalAction,
+};
+
+type CowStr = Cow<'static, str>;
+
+/// The payload of a draggable element is retained in this attribute, so that the `dragstart` listener can read it.
+const PAYLOAD_ATTR: &str = "data-xilem-drag-payload";
+/// The type of the payload in the `DataTransfer`, it's also set as `text/plain`, so that it can be dropped e.g. into text fields.
+const PAYLOAD_MIME: &str = "application/x-xilem-payload";
+
+/// What was dropped on a [drop target](Element::drop_target).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DropData {
+    /// The payload of a [draggable](Element::draggable) element, or other text (e.g. a selection from another page).
+    pub payload: Option<String>,
+    /// The files which were dropped (e.g. from the file manager), with their contents.
+    pub files: Vec<FormFile>,
+}
+
+/// Makes the element draggable with a payload, see [`Element::draggable`].
+pub struct Draggable<E, T, A> {
+    pub(crate) element: E,
+    pub(crate) payload: CowStr,
+    pub(crate) phantom: PhantomData<fn() -> (T, A)>,
+}
+
+pub struct DraggableState<S> {
+    #[allow(unused)]
+    listener: EventListener,
+    child_state: S,
+}
+
+fn create_dragstart_listener(element: &web_sys::Node) -> EventListener {
+    let el: web_sys::Element = element.clone().unchecked_into();
+    EventListener::new(element, "dragstart", move |event| {
+        let event: &web_sys::DragEvent = event.unchecked_ref();
+        if let (Some(data_transfer), Some(payload)) =
+            (event.data_transfer(), el.get_attribute(PAYLOAD_ATTR))
+        {
+            data_transfer
+                .set_data(PAYLOAD_MIME, &payload)
+                .unwrap_throw();
+            data_transfer
+                .set_data("text/plain", &payload)
+                .unwrap_throw();
+        }
+    })
+}
+
+impl<E, T, A> ViewMarker for Draggable<E, T, A> {}
+impl<E, T, A> Sealed for Draggable<E, T, A> {}
+
+impl<E: Element<T, A>, T, A> View<T, A> for Draggable<E, T, A> {
+    type State = DraggableState<E::State>;
+    type Element = E::Element;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        cx.add_attr_to_element(&"draggable".into(), &"true".into_attr_value());
+        cx.add_attr_to_element(
+            &PAYLOAD_ATTR.into(),
+            &self.payload.clone().into_attr_value(),
+        );
+        let (id, child_state, element) = self.element.build(cx);
+        let listener = create_dragstart_listener(element.as_node_ref());
+        let state = DraggableState {
+            listener,
+            child_state,
+        };
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        cx.add_attr_to_element(&"draggable".into(), &"true".into_attr_value());
+        cx.add_attr_to_element(
+            &PAYLOAD_ATTR.into(),
+            &self.payload.clone().into_attr_value(),
+        );
+        let changed = self
+            .element
+            .rebuild(cx, &prev.element, id, &mut state.child_state, element);
+        if changed.contains(ChangeFlags::STRUCTURE) {
+            state.listener = create_dragstart_listener(element.as_node_ref());
+        }
+        changed
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Box<dyn Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        self.element
+            .message(id_path, &mut state.child_state, message, app_state)
+    }
+}
+
+crate::interfaces::impl_dom_interfaces_for_ty!(Element, Draggable);
+
+/// Reads the payload and the files of `data_transfer`, file contents are read asynchronously,
+/// so the resulting message is sent when all of them are available.
+fn push_drop_data(data_transfer: &web_sys::DataTransfer, thunk: &Rc<MessageThunk>) {
+    let payload = data_transfer
+        .get_data(PAYLOAD_MIME)
+        .ok()
+        .filter(|payload| !payload.is_empty())
+        .or_else(|| data_transfer.get_data("text/plain").ok())
+        .filter(|payload| !payload.is_empty());
+    let files: Vec<web_sys::File> = data_transfer
+        .files()
+        .map(|files| (0..files.length()).filter_map(|i| files.get(i)).collect())
+        .unwrap_or_default();
+    if files.is_empty() {
+        thunk.push_message(DropData {
+            payload,
+            files: vec![],
+        });
+        return;
+    }
+    let thunk = Rc::clone(thunk);
+    wasm_bindgen_futures::spawn_local(async move {
+        let mut drop_data = DropData {
+            payload,
+            files: Vec::with_capacity(files.len()),
+        };
+        // Files that couldn't be read are skipped (with a warning), the same as with `file_input`
+        for file in files {
+            drop_data.files.extend(read_file(&file, |_| {}).await);
+        }
+        thunk.push_message(drop_data);
+    });
+}
+
+/// Accepts drops on the element, see [`Element::drop_target`].
+pub struct DropTarget<E, T, A, C> {
+    pub(crate) element: E,
+    pub(crate) handler: C,
+    pub(crate) phantom: PhantomData<fn() -> (T, A)>,
+}
+
+pub struct DropTargetState<S> {
+    // Retained, so that the listeners are removed when dropped
+    #[allow(unused)]
+    listeners: [EventListener; 2],
+    child_id: Id,
+    child_state: S,
+}
+
+fn create_drop_listeners(element: &web_sys::Node, cx: &Cx) -> [EventListener; 2] {
+    let thunk = Rc::new(cx.message_thunk());
+    // The default action of `dragover` has to be prevented, otherwise the element doesn't accept drops
+    let dragover = EventListener::new_with_options(
+        element,
+        "dragover",
+        EventListenerOptions::enable_prevent_default(),
+        |event| event.prevent_default(),
+    );
+    let drop = EventListener::new_with_options(
+        element,
+        "drop",
+        EventListenerOptions::enable_prevent_default(),
+        move |event| {
+            // Otherwise the browser would e.g. open a dropped file
+            event.prevent_default();
+            let event: &web_sys::DragEvent = event.unchecked_ref();
+            if let Some(data_transfer) = event.data_transfer() {
+                push_drop_data(&data_transfer, &thunk);
+            }
+        },
+    );
+    [dragover, drop]
+}
+
+impl<E, T, A, C> ViewMarker for DropTarget<E, T, A, C> {}
+impl<E, T, A, C> Sealed for DropTarget<E, T, A, C> {}
+
+impl<E, T, A, C, OA> View<T, A> for DropTarget<E, T, A, C>
+where
+    OA: OptionalAction<A>,
+    C: Fn(&mut T, DropData) -> OA,
+    E: Element<T, A>,
+{
+    type State = DropTargetState<E::State>;
+    type Element = E::Element;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, (element, state)) = cx.with_new_id(|cx| {
+            let (child_id, child_state, element) = self.element.build(cx);
+            let listeners = create_drop_listeners(element.as_node_ref(), cx);
+            let state = DropTargetState {
+                listeners,
+                child_id,
+                child_state,
+            };
+            (element, state)
+        });
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        cx.with_id(*id, |cx| {
+            let prev_child_id = state.child_id;
+            let mut changed = self.element.rebuild(
+                cx,
+                &prev.element,
+                &mut state.child_id,
+                &mut state.child_state,
+                element,
+            );
+            if state.child_id != prev_child_id {
+                changed |= ChangeFlags::OTHER_CHANGE;
+            }
+            if changed.contains(ChangeFlags::STRUCTURE) {
+                state.listeners = create_drop_listeners(element.as_node_ref(), cx);
+                changed |= ChangeFlags::LISTENERS;
+            }
+            changed
+        })
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Box<dyn Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        match id_path {
+            [] if message.downcast_ref::<DropData>().is_some() => {
+                let drop_data = message.downcast::<DropData>().unwrap();
+                match (self.handler)(app_state, *drop_data).action() {
+                    Some(a) => MessageResult::Action(a),
+                    None => MessageResult::Nop,
+                }
+            }
+            [element_id, rest_path @ ..] if *element_id == state.child_id => {
+                self.element
+                    .message(rest_path, &mut state.child_state, message, app_state)
+            }
+            _ => MessageResult::Stale(message),
+        }
+    }
+}
+
+crate::interfaces::impl_dom_interfaces_for_ty!(
+    Element,
+    DropTarget,
+    vars: <C, OA,>,
+    vars_on_ty: <C,>,
+    bounds: {
+        OA: OptionalAction<A>,
+        C: Fn(&mut T, DropData) -> OA,
+    }
+);
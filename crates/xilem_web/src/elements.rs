@@ -4,7 +4,7 @@ use wasm_bindgen::{JsCast, UnwrapThrowExt};
 use xilem_core::{Id, MessageResult, VecSplice};
 
 use crate::{
-    interfaces::sealed::Sealed, vecmap::VecMap, view::DomNode, AttributeValue, ChangeFlags, Cx,
+    context::ElementProps, interfaces::sealed::Sealed, transition, view::DomNode, ChangeFlags, Cx,
     ElementsSplice, Pod, View, ViewMarker, ViewSequence, HTML_NS,
 };
 
@@ -14,10 +14,10 @@ type CowStr = std::borrow::Cow<'static, str>;
 
 /// The state associated with a HTML element `View`.
 ///
-/// Stores handles to the child elements and any child state, as well as attributes and event listeners
+/// Stores handles to the child elements and any child state, as well as attributes, properties and event listeners
 pub struct ElementState<ViewSeqState> {
     pub(crate) children_states: ViewSeqState,
-    pub(crate) attributes: VecMap<CowStr, AttributeValue>,
+    pub(crate) props: ElementProps,
     pub(crate) child_elements: Vec<Pod>,
     /// This is temporary cache for elements while updating/diffing,
     /// after usage it shouldn't contain any elements,
@@ -53,59 +53,81 @@ impl<T, A, Children> CustomElement<T, A, Children> {
 }
 
 /// An `ElementsSplice` that does DOM updates in place
-struct ChildrenSplice<'a, 'b, 'c> {
+///
+/// The position of a child in the DOM is derived from the other children, not read from the DOM,
+/// as the DOM mutations are only applied after the (re)build, see [`DomOp`](crate::mutations::DomOp).
+pub(crate) struct ChildrenSplice<'a, 'b, 'c> {
     children: VecSplice<'a, 'b, Pod>,
-    child_idx: u32,
+    /// Whether the children are only a part of the children of `parent`, see [`ChildrenSplice::new_in_range`]
+    in_range: bool,
+    /// The (not managed) DOM node after the children, new children are inserted in front of it
+    end: Option<&'c web_sys::Node>,
     parent: &'c web_sys::Node,
-    node_list: Option<web_sys::NodeList>,
     prev_element_count: usize,
+    /// The DOM node of the child which is currently mutated, which is replaced, when the child returns a new node (see `mark`)
+    mutated_node: Option<web_sys::Node>,
 }
 
 impl<'a, 'b, 'c> ChildrenSplice<'a, 'b, 'c> {
-    fn new(
+    pub(crate) fn new(
         children: &'a mut Vec<Pod>,
         scratch: &'b mut Vec<Pod>,
         parent: &'c web_sys::Node,
+    ) -> Self {
+        Self::new_in_range(children, scratch, parent, None, None)
+    }
+
+    /// Like [`ChildrenSplice::new`], but the children are between the DOM nodes `start` and `end` of `parent`,
+    /// the nodes outside of that range are left untouched (e.g. static content of the `<head>`).
+    pub(crate) fn new_in_range(
+        children: &'a mut Vec<Pod>,
+        scratch: &'b mut Vec<Pod>,
+        parent: &'c web_sys::Node,
+        start: Option<&'c web_sys::Node>,
+        end: Option<&'c web_sys::Node>,
     ) -> Self {
         let prev_element_count = children.len();
         Self {
             children: VecSplice::new(children, scratch),
-            child_idx: 0,
+            in_range: start.is_some() || end.is_some(),
+            end,
             parent,
-            node_list: None,
             prev_element_count,
+            mutated_node: None,
         }
     }
 }
 
 impl<'a, 'b, 'c> ElementsSplice for ChildrenSplice<'a, 'b, 'c> {
-    fn push(&mut self, element: Pod, _cx: &mut Cx) {
-        self.parent
-            .append_child(element.0.as_node_ref())
-            .unwrap_throw();
-        self.child_idx += 1;
+    fn push(&mut self, element: Pod, cx: &mut Cx) {
+        // In front of the next remaining child, which may be followed by children that are still exiting
+        let before = self
+            .children
+            .peek(0)
+            .map(|next| next.0.as_node_ref())
+            .or(self.end);
+        cx.insert_node(self.parent, element.0.as_node_ref(), before);
         self.children.push(element);
     }
 
     fn mutate(&mut self, _cx: &mut Cx) -> &mut Pod {
-        self.children.mutate()
+        let pod = self.children.mutate();
+        self.mutated_node = Some(pod.0.as_node_ref().clone());
+        pod
     }
 
-    fn delete(&mut self, n: usize, _cx: &mut Cx) {
+    fn delete(&mut self, n: usize, cx: &mut Cx) {
         // Optimization in case all elements are deleted at once
-        if n == self.prev_element_count {
-            self.parent.set_text_content(None);
+        if n == self.prev_element_count
+            && self.children.is_empty()
+            && !self.in_range
+            && !transition::has_exit_transition_child(self.parent)
+        {
+            cx.set_text(self.parent, None);
         } else {
-            // lazy NodeList access, in case it's not necessary at all, which is slightly faster when there's no need for the NodeList
-            let node_list = if let Some(node_list) = &self.node_list {
-                node_list
-            } else {
-                self.node_list = Some(self.parent.child_nodes());
-                self.node_list.as_ref().unwrap()
-            };
-            for _ in 0..n {
-                let child = node_list.get(self.child_idx).unwrap_throw();
-                self.parent.remove_child(&child).unwrap_throw();
+            for idx in 0..n {
+                let child = self.children.peek(idx).unwrap_throw();
+                cx.remove_node(child.0.as_node_ref());
             }
         }
         self.children.delete(n);
@@ -115,23 +137,14 @@ impl<'a, 'b, 'c> ElementsSplice for ChildrenSplice<'a, 'b, 'c> {
         self.children.len()
     }
 
-    fn mark(&mut self, mut changeflags: ChangeFlags, _cx: &mut Cx) -> ChangeFlags {
+    fn mark(&mut self, mut changeflags: ChangeFlags, cx: &mut Cx) -> ChangeFlags {
+        let old_node = self.mutated_node.take().unwrap_throw();
         if changeflags.contains(ChangeFlags::STRUCTURE) {
-            let node_list = if let Some(node_list) = &self.node_list {
-                node_list
-            } else {
-                self.node_list = Some(self.parent.child_nodes());
-                self.node_list.as_ref().unwrap()
-            };
-            let cur_child = self.children.last_mutated_mut().unwrap_throw();
-            let old_child = node_list.get(self.child_idx).unwrap_throw();
-            self.parent
-                .replace_child(cur_child.0.as_node_ref(), &old_child)
-                .unwrap_throw();
+            let cur_child = self.children.last_mutated().unwrap_throw();
+            cx.replace_node(self.parent, &old_node, cur_child.0.as_node_ref());
             // TODO(#160) do something else with the structure information?
             changeflags.remove(ChangeFlags::STRUCTURE);
         }
-        self.child_idx += 1;
         changeflags
     }
 }
@@ -150,7 +163,7 @@ where
     type Element = web_sys::HtmlElement;
 
     fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
-        let (el, attributes) = cx.build_element(HTML_NS, &self.name);
+        let (el, props) = cx.build_element(HTML_NS, &self.name);
 
         let mut child_elements = vec![];
         let mut scratch = vec![];
@@ -163,15 +176,14 @@ where
         // Set the id used internally to the `data-debugid` attribute.
         // This allows the user to see if an element has been re-created or only altered.
         #[cfg(debug_assertions)]
-        el.set_attribute("data-debugid", &id.to_raw().to_string())
-            .unwrap_throw();
+        cx.set_attribute(&el, "data-debugid", Some(id.to_raw().to_string().into()));
 
         let el = el.dyn_into().unwrap_throw();
         let state = ElementState {
             children_states,
             child_elements,
             scratch,
-            attributes,
+            props,
         };
         (id, state, el)
     }
@@ -187,23 +199,19 @@ where
         let mut changed = ChangeFlags::empty();
 
         // update tag name
-        if prev.name != self.name {
-            // recreate element
-            let parent = element
-                .parent_element()
-                .expect_throw("this element was mounted and so should have a parent");
-            parent.remove_child(element).unwrap_throw();
-            let (new_element, attributes) = cx.build_element(HTML_NS, self.node_name());
-            state.attributes = attributes;
+        let recreated = prev.name != self.name;
+        if recreated {
+            // recreate element, it replaces the old one in the parent (via the returned `STRUCTURE` flag)
+            let (new_element, props) = cx.build_element(HTML_NS, self.node_name());
+            state.props = props;
             // TODO could this be combined with child updates?
-            while let Some(child) = element.child_nodes().get(0) {
-                new_element.append_child(&child).unwrap_throw();
+            for child in &state.child_elements {
+                cx.insert_node(&new_element, child.0.as_node_ref(), None);
             }
             *element = new_element.dyn_into().unwrap_throw();
-            changed |= ChangeFlags::STRUCTURE;
         }
 
-        changed |= cx.rebuild_element(element, &mut state.attributes);
+        changed |= cx.rebuild_element(element, &mut state.props);
 
         // update children
         let mut splice =
@@ -214,6 +222,9 @@ where
         });
         debug_assert!(state.scratch.is_empty());
         changed.remove(ChangeFlags::STRUCTURE);
+        if recreated {
+            changed |= ChangeFlags::STRUCTURE;
+        }
         changed
     }
 
@@ -280,7 +291,7 @@ macro_rules! define_element {
             type Element = web_sys::$dom_interface;
 
             fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
-                let (el, attributes) = cx.build_element($ns, $tag_name);
+                let (el, props) = cx.build_element($ns, $tag_name);
 
                 let mut child_elements = vec![];
                 let mut scratch = vec![];
@@ -292,15 +303,14 @@ macro_rules! define_element {
                 // Set the id used internally to the `data-debugid` attribute.
                 // This allows the user to see if an element has been re-created or only altered.
                 #[cfg(debug_assertions)]
-                el.set_attribute("data-debugid", &id.to_raw().to_string())
-                    .unwrap_throw();
+                cx.set_attribute(&el, "data-debugid", Some(id.to_raw().to_string().into()));
 
                 let el = el.dyn_into().unwrap_throw();
                 let state = ElementState {
                     children_states,
                     child_elements,
                     scratch,
-                    attributes,
+                    props,
                 };
                 (id, state, el)
             }
@@ -315,7 +325,7 @@ macro_rules! define_element {
             ) -> ChangeFlags {
                 let mut changed = ChangeFlags::empty();
 
-                changed |= cx.rebuild_element(element, &mut state.attributes);
+                changed |= cx.rebuild_element(element, &mut state.props);
 
                 // update children
                 let mut splice = ChildrenSplice::new(&mut state.child_elements, &mut state.scratch, element);
@@ -0,0 +1,282 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    any::Any,
+    borrow::Cow,
+    fmt::Display,
+    marker::PhantomData,
+    panic::{catch_unwind, AssertUnwindSafe},
+};
+
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    context::{ChangeFlags, Cx},
+    one_of::OneOf2,
+    view::{View, ViewMarker},
+    Action,
+};
+
+type CowStr = Cow<'static, str>;
+
+/// The action of the fallback view of an [`error_boundary`], which tries to show the child view again.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ResetErrorBoundary;
+
+impl Action for ResetErrorBoundary {}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> CowStr {
+    match payload.downcast::<&'static str>() {
+        Ok(message) => (*message).into(),
+        Err(payload) => match payload.downcast::<String>() {
+            Ok(message) => (*message).into(),
+            Err(_) => "unknown panic".into(),
+        },
+    }
+}
+
+/// Runs `f`, and recovers the context when it panics.
+fn catch<R>(cx: &mut Cx, f: impl FnOnce(&mut Cx) -> R) -> Result<R, CowStr> {
+    let depth = cx.depth();
+    catch_unwind(AssertUnwindSafe(|| f(cx))).map_err(|payload| {
+        cx.recover_from_panic(depth);
+        panic_message(payload)
+    })
+}
+
+/// Shows a fallback view instead of the child view, when it failed, see [`error_boundary`].
+pub struct ErrorBoundary<V, F, T, A> {
+    child: Result<V, CowStr>,
+    fallback: F,
+    phantom: PhantomData<fn() -> (T, A)>,
+}
+
+/// Shows the view returned by `fallback` with the panic message, instead of the `child` view, when building or rebuilding it panics.
+///
+/// The fallback view is shown until it emits the action [`ResetErrorBoundary`] (e.g. with a "Retry" button),
+/// after which the child view is built again.
+///
+/// Panics can only be caught when unwinding is supported, which isn't the case on `wasm32-unknown-unknown` by default (it aborts instead).
+/// Failures which can be anticipated should therefore rather be handled with [`error_boundary_result`].
+///
+/// ```ignore
+/// error_boundary(chart_view(&state.data), |message| {
+///     el::div((
+///         format!("The chart couldn't be shown: {message}"),
+///         el::button("Retry").on_click(|_, _| ResetErrorBoundary),
+///     ))
+/// })
+/// ```
+pub fn error_boundary<V, F, FV, T, A>(child: V, fallback: F) -> ErrorBoundary<V, F, T, A>
+where
+    V: View<T, A>,
+    F: Fn(&str) -> FV,
+    FV: View<T, ResetErrorBoundary>,
+{
+    ErrorBoundary {
+        child: Ok(child),
+        fallback,
+        phantom: PhantomData,
+    }
+}
+
+/// Like [`error_boundary`], but the child view is fallible, the fallback view is shown with the error, as long as `child` is `Err`.
+pub fn error_boundary_result<V, E, F, FV, T, A>(
+    child: Result<V, E>,
+    fallback: F,
+) -> ErrorBoundary<V, F, T, A>
+where
+    V: View<T, A>,
+    E: Display,
+    F: Fn(&str) -> FV,
+    FV: View<T, ResetErrorBoundary>,
+{
+    ErrorBoundary {
+        child: child.map_err(|err| err.to_string().into()),
+        fallback,
+        phantom: PhantomData,
+    }
+}
+
+pub struct ErrorBoundaryState<VS, FV, FS> {
+    child_id: Id,
+    /// The state of either the child view, or the fallback view, which is retained for rebuilding it.
+    child: OneOf2<VS, (FV, FS)>,
+    /// The error the fallback view is shown with.
+    error: CowStr,
+    /// Whether the fallback view is shown because of a panic, in which case it's shown until it's reset.
+    panicked: bool,
+    reset: bool,
+}
+
+type ChildState<V, FV, T, A> =
+    OneOf2<<V as View<T, A>>::State, (FV, <FV as View<T, ResetErrorBoundary>>::State)>;
+type ChildElement<V, FV, T, A> =
+    OneOf2<<V as View<T, A>>::Element, <FV as View<T, ResetErrorBoundary>>::Element>;
+
+impl<V, F, T, A> ViewMarker for ErrorBoundary<V, F, T, A> {}
+
+impl<V, F, FV, T, A> ErrorBoundary<V, F, T, A>
+where
+    V: View<T, A>,
+    F: Fn(&str) -> FV,
+    FV: View<T, ResetErrorBoundary>,
+{
+    fn build_child(
+        &self,
+        cx: &mut Cx,
+    ) -> Result<(Id, ChildState<V, FV, T, A>, ChildElement<V, FV, T, A>), CowStr> {
+        let child = self.child.as_ref().map_err(Clone::clone)?;
+        let (id, state, element) = catch(cx, |cx| child.build(cx))?;
+        Ok((id, OneOf2::A(state), OneOf2::A(element)))
+    }
+
+    fn build_fallback(
+        &self,
+        cx: &mut Cx,
+        error: &str,
+    ) -> (Id, ChildState<V, FV, T, A>, ChildElement<V, FV, T, A>) {
+        let view = (self.fallback)(error);
+        let (id, state, element) = view.build(cx);
+        (id, OneOf2::B((view, state)), OneOf2::B(element))
+    }
+
+    /// Shows the fallback view with `state.error`, either by rebuilding it, or by replacing the child view.
+    fn show_fallback(
+        &self,
+        cx: &mut Cx,
+        state: &mut ErrorBoundaryState<V::State, FV, FV::State>,
+        element: &mut ChildElement<V, FV, T, A>,
+    ) -> ChangeFlags {
+        if let (OneOf2::B((prev_view, fallback_state)), OneOf2::B(fallback_element)) =
+            (&mut state.child, &mut *element)
+        {
+            let view = (self.fallback)(&state.error);
+            let changed = view.rebuild(
+                cx,
+                prev_view,
+                &mut state.child_id,
+                fallback_state,
+                fallback_element,
+            );
+            *prev_view = view;
+            return changed;
+        }
+        let (child_id, child, fallback_element) = self.build_fallback(cx, &state.error);
+        state.child_id = child_id;
+        state.child = child;
+        *element = fallback_element;
+        ChangeFlags::STRUCTURE
+    }
+}
+
+impl<V, F, FV, T, A> View<T, A> for ErrorBoundary<V, F, T, A>
+where
+    V: View<T, A>,
+    F: Fn(&str) -> FV,
+    FV: View<T, ResetErrorBoundary>,
+{
+    type State = ErrorBoundaryState<V::State, FV, FV::State>;
+    type Element = ChildElement<V, FV, T, A>;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, (state, element)) = cx.with_new_id(|cx| {
+            let (error, (child_id, child, element)) = match self.build_child(cx) {
+                Ok(child) => (CowStr::default(), child),
+                Err(error) => {
+                    let fallback = self.build_fallback(cx, &error);
+                    (error, fallback)
+                }
+            };
+            let state = ErrorBoundaryState {
+                child_id,
+                child,
+                panicked: self.child.is_ok() && !error.is_empty(),
+                error,
+                reset: false,
+            };
+            (state, element)
+        });
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        cx.with_id(*id, |cx| {
+            let reset = std::mem::take(&mut state.reset);
+            if let (OneOf2::A(child_state), OneOf2::A(child_element), Ok(child), Ok(prev_child)) =
+                (&mut state.child, &mut *element, &self.child, &prev.child)
+            {
+                let child_id = &mut state.child_id;
+                match catch(cx, |cx| {
+                    child.rebuild(cx, prev_child, child_id, child_state, child_element)
+                }) {
+                    Ok(changed) => return changed,
+                    Err(error) => {
+                        state.error = error;
+                        state.panicked = true;
+                    }
+                }
+            } else if let Err(error) = &self.child {
+                state.error = error.clone();
+                state.panicked = false;
+            } else if !state.panicked || reset {
+                // The child view is shown (again), unless it panics
+                match self.build_child(cx) {
+                    Ok((child_id, child, child_element)) => {
+                        state.child_id = child_id;
+                        state.child = child;
+                        state.error = CowStr::default();
+                        state.panicked = false;
+                        *element = child_element;
+                        return ChangeFlags::STRUCTURE;
+                    }
+                    Err(error) => {
+                        state.error = error;
+                        state.panicked = true;
+                    }
+                }
+            }
+            self.show_fallback(cx, state, element)
+        })
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Box<dyn std::any::Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        let Some((first, rest)) = id_path.split_first() else {
+            return MessageResult::Stale(message);
+        };
+        if *first != state.child_id {
+            return MessageResult::Stale(message);
+        }
+        match (&mut state.child, &self.child) {
+            (OneOf2::A(child_state), Ok(child)) => {
+                child.message(rest, child_state, message, app_state)
+            }
+            (OneOf2::B((view, fallback_state)), _) => {
+                match view.message(rest, fallback_state, message, app_state) {
+                    MessageResult::Action(ResetErrorBoundary) => {
+                        state.reset = true;
+                        MessageResult::Nop
+                    }
+                    MessageResult::Nop => MessageResult::Nop,
+                    MessageResult::RequestRebuild => MessageResult::RequestRebuild,
+                    MessageResult::Stale(message) => MessageResult::Stale(message),
+                }
+            }
+            _ => MessageResult::Stale(message),
+        }
+    }
+}
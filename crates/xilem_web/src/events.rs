@@ -1,14 +1,72 @@
 use crate::{
     interfaces::{sealed::Sealed, Element},
+    timer::Timeout,
     view::DomNode,
     ChangeFlags, Cx, OptionalAction, View, ViewMarker,
 };
-use std::{any::Any, borrow::Cow, marker::PhantomData};
+use std::{any::Any, borrow::Cow, cell::RefCell, marker::PhantomData, rc::Rc};
 use wasm_bindgen::{JsCast, UnwrapThrowExt};
 use xilem_core::{Id, MessageResult};
 
 pub use gloo::events::EventListenerOptions;
 
+/// When events are delivered to the app, see e.g. [`OnEvent::debounced`] and [`OnEvent::throttled`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum Delivery {
+    #[default]
+    Immediate,
+    /// Only the last event is delivered, after no event happened for the given time (in ms).
+    Debounced(u32),
+    /// At most one event is delivered per interval (in ms), the last event within an interval is delivered at its end.
+    Throttled(u32),
+}
+
+/// What's done synchronously during the dispatch of the event, see e.g. [`OnEvent::prevent_default`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Dispatch {
+    prevent_default: bool,
+    stop_propagation: bool,
+}
+
+macro_rules! listener_builder_methods {
+    () => {
+        /// Delays delivering the event until no other event occurred for `ms` milliseconds,
+        /// only the last event is delivered then.
+        ///
+        /// This is useful e.g. for search inputs, to avoid handling every keystroke.
+        /// Note that the event is delivered after the event dispatch, so calling `prevent_default` in the handler has no effect,
+        /// use [`prevent_default`](Self::prevent_default) instead.
+        pub fn debounced(mut self, ms: u32) -> Self {
+            self.delivery = Delivery::Debounced(ms);
+            self
+        }
+
+        /// Delivers at most one event every `ms` milliseconds,
+        /// the last event within that interval is delivered at its end.
+        ///
+        /// This is useful for high-frequency events like `scroll` or `mousemove`.
+        pub fn throttled(mut self, ms: u32) -> Self {
+            self.delivery = Delivery::Throttled(ms);
+            self
+        }
+
+        /// Calls `event.prevent_default()` on each event, before it's delivered to the app.
+        ///
+        /// This makes the event listener non-passive, regardless of [`passive`](Self::passive),
+        /// and works with debounced and throttled delivery as well.
+        pub fn prevent_default(mut self) -> Self {
+            self.dispatch.prevent_default = true;
+            self
+        }
+
+        /// Calls `event.stop_propagation()` on each event, before it's delivered to the app.
+        pub fn stop_propagation(mut self) -> Self {
+            self.dispatch.stop_propagation = true;
+            self
+        }
+    };
+}
+
 /// Wraps a [`View`] `V` and attaches an event listener.
 ///
 /// The event type `E` should inherit from [`web_sys::Event`]
@@ -16,6 +74,8 @@ pub struct OnEvent<E, T, A, Ev, C> {
     pub(crate) element: E,
     pub(crate) event: Cow<'static, str>,
     pub(crate) options: EventListenerOptions,
+    pub(crate) delivery: Delivery,
+    pub(crate) dispatch: Dispatch,
     pub(crate) handler: C,
     #[allow(clippy::type_complexity)]
     pub(crate) phantom_event_ty: PhantomData<fn() -> (T, A, Ev)>,
@@ -30,6 +90,8 @@ where
             element,
             event: event.into(),
             options: Default::default(),
+            delivery: Delivery::Immediate,
+            dispatch: Dispatch::default(),
             handler,
             phantom_event_ty: PhantomData,
         }
@@ -45,6 +107,8 @@ where
             element,
             event: event.into(),
             options,
+            delivery: Delivery::Immediate,
+            dispatch: Dispatch::default(),
             handler,
             phantom_event_ty: PhantomData,
         }
@@ -59,22 +123,88 @@ where
         self.options.passive = value;
         self
     }
+
+    listener_builder_methods!();
+}
+
+struct ThrottleState<Ev> {
+    last_delivery: f64,
+    trailing: Option<Ev>,
 }
 
-fn create_event_listener<Ev: JsCast + 'static>(
+pub(crate) fn create_event_listener<Ev: JsCast + 'static>(
     target: &web_sys::EventTarget,
     event: impl Into<Cow<'static, str>>,
-    options: EventListenerOptions,
+    mut options: EventListenerOptions,
+    delivery: Delivery,
+    dispatch: Dispatch,
     cx: &Cx,
 ) -> gloo::events::EventListener {
-    let thunk = cx.message_thunk();
+    let event = event.into();
+    if dispatch.prevent_default {
+        options.passive = false;
+    }
+    #[cfg(debug_assertions)]
+    crate::a11y::register_listener(target, &event);
+    let thunk = Rc::new(cx.message_thunk());
+    // The pending timeout is owned by the listener, so it's cleared when the listener is dropped
+    let mut pending: Option<Timeout> = None;
+    let throttle = Rc::new(RefCell::new(ThrottleState {
+        last_delivery: f64::NEG_INFINITY,
+        trailing: None,
+    }));
     gloo::events::EventListener::new_with_options(
         target,
         event,
         options,
         move |event: &web_sys::Event| {
+            if dispatch.prevent_default {
+                event.prevent_default();
+            }
+            if dispatch.stop_propagation {
+                event.stop_propagation();
+            }
             let event = (*event).clone().dyn_into::<Ev>().unwrap_throw();
-            thunk.push_message(event);
+            match delivery {
+                Delivery::Immediate => thunk.push_message(event),
+                Delivery::Debounced(ms) => {
+                    let thunk = Rc::clone(&thunk);
+                    let mut event = Some(event);
+                    // replacing the previous timeout clears it
+                    pending.replace(Timeout::new(ms as i32, move || {
+                        if let Some(event) = event.take() {
+                            thunk.push_message(event);
+                        }
+                    }));
+                }
+                Delivery::Throttled(ms) => {
+                    let now = js_sys::Date::now();
+                    let mut state = throttle.borrow_mut();
+                    let is_scheduled = state.trailing.is_some();
+                    if !is_scheduled && now - state.last_delivery >= ms as f64 {
+                        state.last_delivery = now;
+                        drop(state);
+                        thunk.push_message(event);
+                        return;
+                    }
+                    state.trailing = Some(event);
+                    if !is_scheduled {
+                        let delay = (state.last_delivery + ms as f64 - now).max(0.0);
+                        let throttle = Rc::clone(&throttle);
+                        let thunk = Rc::clone(&thunk);
+                        pending.replace(Timeout::new(delay as i32, move || {
+                            let event = {
+                                let mut state = throttle.borrow_mut();
+                                state.last_delivery = js_sys::Date::now();
+                                state.trailing.take()
+                            };
+                            if let Some(event) = event {
+                                thunk.push_message(event);
+                            }
+                        }));
+                    }
+                }
+            }
         },
     )
 }
@@ -108,6 +238,8 @@ where
                 element.as_node_ref(),
                 self.event.clone(),
                 self.options,
+                self.delivery,
+                self.dispatch,
                 cx,
             );
             let state = OnEventState {
@@ -141,14 +273,20 @@ where
                 changed |= ChangeFlags::OTHER_CHANGE;
             }
             // TODO check equality of prev and current element somehow
-            if prev.event != self.event || changed.contains(ChangeFlags::STRUCTURE) {
+            if prev.event != self.event
+                || prev.delivery != self.delivery
+                || prev.dispatch != self.dispatch
+                || changed.contains(ChangeFlags::STRUCTURE)
+            {
                 state.listener = create_event_listener::<Ev>(
                     element.as_node_ref(),
                     self.event.clone(),
                     self.options,
+                    self.delivery,
+                    self.dispatch,
                     cx,
                 );
-                changed |= ChangeFlags::OTHER_CHANGE;
+                changed |= ChangeFlags::LISTENERS;
             }
             changed
         })
@@ -190,6 +328,502 @@ crate::interfaces::impl_dom_interfaces_for_ty!(
     }
 );
 
+/// Handles events of descendants with a single listener, see [`Element::on_delegated`].
+pub struct OnDelegatedEvent<E, T, A, Ev, C> {
+    element: E,
+    event: Cow<'static, str>,
+    key_attribute: Cow<'static, str>,
+    handler: C,
+    #[allow(clippy::type_complexity)]
+    phantom: PhantomData<fn() -> (T, A, Ev)>,
+}
+
+impl<E, T, A, Ev, C> OnDelegatedEvent<E, T, A, Ev, C> {
+    pub fn new(
+        element: E,
+        event: impl Into<Cow<'static, str>>,
+        key_attribute: impl Into<Cow<'static, str>>,
+        handler: C,
+    ) -> Self {
+        OnDelegatedEvent {
+            element,
+            event: event.into(),
+            key_attribute: key_attribute.into(),
+            handler,
+            phantom: PhantomData,
+        }
+    }
+}
+
+/// State for the `OnDelegatedEvent` view.
+pub struct OnDelegatedEventState<S> {
+    #[allow(unused)]
+    listener: gloo::events::EventListener,
+    container: web_sys::Node,
+    child_id: Id,
+    child_state: S,
+}
+
+/// The value of `attribute` of the closest ancestor of the event target within `container`, which has this attribute.
+fn delegated_key(
+    event: &web_sys::Event,
+    container: &web_sys::Node,
+    attribute: &str,
+) -> Option<String> {
+    let target: web_sys::Node = event.target()?.dyn_into().ok()?;
+    let target = match target.dyn_into::<web_sys::Element>() {
+        Ok(element) => element,
+        // e.g. a text node
+        Err(node) => node.parent_element()?,
+    };
+    let owner = target.closest(&format!("[{attribute}]")).ok()??;
+    if !container.contains(Some(&owner)) {
+        return None;
+    }
+    owner.get_attribute(attribute)
+}
+
+impl<E, T, A, Ev, C> ViewMarker for OnDelegatedEvent<E, T, A, Ev, C> {}
+impl<E, T, A, Ev, C> Sealed for OnDelegatedEvent<E, T, A, Ev, C> {}
+
+impl<E, T, A, Ev, C, OA> View<T, A> for OnDelegatedEvent<E, T, A, Ev, C>
+where
+    OA: OptionalAction<A>,
+    C: Fn(&mut T, Ev, &str) -> OA,
+    E: Element<T, A>,
+    Ev: JsCast + AsRef<web_sys::Event> + 'static,
+{
+    type State = OnDelegatedEventState<E::State>;
+
+    type Element = E::Element;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, (element, state)) = cx.with_new_id(|cx| {
+            let (child_id, child_state, element) = self.element.build(cx);
+            let listener = create_event_listener::<Ev>(
+                element.as_node_ref(),
+                self.event.clone(),
+                Default::default(),
+                Delivery::Immediate,
+                Dispatch::default(),
+                cx,
+            );
+            let state = OnDelegatedEventState {
+                listener,
+                container: element.as_node_ref().clone(),
+                child_id,
+                child_state,
+            };
+            (element, state)
+        });
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        cx.with_id(*id, |cx| {
+            let mut changed = self.element.rebuild(
+                cx,
+                &prev.element,
+                &mut state.child_id,
+                &mut state.child_state,
+                element,
+            );
+            if prev.event != self.event || changed.contains(ChangeFlags::STRUCTURE) {
+                state.listener = create_event_listener::<Ev>(
+                    element.as_node_ref(),
+                    self.event.clone(),
+                    Default::default(),
+                    Delivery::Immediate,
+                    Dispatch::default(),
+                    cx,
+                );
+                state.container = element.as_node_ref().clone();
+                changed |= ChangeFlags::LISTENERS;
+            }
+            changed
+        })
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Box<dyn Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        match id_path {
+            [] if message.downcast_ref::<Ev>().is_some() => {
+                let event = *message.downcast::<Ev>().unwrap();
+                let Some(key) =
+                    delegated_key(event.as_ref(), &state.container, &self.key_attribute)
+                else {
+                    return MessageResult::Nop;
+                };
+                match (self.handler)(app_state, event, &key).action() {
+                    Some(a) => MessageResult::Action(a),
+                    None => MessageResult::Nop,
+                }
+            }
+            [element_id, rest_path @ ..] if *element_id == state.child_id => {
+                self.element
+                    .message(rest_path, &mut state.child_state, message, app_state)
+            }
+            _ => MessageResult::Stale(message),
+        }
+    }
+}
+
+crate::interfaces::impl_dom_interfaces_for_ty!(
+    Element,
+    OnDelegatedEvent,
+    vars: <Ev, C, OA,>,
+    vars_on_ty: <Ev, C,>,
+    bounds: {
+        Ev: JsCast + AsRef<web_sys::Event> + 'static,
+        OA: OptionalAction<A>,
+        C: Fn(&mut T, Ev, &str) -> OA,
+    }
+);
+
+/// The message of [`OnClickOrDblClick`].
+enum ClickMsg {
+    Single(web_sys::MouseEvent),
+    Double(web_sys::MouseEvent),
+}
+
+/// Distinguishes single clicks from double clicks, see [`Element::on_click_or_dblclick`].
+pub struct OnClickOrDblClick<E, T, A, S, D> {
+    element: E,
+    single: S,
+    double: D,
+    delay_ms: u32,
+    phantom: PhantomData<fn() -> (T, A)>,
+}
+
+impl<E, T, A, S, D> OnClickOrDblClick<E, T, A, S, D> {
+    pub fn new(element: E, single: S, double: D, delay_ms: u32) -> Self {
+        OnClickOrDblClick {
+            element,
+            single,
+            double,
+            delay_ms,
+            phantom: PhantomData,
+        }
+    }
+}
+
+/// A single click is only delivered after `delay_ms` without a second click,
+/// otherwise the second click is delivered as double click.
+fn create_click_or_dblclick_listener(
+    target: &web_sys::EventTarget,
+    delay_ms: u32,
+    cx: &Cx,
+) -> gloo::events::EventListener {
+    #[cfg(debug_assertions)]
+    crate::a11y::register_listener(target, "click");
+    let thunk = Rc::new(cx.message_thunk());
+    let mut pending: Option<Timeout> = None;
+    let is_pending = Rc::new(std::cell::Cell::new(false));
+    gloo::events::EventListener::new(target, "click", move |event: &web_sys::Event| {
+        let event: web_sys::MouseEvent = event.clone().unchecked_into();
+        if is_pending.replace(false) {
+            // clears the pending single click
+            pending.take();
+            thunk.push_message(ClickMsg::Double(event));
+        } else {
+            is_pending.set(true);
+            let is_pending = Rc::clone(&is_pending);
+            let thunk = Rc::clone(&thunk);
+            let mut event = Some(event);
+            pending.replace(Timeout::new(delay_ms as i32, move || {
+                is_pending.set(false);
+                if let Some(event) = event.take() {
+                    thunk.push_message(ClickMsg::Single(event));
+                }
+            }));
+        }
+    })
+}
+
+impl<E, T, A, S, D> ViewMarker for OnClickOrDblClick<E, T, A, S, D> {}
+impl<E, T, A, S, D> Sealed for OnClickOrDblClick<E, T, A, S, D> {}
+
+impl<E, T, A, S, D, OA> View<T, A> for OnClickOrDblClick<E, T, A, S, D>
+where
+    OA: OptionalAction<A>,
+    S: Fn(&mut T, web_sys::MouseEvent) -> OA,
+    D: Fn(&mut T, web_sys::MouseEvent) -> OA,
+    E: Element<T, A>,
+{
+    type State = OnEventState<E::State>;
+
+    type Element = E::Element;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, (element, state)) = cx.with_new_id(|cx| {
+            let (child_id, child_state, element) = self.element.build(cx);
+            let listener =
+                create_click_or_dblclick_listener(element.as_node_ref(), self.delay_ms, cx);
+            let state = OnEventState {
+                child_state,
+                child_id,
+                listener,
+            };
+            (element, state)
+        });
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        cx.with_id(*id, |cx| {
+            let prev_child_id = state.child_id;
+            let mut changed = self.element.rebuild(
+                cx,
+                &prev.element,
+                &mut state.child_id,
+                &mut state.child_state,
+                element,
+            );
+            if state.child_id != prev_child_id {
+                changed |= ChangeFlags::OTHER_CHANGE;
+            }
+            if prev.delay_ms != self.delay_ms || changed.contains(ChangeFlags::STRUCTURE) {
+                state.listener =
+                    create_click_or_dblclick_listener(element.as_node_ref(), self.delay_ms, cx);
+                changed |= ChangeFlags::LISTENERS;
+            }
+            changed
+        })
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Box<dyn Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        match id_path {
+            [] if message.downcast_ref::<ClickMsg>().is_some() => {
+                let action = match *message.downcast::<ClickMsg>().unwrap() {
+                    ClickMsg::Single(event) => (self.single)(app_state, event),
+                    ClickMsg::Double(event) => (self.double)(app_state, event),
+                };
+                match action.action() {
+                    Some(a) => MessageResult::Action(a),
+                    None => MessageResult::Nop,
+                }
+            }
+            [element_id, rest_path @ ..] if *element_id == state.child_id => {
+                self.element
+                    .message(rest_path, &mut state.child_state, message, app_state)
+            }
+            _ => MessageResult::Stale(message),
+        }
+    }
+}
+
+crate::interfaces::impl_dom_interfaces_for_ty!(
+    Element,
+    OnClickOrDblClick,
+    vars: <S, D, OA,>,
+    vars_on_ty: <S, D,>,
+    bounds: {
+        OA: OptionalAction<A>,
+        S: Fn(&mut T, web_sys::MouseEvent) -> OA,
+        D: Fn(&mut T, web_sys::MouseEvent) -> OA,
+    }
+);
+
+/// The global object an event listener of [`GlobalEvent`] is attached to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum GlobalTarget {
+    Window,
+    Document,
+}
+
+impl GlobalTarget {
+    fn event_target(self) -> web_sys::EventTarget {
+        match self {
+            GlobalTarget::Window => web_sys::window().unwrap_throw().into(),
+            GlobalTarget::Document => crate::document().into(),
+        }
+    }
+}
+
+/// Listens to events on `window` or `document`, see e.g. [`on_window_event`].
+///
+/// It doesn't create any visible content, in the DOM it's represented by an (empty) comment node,
+/// the listener is removed when this view is removed from the view tree.
+pub struct GlobalEvent<T, A, Ev, C> {
+    target: GlobalTarget,
+    event: Cow<'static, str>,
+    options: EventListenerOptions,
+    delivery: Delivery,
+    dispatch: Dispatch,
+    handler: C,
+    phantom: PhantomData<fn() -> (T, A, Ev)>,
+}
+
+impl<T, A, Ev, C> GlobalEvent<T, A, Ev, C> {
+    fn new(target: GlobalTarget, event: impl Into<Cow<'static, str>>, handler: C) -> Self {
+        GlobalEvent {
+            target,
+            event: event.into(),
+            options: Default::default(),
+            delivery: Delivery::Immediate,
+            dispatch: Dispatch::default(),
+            handler,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Whether the event handler should be passive. (default = `true`)
+    ///
+    /// Passive event handlers can't prevent the browser's default action from
+    /// running (otherwise possible with `event.prevent_default()`), which
+    /// restricts what they can be used for, but reduces overhead.
+    pub fn passive(mut self, value: bool) -> Self {
+        self.options.passive = value;
+        self
+    }
+
+    listener_builder_methods!();
+}
+
+/// Calls `handler` for each `event` on `window`, e.g. `on_window_event("keydown", |state, e: web_sys::KeyboardEvent| ...)` for global shortcuts.
+pub fn on_window_event<T, A, Ev, C, OA>(
+    event: impl Into<Cow<'static, str>>,
+    handler: C,
+) -> GlobalEvent<T, A, Ev, C>
+where
+    Ev: JsCast + 'static,
+    OA: OptionalAction<A>,
+    C: Fn(&mut T, Ev) -> OA,
+{
+    GlobalEvent::new(GlobalTarget::Window, event, handler)
+}
+
+/// Calls `handler` for each `event` on `document`, e.g. `"visibilitychange"`.
+pub fn on_document_event<T, A, Ev, C, OA>(
+    event: impl Into<Cow<'static, str>>,
+    handler: C,
+) -> GlobalEvent<T, A, Ev, C>
+where
+    Ev: JsCast + 'static,
+    OA: OptionalAction<A>,
+    C: Fn(&mut T, Ev) -> OA,
+{
+    GlobalEvent::new(GlobalTarget::Document, event, handler)
+}
+
+/// Calls `handler` when the window is resized, the new size can be read with e.g. `window.inner_width()`.
+pub fn on_resize_window<T, A, C, OA>(handler: C) -> GlobalEvent<T, A, web_sys::Event, C>
+where
+    OA: OptionalAction<A>,
+    C: Fn(&mut T, web_sys::Event) -> OA,
+{
+    on_window_event("resize", handler)
+}
+
+/// Calls `handler` when the fragment identifier (`#...`) of the url changes.
+pub fn on_hash_change<T, A, C, OA>(handler: C) -> GlobalEvent<T, A, web_sys::HashChangeEvent, C>
+where
+    OA: OptionalAction<A>,
+    C: Fn(&mut T, web_sys::HashChangeEvent) -> OA,
+{
+    on_window_event("hashchange", handler)
+}
+
+impl<T, A, Ev, C> ViewMarker for GlobalEvent<T, A, Ev, C> {}
+
+impl<T, A, Ev, C, OA> View<T, A> for GlobalEvent<T, A, Ev, C>
+where
+    Ev: JsCast + 'static,
+    OA: OptionalAction<A>,
+    C: Fn(&mut T, Ev) -> OA,
+{
+    type State = gloo::events::EventListener;
+    type Element = web_sys::Comment;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, listener) = cx.with_new_id(|cx| {
+            create_event_listener::<Ev>(
+                &self.target.event_target(),
+                self.event.clone(),
+                self.options,
+                self.delivery,
+                self.dispatch,
+                cx,
+            )
+        });
+        let el = cx.create_comment(&self.event);
+        (id, listener, el)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        if prev.target != self.target
+            || prev.event != self.event
+            || prev.options.passive != self.options.passive
+            || prev.delivery != self.delivery
+            || prev.dispatch != self.dispatch
+        {
+            *state = cx.with_id(*id, |cx| {
+                create_event_listener::<Ev>(
+                    &self.target.event_target(),
+                    self.event.clone(),
+                    self.options,
+                    self.delivery,
+                    self.dispatch,
+                    cx,
+                )
+            });
+            cx.set_text(element, Some(self.event.clone()));
+        }
+        ChangeFlags::empty()
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        _state: &mut Self::State,
+        message: Box<dyn Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        match id_path {
+            [] if message.downcast_ref::<Ev>().is_some() => {
+                let event = message.downcast::<Ev>().unwrap();
+                match (self.handler)(app_state, *event).action() {
+                    Some(a) => MessageResult::Action(a),
+                    None => MessageResult::Nop,
+                }
+            }
+            _ => MessageResult::Stale(message),
+        }
+    }
+}
+
 macro_rules! event_definitions {
     ($(($ty_name:ident, $event_name:literal, $web_sys_ty:ident)),*) => {
         $(
@@ -208,6 +842,8 @@ macro_rules! event_definitions {
             target: E,
             callback: C,
             options: EventListenerOptions,
+            delivery: Delivery,
+            dispatch: Dispatch,
             phantom: PhantomData<fn() -> (T, A)>,
         }
 
@@ -216,6 +852,8 @@ macro_rules! event_definitions {
                 Self {
                     target,
                     options: Default::default(),
+                    delivery: Delivery::Immediate,
+                    dispatch: Dispatch::default(),
                     callback,
                     phantom: PhantomData,
                 }
@@ -230,6 +868,8 @@ macro_rules! event_definitions {
                 self.options.passive = value;
                 self
             }
+
+            listener_builder_methods!();
         }
 
         impl<E, T, A, C> ViewMarker for $ty_name<E, T, A, C> {}
@@ -248,7 +888,7 @@ macro_rules! event_definitions {
             fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
                 let (id, (element, state)) = cx.with_new_id(|cx| {
                     let (child_id, child_state, el) = self.target.build(cx);
-                    let listener = create_event_listener::<web_sys::$web_sys_ty>(el.as_node_ref(), $event_name, self.options, cx);
+                    let listener = create_event_listener::<web_sys::$web_sys_ty>(el.as_node_ref(), $event_name, self.options, self.delivery, self.dispatch, cx);
                     (el, OnEventState { child_state, child_id, listener })
                 });
                 (id, state, element)
@@ -269,9 +909,9 @@ macro_rules! event_definitions {
                         changed |= ChangeFlags::OTHER_CHANGE;
                     }
                     // TODO check equality of prev and current element somehow
-                    if changed.contains(ChangeFlags::STRUCTURE) {
-                        state.listener = create_event_listener::<web_sys::$web_sys_ty>(element.as_node_ref(), $event_name, self.options, cx);
-                        changed |= ChangeFlags::OTHER_CHANGE;
+                    if prev.delivery != self.delivery || prev.dispatch != self.dispatch || changed.contains(ChangeFlags::STRUCTURE) {
+                        state.listener = create_event_listener::<web_sys::$web_sys_ty>(element.as_node_ref(), $event_name, self.options, self.delivery, self.dispatch, cx);
+                        changed |= ChangeFlags::LISTENERS;
                     }
                     changed
                 })
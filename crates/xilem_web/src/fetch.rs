@@ -0,0 +1,203 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Fetching a resource via HTTP, owned by a view, see [`fetch_resource`].
+
+use std::{borrow::Cow, marker::PhantomData};
+
+use wasm_bindgen::{JsCast, JsValue, UnwrapThrowExt};
+use wasm_bindgen_futures::JsFuture;
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    context::{ChangeFlags, Cx},
+    view::{View, ViewMarker},
+    OptionalAction,
+};
+
+type CowStr = Cow<'static, str>;
+
+/// How the body of the response is parsed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResponseFormat {
+    Text,
+    Json,
+    Bytes,
+}
+
+/// The parsed body of a successful response, depending on the [`ResponseFormat`].
+#[derive(Clone, Debug)]
+pub enum FetchBody {
+    Text(String),
+    Json(JsValue),
+    Bytes(Vec<u8>),
+}
+
+#[derive(Clone, Debug)]
+pub enum FetchError {
+    /// The request failed, e.g. because of a network error or CORS.
+    Network(JsValue),
+    /// The server responded with a status which isn't in the range 200-299.
+    Status(u16),
+    /// The body couldn't be read or parsed (e.g. invalid JSON).
+    Body(JsValue),
+}
+
+/// The message sent to the view when the request completed.
+struct FetchResult(Result<FetchBody, FetchError>);
+
+/// An in-flight request, which is aborted when dropped.
+struct InFlight(web_sys::AbortController);
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        // No-op if the request already completed
+        self.0.abort();
+    }
+}
+
+async fn fetch(
+    url: &str,
+    format: ResponseFormat,
+    signal: &web_sys::AbortSignal,
+) -> Result<FetchBody, FetchError> {
+    let mut init = web_sys::RequestInit::new();
+    init.signal(Some(signal));
+    let promise = web_sys::window()
+        .unwrap_throw()
+        .fetch_with_str_and_init(url, &init);
+    let response: web_sys::Response = JsFuture::from(promise)
+        .await
+        .map_err(FetchError::Network)?
+        .unchecked_into();
+    if !response.ok() {
+        return Err(FetchError::Status(response.status()));
+    }
+    let body = match format {
+        ResponseFormat::Text => response.text(),
+        ResponseFormat::Json => response.json(),
+        ResponseFormat::Bytes => response.array_buffer(),
+    }
+    .map_err(FetchError::Body)?;
+    let body = JsFuture::from(body).await.map_err(FetchError::Body)?;
+    Ok(match format {
+        ResponseFormat::Text => FetchBody::Text(body.as_string().unwrap_throw()),
+        ResponseFormat::Json => FetchBody::Json(body),
+        ResponseFormat::Bytes => FetchBody::Bytes(js_sys::Uint8Array::new(&body).to_vec()),
+    })
+}
+
+/// Fetches a resource when built, or when its url changed, see [`fetch_resource`].
+pub struct FetchResource<T, A, F> {
+    url: CowStr,
+    format: ResponseFormat,
+    callback: F,
+    phantom: PhantomData<fn() -> (T, A)>,
+}
+
+/// Fetches `url` via HTTP, and calls `callback` with the parsed response, by default as text (see [`FetchResource::format`]).
+///
+/// The request is issued when the view is built, and again when it's rebuilt with a different url (or format).
+/// The in-flight request is aborted when it's replaced by a new request, or when the view is removed from the view tree,
+/// so `callback` is only called with the result of the current url.
+///
+/// ```ignore
+/// (
+///     fetch_resource(format!("/api/users/{}", state.user_id), |state: &mut AppState, result| {
+///         state.user = result.ok();
+///     })
+///     .format(ResponseFormat::Json),
+///     user_view(state),
+/// )
+/// ```
+pub fn fetch_resource<T, A, F, OA>(url: impl Into<CowStr>, callback: F) -> FetchResource<T, A, F>
+where
+    F: Fn(&mut T, Result<FetchBody, FetchError>) -> OA,
+    OA: OptionalAction<A>,
+{
+    FetchResource {
+        url: url.into(),
+        format: ResponseFormat::Text,
+        callback,
+        phantom: PhantomData,
+    }
+}
+
+impl<T, A, F> FetchResource<T, A, F> {
+    /// How the body of the response is parsed (default = [`ResponseFormat::Text`]).
+    pub fn format(mut self, format: ResponseFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    fn start(&self, cx: &Cx) -> InFlight {
+        let controller = web_sys::AbortController::new().unwrap_throw();
+        let signal = controller.signal();
+        let (url, format, thunk) = (self.url.clone(), self.format, cx.message_thunk());
+        wasm_bindgen_futures::spawn_local(async move {
+            let result = fetch(&url, format, &signal).await;
+            // The result of a replaced request must not be delivered, even when it completed before it was aborted
+            if !signal.aborted() {
+                thunk.push_message(FetchResult(result));
+            }
+        });
+        InFlight(controller)
+    }
+}
+
+pub struct FetchResourceState {
+    // Retained, so that the request is aborted when the state is dropped
+    #[allow(unused)]
+    in_flight: InFlight,
+}
+
+impl<T, A, F> ViewMarker for FetchResource<T, A, F> {}
+
+impl<T, A, F, OA> View<T, A> for FetchResource<T, A, F>
+where
+    F: Fn(&mut T, Result<FetchBody, FetchError>) -> OA,
+    OA: OptionalAction<A>,
+{
+    type State = FetchResourceState;
+    type Element = web_sys::Comment;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, in_flight) = cx.with_new_id(|cx| self.start(cx));
+        let element = cx.create_comment("fetch_resource");
+        (id, FetchResourceState { in_flight }, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        _element: &mut Self::Element,
+    ) -> ChangeFlags {
+        if prev.url != self.url || prev.format != self.format {
+            // The previous request is aborted
+            state.in_flight = cx.with_id(*id, |cx| self.start(cx));
+        }
+        ChangeFlags::empty()
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        _state: &mut Self::State,
+        message: Box<dyn std::any::Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        match id_path {
+            [] if message.downcast_ref::<FetchResult>().is_some() => {
+                let FetchResult(result) = *message.downcast().unwrap();
+                match (self.callback)(app_state, result).action() {
+                    Some(a) => MessageResult::Action(a),
+                    None => MessageResult::Nop,
+                }
+            }
+            _ => MessageResult::Stale(message),
+        }
+    }
+}
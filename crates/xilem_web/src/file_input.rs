@@ -0,0 +1,231 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Selecting and reading files, see [`file_input`].
+
+use std::{any::Any, borrow::Cow, marker::PhantomData, rc::Rc};
+
+use gloo::events::EventListener;
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    context::{ChangeFlags, Cx, ElementProps, MessageThunk},
+    form::{read_file, FormFile},
+    interfaces::sealed::Sealed,
+    view::{View, ViewMarker},
+    IntoAttributeValue, OptionalAction, HTML_NS,
+};
+
+type CowStr = Cow<'static, str>;
+
+macro_rules! generate_dom_interface_impl {
+    ($dom_interface:ident, ()) => {
+        impl<T, A, F, P, OA> $crate::interfaces::$dom_interface<T, A> for FileInput<T, A, F, P>
+        where
+            OA: OptionalAction<A>,
+            F: Fn(&mut T, Vec<FormFile>) -> OA,
+            P: Fn(&mut T, FileProgress),
+        {
+        }
+    };
+}
+
+/// The progress of reading the selected files of a [`file_input`], see [`FileInput::on_progress`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileProgress {
+    /// The name of the file which is currently read.
+    pub name: String,
+    /// The index of the file which is currently read.
+    pub index: usize,
+    /// The number of selected files.
+    pub count: usize,
+    /// The number of bytes of the current file which are read.
+    pub loaded: u64,
+    /// The size of the current file in bytes.
+    pub total: u64,
+}
+
+enum FileInputMsg {
+    Progress(FileProgress),
+    Loaded(Vec<FormFile>),
+}
+
+/// An `<input type="file">`, which reads the selected files, see [`file_input`].
+pub struct FileInput<T, A, F, P = fn(&mut T, FileProgress)> {
+    accept: CowStr,
+    multiple: bool,
+    handler: F,
+    on_progress: Option<P>,
+    phantom: PhantomData<fn() -> (T, A)>,
+}
+
+/// An `<input type="file">`, the contents of the selected files are read asynchronously,
+/// and `handler` is called with them when all of them are read.
+///
+/// `accept` restricts the selectable files (e.g. `"image/*,.pdf"`, or `""` for any file), and `multiple` allows selecting more than one file.
+/// The input is cleared after the files were read, so that the same file can be selected again.
+///
+/// ```ignore
+/// file_input("image/*", true, |state: &mut Gallery, files| {
+///     state.images.extend(files.into_iter().map(Image::from_file));
+/// })
+/// .on_progress(|state, progress| {
+///     state.upload_progress = Some(progress.loaded as f64 / progress.total.max(1) as f64);
+/// })
+/// ```
+pub fn file_input<T, A, F, OA>(
+    accept: impl Into<CowStr>,
+    multiple: bool,
+    handler: F,
+) -> FileInput<T, A, F>
+where
+    OA: OptionalAction<A>,
+    F: Fn(&mut T, Vec<FormFile>) -> OA,
+{
+    FileInput {
+        accept: accept.into(),
+        multiple,
+        handler,
+        on_progress: None,
+        phantom: PhantomData,
+    }
+}
+
+impl<T, A, F> FileInput<T, A, F> {
+    /// Calls `on_progress` while the selected files are read, which is useful to show the progress of large files.
+    pub fn on_progress<P: Fn(&mut T, FileProgress)>(self, on_progress: P) -> FileInput<T, A, F, P> {
+        FileInput {
+            accept: self.accept,
+            multiple: self.multiple,
+            handler: self.handler,
+            on_progress: Some(on_progress),
+            phantom: PhantomData,
+        }
+    }
+}
+
+/// Reads the selected files one after another, and sends the progress and finally the contents as messages.
+fn read_files(files: Vec<web_sys::File>, thunk: Rc<MessageThunk>) {
+    wasm_bindgen_futures::spawn_local(async move {
+        let count = files.len();
+        let mut loaded_files = Vec::with_capacity(count);
+        for (index, file) in files.into_iter().enumerate() {
+            let progress_thunk = Rc::clone(&thunk);
+            let (name, total) = (file.name(), file.size() as u64);
+            let on_progress = move |loaded| {
+                progress_thunk.push_message(FileInputMsg::Progress(FileProgress {
+                    name: name.clone(),
+                    index,
+                    count,
+                    loaded,
+                    total,
+                }));
+            };
+            // Files that couldn't be read are skipped (with a warning), the same as with drop targets
+            loaded_files.extend(read_file(&file, on_progress).await);
+        }
+        thunk.push_message(FileInputMsg::Loaded(loaded_files));
+    });
+}
+
+pub struct FileInputState {
+    props: ElementProps,
+    // Retained, so that the listener is removed when dropped
+    #[allow(unused)]
+    change_listener: EventListener,
+}
+
+impl<T, A, F, P> FileInput<T, A, F, P> {
+    fn add_attributes(&self, cx: &mut Cx) {
+        cx.add_attr_to_element(&"type".into(), &"file".into_attr_value());
+        let accept = (!self.accept.is_empty()).then(|| self.accept.clone());
+        cx.add_attr_to_element(&"accept".into(), &accept.into_attr_value());
+        cx.add_attr_to_element(&"multiple".into(), &self.multiple.into_attr_value());
+    }
+}
+
+generate_dom_interface_impl!(HtmlInputElement, ());
+crate::interfaces::for_all_html_input_element_ancestors!(generate_dom_interface_impl, ());
+
+impl<T, A, F, P> ViewMarker for FileInput<T, A, F, P> {}
+impl<T, A, F, P> Sealed for FileInput<T, A, F, P> {}
+
+impl<T, A, F, P, OA> View<T, A> for FileInput<T, A, F, P>
+where
+    OA: OptionalAction<A>,
+    F: Fn(&mut T, Vec<FormFile>) -> OA,
+    P: Fn(&mut T, FileProgress),
+{
+    type State = FileInputState;
+    type Element = web_sys::HtmlInputElement;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, (input, state)) = cx.with_new_id(|cx| {
+            self.add_attributes(cx);
+            let (el, props) = cx.build_element(HTML_NS, "input");
+            let input: web_sys::HtmlInputElement = el.unchecked_into();
+            let thunk = Rc::new(cx.message_thunk());
+            let change_listener = EventListener::new(&input, "change", move |event| {
+                let input: web_sys::HtmlInputElement =
+                    event.current_target().unwrap_throw().unchecked_into();
+                let files: Vec<web_sys::File> = input
+                    .files()
+                    .map(|files| (0..files.length()).filter_map(|i| files.get(i)).collect())
+                    .unwrap_or_default();
+                // The files are retained above, so the input can be cleared to allow selecting the same files again
+                input.set_value("");
+                if !files.is_empty() {
+                    read_files(files, Rc::clone(&thunk));
+                }
+            });
+            let state = FileInputState {
+                props,
+                change_listener,
+            };
+            (input, state)
+        });
+        (id, state, input)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        _prev: &Self,
+        _id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        self.add_attributes(cx);
+        cx.rebuild_element(element, &mut state.props)
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        _state: &mut Self::State,
+        message: Box<dyn Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        match id_path {
+            [] if message.downcast_ref::<FileInputMsg>().is_some() => {
+                match *message.downcast::<FileInputMsg>().unwrap() {
+                    FileInputMsg::Progress(progress) => match &self.on_progress {
+                        Some(on_progress) => {
+                            on_progress(app_state, progress);
+                            MessageResult::RequestRebuild
+                        }
+                        None => MessageResult::Nop,
+                    },
+                    FileInputMsg::Loaded(files) => {
+                        match (self.handler)(app_state, files).action() {
+                            Some(a) => MessageResult::Action(a),
+                            None => MessageResult::Nop,
+                        }
+                    }
+                }
+            }
+            _ => MessageResult::Stale(message),
+        }
+    }
+}
@@ -0,0 +1,260 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::marker::PhantomData;
+
+use gloo::events::{EventListener, EventListenerOptions};
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    interfaces::{sealed::Sealed, HtmlElement},
+    timer::Timeout,
+    view::DomNode,
+    ChangeFlags, Cx, View, ViewMarker,
+};
+
+/// The elements that can receive the focus via `Tab`, used by [`FocusTrap`].
+const FOCUSABLE_SELECTOR: &str = "a[href], area[href], button:not([disabled]), input:not([disabled]), \
+    select:not([disabled]), textarea:not([disabled]), iframe, [contenteditable], [tabindex]:not([tabindex=\"-1\"])";
+
+/// The element has to be in the document to be focused, so this is done after the current (re)build.
+fn schedule_focus(el: &web_sys::HtmlElement) -> Timeout {
+    let el = el.clone();
+    Timeout::new(0, move || {
+        let _ = el.focus();
+    })
+}
+
+/// Focuses the element when `focus` changes to `true`, see [`HtmlElement::focus_when`] and [`HtmlElement::autofocus`].
+pub struct FocusWhen<E, T, A> {
+    element: E,
+    focus: bool,
+    phantom: PhantomData<fn() -> (T, A)>,
+}
+
+impl<E, T, A> FocusWhen<E, T, A> {
+    pub fn new(element: E, focus: bool) -> Self {
+        FocusWhen {
+            element,
+            focus,
+            phantom: PhantomData,
+        }
+    }
+}
+
+pub struct FocusWhenState<S> {
+    child_state: S,
+    #[allow(unused)]
+    pending_focus: Option<Timeout>,
+}
+
+impl<E, T, A> ViewMarker for FocusWhen<E, T, A> {}
+impl<E, T, A> Sealed for FocusWhen<E, T, A> {}
+
+impl<E: HtmlElement<T, A>, T, A> View<T, A> for FocusWhen<E, T, A> {
+    type State = FocusWhenState<E::State>;
+    type Element = E::Element;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, child_state, element) = self.element.build(cx);
+        let pending_focus = self
+            .focus
+            .then(|| schedule_focus(element.as_node_ref().unchecked_ref()));
+        let state = FocusWhenState {
+            child_state,
+            pending_focus,
+        };
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let changed = self
+            .element
+            .rebuild(cx, &prev.element, id, &mut state.child_state, element);
+        // A recreated element has lost the focus
+        if self.focus && (!prev.focus || changed.contains(ChangeFlags::STRUCTURE)) {
+            state.pending_focus = Some(schedule_focus(element.as_node_ref().unchecked_ref()));
+        }
+        changed
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Box<dyn std::any::Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        self.element
+            .message(id_path, &mut state.child_state, message, app_state)
+    }
+}
+
+crate::interfaces::impl_dom_interfaces_for_ty!(HtmlElement, FocusWhen);
+
+/// Keeps the focus within the element, see [`HtmlElement::focus_trap`].
+pub struct FocusTrap<E, T, A> {
+    element: E,
+    phantom: PhantomData<fn() -> (T, A)>,
+}
+
+impl<E, T, A> FocusTrap<E, T, A> {
+    pub fn new(element: E) -> Self {
+        FocusTrap {
+            element,
+            phantom: PhantomData,
+        }
+    }
+}
+
+/// Restores the focus to the element that had it before the trap was built, when dropped.
+pub(crate) struct RestoreFocus(Option<web_sys::HtmlElement>);
+
+impl RestoreFocus {
+    /// Restores the focus to the currently focused element.
+    pub(crate) fn active_element() -> Self {
+        RestoreFocus(
+            crate::document()
+                .active_element()
+                .and_then(|el| el.dyn_into().ok()),
+        )
+    }
+}
+
+impl Drop for RestoreFocus {
+    fn drop(&mut self) {
+        if let Some(el) = &self.0 {
+            let _ = el.focus();
+        }
+    }
+}
+
+pub struct FocusTrapState<S> {
+    child_state: S,
+    #[allow(unused)]
+    listener: EventListener,
+    #[allow(unused)]
+    initial_focus: Timeout,
+    #[allow(unused)]
+    restore_focus: RestoreFocus,
+}
+
+fn focusable_elements(container: &web_sys::Element) -> Vec<web_sys::HtmlElement> {
+    let nodes = container
+        .query_selector_all(FOCUSABLE_SELECTOR)
+        .unwrap_throw();
+    (0..nodes.length())
+        .filter_map(|idx| nodes.item(idx))
+        .map(|node| node.unchecked_into())
+        .collect()
+}
+
+/// Focuses the first focusable element within the trap, or the container itself.
+fn focus_first(container: &web_sys::HtmlElement) {
+    match focusable_elements(container).first() {
+        Some(el) => {
+            let _ = el.focus();
+        }
+        None => {
+            let _ = container.focus();
+        }
+    }
+}
+
+pub(crate) fn create_tab_listener(container: &web_sys::HtmlElement) -> EventListener {
+    let target = container.clone();
+    let container = container.clone();
+    EventListener::new_with_options(
+        &target,
+        "keydown",
+        EventListenerOptions::enable_prevent_default(),
+        move |event| {
+            let event: &web_sys::KeyboardEvent = event.unchecked_ref();
+            if event.key() != "Tab" {
+                return;
+            }
+            let focusable = focusable_elements(&container);
+            let (Some(first), Some(last)) = (focusable.first(), focusable.last()) else {
+                // There's nothing to move the focus to
+                event.prevent_default();
+                return;
+            };
+            let active = crate::document().active_element();
+            let is_active = |el: &web_sys::HtmlElement| {
+                active
+                    .as_ref()
+                    .is_some_and(|active| active == el.unchecked_ref::<web_sys::Element>())
+            };
+            if event.shift_key() && (is_active(first) || is_active(&container)) {
+                event.prevent_default();
+                let _ = last.focus();
+            } else if !event.shift_key() && is_active(last) {
+                event.prevent_default();
+                let _ = first.focus();
+            }
+        },
+    )
+}
+
+impl<E, T, A> ViewMarker for FocusTrap<E, T, A> {}
+impl<E, T, A> Sealed for FocusTrap<E, T, A> {}
+
+impl<E: HtmlElement<T, A>, T, A> View<T, A> for FocusTrap<E, T, A> {
+    type State = FocusTrapState<E::State>;
+    type Element = E::Element;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let restore_focus = RestoreFocus::active_element();
+        let (id, child_state, element) = self.element.build(cx);
+        let el: &web_sys::HtmlElement = element.as_node_ref().unchecked_ref();
+        let container = el.clone();
+        let state = FocusTrapState {
+            child_state,
+            listener: create_tab_listener(el),
+            initial_focus: Timeout::new(0, move || focus_first(&container)),
+            restore_focus,
+        };
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let changed = self
+            .element
+            .rebuild(cx, &prev.element, id, &mut state.child_state, element);
+        if changed.contains(ChangeFlags::STRUCTURE) {
+            let el: &web_sys::HtmlElement = element.as_node_ref().unchecked_ref();
+            let container = el.clone();
+            state.listener = create_tab_listener(el);
+            state.initial_focus = Timeout::new(0, move || focus_first(&container));
+        }
+        changed
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Box<dyn std::any::Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        self.element
+            .message(id_path, &mut state.child_state, message, app_state)
+    }
+}
+
+crate::interfaces::impl_dom_interfaces_for_ty!(HtmlElement, FocusTrap);
@@ -0,0 +1,302 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Handling of uncontrolled forms, see [`HtmlFormElement::on_submit_form`](crate::interfaces::HtmlFormElement::on_submit_form).
+
+use std::{any::Any, marker::PhantomData, rc::Rc};
+
+use gloo::events::{EventListener, EventListenerOptions};
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use wasm_bindgen_futures::JsFuture;
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    context::{ChangeFlags, Cx},
+    interfaces::{sealed::Sealed, HtmlFormElement},
+    view::{DomNode, View, ViewMarker},
+    OptionalAction,
+};
+
+/// A file with its contents, which was selected in an `<input type="file">` of a submitted form or a [`file_input`](crate::file_input),
+/// or which was dropped on a [drop target](crate::interfaces::Element::drop_target).
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormFile {
+    pub name: String,
+    pub mime_type: String,
+    /// The time of the last modification in milliseconds since the UNIX epoch.
+    pub last_modified: f64,
+    pub bytes: Vec<u8>,
+}
+
+/// Reads `file` via a `FileReader`, so that the progress can be reported via `on_progress` (with the number of read bytes).
+///
+/// A file that couldn't be read is logged as warning, and `None` is returned, callers skip such files.
+pub(crate) async fn read_file(
+    file: &web_sys::File,
+    on_progress: impl Fn(u64) + 'static,
+) -> Option<FormFile> {
+    let bytes = read_file_contents(file, on_progress).await;
+    if bytes.is_none() {
+        web_sys::console::warn_1(&format!("could not read file {}", file.name()).into());
+    }
+    Some(FormFile {
+        name: file.name(),
+        mime_type: file.type_(),
+        last_modified: file.last_modified(),
+        bytes: bytes?,
+    })
+}
+
+async fn read_file_contents(
+    file: &web_sys::File,
+    on_progress: impl Fn(u64) + 'static,
+) -> Option<Vec<u8>> {
+    let reader = web_sys::FileReader::new().ok()?;
+    // Retained until the file is read
+    let _progress_listener = EventListener::new(&reader, "progress", move |event| {
+        let event: &web_sys::ProgressEvent = event.unchecked_ref();
+        on_progress(event.loaded() as u64);
+    });
+    let loaded = js_sys::Promise::new(&mut |resolve, reject| {
+        reader.set_onload(Some(&resolve));
+        reader.set_onerror(Some(&reject));
+    });
+    reader.read_as_array_buffer(file).ok()?;
+    let result = JsFuture::from(loaded).await;
+    reader.set_onload(None);
+    reader.set_onerror(None);
+    result.ok()?;
+    let buffer = reader.result().ok()?;
+    Some(js_sys::Uint8Array::new(&buffer).to_vec())
+}
+
+/// A single value of a submitted form.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormDataValue {
+    Text(String),
+    File(FormFile),
+}
+
+/// The (name, value) entries of a submitted form, in the same order as in the `FormData` of the browser.
+///
+/// A name can occur multiple times (e.g. with `<select multiple>`), use [`TypedFormData::get_all`] in that case.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TypedFormData {
+    entries: Vec<(String, FormDataValue)>,
+}
+
+impl TypedFormData {
+    /// Returns the first value with the given `name`.
+    pub fn get(&self, name: &str) -> Option<&FormDataValue> {
+        self.get_all(name).next()
+    }
+
+    /// Returns all values with the given `name`.
+    pub fn get_all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a FormDataValue> + 'a {
+        self.entries
+            .iter()
+            .filter(move |(entry_name, _)| entry_name == name)
+            .map(|(_, value)| value)
+    }
+
+    /// Returns the first value with the given `name`, if it's text.
+    pub fn text(&self, name: &str) -> Option<&str> {
+        match self.get(name)? {
+            FormDataValue::Text(text) => Some(text),
+            FormDataValue::File(_) => None,
+        }
+    }
+
+    /// Returns the first value with the given `name`, if it's a file.
+    pub fn file(&self, name: &str) -> Option<&FormFile> {
+        match self.get(name)? {
+            FormDataValue::File(file) => Some(file),
+            FormDataValue::Text(_) => None,
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &FormDataValue)> {
+        self.entries
+            .iter()
+            .map(|(name, value)| (name.as_str(), value))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Reads all entries of `form_data`, file contents are read asynchronously,
+/// so the resulting message is sent when all of them are available.
+fn push_form_data(form_data: &web_sys::FormData, thunk: &Rc<crate::context::MessageThunk>) {
+    // The values of files are missing until they're read, and files that couldn't be read are skipped
+    let mut entries: Vec<(String, Option<FormDataValue>)> = vec![];
+    let mut files = vec![];
+    let iter = js_sys::try_iter(form_data)
+        .unwrap_throw()
+        .expect_throw("FormData should be iterable");
+    for entry in iter {
+        let entry: js_sys::Array = entry.unwrap_throw().unchecked_into();
+        let name = entry.get(0).as_string().unwrap_throw();
+        let value = match entry.get(1).dyn_into::<web_sys::File>() {
+            Ok(file) => {
+                files.push((entries.len(), file));
+                None
+            }
+            Err(value) => Some(FormDataValue::Text(value.as_string().unwrap_or_default())),
+        };
+        entries.push((name, value));
+    }
+
+    let thunk = Rc::clone(thunk);
+    let push_entries = move |entries: Vec<(String, Option<FormDataValue>)>| {
+        let entries = entries
+            .into_iter()
+            .filter_map(|(name, value)| Some((name, value?)))
+            .collect();
+        thunk.push_message(TypedFormData { entries });
+    };
+    if files.is_empty() {
+        push_entries(entries);
+        return;
+    }
+    wasm_bindgen_futures::spawn_local(async move {
+        for (idx, file) in files {
+            entries[idx].1 = read_file(&file, |_| {}).await.map(FormDataValue::File);
+        }
+        push_entries(entries);
+    });
+}
+
+fn create_submit_listener(form: &web_sys::Node, cx: &Cx) -> EventListener {
+    let thunk = Rc::new(cx.message_thunk());
+    let form: web_sys::HtmlFormElement = form.clone().unchecked_into();
+    EventListener::new_with_options(
+        &form.clone(),
+        "submit",
+        EventListenerOptions::enable_prevent_default(),
+        move |event| {
+            event.prevent_default();
+            let form_data = web_sys::FormData::new_with_form(&form).unwrap_throw();
+            push_form_data(&form_data, &thunk);
+        },
+    )
+}
+
+/// Handles the submission of a form by collecting all of its values, see [`HtmlFormElement::on_submit_form`].
+pub struct OnSubmitForm<E, T, A, C> {
+    element: E,
+    handler: C,
+    phantom: PhantomData<fn() -> (T, A)>,
+}
+
+impl<E, T, A, C> OnSubmitForm<E, T, A, C> {
+    pub fn new(element: E, handler: C) -> Self {
+        OnSubmitForm {
+            element,
+            handler,
+            phantom: PhantomData,
+        }
+    }
+}
+
+/// State for the `OnSubmitForm` view.
+pub struct OnSubmitFormState<S> {
+    #[allow(unused)]
+    listener: EventListener,
+    child_id: Id,
+    child_state: S,
+}
+
+impl<E, T, A, C> ViewMarker for OnSubmitForm<E, T, A, C> {}
+impl<E, T, A, C> Sealed for OnSubmitForm<E, T, A, C> {}
+
+impl<E, T, A, C, OA> View<T, A> for OnSubmitForm<E, T, A, C>
+where
+    OA: OptionalAction<A>,
+    C: Fn(&mut T, TypedFormData) -> OA,
+    E: HtmlFormElement<T, A>,
+{
+    type State = OnSubmitFormState<E::State>;
+    type Element = E::Element;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, (element, state)) = cx.with_new_id(|cx| {
+            let (child_id, child_state, element) = self.element.build(cx);
+            let listener = create_submit_listener(element.as_node_ref(), cx);
+            let state = OnSubmitFormState {
+                listener,
+                child_id,
+                child_state,
+            };
+            (element, state)
+        });
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        cx.with_id(*id, |cx| {
+            let prev_child_id = state.child_id;
+            let mut changed = self.element.rebuild(
+                cx,
+                &prev.element,
+                &mut state.child_id,
+                &mut state.child_state,
+                element,
+            );
+            if state.child_id != prev_child_id {
+                changed |= ChangeFlags::OTHER_CHANGE;
+            }
+            if changed.contains(ChangeFlags::STRUCTURE) {
+                state.listener = create_submit_listener(element.as_node_ref(), cx);
+                changed |= ChangeFlags::LISTENERS;
+            }
+            changed
+        })
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Box<dyn Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        match id_path {
+            [] if message.downcast_ref::<TypedFormData>().is_some() => {
+                let form_data = message.downcast::<TypedFormData>().unwrap();
+                match (self.handler)(app_state, *form_data).action() {
+                    Some(a) => MessageResult::Action(a),
+                    None => MessageResult::Nop,
+                }
+            }
+            [element_id, rest_path @ ..] if *element_id == state.child_id => {
+                self.element
+                    .message(rest_path, &mut state.child_state, message, app_state)
+            }
+            _ => MessageResult::Stale(message),
+        }
+    }
+}
+
+crate::interfaces::impl_dom_interfaces_for_ty!(
+    HtmlFormElement,
+    OnSubmitForm,
+    vars: <C, OA,>,
+    vars_on_ty: <C,>,
+    bounds: {
+        OA: OptionalAction<A>,
+        C: Fn(&mut T, TypedFormData) -> OA,
+    }
+);
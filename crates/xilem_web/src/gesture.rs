@@ -0,0 +1,505 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Recognition of high-level gestures (tap, double tap, long press, swipe and pinch) from pointer events.
+//!
+//! The recognized gesture is selected via the type parameter `G` of [`OnGesture`],
+//! usually through the methods on [`Element`], like [`Element::on_long_press`].
+//! Only messages for that gesture are sent to the app.
+//!
+//! Swipe and pinch gestures with touch input need the CSS `touch-action: none` on the element,
+//! otherwise the browser handles them itself (scrolling/zooming), and cancels the pointers.
+
+use std::{
+    any::Any,
+    cell::RefCell,
+    marker::PhantomData,
+    rc::{Rc, Weak},
+};
+
+use gloo::events::EventListener;
+use wasm_bindgen::JsCast;
+use web_sys::PointerEvent;
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    context::{ChangeFlags, Cx, MessageThunk},
+    interfaces::Element,
+    timer::Timeout,
+    view::{DomNode, View, ViewMarker},
+    OptionalAction,
+};
+
+/// Thresholds used to recognize gestures.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GestureOptions {
+    /// How long a pointer has to be held down without moving for a long press (in ms).
+    pub long_press_ms: i32,
+    /// The maximum time between two taps of a double tap (in ms).
+    pub double_tap_ms: f64,
+    /// How far (in px) a pointer can move while still being a tap or long press.
+    pub tap_slop: f64,
+    /// The minimum distance (in px) of a swipe.
+    pub swipe_min_distance: f64,
+    /// The maximum duration of a swipe (in ms).
+    pub swipe_max_ms: f64,
+}
+
+impl Default for GestureOptions {
+    fn default() -> Self {
+        GestureOptions {
+            long_press_ms: 500,
+            double_tap_ms: 300.0,
+            tap_slop: 10.0,
+            swipe_min_distance: 50.0,
+            swipe_max_ms: 500.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SwipeDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// The position (in client coordinates) of a tap, double tap or long press.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GesturePoint {
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[doc(hidden)]
+pub enum GestureKind {
+    Tap,
+    DoubleTap,
+    LongPress,
+    Swipe,
+    Pinch,
+}
+
+/// The message sent by the event listeners, it's converted to [`Gesture::Msg`] in [`View::message`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[doc(hidden)]
+pub enum GestureMsg {
+    Point(GesturePoint),
+    Swipe(SwipeDirection),
+    /// The scale relative to the distance of the two pointers at the start of the pinch.
+    Pinch(f64),
+}
+
+/// A gesture that can be recognized by [`OnGesture`].
+pub trait Gesture: 'static {
+    /// The value the callback is called with.
+    type Msg;
+
+    #[doc(hidden)]
+    const KIND: GestureKind;
+
+    #[doc(hidden)]
+    fn from_msg(msg: GestureMsg) -> Option<Self::Msg>;
+}
+
+macro_rules! point_gesture {
+    ($name:ident, $doc:expr) => {
+        #[doc = $doc]
+        pub struct $name;
+
+        impl Gesture for $name {
+            type Msg = GesturePoint;
+
+            const KIND: GestureKind = GestureKind::$name;
+
+            fn from_msg(msg: GestureMsg) -> Option<Self::Msg> {
+                match msg {
+                    GestureMsg::Point(point) => Some(point),
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+point_gesture!(
+    Tap,
+    "A pointer is pressed and released without moving (much)."
+);
+point_gesture!(DoubleTap, "Two taps in quick succession.");
+point_gesture!(
+    LongPress,
+    "A pointer is held down without moving (much) for a while, the message is sent while the pointer is still down."
+);
+
+/// A pointer quickly moved in one direction.
+pub struct Swipe;
+
+impl Gesture for Swipe {
+    type Msg = SwipeDirection;
+
+    const KIND: GestureKind = GestureKind::Swipe;
+
+    fn from_msg(msg: GestureMsg) -> Option<Self::Msg> {
+        match msg {
+            GestureMsg::Swipe(direction) => Some(direction),
+            _ => None,
+        }
+    }
+}
+
+/// Two pointers moving apart or towards each other, the message is the scale relative to the start of the pinch.
+pub struct Pinch;
+
+impl Gesture for Pinch {
+    type Msg = f64;
+
+    const KIND: GestureKind = GestureKind::Pinch;
+
+    fn from_msg(msg: GestureMsg) -> Option<Self::Msg> {
+        match msg {
+            GestureMsg::Pinch(scale) => Some(scale),
+            _ => None,
+        }
+    }
+}
+
+struct TrackedPointer {
+    id: i32,
+    start_x: f64,
+    start_y: f64,
+    x: f64,
+    y: f64,
+    start_time: f64,
+}
+
+impl TrackedPointer {
+    fn distance_moved(&self) -> f64 {
+        (self.x - self.start_x).hypot(self.y - self.start_y)
+    }
+}
+
+struct Tracker {
+    kind: GestureKind,
+    options: GestureOptions,
+    pointers: Vec<TrackedPointer>,
+    pinch_start_distance: Option<f64>,
+    /// time and position of the last tap (for double taps)
+    last_tap: Option<(f64, GesturePoint)>,
+    long_press_timer: Option<Timeout>,
+    long_pressed: bool,
+}
+
+impl Tracker {
+    fn pointer_distance(&self) -> f64 {
+        let [a, b, ..] = &self.pointers[..] else {
+            return 0.0;
+        };
+        (a.x - b.x).hypot(a.y - b.y)
+    }
+
+    fn down(&mut self, e: &PointerEvent, tracker: Weak<RefCell<Tracker>>, thunk: Rc<MessageThunk>) {
+        let (x, y) = (e.client_x() as f64, e.client_y() as f64);
+        self.pointers.push(TrackedPointer {
+            id: e.pointer_id(),
+            start_x: x,
+            start_y: y,
+            x,
+            y,
+            start_time: e.time_stamp(),
+        });
+        self.long_pressed = false;
+        self.long_press_timer = None;
+        match (self.kind, self.pointers.len()) {
+            (GestureKind::LongPress, 1) => {
+                self.long_press_timer = Some(Timeout::new(self.options.long_press_ms, move || {
+                    let Some(tracker) = tracker.upgrade() else {
+                        return;
+                    };
+                    let msg = tracker.borrow_mut().long_press();
+                    if let Some(msg) = msg {
+                        thunk.push_message(msg);
+                    }
+                }));
+            }
+            (GestureKind::Pinch, 2) => self.pinch_start_distance = Some(self.pointer_distance()),
+            _ => {}
+        }
+    }
+
+    fn long_press(&mut self) -> Option<GestureMsg> {
+        let [pointer] = &self.pointers[..] else {
+            return None;
+        };
+        if pointer.distance_moved() > self.options.tap_slop {
+            return None;
+        }
+        self.long_pressed = true;
+        Some(GestureMsg::Point(GesturePoint {
+            x: pointer.x,
+            y: pointer.y,
+        }))
+    }
+
+    fn moved(&mut self, e: &PointerEvent) -> Option<GestureMsg> {
+        let pointer = self.pointers.iter_mut().find(|p| p.id == e.pointer_id())?;
+        pointer.x = e.client_x() as f64;
+        pointer.y = e.client_y() as f64;
+        if pointer.distance_moved() > self.options.tap_slop {
+            self.long_press_timer = None;
+        }
+        let start_distance = self.pinch_start_distance?;
+        if self.pointers.len() < 2 || start_distance == 0.0 {
+            return None;
+        }
+        Some(GestureMsg::Pinch(self.pointer_distance() / start_distance))
+    }
+
+    fn up(&mut self, e: &PointerEvent) -> Option<GestureMsg> {
+        let idx = self.pointers.iter().position(|p| p.id == e.pointer_id())?;
+        let pointer = self.pointers.remove(idx);
+        self.long_press_timer = None;
+        if self.pointers.len() < 2 {
+            self.pinch_start_distance = None;
+        }
+        let time = e.time_stamp();
+        let duration = time - pointer.start_time;
+        let point = GesturePoint {
+            x: pointer.x,
+            y: pointer.y,
+        };
+        let is_tap = pointer.distance_moved() <= self.options.tap_slop && !self.long_pressed;
+        match self.kind {
+            GestureKind::Tap if is_tap => Some(GestureMsg::Point(point)),
+            GestureKind::DoubleTap if is_tap => match self.last_tap.take() {
+                Some((last_time, last_point))
+                    if time - last_time <= self.options.double_tap_ms
+                        && (point.x - last_point.x).hypot(point.y - last_point.y)
+                            <= self.options.tap_slop =>
+                {
+                    Some(GestureMsg::Point(point))
+                }
+                _ => {
+                    self.last_tap = Some((time, point));
+                    None
+                }
+            },
+            GestureKind::Swipe => {
+                let (dx, dy) = (pointer.x - pointer.start_x, pointer.y - pointer.start_y);
+                if duration > self.options.swipe_max_ms
+                    || pointer.distance_moved() < self.options.swipe_min_distance
+                {
+                    return None;
+                }
+                let direction = if dx.abs() >= dy.abs() {
+                    if dx > 0.0 {
+                        SwipeDirection::Right
+                    } else {
+                        SwipeDirection::Left
+                    }
+                } else if dy > 0.0 {
+                    SwipeDirection::Down
+                } else {
+                    SwipeDirection::Up
+                };
+                Some(GestureMsg::Swipe(direction))
+            }
+            _ => None,
+        }
+    }
+
+    fn cancel(&mut self, e: &PointerEvent) {
+        self.pointers.retain(|p| p.id != e.pointer_id());
+        self.long_press_timer = None;
+        self.pinch_start_distance = None;
+    }
+}
+
+type PointerHandler =
+    fn(&Rc<RefCell<Tracker>>, &Rc<MessageThunk>, &web_sys::Element, &PointerEvent);
+
+/// The event listeners and the state of the gesture recognition.
+struct Recognizer {
+    #[allow(unused)]
+    listeners: [EventListener; 4],
+    tracker: Rc<RefCell<Tracker>>,
+}
+
+impl Recognizer {
+    fn new(node: &web_sys::Node, kind: GestureKind, options: &GestureOptions, cx: &Cx) -> Self {
+        let element: &web_sys::Element = node.unchecked_ref();
+        let thunk = Rc::new(cx.message_thunk());
+        let tracker = Rc::new(RefCell::new(Tracker {
+            kind,
+            options: options.clone(),
+            pointers: Vec::new(),
+            pinch_start_distance: None,
+            last_tap: None,
+            long_press_timer: None,
+            long_pressed: false,
+        }));
+
+        let listener = |event: &'static str, handle: PointerHandler| {
+            let tracker = Rc::clone(&tracker);
+            let thunk = Rc::clone(&thunk);
+            let el = element.clone();
+            EventListener::new(element, event, move |e| {
+                handle(&tracker, &thunk, &el, e.unchecked_ref());
+            })
+        };
+        let listeners = [
+            listener("pointerdown", |tracker, thunk, el, e| {
+                // Capture the pointer, so that it's tracked outside of the element as well
+                let _ = el.set_pointer_capture(e.pointer_id());
+                let weak = Rc::downgrade(tracker);
+                tracker.borrow_mut().down(e, weak, Rc::clone(thunk));
+            }),
+            listener("pointermove", |tracker, thunk, _, e| {
+                let msg = tracker.borrow_mut().moved(e);
+                if let Some(msg) = msg {
+                    thunk.push_message(msg);
+                }
+            }),
+            listener("pointerup", |tracker, thunk, _, e| {
+                let msg = tracker.borrow_mut().up(e);
+                if let Some(msg) = msg {
+                    thunk.push_message(msg);
+                }
+            }),
+            listener("pointercancel", |tracker, _, _, e| {
+                tracker.borrow_mut().cancel(e);
+            }),
+        ];
+        Recognizer { listeners, tracker }
+    }
+}
+
+/// Recognizes the gesture `G` on the element of the child view `V`, see e.g. [`Element::on_tap`].
+pub struct OnGesture<V, T, A, G, F> {
+    child: V,
+    callback: F,
+    options: GestureOptions,
+    phantom: PhantomData<fn() -> (T, A, G)>,
+}
+
+impl<V, T, A, G, F> OnGesture<V, T, A, G, F> {
+    pub fn new(child: V, callback: F) -> Self {
+        OnGesture {
+            child,
+            callback,
+            options: GestureOptions::default(),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Set the thresholds used to recognize the gesture.
+    pub fn options(mut self, options: GestureOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+pub struct OnGestureState<S> {
+    recognizer: Recognizer,
+    child_id: Id,
+    child_state: S,
+}
+
+crate::interfaces::impl_dom_interfaces_for_ty!(
+    Element,
+    OnGesture,
+    vars: <G, F, OA,>,
+    vars_on_ty: <G, F,>,
+    bounds: {
+        G: Gesture,
+        OA: OptionalAction<A>,
+        F: Fn(&mut T, G::Msg) -> OA,
+    }
+);
+
+impl<V, T, A, G, F> ViewMarker for OnGesture<V, T, A, G, F> {}
+impl<V, T, A, G, F> crate::interfaces::sealed::Sealed for OnGesture<V, T, A, G, F> {}
+
+impl<V, T, A, G, F, OA> View<T, A> for OnGesture<V, T, A, G, F>
+where
+    V: View<T, A>,
+    G: Gesture,
+    OA: OptionalAction<A>,
+    F: Fn(&mut T, G::Msg) -> OA,
+{
+    type State = OnGestureState<V::State>;
+    type Element = V::Element;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, (element, state)) = cx.with_new_id(|cx| {
+            let (child_id, child_state, element) = self.child.build(cx);
+            let recognizer = Recognizer::new(element.as_node_ref(), G::KIND, &self.options, cx);
+            let state = OnGestureState {
+                recognizer,
+                child_id,
+                child_state,
+            };
+            (element, state)
+        });
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        cx.with_id(*id, |cx| {
+            let prev_child_id = state.child_id;
+            let mut changed = self.child.rebuild(
+                cx,
+                &prev.child,
+                &mut state.child_id,
+                &mut state.child_state,
+                element,
+            );
+            if state.child_id != prev_child_id {
+                changed |= ChangeFlags::OTHER_CHANGE;
+            }
+            if changed.contains(ChangeFlags::STRUCTURE) {
+                state.recognizer =
+                    Recognizer::new(element.as_node_ref(), G::KIND, &self.options, cx);
+                changed |= ChangeFlags::LISTENERS;
+            } else if prev.options != self.options {
+                state.recognizer.tracker.borrow_mut().options = self.options.clone();
+            }
+            changed
+        })
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Box<dyn Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        match id_path {
+            [] if message.downcast_ref::<GestureMsg>().is_some() => {
+                let msg = message.downcast::<GestureMsg>().unwrap();
+                let Some(msg) = G::from_msg(*msg) else {
+                    return MessageResult::Nop;
+                };
+                match (self.callback)(app_state, msg).action() {
+                    Some(a) => MessageResult::Action(a),
+                    None => MessageResult::Nop,
+                }
+            }
+            [child_id, rest_path @ ..] if *child_id == state.child_id => {
+                self.child
+                    .message(rest_path, &mut state.child_state, message, app_state)
+            }
+            _ => MessageResult::Stale(message),
+        }
+    }
+}
@@ -0,0 +1,364 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Management of the document `<head>`, like the title and the metadata used by search engines and social media.
+
+use std::{borrow::Cow, cell::RefCell, collections::HashMap, marker::PhantomData};
+
+use wasm_bindgen::UnwrapThrowExt;
+use xilem_core::{Id, MessageResult};
+
+use crate::{ChangeFlags, Cx, View, ViewMarker};
+
+type CowStr = Cow<'static, str>;
+
+/// The (social) metadata of a page, see [`page_meta`].
+///
+/// Fields which are `None` remove the corresponding tags.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PageMeta {
+    /// The document title, also used for `og:title`.
+    pub title: Option<CowStr>,
+    /// Used for `<meta name="description">` and `og:description`.
+    pub description: Option<CowStr>,
+    /// The (absolute) url of the image shown in previews of the page, `og:image`.
+    pub og_image: Option<CowStr>,
+    /// The canonical url of the page, used for `<link rel="canonical">` and `og:url`.
+    pub canonical: Option<CowStr>,
+}
+
+/// Sets the title, description, image and canonical url of the page in the document `<head>`.
+///
+/// This can be placed anywhere in the view tree (e.g. in the view of a page of the app),
+/// the tags in the head are updated when the `meta` changes.
+/// Like [`document_title`], [`meta`] and [`link`] the last writer wins, and the previous values are restored when this view is removed.
+/// It doesn't create any visible content, in the DOM it's represented by an (empty) comment node.
+pub fn page_meta<T, A>(meta: PageMeta) -> PageMetaView<T, A> {
+    PageMetaView {
+        meta,
+        phantom: PhantomData,
+    }
+}
+
+pub struct PageMetaView<T, A = ()> {
+    meta: PageMeta,
+    phantom: PhantomData<fn() -> (T, A)>,
+}
+
+/// Inserts, updates or removes (when `content` is `None`) the `<meta>` tag in the head with the given `key` attribute (`name` or `property`).
+fn set_meta(key: &str, key_value: &str, content: Option<&str>) {
+    let document = crate::document();
+    let head = document.head().unwrap_throw();
+    let existing = head
+        .query_selector(&format!("meta[{key}=\"{key_value}\"]"))
+        .unwrap_throw();
+    match (existing, content) {
+        (Some(meta), Some(content)) => meta.set_attribute("content", content).unwrap_throw(),
+        (Some(meta), None) => meta.remove(),
+        (None, Some(content)) => {
+            let meta = document.create_element("meta").unwrap_throw();
+            meta.set_attribute(key, key_value).unwrap_throw();
+            meta.set_attribute("content", content).unwrap_throw();
+            head.append_child(&meta).unwrap_throw();
+        }
+        (None, None) => {}
+    }
+}
+
+/// Inserts, updates or removes (when `href` is `None`) the `<link>` tag in the head with the given `rel` attribute.
+fn set_link(rel: &str, href: Option<&str>) {
+    let document = crate::document();
+    let head = document.head().unwrap_throw();
+    let existing = head
+        .query_selector(&format!("link[rel=\"{rel}\"]"))
+        .unwrap_throw();
+    match (existing, href) {
+        (Some(link), Some(href)) => link.set_attribute("href", href).unwrap_throw(),
+        (Some(link), None) => link.remove(),
+        (None, Some(href)) => {
+            let link = document.create_element("link").unwrap_throw();
+            link.set_attribute("rel", rel).unwrap_throw();
+            link.set_attribute("href", href).unwrap_throw();
+            head.append_child(&link).unwrap_throw();
+        }
+        (None, None) => {}
+    }
+}
+
+impl PageMeta {
+    /// The values of the tags in the head, `None` for the ones which are removed.
+    fn head_entries(&self) -> [(HeadKey, Option<&CowStr>); 7] {
+        [
+            (HeadKey::Title, self.title.as_ref()),
+            (HeadKey::Property("og:title".into()), self.title.as_ref()),
+            (
+                HeadKey::Meta("description".into()),
+                self.description.as_ref(),
+            ),
+            (
+                HeadKey::Property("og:description".into()),
+                self.description.as_ref(),
+            ),
+            (HeadKey::Property("og:image".into()), self.og_image.as_ref()),
+            (HeadKey::Property("og:url".into()), self.canonical.as_ref()),
+            (HeadKey::Link("canonical".into()), self.canonical.as_ref()),
+        ]
+    }
+}
+
+/// Writes the tags via the same registry as [`HeadTag`], so that both can be combined (the last writer wins),
+/// and the previous values are restored when the view is removed.
+fn apply_page_meta(meta: &PageMeta, id: Id) {
+    for (key, value) in meta.head_entries() {
+        match value {
+            Some(value) => write_head(&key, id, value),
+            None => remove_head_writer(&key, id),
+        }
+    }
+}
+
+/// Removes the values written by a [`PageMetaView`], when it's removed from the view tree.
+pub struct PageMetaState {
+    id: Id,
+}
+
+impl Drop for PageMetaState {
+    fn drop(&mut self) {
+        for (key, _) in PageMeta::default().head_entries() {
+            remove_head_writer(&key, self.id);
+        }
+    }
+}
+
+impl<T, A> ViewMarker for PageMetaView<T, A> {}
+
+impl<T, A> View<T, A> for PageMetaView<T, A> {
+    type State = PageMetaState;
+    type Element = web_sys::Comment;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let id = Id::next();
+        apply_page_meta(&self.meta, id);
+        let el = cx.create_comment("page_meta");
+        (id, PageMetaState { id }, el)
+    }
+
+    fn rebuild(
+        &self,
+        _cx: &mut Cx,
+        prev: &Self,
+        _id: &mut Id,
+        state: &mut Self::State,
+        _element: &mut Self::Element,
+    ) -> ChangeFlags {
+        if prev.meta != self.meta {
+            apply_page_meta(&self.meta, state.id);
+        }
+        ChangeFlags::empty()
+    }
+
+    fn message(
+        &self,
+        _id_path: &[Id],
+        _state: &mut Self::State,
+        message: Box<dyn std::any::Any>,
+        _app_state: &mut T,
+    ) -> MessageResult<A> {
+        MessageResult::Stale(message)
+    }
+}
+
+/// What's written by a [`HeadTag`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum HeadKey {
+    Title,
+    /// `<meta name="...">`
+    Meta(CowStr),
+    /// `<meta property="...">`, used by Open Graph.
+    Property(CowStr),
+    Link(CowStr),
+}
+
+impl HeadKey {
+    /// The current value in the document.
+    fn read(&self) -> Option<String> {
+        let document = crate::document();
+        let head = document.head().unwrap_throw();
+        match self {
+            HeadKey::Title => Some(document.title()),
+            HeadKey::Meta(name) => head
+                .query_selector(&format!("meta[name=\"{name}\"]"))
+                .unwrap_throw()
+                .and_then(|meta| meta.get_attribute("content")),
+            HeadKey::Property(property) => head
+                .query_selector(&format!("meta[property=\"{property}\"]"))
+                .unwrap_throw()
+                .and_then(|meta| meta.get_attribute("content")),
+            HeadKey::Link(rel) => head
+                .query_selector(&format!("link[rel=\"{rel}\"]"))
+                .unwrap_throw()
+                .and_then(|link| link.get_attribute("href")),
+        }
+    }
+
+    fn write(&self, value: Option<&str>) {
+        match self {
+            HeadKey::Title => crate::document().set_title(value.unwrap_or_default()),
+            HeadKey::Meta(name) => set_meta("name", name, value),
+            HeadKey::Property(property) => set_meta("property", property, value),
+            HeadKey::Link(rel) => set_link(rel, value),
+        }
+    }
+}
+
+/// The values written to a [`HeadKey`] by each `HeadTag` in the view tree, by its id.
+struct HeadWriters {
+    /// The value before the first `HeadTag` wrote it, which is restored when the last one is removed.
+    original: Option<String>,
+    writers: Vec<(Id, CowStr)>,
+}
+
+thread_local! {
+    static HEAD_WRITERS: RefCell<HashMap<HeadKey, HeadWriters>> = RefCell::new(HashMap::new());
+}
+
+/// Sets the value written by `id`, which is the last writer afterwards, so its value is applied.
+fn write_head(key: &HeadKey, id: Id, value: &CowStr) {
+    HEAD_WRITERS.with(|head_writers| {
+        let mut head_writers = head_writers.borrow_mut();
+        let entry = head_writers
+            .entry(key.clone())
+            .or_insert_with(|| HeadWriters {
+                original: key.read(),
+                writers: vec![],
+            });
+        entry.writers.retain(|(writer, _)| *writer != id);
+        entry.writers.push((id, value.clone()));
+        key.write(Some(value));
+    });
+}
+
+/// Removes the value written by `id`, the value of the previous writer (or the original value) is applied again.
+fn remove_head_writer(key: &HeadKey, id: Id) {
+    HEAD_WRITERS.with(|head_writers| {
+        let mut head_writers = head_writers.borrow_mut();
+        let Some(entry) = head_writers.get_mut(key) else {
+            return;
+        };
+        let Some(idx) = entry.writers.iter().position(|(writer, _)| *writer == id) else {
+            return;
+        };
+        let was_last = idx == entry.writers.len() - 1;
+        entry.writers.remove(idx);
+        match entry.writers.last() {
+            Some((_, value)) if was_last => key.write(Some(value)),
+            Some(_) => {}
+            None => {
+                key.write(entry.original.as_deref());
+                head_writers.remove(key);
+            }
+        }
+    });
+}
+
+/// Writes the document title, a `<meta>` or a `<link>` tag, see [`document_title`], [`meta`] and [`link`].
+pub struct HeadTag<T, A = ()> {
+    key: HeadKey,
+    value: CowStr,
+    phantom: PhantomData<fn() -> (T, A)>,
+}
+
+/// Sets `document.title`, e.g. to the title of the current route.
+///
+/// When multiple of these views are in the view tree, the one which was built or changed last wins.
+/// When it's removed, the title of the previous one, or the title before any of them was set, is restored.
+pub fn document_title<T, A>(title: impl Into<CowStr>) -> HeadTag<T, A> {
+    HeadTag {
+        key: HeadKey::Title,
+        value: title.into(),
+        phantom: PhantomData,
+    }
+}
+
+/// Sets the `content` of the `<meta name="{name}">` tag in the head (which is created if necessary), e.g. `meta("theme-color", "#317EFB")`.
+///
+/// Like [`document_title`] the last writer wins, and the previous content is restored when this view is removed.
+pub fn meta<T, A>(name: impl Into<CowStr>, content: impl Into<CowStr>) -> HeadTag<T, A> {
+    HeadTag {
+        key: HeadKey::Meta(name.into()),
+        value: content.into(),
+        phantom: PhantomData,
+    }
+}
+
+/// Sets the `href` of the `<link rel="{rel}">` tag in the head (which is created if necessary), e.g. `link("icon", "/favicon-unread.png")`.
+///
+/// Like [`document_title`] the last writer wins, and the previous `href` is restored when this view is removed.
+pub fn link<T, A>(rel: impl Into<CowStr>, href: impl Into<CowStr>) -> HeadTag<T, A> {
+    HeadTag {
+        key: HeadKey::Link(rel.into()),
+        value: href.into(),
+        phantom: PhantomData,
+    }
+}
+
+/// Removes the value written by a [`HeadTag`], when it's removed from the view tree.
+pub struct HeadTagState {
+    key: HeadKey,
+    id: Id,
+}
+
+impl Drop for HeadTagState {
+    fn drop(&mut self) {
+        remove_head_writer(&self.key, self.id);
+    }
+}
+
+impl<T, A> ViewMarker for HeadTag<T, A> {}
+
+impl<T, A> View<T, A> for HeadTag<T, A> {
+    type State = HeadTagState;
+    type Element = web_sys::Comment;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let id = Id::next();
+        write_head(&self.key, id, &self.value);
+        let el = cx.create_comment("head");
+        let state = HeadTagState {
+            key: self.key.clone(),
+            id,
+        };
+        (id, state, el)
+    }
+
+    fn rebuild(
+        &self,
+        _cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        _element: &mut Self::Element,
+    ) -> ChangeFlags {
+        if prev.key != self.key {
+            write_head(&self.key, *id, &self.value);
+            // The previous value is removed, when the previous state is dropped
+            *state = HeadTagState {
+                key: self.key.clone(),
+                id: *id,
+            };
+        } else if prev.value != self.value {
+            write_head(&self.key, *id, &self.value);
+        }
+        ChangeFlags::empty()
+    }
+
+    fn message(
+        &self,
+        _id_path: &[Id],
+        _state: &mut Self::State,
+        message: Box<dyn std::any::Any>,
+        _app_state: &mut T,
+    ) -> MessageResult<A> {
+        MessageResult::Stale(message)
+    }
+}
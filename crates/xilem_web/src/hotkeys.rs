@@ -0,0 +1,349 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Document-wide keyboard shortcuts, see [`hotkeys`].
+
+use std::{any::Any, borrow::Cow, cell::RefCell, rc::Rc};
+
+use gloo::events::{EventListener, EventListenerOptions};
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    context::{ChangeFlags, Cx},
+    view::{View, ViewMarker},
+    OptionalAction,
+};
+
+type CowStr = Cow<'static, str>;
+
+/// The platform a [`Hotkey`] is parsed for, which determines what `Mod` means.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HotkeyPlatform {
+    /// macOS and iOS, where `Cmd` is used instead of `Ctrl` for shortcuts.
+    Apple,
+    Other,
+}
+
+impl HotkeyPlatform {
+    /// The platform the app currently runs on, according to `navigator.platform`.
+    pub fn current() -> Self {
+        let navigator = web_sys::window().unwrap_throw().navigator();
+        let platform = navigator.platform().unwrap_or_default();
+        if platform.starts_with("Mac") || platform.starts_with("iP") {
+            HotkeyPlatform::Apple
+        } else {
+            HotkeyPlatform::Other
+        }
+    }
+}
+
+/// A key combination like `"Ctrl+Shift+K"`, which can be matched against a `keydown` event.
+///
+/// The combination is written as modifiers followed by the key, separated by `+` (case-insensitive):
+///
+/// * `Ctrl`, `Alt` (or `Option`), `Shift` and `Meta` (or `Cmd`) match the respective modifier.
+/// * `Mod` matches `Cmd` on Apple platforms, and `Ctrl` otherwise, so `"Mod+S"` is the usual "save" shortcut everywhere.
+/// * The key is either a character (`K`, `1`, `/`), or the name of a key as in [`KeyboardEvent.key`](https://developer.mozilla.org/en-US/docs/Web/API/KeyboardEvent/key)
+///   (`Escape`, `Enter`, `ArrowUp`, `F2`, ...), `Space` and `Plus` can be used for ` ` and `+`.
+///
+/// Modifiers which aren't specified must not be pressed, so `"Ctrl+K"` doesn't match `Ctrl+Shift+K`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Hotkey {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    pub meta: bool,
+    /// The lowercased key.
+    pub key: String,
+}
+
+impl Hotkey {
+    /// Parses a combination like `"Mod+Shift+P"` for `platform`, returns `None` when it's invalid.
+    pub fn parse(combination: &str, platform: HotkeyPlatform) -> Option<Self> {
+        let mut hotkey = Hotkey {
+            ctrl: false,
+            alt: false,
+            shift: false,
+            meta: false,
+            key: String::new(),
+        };
+        let mut parts = combination.split('+').map(str::trim).peekable();
+        while let Some(part) = parts.next() {
+            if parts.peek().is_none() {
+                hotkey.key = match part.to_lowercase().as_str() {
+                    "" => return None,
+                    "space" => " ".into(),
+                    "plus" => "+".into(),
+                    "esc" => "escape".into(),
+                    key => key.into(),
+                };
+                break;
+            }
+            match part.to_lowercase().as_str() {
+                "ctrl" | "control" => hotkey.ctrl = true,
+                "alt" | "option" => hotkey.alt = true,
+                "shift" => hotkey.shift = true,
+                "meta" | "cmd" | "command" | "super" => hotkey.meta = true,
+                "mod" | "cmdorctrl" => match platform {
+                    HotkeyPlatform::Apple => hotkey.meta = true,
+                    HotkeyPlatform::Other => hotkey.ctrl = true,
+                },
+                _ => return None,
+            }
+        }
+        Some(hotkey)
+    }
+
+    /// Whether the key and the modifiers of `event` match this combination.
+    pub fn matches(&self, event: &web_sys::KeyboardEvent) -> bool {
+        if event.ctrl_key() != self.ctrl
+            || event.alt_key() != self.alt
+            || event.shift_key() != self.shift
+            || event.meta_key() != self.meta
+        {
+            return false;
+        }
+        if event.key().to_lowercase() == self.key {
+            return true;
+        }
+        // With modifiers (e.g. `Alt` on macOS, or `Shift`) the key may be a different character,
+        // so letters and digits are also compared with the physical key
+        let mut chars = self.key.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) if c.is_ascii_alphabetic() => {
+                event.code() == format!("Key{}", c.to_ascii_uppercase())
+            }
+            (Some(c), None) if c.is_ascii_digit() => event.code() == format!("Digit{c}"),
+            _ => false,
+        }
+    }
+}
+
+/// Whether `target` is an element where the user types text, where key presses aren't hotkeys.
+fn is_editable(target: Option<web_sys::EventTarget>) -> bool {
+    let Some(element) = target.and_then(|target| target.dyn_into::<web_sys::HtmlElement>().ok())
+    else {
+        return false;
+    };
+    matches!(element.local_name().as_str(), "input" | "textarea") || element.is_content_editable()
+}
+
+/// Sent when the hotkey at this index was pressed.
+struct HotkeyPressed(usize);
+
+type HotkeyHandler<T, A> = Box<dyn Fn(&mut T) -> Option<A>>;
+
+/// Document-wide keyboard shortcuts, see [`hotkeys`].
+pub struct Hotkeys<T, A = ()> {
+    bindings: Vec<(CowStr, HotkeyHandler<T, A>)>,
+}
+
+/// Installs a `keydown` listener on the document, which calls the handler of the first matching combination (see [`Hotkey`]).
+/// The combinations and their handlers are added with [`Hotkeys::bind`].
+///
+/// The default action of the browser (e.g. saving the page with `Ctrl+S`) is prevented for registered combinations.
+/// Key presses while typing in an `<input>`, a `<textarea>` or a `contenteditable` element are ignored.
+/// Invalid combinations are logged to the console. The listener is removed, when this view is removed from the view tree.
+///
+/// ```ignore
+/// hotkeys()
+///     .bind("Mod+K", |state: &mut AppState| state.show_command_palette = true)
+///     .bind("Escape", |state: &mut AppState| state.show_command_palette = false)
+///     .bind("Mod+Shift+P", |state: &mut AppState| state.show_preferences = true)
+/// ```
+pub fn hotkeys<T, A>() -> Hotkeys<T, A> {
+    Hotkeys { bindings: vec![] }
+}
+
+impl<T, A> Hotkeys<T, A> {
+    /// Adds the combination `combination`, which calls `handler` when pressed.
+    pub fn bind<F, OA>(mut self, combination: impl Into<CowStr>, handler: F) -> Self
+    where
+        F: Fn(&mut T) -> OA + 'static,
+        OA: OptionalAction<A>,
+    {
+        self.bindings.push((
+            combination.into(),
+            Box::new(move |state| handler(state).action()),
+        ));
+        self
+    }
+
+    fn parse_hotkeys(&self) -> Vec<Option<Hotkey>> {
+        let platform = HotkeyPlatform::current();
+        self.bindings
+            .iter()
+            .map(|(combination, _)| {
+                let hotkey = Hotkey::parse(combination, platform);
+                if hotkey.is_none() {
+                    web_sys::console::warn_1(&format!("invalid hotkey: {combination:?}").into());
+                }
+                hotkey
+            })
+            .collect()
+    }
+}
+
+pub struct HotkeysState {
+    /// The combinations of the current view, which are shared with the listener.
+    hotkeys: Rc<RefCell<Vec<Option<Hotkey>>>>,
+    // Retained, so that the listener is removed when dropped
+    #[allow(unused)]
+    listener: EventListener,
+}
+
+impl<T, A> ViewMarker for Hotkeys<T, A> {}
+
+impl<T, A> View<T, A> for Hotkeys<T, A> {
+    type State = HotkeysState;
+    type Element = web_sys::Comment;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let hotkeys = Rc::new(RefCell::new(self.parse_hotkeys()));
+        let (id, listener) = cx.with_new_id(|cx| {
+            let thunk = cx.message_thunk();
+            let hotkeys = Rc::clone(&hotkeys);
+            EventListener::new_with_options(
+                cx.document(),
+                "keydown",
+                EventListenerOptions::enable_prevent_default(),
+                move |event| {
+                    let event: &web_sys::KeyboardEvent = event.unchecked_ref();
+                    if is_editable(event.target()) {
+                        return;
+                    }
+                    let pressed = hotkeys
+                        .borrow()
+                        .iter()
+                        .position(|hotkey| hotkey.as_ref().is_some_and(|h| h.matches(event)));
+                    if let Some(idx) = pressed {
+                        event.prevent_default();
+                        thunk.push_message(HotkeyPressed(idx));
+                    }
+                },
+            )
+        });
+        let el = cx.create_comment("hotkeys");
+        (id, HotkeysState { hotkeys, listener }, el)
+    }
+
+    fn rebuild(
+        &self,
+        _cx: &mut Cx,
+        prev: &Self,
+        _id: &mut Id,
+        state: &mut Self::State,
+        _element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let combinations_changed = prev.bindings.len() != self.bindings.len()
+            || prev
+                .bindings
+                .iter()
+                .zip(&self.bindings)
+                .any(|((prev, _), (next, _))| prev != next);
+        if combinations_changed {
+            *state.hotkeys.borrow_mut() = self.parse_hotkeys();
+        }
+        ChangeFlags::empty()
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        _state: &mut Self::State,
+        message: Box<dyn Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        match id_path {
+            [] if message.downcast_ref::<HotkeyPressed>().is_some() => {
+                let HotkeyPressed(idx) = *message.downcast::<HotkeyPressed>().unwrap();
+                let Some((_, handler)) = self.bindings.get(idx) else {
+                    return MessageResult::Nop;
+                };
+                match handler(app_state) {
+                    Some(a) => MessageResult::Action(a),
+                    None => MessageResult::Nop,
+                }
+            }
+            _ => MessageResult::Stale(message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hotkey(ctrl: bool, alt: bool, shift: bool, meta: bool, key: &str) -> Hotkey {
+        Hotkey {
+            ctrl,
+            alt,
+            shift,
+            meta,
+            key: key.into(),
+        }
+    }
+
+    #[test]
+    fn parses_modifiers_and_key() {
+        let parse = |combination| Hotkey::parse(combination, HotkeyPlatform::Other);
+        assert_eq!(parse("K"), Some(hotkey(false, false, false, false, "k")));
+        assert_eq!(
+            parse("Ctrl+Shift+K"),
+            Some(hotkey(true, false, true, false, "k"))
+        );
+        assert_eq!(
+            parse("alt + cmd + ArrowUp"),
+            Some(hotkey(false, true, false, true, "arrowup"))
+        );
+        assert_eq!(
+            parse("Control+Option+F2"),
+            Some(hotkey(true, true, false, false, "f2"))
+        );
+    }
+
+    #[test]
+    fn parses_key_aliases() {
+        let parse = |combination| Hotkey::parse(combination, HotkeyPlatform::Other);
+        assert_eq!(
+            parse("Space"),
+            Some(hotkey(false, false, false, false, " "))
+        );
+        assert_eq!(
+            parse("Ctrl+Plus"),
+            Some(hotkey(true, false, false, false, "+"))
+        );
+        assert_eq!(
+            parse("Esc"),
+            Some(hotkey(false, false, false, false, "escape"))
+        );
+    }
+
+    #[test]
+    fn mod_depends_on_the_platform() {
+        assert_eq!(
+            Hotkey::parse("Mod+S", HotkeyPlatform::Apple),
+            Some(hotkey(false, false, false, true, "s"))
+        );
+        assert_eq!(
+            Hotkey::parse("Mod+S", HotkeyPlatform::Other),
+            Some(hotkey(true, false, false, false, "s"))
+        );
+        assert_eq!(
+            Hotkey::parse("CmdOrCtrl+Shift+P", HotkeyPlatform::Apple),
+            Some(hotkey(false, false, true, true, "p"))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_combinations() {
+        for combination in ["", "Ctrl+", "Hyper+K", "K+L"] {
+            assert_eq!(
+                Hotkey::parse(combination, HotkeyPlatform::Other),
+                None,
+                "{combination:?}"
+            );
+        }
+    }
+}
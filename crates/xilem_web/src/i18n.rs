@@ -0,0 +1,310 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Translated texts via message catalogs, see [`locale_provider`] and [`t!`](crate::t).
+//!
+//! A [`Catalog`] is provided to a subtree with [`locale_provider`], and messages are looked up in it with
+//! [`t!`](crate::t), which creates a text view. Switching the locale is done by providing another catalog
+//! (e.g. selected by a locale in the app state), after which the texts are updated with the next rebuild.
+//!
+//! ```ignore
+//! let catalogs = Rc::new(HashMap::from([
+//!     ("en", Rc::new(Catalog::new("en").message("greeting", "Hello, {name}!"))),
+//!     ("de", Rc::new(Catalog::new("de").message("greeting", "Hallo, {name}!"))),
+//! ]));
+//!
+//! fn app_logic(state: &mut AppState) -> impl View<AppState> {
+//!     locale_provider(state.catalogs[state.locale].clone(), el::div((
+//!         el::h1(t!("greeting", name = state.user_name)),
+//!         el::button("EN/DE").on_click(|state: &mut AppState, _| state.toggle_locale()),
+//!     )))
+//! }
+//! ```
+
+use std::{
+    any::Any,
+    borrow::Cow,
+    collections::HashMap,
+    fmt::{Display, Write},
+    rc::Rc,
+};
+
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    context::{ChangeFlags, Cx},
+    view::{View, ViewMarker},
+};
+
+type CowStr = Cow<'static, str>;
+
+/// The messages of a locale, by key.
+///
+/// Messages can contain placeholders like `{name}`, which are replaced by the arguments of [`Catalog::format`],
+/// `{{` and `}}` are escaped braces.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Catalog {
+    locale: CowStr,
+    messages: HashMap<CowStr, CowStr>,
+}
+
+impl Catalog {
+    /// An empty catalog of `locale`, a BCP 47 language tag like `"de-DE"`,
+    /// which can also be used for the formatters in [`intl`](crate::intl).
+    pub fn new(locale: impl Into<CowStr>) -> Self {
+        Catalog {
+            locale: locale.into(),
+            messages: HashMap::new(),
+        }
+    }
+
+    /// Adds the message `key`.
+    pub fn message(mut self, key: impl Into<CowStr>, message: impl Into<CowStr>) -> Self {
+        self.messages.insert(key.into(), message.into());
+        self
+    }
+
+    /// Adds all messages of `messages`, e.g. parsed from a JSON file.
+    pub fn messages<K: Into<CowStr>, M: Into<CowStr>>(
+        mut self,
+        messages: impl IntoIterator<Item = (K, M)>,
+    ) -> Self {
+        self.messages.extend(
+            messages
+                .into_iter()
+                .map(|(key, message)| (key.into(), message.into())),
+        );
+        self
+    }
+
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    /// The (unformatted) message `key`.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.messages.get(key).map(|message| &**message)
+    }
+
+    /// The message `key`, with its placeholders replaced by `args`.
+    ///
+    /// When there's no such message, the key itself is returned (and a warning is logged in debug builds),
+    /// so that missing translations are visible, but don't break the app.
+    pub fn format(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        let Some(message) = self.get(key) else {
+            #[cfg(debug_assertions)]
+            web_sys::console::warn_1(
+                &format!(
+                    "i18n: no message `{key}` in the catalog of `{}`",
+                    self.locale
+                )
+                .into(),
+            );
+            return key.into();
+        };
+        format_message(message, args)
+    }
+}
+
+fn format_message(message: &str, args: &[(&str, &dyn Display)]) -> String {
+    let mut result = String::with_capacity(message.len());
+    let mut rest = message;
+    while let Some(idx) = rest.find(['{', '}']) {
+        result.push_str(&rest[..idx]);
+        rest = &rest[idx..];
+        if rest.starts_with("{{") || rest.starts_with("}}") {
+            result.push_str(&rest[..1]);
+            rest = &rest[2..];
+            continue;
+        }
+        let placeholder = rest[1..]
+            .find('}')
+            .filter(|_| rest.starts_with('{'))
+            .map(|end| &rest[1..end + 1]);
+        let arg = placeholder.and_then(|placeholder| {
+            let (_, value) = args.iter().find(|(name, _)| *name == placeholder.trim())?;
+            Some((placeholder, value))
+        });
+        match arg {
+            Some((placeholder, value)) => {
+                let _ = write!(result, "{value}");
+                rest = &rest[placeholder.len() + 2..];
+            }
+            // Unknown placeholders and stray braces are kept
+            None => {
+                result.push_str(&rest[..1]);
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Provides a [`Catalog`] to a subtree, see [`locale_provider`].
+pub struct LocaleProvider<V> {
+    catalog: Rc<Catalog>,
+    child: V,
+}
+
+/// Provides `catalog` to all descendants of `child`, which can look up messages with [`t!`](crate::t),
+/// or access the catalog directly with [`use_context`](crate::use_context) (e.g. for translated attributes).
+///
+/// The catalog is provided as context (see [`provide_context`](crate::provide_context)),
+/// so the nearest provider is used. When the catalog changes, all descendants are rebuilt with it,
+/// including memoized views (like [`memoize`](crate::memoize)) whose data didn't change.
+pub fn locale_provider<V>(catalog: Rc<Catalog>, child: V) -> LocaleProvider<V> {
+    LocaleProvider { catalog, child }
+}
+
+impl<V> ViewMarker for LocaleProvider<V> {}
+
+impl<T, A, V: View<T, A>> View<T, A> for LocaleProvider<V> {
+    type State = V::State;
+    type Element = V::Element;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let catalog: Rc<dyn Any> = self.catalog.clone();
+        cx.with_context(catalog, false, |cx| self.child.build(cx))
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        // Memoized descendants are rebuilt with the new catalog, as they may contain translated texts
+        let changed = !Rc::ptr_eq(&prev.catalog, &self.catalog) && prev.catalog != self.catalog;
+        let catalog: Rc<dyn Any> = self.catalog.clone();
+        cx.with_context(catalog, changed, |cx| {
+            self.child.rebuild(cx, &prev.child, id, state, element)
+        })
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Box<dyn Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        self.child.message(id_path, state, message, app_state)
+    }
+}
+
+/// A text view with a message of the provided [`Catalog`], see [`t!`](crate::t).
+pub struct Translate {
+    key: CowStr,
+    args: Vec<(&'static str, String)>,
+}
+
+/// The text of a translated message, see [`t!`](crate::t) which should usually be used instead.
+pub fn translate(key: impl Into<CowStr>, args: Vec<(&'static str, String)>) -> Translate {
+    Translate {
+        key: key.into(),
+        args,
+    }
+}
+
+impl Translate {
+    fn text(&self, cx: &Cx) -> String {
+        let args: Vec<(&str, &dyn Display)> = self
+            .args
+            .iter()
+            .map(|(name, value)| (*name, value as &dyn Display))
+            .collect();
+        match cx.context::<Catalog>() {
+            Some(catalog) => catalog.format(&self.key, &args),
+            None => {
+                #[cfg(debug_assertions)]
+                web_sys::console::warn_1(
+                    &format!(
+                        "i18n: `{}` is translated without a `locale_provider`",
+                        self.key
+                    )
+                    .into(),
+                );
+                self.key.to_string()
+            }
+        }
+    }
+}
+
+impl ViewMarker for Translate {}
+
+impl<T, A> View<T, A> for Translate {
+    /// The current text, the catalog may have changed, even when the key and the arguments didn't.
+    type State = String;
+    type Element = web_sys::Text;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let text = self.text(cx);
+        let element = cx.create_text(&text);
+        (Id::next(), text, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        _prev: &Self,
+        _id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let text = self.text(cx);
+        if text == *state {
+            return ChangeFlags::empty();
+        }
+        cx.set_text(element, Some(text.clone().into()));
+        *state = text;
+        ChangeFlags::TEXT
+    }
+
+    fn message(
+        &self,
+        _id_path: &[Id],
+        _state: &mut Self::State,
+        message: Box<dyn Any>,
+        _app_state: &mut T,
+    ) -> MessageResult<A> {
+        MessageResult::Stale(message)
+    }
+}
+
+/// A text view with the message `key` of the [`Catalog`] provided by [`locale_provider`],
+/// with its placeholders replaced by the named arguments, e.g. `t!("greeting", name = state.user_name)`.
+///
+/// The arguments can be anything that implements `Display`.
+#[macro_export]
+macro_rules! t {
+    ($key:expr $(,)?) => {
+        $crate::i18n::translate($key, ::std::vec::Vec::new())
+    };
+    ($key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::translate(
+            $key,
+            ::std::vec![$((::core::stringify!($name), ::std::string::ToString::to_string(&$value))),+],
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_message_replaces_placeholders() {
+        let name = "Ada";
+        let count = 3;
+        let args: [(&str, &dyn Display); 2] = [("name", &name), ("count", &count)];
+        assert_eq!(
+            format_message("Hello, {name}! You have {count} new messages.", &args),
+            "Hello, Ada! You have 3 new messages."
+        );
+        assert_eq!(format_message("{{name}} is {name}", &args), "{name} is Ada");
+        assert_eq!(format_message("{unknown} and {", &args), "{unknown} and {");
+    }
+}
@@ -0,0 +1,149 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    borrow::Cow,
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    rc::Rc,
+};
+
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    context::{ChangeFlags, Cx, ElementProps},
+    interfaces::sealed::Sealed,
+    view::{View, ViewMarker},
+    HTML_NS,
+};
+
+type CowStr = Cow<'static, str>;
+
+macro_rules! generate_dom_interface_impl {
+    ($dom_interface:ident, ()) => {
+        impl<T, A> $crate::interfaces::$dom_interface<T, A> for ImageFromBytes<T, A> {}
+    };
+}
+
+/// An object url of a `Blob`, which is revoked when dropped.
+struct ObjectUrl(String);
+
+impl ObjectUrl {
+    fn new(bytes: &[u8], mime: &str) -> Self {
+        let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(bytes));
+        let mut options = web_sys::BlobPropertyBag::new();
+        options.type_(mime);
+        let blob =
+            web_sys::Blob::new_with_u8_array_sequence_and_options(&parts, &options).unwrap_throw();
+        ObjectUrl(web_sys::Url::create_object_url_with_blob(&blob).unwrap_throw())
+    }
+}
+
+impl Drop for ObjectUrl {
+    fn drop(&mut self) {
+        let _ = web_sys::Url::revoke_object_url(&self.0);
+    }
+}
+
+fn hash_bytes(bytes: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// An `<img>` showing an image from memory, see [`image_from_bytes`].
+pub struct ImageFromBytes<T, A = ()> {
+    bytes: Rc<[u8]>,
+    mime: CowStr,
+    phantom: PhantomData<fn() -> (T, A)>,
+}
+
+/// An `<img>` showing the encoded image `bytes` (e.g. a PNG generated in Rust) of the MIME type `mime` (e.g. `"image/png"`),
+/// without a round trip to a server.
+///
+/// The bytes are provided to the `<img>` via an object url, which is recreated only when the bytes (compared via a hash) or the MIME type change,
+/// and revoked when it's not used anymore.
+pub fn image_from_bytes<T, A>(
+    bytes: impl Into<Rc<[u8]>>,
+    mime: impl Into<CowStr>,
+) -> ImageFromBytes<T, A> {
+    ImageFromBytes {
+        bytes: bytes.into(),
+        mime: mime.into(),
+        phantom: PhantomData,
+    }
+}
+
+pub struct ImageFromBytesState {
+    props: ElementProps,
+    url: ObjectUrl,
+    hash: u64,
+}
+
+generate_dom_interface_impl!(HtmlImageElement, ());
+crate::interfaces::for_all_html_image_element_ancestors!(generate_dom_interface_impl, ());
+
+impl<T, A> ViewMarker for ImageFromBytes<T, A> {}
+impl<T, A> Sealed for ImageFromBytes<T, A> {}
+
+impl<T, A> View<T, A> for ImageFromBytes<T, A> {
+    type State = ImageFromBytesState;
+    type Element = web_sys::HtmlImageElement;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (el, props) = cx.build_element(HTML_NS, "img");
+        let el: web_sys::HtmlImageElement = el.unchecked_into();
+        let url = ObjectUrl::new(&self.bytes, &self.mime);
+        el.set_src(&url.0);
+        let id = Id::next();
+
+        // Set the id used internally to the `data-debugid` attribute.
+        // This allows the user to see if an element has been re-created or only altered.
+        #[cfg(debug_assertions)]
+        cx.set_attribute(&el, "data-debugid", Some(id.to_raw().to_string().into()));
+
+        let state = ImageFromBytesState {
+            props,
+            url,
+            hash: hash_bytes(&self.bytes),
+        };
+        (id, state, el)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        _id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let mut changed = cx.rebuild_element(element, &mut state.props);
+        let bytes_changed = !Rc::ptr_eq(&prev.bytes, &self.bytes) && {
+            let hash = hash_bytes(&self.bytes);
+            let changed = hash != state.hash;
+            state.hash = hash;
+            changed
+        };
+        if bytes_changed || prev.mime != self.mime {
+            // The previous url is revoked, after the new one is set
+            let url = ObjectUrl::new(&self.bytes, &self.mime);
+            element.set_src(&url.0);
+            state.url = url;
+            changed |= ChangeFlags::OTHER_CHANGE;
+        }
+        changed
+    }
+
+    fn message(
+        &self,
+        _id_path: &[Id],
+        _state: &mut Self::State,
+        message: Box<dyn std::any::Any>,
+        _app_state: &mut T,
+    ) -> MessageResult<A> {
+        MessageResult::Stale(message)
+    }
+}
@@ -1,12 +1,19 @@
-use crate::{Pointer, PointerMsg, View, ViewMarker};
+use crate::{IntersectionMsg, OnIntersect, Pointer, PointerMsg, View, ViewMarker, WhenVisible};
 use std::borrow::Cow;
 
 use gloo::events::EventListenerOptions;
 use wasm_bindgen::JsCast;
 
 use crate::{
-    events::{self, OnEvent},
-    Attr, IntoAttributeValue, OptionalAction,
+    aria::Role,
+    class::{ClassIf, Classes},
+    css::Css,
+    devtools::DebugId,
+    drag_drop::{Draggable, DropData, DropTarget},
+    events::{self, OnClickOrDblClick, OnDelegatedEvent, OnEvent},
+    gesture::{self, GesturePoint, OnGesture, SwipeDirection},
+    style::{IntoStyleValue, Style},
+    Attr, IntoAttributeValue, IntoPropertyValue, OptionalAction, Prop,
 };
 
 pub(crate) mod sealed {
@@ -62,10 +69,136 @@ where
         OnEvent::new_with_options(self, event, handler, options)
     }
 
+    /// Handles `event` of all descendants with a single listener on this element,
+    /// instead of a listener per descendant (e.g. for the rows of a large table).
+    ///
+    /// `handler` is called with the value of the attribute `key_attribute` of the closest ancestor of the event target
+    /// within this element, which has that attribute. Events without such an ancestor are ignored.
+    ///
+    /// ```ignore
+    /// let rows: Vec<_> = state.rows.iter().map(|row| el::tr(row_cells(row)).attr("data-row", row.id)).collect();
+    /// el::table(rows).on_delegated("click", "data-row", |state: &mut AppState, _: web_sys::MouseEvent, row| {
+    ///     state.selected = row.parse().ok();
+    /// })
+    /// ```
+    fn on_delegated<Ev, EH, OA>(
+        self,
+        event: impl Into<Cow<'static, str>>,
+        key_attribute: impl Into<Cow<'static, str>>,
+        handler: EH,
+    ) -> OnDelegatedEvent<Self, T, A, Ev, EH>
+    where
+        Ev: JsCast + AsRef<web_sys::Event> + 'static,
+        OA: OptionalAction<A>,
+        EH: Fn(&mut T, Ev, &str) -> OA,
+    {
+        OnDelegatedEvent::new(self, event, key_attribute, handler)
+    }
+
     fn pointer<F: Fn(&mut T, PointerMsg)>(self, f: F) -> Pointer<Self, T, A, F> {
         crate::pointer::pointer(self, f)
     }
 
+    /// Calls `callback` each time the visibility of this element in the viewport crosses one of the thresholds.
+    ///
+    /// This is e.g. useful for infinite scrolling, by observing a sentinel element at the end of a list.
+    /// The thresholds and root margin can be configured on the returned [`OnIntersect`] view.
+    fn on_intersect<F, OA>(self, callback: F) -> OnIntersect<Self, T, A, F>
+    where
+        OA: OptionalAction<A>,
+        F: Fn(&mut T, IntersectionMsg) -> OA,
+    {
+        crate::intersection_observer::on_intersect(self, callback)
+    }
+
+    /// Calls `callback` once, when this element becomes visible in the viewport for the first time.
+    ///
+    /// This can be used to lazily show expensive content, by setting a flag in the app state.
+    fn when_visible<F, OA>(self, callback: F) -> WhenVisible<Self, T, A, F>
+    where
+        OA: OptionalAction<A>,
+        F: Fn(&mut T) -> OA,
+    {
+        crate::intersection_observer::when_visible(self, callback)
+    }
+
+    /// Calls `single` on a single click and `double` on a double click, but never both for the same double click.
+    ///
+    /// To achieve this, a single click is delivered only after `delay_ms` milliseconds passed without another click.
+    fn on_click_or_dblclick<S, D, OA>(
+        self,
+        single: S,
+        double: D,
+        delay_ms: u32,
+    ) -> OnClickOrDblClick<Self, T, A, S, D>
+    where
+        OA: OptionalAction<A>,
+        S: Fn(&mut T, web_sys::MouseEvent) -> OA,
+        D: Fn(&mut T, web_sys::MouseEvent) -> OA,
+    {
+        OnClickOrDblClick::new(self, single, double, delay_ms)
+    }
+
+    /// Calls `callback` when this element is tapped (or clicked), see [`gesture`](crate::gesture).
+    fn on_tap<F, OA>(self, callback: F) -> OnGesture<Self, T, A, gesture::Tap, F>
+    where
+        OA: OptionalAction<A>,
+        F: Fn(&mut T, GesturePoint) -> OA,
+    {
+        OnGesture::new(self, callback)
+    }
+
+    /// Calls `callback` when this element is tapped twice in quick succession.
+    fn on_double_tap<F, OA>(self, callback: F) -> OnGesture<Self, T, A, gesture::DoubleTap, F>
+    where
+        OA: OptionalAction<A>,
+        F: Fn(&mut T, GesturePoint) -> OA,
+    {
+        OnGesture::new(self, callback)
+    }
+
+    /// Applies enter and exit transitions via classes (`enter`, `enter-active` and `exit` by default).
+    ///
+    /// When the element is inserted, the classes `enter` and `enter-active` are added,
+    /// `enter` is removed in the next frame, and `enter-active` when the transition or animation ended.
+    /// When it's removed from a parent element, the class `exit` is added,
+    /// and the element is removed from the DOM when its transition or animation ended.
+    ///
+    /// ```ignore
+    /// // With CSS like `.fade { transition: opacity 0.3s; } .fade.enter, .fade.exit { opacity: 0; }`
+    /// el::li(item.title.clone()).attr("class", "fade").transition()
+    /// ```
+    fn transition(self) -> crate::transition::Transition<Self, T, A> {
+        crate::transition::Transition::new(self)
+    }
+
+    /// Calls `callback` when a pointer is held down on this element for a while (500 ms by default).
+    fn on_long_press<F, OA>(self, callback: F) -> OnGesture<Self, T, A, gesture::LongPress, F>
+    where
+        OA: OptionalAction<A>,
+        F: Fn(&mut T, GesturePoint) -> OA,
+    {
+        OnGesture::new(self, callback)
+    }
+
+    /// Calls `callback` with the direction of a swipe on this element.
+    fn on_swipe<F, OA>(self, callback: F) -> OnGesture<Self, T, A, gesture::Swipe, F>
+    where
+        OA: OptionalAction<A>,
+        F: Fn(&mut T, SwipeDirection) -> OA,
+    {
+        OnGesture::new(self, callback)
+    }
+
+    /// Calls `callback` with the scale (relative to the start of the gesture) while two pointers are pinching on this element.
+    fn on_pinch<F, OA>(self, callback: F) -> OnGesture<Self, T, A, gesture::Pinch, F>
+    where
+        OA: OptionalAction<A>,
+        F: Fn(&mut T, f64) -> OA,
+    {
+        OnGesture::new(self, callback)
+    }
+
     // TODO should the API be "functional" in the sense, that new attributes are wrappers around the type,
     // or should they modify the underlying instance (e.g. via the following methods)?
     // The disadvantage that "functional" brings in, is that elements are not modifiable (i.e. attributes can't be simply added etc.)
@@ -91,6 +224,44 @@ where
         }
     }
 
+    /// Set a (JS) property on this element, in contrast to [`Element::attr`], which sets a content attribute.
+    ///
+    /// This is necessary for values that are changed by user interaction (like `value`, `checked` or `scrollTop`),
+    /// or which have no corresponding attribute at all (like `indeterminate` or `selectedIndex`).
+    fn prop(
+        self,
+        name: impl Into<Cow<'static, str>>,
+        value: impl IntoPropertyValue,
+    ) -> Prop<Self, T, A> {
+        Prop {
+            element: self,
+            name: name.into(),
+            value: value.into_prop_value(),
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Sets the inline style `name` of this element (e.g. `width`) to `value`, which is removed when `value` is `None`.
+    ///
+    /// Numbers with units (see [`px`](crate::style::px), [`percent`](crate::style::percent), ...) and colors are only serialized,
+    /// when they changed. Styles set this way shouldn't be combined with the `style` attribute on the same element.
+    ///
+    /// ```ignore
+    /// el::div(()).style("width", px(state.width)).style("background-color", Color::rgb8(0, 128, 128))
+    /// ```
+    fn style(
+        self,
+        name: impl Into<Cow<'static, str>>,
+        value: impl IntoStyleValue,
+    ) -> Style<Self, T, A> {
+        Style {
+            element: self,
+            name: name.into(),
+            value: value.into_style_value(),
+            phantom: std::marker::PhantomData,
+        }
+    }
+
     // TODO should some methods extend some properties automatically,
     // instead of overwriting the (possibly set) inner value
     // or should there be (extra) "modifier" methods like `add_class` and/or `remove_class`
@@ -98,6 +269,218 @@ where
         self.attr("class", class.into())
     }
 
+    /// Sets the ARIA role of this element, for custom widgets, which aren't covered by the semantics of an HTML element.
+    ///
+    /// In debug builds, attributes which are required by the role (e.g. `aria-checked` for [`Role::Checkbox`]) are checked.
+    ///
+    /// ```ignore
+    /// el::div(children).role(Role::Dialog).aria_modal(true).aria_labelledby("dialog-title")
+    /// ```
+    fn role(self, role: Role) -> Attr<Self, T, A> {
+        self.attr("role", role)
+    }
+
+    /// Sets `aria-label`, the accessible name of this element, when it has no (descriptive) text content, e.g. an icon button.
+    fn aria_label(self, label: impl Into<Cow<'static, str>>) -> Attr<Self, T, A> {
+        self.attr("aria-label", label.into())
+    }
+
+    /// Sets `aria-labelledby`, the (space-separated) ids of the elements which contain the accessible name of this element.
+    fn aria_labelledby(self, ids: impl Into<Cow<'static, str>>) -> Attr<Self, T, A> {
+        self.attr("aria-labelledby", ids.into())
+    }
+
+    /// Sets `aria-describedby`, the (space-separated) ids of the elements which describe this element.
+    fn aria_describedby(self, ids: impl Into<Cow<'static, str>>) -> Attr<Self, T, A> {
+        self.attr("aria-describedby", ids.into())
+    }
+
+    /// Sets `aria-controls`, the (space-separated) ids of the elements which are controlled by this element, e.g. the panel of a tab.
+    fn aria_controls(self, ids: impl Into<Cow<'static, str>>) -> Attr<Self, T, A> {
+        self.attr("aria-controls", ids.into())
+    }
+
+    /// Sets `aria-expanded`, whether the element controlled by this element (e.g. a menu or a disclosure) is expanded.
+    fn aria_expanded(self, expanded: bool) -> Attr<Self, T, A> {
+        self.attr("aria-expanded", crate::aria::bool_attr_value(expanded))
+    }
+
+    /// Sets `aria-selected`, e.g. of a tab or an option of a listbox.
+    fn aria_selected(self, selected: bool) -> Attr<Self, T, A> {
+        self.attr("aria-selected", crate::aria::bool_attr_value(selected))
+    }
+
+    /// Sets `aria-checked`, e.g. of an element with [`Role::Checkbox`] or [`Role::Switch`].
+    fn aria_checked(self, checked: bool) -> Attr<Self, T, A> {
+        self.attr("aria-checked", crate::aria::bool_attr_value(checked))
+    }
+
+    /// Sets `aria-pressed`, which makes a button a toggle button.
+    fn aria_pressed(self, pressed: bool) -> Attr<Self, T, A> {
+        self.attr("aria-pressed", crate::aria::bool_attr_value(pressed))
+    }
+
+    /// Sets `aria-modal`, whether an element with [`Role::Dialog`] is modal.
+    fn aria_modal(self, modal: bool) -> Attr<Self, T, A> {
+        self.attr("aria-modal", crate::aria::bool_attr_value(modal))
+    }
+
+    /// Sets `aria-hidden`, which hides this element and its descendants from assistive technologies, e.g. for decorative icons.
+    ///
+    /// The attribute is removed when `hidden` is `false`.
+    fn aria_hidden(self, hidden: bool) -> Attr<Self, T, A> {
+        self.attr("aria-hidden", hidden.then_some("true"))
+    }
+
+    /// Sets the `data-{key}` attribute to `value` serialized as JSON, e.g. to pass structured data to (non-xilem) scripts.
+    ///
+    /// The value is compared as JSON value, so the attribute is only serialized again, when `value` changed.
+    /// When `value` can't be serialized, the error is logged, and the attribute is removed.
+    #[cfg(feature = "json")]
+    fn data_json(self, key: &str, value: &impl serde::Serialize) -> Attr<Self, T, A> {
+        let value = match serde_json::to_value(value) {
+            Ok(value) => Some(crate::AttributeValue::Json(value)),
+            Err(err) => {
+                web_sys::console::error_1(
+                    &format!("Couldn't serialize the value of `data-{key}`: {err}").into(),
+                );
+                None
+            }
+        };
+        self.attr(format!("data-{key}"), value)
+    }
+
+    /// Dispatches a (bubbling) `CustomEvent` named `name` from this element, with `detail` serialized as JSON,
+    /// when it's built and whenever `detail` changes, so that surrounding (non-xilem) scripts can listen to state changes.
+    ///
+    /// ```ignore
+    /// el::div(cart_view(&state.cart)).emit_custom_event("cart-changed", &state.cart.items)
+    /// ```
+    #[cfg(feature = "json")]
+    fn emit_custom_event(
+        self,
+        name: impl Into<Cow<'static, str>>,
+        detail: &impl serde::Serialize,
+    ) -> crate::EmitCustomEvent<Self, T, A> {
+        crate::EmitCustomEvent::new(self, name.into(), detail)
+    }
+
+    /// Names this element for debugging via the `data-debugname` attribute,
+    /// with the `devtools` feature it's also shown in the view tree of `App::devtools`.
+    fn debug_id(self, name: impl Into<Cow<'static, str>>) -> DebugId<Self, T, A> {
+        DebugId {
+            element: self,
+            name: name.into(),
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Makes this element draggable, `payload` is set as data of the drag (also as `text/plain`),
+    /// and received by the handler of a [`drop_target`](Self::drop_target).
+    ///
+    /// ```ignore
+    /// el::li(card.title.clone()).draggable(card.id.to_string())
+    /// ```
+    fn draggable(self, payload: impl Into<Cow<'static, str>>) -> Draggable<Self, T, A> {
+        Draggable {
+            element: self,
+            payload: payload.into(),
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Accepts drops on this element, `handler` is called with the payload of a [`draggable`](Self::draggable) element,
+    /// or the dropped files, which contents are read before the handler is called.
+    ///
+    /// ```ignore
+    /// el::ul(cards).drop_target(|state: &mut Board, data| {
+    ///     if let Some(id) = data.payload.and_then(|id| id.parse().ok()) {
+    ///         state.move_card(id, Column::Done);
+    ///     }
+    /// })
+    /// ```
+    fn drop_target<F, OA>(self, handler: F) -> DropTarget<Self, T, A, F>
+    where
+        OA: OptionalAction<A>,
+        F: Fn(&mut T, DropData) -> OA,
+    {
+        DropTarget {
+            element: self,
+            handler,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Styles this element and its descendants with `stylesheet`, which is scoped to this element via a generated class.
+    ///
+    /// The rules of the stylesheet are nested within the scope (via CSS nesting), so they only match descendants,
+    /// and `&` refers to this element. Each distinct stylesheet is only injected once into the document, so components
+    /// can ship their styles with their views.
+    ///
+    /// ```ignore
+    /// el::div((el::h2(title), el::p(body))).css("
+    ///     & { padding: 1em; border-radius: 0.5em; }
+    ///     h2 { margin-top: 0; }
+    /// ")
+    /// ```
+    fn css(self, stylesheet: impl Into<Cow<'static, str>>) -> Css<Self, T, A> {
+        Css {
+            element: self,
+            stylesheet: stylesheet.into(),
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Adds `class` to the classes of this element (set via [`class`](Self::class) or the `class` attribute),
+    /// when `condition` holds.
+    ///
+    /// Toggling the condition only adds or removes this single class in the DOM.
+    ///
+    /// ```ignore
+    /// el::li(todo.title.clone())
+    ///     .class("todo")
+    ///     .class_if(todo.completed, "completed")
+    ///     .class_if(state.editing == Some(todo.id), "editing")
+    /// ```
+    fn class_if(self, condition: bool, class: impl Into<Cow<'static, str>>) -> ClassIf<Self, T, A> {
+        ClassIf {
+            element: self,
+            class: class.into(),
+            condition,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Adds `classes` to the classes of this element, unlike [`class`](Self::class) this composes,
+    /// e.g. wrapper views (also for SVG elements) can each add their own classes.
+    ///
+    /// Each class is only contained once, in the order it was first added.
+    ///
+    /// ```ignore
+    /// svg::circle(center, radius).classes(["node", "selected"])
+    /// ```
+    fn classes(
+        self,
+        classes: impl IntoIterator<Item = impl Into<Cow<'static, str>>>,
+    ) -> Classes<Self, T, A> {
+        Classes {
+            element: self,
+            classes: classes.into_iter().map(Into::into).collect(),
+            present: true,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Removes `class` from the classes of this element, which were added by inner views (or the `class` attribute).
+    fn remove_class(self, class: impl Into<Cow<'static, str>>) -> Classes<Self, T, A> {
+        Classes {
+            element: self,
+            classes: vec![class.into()],
+            present: false,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
     // event list from
     // https://html.spec.whatwg.org/multipage/webappapis.html#idl-definitions
     //
@@ -317,12 +700,38 @@ pub(crate) use impl_dom_interfaces_for_ty;
 
 dom_interface_macro_and_trait_definitions!(
     HtmlElement {
-        methods: {},
+        methods: {
+            /// Clamps the text content to `lines` lines (with an ellipsis), using the `line-clamp` styles.
+            ///
+            /// When the content actually overflows, the full text is shown as `title` (tooltip),
+            /// unless a `title` attribute is set otherwise.
+            fn truncate(self, lines: u32) -> crate::truncate::Truncate<Self, T, A> {
+                crate::truncate::Truncate::new(self, lines)
+            }
+
+            /// Focuses the element once it's in the document.
+            fn autofocus(self) -> crate::focus::FocusWhen<Self, T, A> {
+                crate::focus::FocusWhen::new(self, true)
+            }
+
+            /// Focuses the element when `focus` changes from `false` to `true` (or it's initially `true`).
+            fn focus_when(self, focus: bool) -> crate::focus::FocusWhen<Self, T, A> {
+                crate::focus::FocusWhen::new(self, focus)
+            }
+
+            /// Keeps the focus within this element (e.g. a dialog), `Tab` and `Shift+Tab` cycle through its focusable descendants.
+            ///
+            /// The first focusable descendant is focused when it's built,
+            /// the focus is restored to the previously focused element when it's removed.
+            fn focus_trap(self) -> crate::focus::FocusTrap<Self, T, A> {
+                crate::focus::FocusTrap::new(self)
+            }
+        },
         child_interfaces: {
             HtmlAnchorElement { methods: {}, child_interfaces: {} },
             HtmlAreaElement { methods: {}, child_interfaces: {} },
             // HtmlBaseElement { methods: {}, child_interfaces: {} }, TODO include metadata?
-            // HtmlBodyElement { methods: {}, child_interfaces: {} }, TODO include body element?
+            HtmlBodyElement { methods: {}, child_interfaces: {} },
             HtmlBrElement { methods: {}, child_interfaces: {} },
             HtmlButtonElement { methods: {}, child_interfaces: {} },
             HtmlCanvasElement {
@@ -347,13 +756,28 @@ dom_interface_macro_and_trait_definitions!(
             HtmlEmbedElement { methods: {}, child_interfaces: {} },
             HtmlFieldSetElement { methods: {}, child_interfaces: {} },
             // HtmlFontElement { methods: {}, child_interfaces: {} }, deprecated
-            HtmlFormElement { methods: {}, child_interfaces: {} },
+            HtmlFormElement {
+                methods: {
+                    /// Calls `handler` with all the values of this form, when it's submitted.
+                    ///
+                    /// The default action (navigation) is prevented, so uncontrolled forms can be used
+                    /// without binding each input to the app state. Values of file inputs are read before the handler is called.
+                    fn on_submit_form<F, OA>(self, handler: F) -> crate::form::OnSubmitForm<Self, T, A, F>
+                    where
+                        OA: OptionalAction<A>,
+                        F: Fn(&mut T, crate::form::TypedFormData) -> OA,
+                    {
+                        crate::form::OnSubmitForm::new(self, handler)
+                    }
+                },
+                child_interfaces: {}
+            },
             // HtmlFrameElement { methods: {}, child_interfaces: {} }, deprecated
             // HtmlFrameSetElement { methods: {}, child_interfaces: {} }, deprecacted
-            // HtmlHeadElement { methods: {}, child_interfaces: {} }, TODO include metadata?
+            HtmlHeadElement { methods: {}, child_interfaces: {} },
             HtmlHeadingElement { methods: {}, child_interfaces: {} },
             HtmlHrElement { methods: {}, child_interfaces: {} },
-            // HtmlHtmlElement { methods: {}, child_interfaces: {} }, TODO include metadata?
+            HtmlHtmlElement { methods: {}, child_interfaces: {} },
             HtmlIFrameElement { methods: {}, child_interfaces: {} },
             HtmlImageElement { methods: {}, child_interfaces: {} },
             HtmlInputElement { methods: {}, child_interfaces: {} },
@@ -416,7 +840,12 @@ dom_interface_macro_and_trait_definitions!(
         }
     },
     SvgElement {
-        methods: {},
+        methods: {
+            /// Sets the `id` of a definition (e.g. a `<pattern>` or `<filter>`), so that it can be referenced by other elements.
+            fn def_id(self, id: &crate::svg::DefId) -> Attr<Self, T, A> {
+                self.attr("id", id)
+            }
+        },
         child_interfaces: {
             SvgAnimationElement {
                 methods: {},
@@ -447,7 +876,22 @@ dom_interface_macro_and_trait_definitions!(
                 }
             },
             SvgGraphicsElement {
-                methods: {},
+                methods: {
+                    /// Fills this element with the definition (e.g. a gradient or pattern) with the id `def`.
+                    fn fill_url(self, def: &crate::svg::DefId) -> Attr<Self, T, A> {
+                        self.attr("fill", def.url())
+                    }
+
+                    /// Strokes this element with the definition (e.g. a gradient or pattern) with the id `def`.
+                    fn stroke_url(self, def: &crate::svg::DefId) -> Attr<Self, T, A> {
+                        self.attr("stroke", def.url())
+                    }
+
+                    /// Applies the `<filter>` with the id `def` to this element.
+                    fn filter_url(self, def: &crate::svg::DefId) -> Attr<Self, T, A> {
+                        self.attr("filter", def.url())
+                    }
+                },
                 child_interfaces: {
                     SvgDefsElement { methods: {}, child_interfaces: {} },
                     SvgForeignObjectElement { methods: {}, child_interfaces: {} },
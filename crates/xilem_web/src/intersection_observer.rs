@@ -0,0 +1,362 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Observing the visibility of elements with an `IntersectionObserver`.
+
+use std::{any::Any, borrow::Cow, marker::PhantomData};
+
+use wasm_bindgen::{prelude::Closure, JsCast, JsValue, UnwrapThrowExt};
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    context::{ChangeFlags, Cx},
+    interfaces::Element,
+    view::{DomNode, View, ViewMarker},
+    OptionalAction,
+};
+
+#[derive(Debug)]
+/// A message sent when the visibility of an observed element crosses one of the thresholds.
+pub struct IntersectionMsg {
+    pub is_intersecting: bool,
+    /// How much of the element is visible, between `0.0` and `1.0`.
+    pub intersection_ratio: f64,
+}
+
+/// Options of the underlying `IntersectionObserver`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IntersectionOptions {
+    /// The visibility ratios (between `0.0` and `1.0`) at which a message is sent.
+    pub thresholds: Vec<f64>,
+    /// Grows or shrinks the bounds of the viewport, with CSS margin syntax (e.g. `"200px 0px"`).
+    pub root_margin: Cow<'static, str>,
+}
+
+impl Default for IntersectionOptions {
+    fn default() -> Self {
+        IntersectionOptions {
+            thresholds: vec![0.0],
+            root_margin: "0px".into(),
+        }
+    }
+}
+
+/// Keeps the `IntersectionObserver` alive, and disconnects it when dropped.
+struct Observer {
+    observer: web_sys::IntersectionObserver,
+    // Closures are retained so they can be called by environment
+    #[allow(unused)]
+    callback: Closure<dyn FnMut(js_sys::Array)>,
+}
+
+impl Observer {
+    fn new(node: &web_sys::Node, options: &IntersectionOptions, cx: &Cx) -> Self {
+        let thunk = cx.message_thunk();
+        let callback = Closure::new(move |entries: js_sys::Array| {
+            for entry in entries.iter() {
+                let entry: web_sys::IntersectionObserverEntry = entry.unchecked_into();
+                thunk.push_message(IntersectionMsg {
+                    is_intersecting: entry.is_intersecting(),
+                    intersection_ratio: entry.intersection_ratio(),
+                });
+            }
+        });
+        let thresholds: js_sys::Array = options
+            .thresholds
+            .iter()
+            .map(|threshold| JsValue::from_f64(*threshold))
+            .collect();
+        let mut init = web_sys::IntersectionObserverInit::new();
+        init.root_margin(&options.root_margin)
+            .threshold(&thresholds);
+        let observer = web_sys::IntersectionObserver::new_with_options(
+            callback.as_ref().unchecked_ref(),
+            &init,
+        )
+        .unwrap_throw();
+        observer.observe(node.unchecked_ref());
+        Observer { observer, callback }
+    }
+}
+
+impl Drop for Observer {
+    fn drop(&mut self) {
+        self.observer.disconnect();
+    }
+}
+
+macro_rules! intersection_options_builder {
+    () => {
+        /// Send a message when the visible ratio of the element crosses `threshold` (default = `0.0`)
+        pub fn threshold(mut self, threshold: f64) -> Self {
+            self.options.thresholds = vec![threshold];
+            self
+        }
+
+        /// Send a message when the visible ratio of the element crosses any of the `thresholds`.
+        pub fn thresholds(mut self, thresholds: impl Into<Vec<f64>>) -> Self {
+            self.options.thresholds = thresholds.into();
+            self
+        }
+
+        /// Grows (or shrinks with negative values) the viewport used for the intersection (default = `"0px"`).
+        ///
+        /// This is useful to e.g. start loading content shortly before it's scrolled into view.
+        pub fn root_margin(mut self, root_margin: impl Into<Cow<'static, str>>) -> Self {
+            self.options.root_margin = root_margin.into();
+            self
+        }
+    };
+}
+
+/// Observes the visibility of the element of the child view `V`, see [`Element::on_intersect`].
+pub struct OnIntersect<V, T, A, F> {
+    child: V,
+    callback: F,
+    options: IntersectionOptions,
+    phantom: PhantomData<fn() -> (T, A)>,
+}
+
+impl<V, T, A, F> OnIntersect<V, T, A, F> {
+    intersection_options_builder!();
+}
+
+pub fn on_intersect<T, A, F, OA, V>(child: V, callback: F) -> OnIntersect<V, T, A, F>
+where
+    V: Element<T, A>,
+    OA: OptionalAction<A>,
+    F: Fn(&mut T, IntersectionMsg) -> OA,
+{
+    OnIntersect {
+        child,
+        callback,
+        options: Default::default(),
+        phantom: PhantomData,
+    }
+}
+
+pub struct OnIntersectState<S> {
+    // Retained, so that the observer is kept alive
+    #[allow(unused)]
+    observer: Observer,
+    child_id: Id,
+    child_state: S,
+}
+
+crate::interfaces::impl_dom_interfaces_for_ty!(
+    Element,
+    OnIntersect,
+    vars: <F, OA,>,
+    vars_on_ty: <F,>,
+    bounds: {
+        OA: OptionalAction<A>,
+        F: Fn(&mut T, IntersectionMsg) -> OA,
+    }
+);
+
+impl<V, T, A, F> ViewMarker for OnIntersect<V, T, A, F> {}
+impl<V, T, A, F> crate::interfaces::sealed::Sealed for OnIntersect<V, T, A, F> {}
+
+impl<V, T, A, F, OA> View<T, A> for OnIntersect<V, T, A, F>
+where
+    V: View<T, A>,
+    OA: OptionalAction<A>,
+    F: Fn(&mut T, IntersectionMsg) -> OA,
+{
+    type State = OnIntersectState<V::State>;
+    type Element = V::Element;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, (element, state)) = cx.with_new_id(|cx| {
+            let (child_id, child_state, element) = self.child.build(cx);
+            let observer = Observer::new(element.as_node_ref(), &self.options, cx);
+            let state = OnIntersectState {
+                observer,
+                child_id,
+                child_state,
+            };
+            (element, state)
+        });
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        cx.with_id(*id, |cx| {
+            let prev_child_id = state.child_id;
+            let mut changed = self.child.rebuild(
+                cx,
+                &prev.child,
+                &mut state.child_id,
+                &mut state.child_state,
+                element,
+            );
+            if state.child_id != prev_child_id {
+                changed |= ChangeFlags::OTHER_CHANGE;
+            }
+            if prev.options != self.options || changed.contains(ChangeFlags::STRUCTURE) {
+                state.observer = Observer::new(element.as_node_ref(), &self.options, cx);
+                changed |= ChangeFlags::LISTENERS;
+            }
+            changed
+        })
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Box<dyn Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        match id_path {
+            [] if message.downcast_ref::<IntersectionMsg>().is_some() => {
+                let msg = message.downcast::<IntersectionMsg>().unwrap();
+                match (self.callback)(app_state, *msg).action() {
+                    Some(a) => MessageResult::Action(a),
+                    None => MessageResult::Nop,
+                }
+            }
+            [child_id, rest_path @ ..] if *child_id == state.child_id => {
+                self.child
+                    .message(rest_path, &mut state.child_state, message, app_state)
+            }
+            _ => MessageResult::Stale(message),
+        }
+    }
+}
+
+/// Calls a callback once, when the element of the child view `V` becomes visible for the first time,
+/// see [`Element::when_visible`].
+pub struct WhenVisible<V, T, A, F> {
+    child: V,
+    callback: F,
+    options: IntersectionOptions,
+    phantom: PhantomData<fn() -> (T, A)>,
+}
+
+impl<V, T, A, F> WhenVisible<V, T, A, F> {
+    intersection_options_builder!();
+}
+
+pub fn when_visible<T, A, F, OA, V>(child: V, callback: F) -> WhenVisible<V, T, A, F>
+where
+    V: Element<T, A>,
+    OA: OptionalAction<A>,
+    F: Fn(&mut T) -> OA,
+{
+    WhenVisible {
+        child,
+        callback,
+        options: Default::default(),
+        phantom: PhantomData,
+    }
+}
+
+pub struct WhenVisibleState<S> {
+    /// This is `None` as soon as the element was visible.
+    observer: Option<Observer>,
+    child_id: Id,
+    child_state: S,
+}
+
+crate::interfaces::impl_dom_interfaces_for_ty!(
+    Element,
+    WhenVisible,
+    vars: <F, OA,>,
+    vars_on_ty: <F,>,
+    bounds: {
+        OA: OptionalAction<A>,
+        F: Fn(&mut T) -> OA,
+    }
+);
+
+impl<V, T, A, F> ViewMarker for WhenVisible<V, T, A, F> {}
+impl<V, T, A, F> crate::interfaces::sealed::Sealed for WhenVisible<V, T, A, F> {}
+
+impl<V, T, A, F, OA> View<T, A> for WhenVisible<V, T, A, F>
+where
+    V: View<T, A>,
+    OA: OptionalAction<A>,
+    F: Fn(&mut T) -> OA,
+{
+    type State = WhenVisibleState<V::State>;
+    type Element = V::Element;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, (element, state)) = cx.with_new_id(|cx| {
+            let (child_id, child_state, element) = self.child.build(cx);
+            let observer = Observer::new(element.as_node_ref(), &self.options, cx);
+            let state = WhenVisibleState {
+                observer: Some(observer),
+                child_id,
+                child_state,
+            };
+            (element, state)
+        });
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        cx.with_id(*id, |cx| {
+            let prev_child_id = state.child_id;
+            let mut changed = self.child.rebuild(
+                cx,
+                &prev.child,
+                &mut state.child_id,
+                &mut state.child_state,
+                element,
+            );
+            if state.child_id != prev_child_id {
+                changed |= ChangeFlags::OTHER_CHANGE;
+            }
+            let needs_new_observer =
+                prev.options != self.options || changed.contains(ChangeFlags::STRUCTURE);
+            if state.observer.is_some() && needs_new_observer {
+                state.observer = Some(Observer::new(element.as_node_ref(), &self.options, cx));
+                changed |= ChangeFlags::LISTENERS;
+            }
+            changed
+        })
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Box<dyn Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        match id_path {
+            [] if message.downcast_ref::<IntersectionMsg>().is_some() => {
+                let msg = message.downcast::<IntersectionMsg>().unwrap();
+                // the observer may still have queued entries after it has been disconnected
+                if !msg.is_intersecting || state.observer.take().is_none() {
+                    return MessageResult::Nop;
+                }
+                match (self.callback)(app_state).action() {
+                    Some(a) => MessageResult::Action(a),
+                    None => MessageResult::Nop,
+                }
+            }
+            [child_id, rest_path @ ..] if *child_id == state.child_id => {
+                self.child
+                    .message(rest_path, &mut state.child_state, message, app_state)
+            }
+            _ => MessageResult::Stale(message),
+        }
+    }
+}
@@ -0,0 +1,116 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Localized formatting of numbers and dates via the `Intl` API of the browser.
+//!
+//! The returned strings can be used directly as text views, so a change of the locale (e.g. stored in the app state)
+//! results in updated text nodes with the next rebuild.
+//! Constructing an `Intl` formatter is relatively expensive, so they're cached per locale and options.
+
+use std::{borrow::Cow, cell::RefCell, collections::HashMap};
+
+use wasm_bindgen::{JsCast, JsValue, UnwrapThrowExt};
+
+type CowStr = Cow<'static, str>;
+
+/// The locale and options of a formatter.
+///
+/// See the MDN documentation of [`Intl.NumberFormat`](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Intl/NumberFormat/NumberFormat#options)
+/// and [`Intl.DateTimeFormat`](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Intl/DateTimeFormat/DateTimeFormat#options)
+/// for the available options.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct FormatOptions {
+    locale: Option<CowStr>,
+    options: Vec<(CowStr, CowStr)>,
+}
+
+impl FormatOptions {
+    /// Options with the default locale of the browser.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A BCP 47 language tag, e.g. `"de-DE"`.
+    pub fn locale(mut self, locale: impl Into<CowStr>) -> Self {
+        self.locale = Some(locale.into());
+        self
+    }
+
+    /// Sets an option, e.g. `.option("style", "currency").option("currency", "EUR")`.
+    ///
+    /// Numeric options (like `maximumFractionDigits`) can be given as strings as well.
+    pub fn option(mut self, key: impl Into<CowStr>, value: impl Into<CowStr>) -> Self {
+        let key = key.into();
+        let value = value.into();
+        match self.options.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, v)) => *v = value,
+            None => self.options.push((key, value)),
+        }
+        self
+    }
+
+    fn locales(&self) -> js_sys::Array {
+        self.locale
+            .iter()
+            .map(|locale| JsValue::from_str(locale))
+            .collect()
+    }
+
+    fn options_object(&self) -> js_sys::Object {
+        let object = js_sys::Object::new();
+        for (key, value) in &self.options {
+            js_sys::Reflect::set(&object, &JsValue::from_str(key), &JsValue::from_str(value))
+                .unwrap_throw();
+        }
+        object
+    }
+}
+
+thread_local! {
+    static NUMBER_FORMATS: RefCell<HashMap<FormatOptions, js_sys::Function>> = RefCell::new(HashMap::new());
+    static DATE_TIME_FORMATS: RefCell<HashMap<FormatOptions, js_sys::Function>> = RefCell::new(HashMap::new());
+}
+
+fn format_with(
+    cache: &'static std::thread::LocalKey<RefCell<HashMap<FormatOptions, js_sys::Function>>>,
+    options: &FormatOptions,
+    create_formatter: impl FnOnce() -> js_sys::Function,
+    value: &JsValue,
+) -> String {
+    cache.with(|formats| {
+        let mut formats = formats.borrow_mut();
+        let format = formats
+            .entry(options.clone())
+            .or_insert_with(create_formatter);
+        format
+            .call1(&JsValue::NULL, value)
+            .unwrap_throw()
+            .as_string()
+            .unwrap_throw()
+    })
+}
+
+/// Formats `value` according to the locale and options, with `Intl.NumberFormat`.
+pub fn format_number(value: f64, options: &FormatOptions) -> String {
+    format_with(
+        &NUMBER_FORMATS,
+        options,
+        || js_sys::Intl::NumberFormat::new(&options.locales(), &options.options_object()).format(),
+        &JsValue::from_f64(value),
+    )
+}
+
+/// Formats the date given in milliseconds since the UNIX epoch (as e.g. returned by `js_sys::Date::now()`)
+/// according to the locale and options, with `Intl.DateTimeFormat`.
+pub fn format_date(timestamp_ms: f64, options: &FormatOptions) -> String {
+    let date = js_sys::Date::new(&JsValue::from_f64(timestamp_ms));
+    format_with(
+        &DATE_TIME_FORMATS,
+        options,
+        || {
+            js_sys::Intl::DateTimeFormat::new(&options.locales(), &options.options_object())
+                .format()
+        },
+        date.unchecked_ref(),
+    )
+}
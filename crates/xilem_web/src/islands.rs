@@ -0,0 +1,245 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{borrow::Cow, marker::PhantomData};
+
+use wasm_bindgen::UnwrapThrowExt;
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    context::{ChangeFlags, Cx},
+    elements::ChildrenSplice,
+    interfaces::sealed::Sealed,
+    view::{DomNode, View, ViewMarker},
+    Pod, ViewSequence,
+};
+
+/// A view mounted into an existing element of the document, see [`island`].
+pub struct Island<V, T, A = ()> {
+    selector: Cow<'static, str>,
+    child: V,
+    phantom: PhantomData<fn() -> (T, A)>,
+}
+
+/// Mounts `child` into the element matching `selector` (e.g. `#cart`), which already exists in the document,
+/// its content (e.g. server-rendered markup) is replaced by `child`.
+///
+/// In its own place it's an empty comment, so it can be used anywhere in the view tree,
+/// but it's mostly intended for the root view of an app with multiple islands, see [`islands`].
+///
+/// When `selector` changes, `child` is built again and moved to the new element.
+///
+/// # Panics
+///
+/// When there's no element matching `selector`.
+pub fn island<V, T, A>(selector: impl Into<Cow<'static, str>>, child: V) -> Island<V, T, A>
+where
+    V: View<T, A>,
+{
+    Island {
+        selector: selector.into(),
+        child,
+        phantom: PhantomData,
+    }
+}
+
+pub struct IslandState<S, E> {
+    child_id: Id,
+    child_state: S,
+    child_element: E,
+    root: web_sys::Element,
+    /// The node of `child_element`, which is removed from `root` when the island is removed
+    mounted: web_sys::Node,
+}
+
+impl<S, E> Drop for IslandState<S, E> {
+    fn drop(&mut self) {
+        let root: &web_sys::Node = &self.root;
+        if self.mounted.parent_node().as_ref() == Some(root) {
+            let _ = self.root.remove_child(&self.mounted);
+        }
+    }
+}
+
+impl<V: View<T, A>, T, A> Island<V, T, A> {
+    /// Builds the child and mounts it into the element matching the selector.
+    fn mount(&self, cx: &mut Cx) -> (Id, IslandState<V::State, V::Element>) {
+        let root = cx
+            .document()
+            .query_selector(&self.selector)
+            .unwrap_throw()
+            .unwrap_or_else(|| panic!("there's no element matching `{}`", self.selector));
+        let (id, (child_id, child_state, child_element)) =
+            cx.with_new_id(|cx| self.child.build(cx));
+        cx.set_text(&root, None);
+        let mounted = child_element.as_node_ref().clone();
+        cx.insert_node(&root, &mounted, None);
+        let state = IslandState {
+            child_id,
+            child_state,
+            child_element,
+            root,
+            mounted,
+        };
+        (id, state)
+    }
+}
+
+impl<V, T, A> ViewMarker for Island<V, T, A> {}
+impl<V, T, A> Sealed for Island<V, T, A> {}
+
+impl<V, T, A> View<T, A> for Island<V, T, A>
+where
+    V: View<T, A>,
+{
+    type State = IslandState<V::State, V::Element>;
+    type Element = web_sys::Comment;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let placeholder = cx.create_comment("");
+        let (id, state) = self.mount(cx);
+        (id, state, placeholder)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        _element: &mut Self::Element,
+    ) -> ChangeFlags {
+        if self.selector != prev.selector {
+            // The child is mounted into the new element, and removed from the previous one, when its state is dropped
+            let (new_id, new_state) = self.mount(cx);
+            *id = new_id;
+            *state = new_state;
+            return ChangeFlags::OTHER_CHANGE;
+        }
+        let mut changed = cx.with_id(*id, |cx| {
+            self.child.rebuild(
+                cx,
+                &prev.child,
+                &mut state.child_id,
+                &mut state.child_state,
+                &mut state.child_element,
+            )
+        });
+        if changed.contains(ChangeFlags::STRUCTURE) {
+            let mounted = state.child_element.as_node_ref().clone();
+            cx.replace_node(&state.root, &state.mounted, &mounted);
+            state.mounted = mounted;
+        }
+        // The placeholder itself is never replaced
+        changed.remove(ChangeFlags::STRUCTURE);
+        changed
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Box<dyn std::any::Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        match id_path {
+            [child_id, rest @ ..] if *child_id == state.child_id => {
+                self.child
+                    .message(rest, &mut state.child_state, message, app_state)
+            }
+            _ => MessageResult::Stale(message),
+        }
+    }
+}
+
+/// The root view of an app with multiple islands, see [`islands`].
+pub struct Islands<T, A, Children> {
+    children: Children,
+    phantom: PhantomData<fn() -> (T, A)>,
+}
+
+/// The root view of an app with multiple [`island`]s (see [`App::run_islands`](crate::App::run_islands)),
+/// which are mounted into different elements of an existing (e.g. server-rendered) page,
+/// but share the app state and the message loop.
+///
+/// ```ignore
+/// App::new(state, |state| {
+///     islands((
+///         island("#search", search_box(&state.query)),
+///         island("#cart", cart_summary(&state.cart)),
+///     ))
+/// })
+/// .run_islands();
+/// ```
+pub fn islands<T, A, Children>(children: Children) -> Islands<T, A, Children>
+where
+    Children: ViewSequence<T, A>,
+{
+    Islands {
+        children,
+        phantom: PhantomData,
+    }
+}
+
+pub struct IslandsState<S> {
+    children_states: S,
+    child_elements: Vec<Pod>,
+    scratch: Vec<Pod>,
+}
+
+impl<T, A, Children> ViewMarker for Islands<T, A, Children> {}
+impl<T, A, Children> Sealed for Islands<T, A, Children> {}
+
+impl<T, A, Children> View<T, A> for Islands<T, A, Children>
+where
+    Children: ViewSequence<T, A>,
+{
+    type State = IslandsState<Children::State>;
+    // Contains the placeholders of the islands, and is not mounted anywhere
+    type Element = web_sys::DocumentFragment;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let fragment = cx.document().create_document_fragment();
+        let mut child_elements = vec![];
+        let mut scratch = vec![];
+        let mut splice = ChildrenSplice::new(&mut child_elements, &mut scratch, &fragment);
+        let (id, children_states) = cx.with_new_id(|cx| self.children.build(cx, &mut splice));
+        debug_assert!(scratch.is_empty());
+        let state = IslandsState {
+            children_states,
+            child_elements,
+            scratch,
+        };
+        (id, state, fragment)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let mut splice =
+            ChildrenSplice::new(&mut state.child_elements, &mut state.scratch, element);
+        let mut changed = cx.with_id(*id, |cx| {
+            self.children
+                .rebuild(cx, &prev.children, &mut state.children_states, &mut splice)
+        });
+        debug_assert!(state.scratch.is_empty());
+        changed.remove(ChangeFlags::STRUCTURE);
+        changed
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Box<dyn std::any::Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        self.children
+            .message(id_path, &mut state.children_states, message, app_state)
+    }
+}
@@ -0,0 +1,186 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{any::Any, cell::RefCell, marker::PhantomData, rc::Rc};
+
+use wasm_bindgen::{JsCast, JsValue};
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    context::{ChangeFlags, Cx, ElementProps},
+    custom_event::json_to_js_value,
+    interfaces::sealed::Sealed,
+    timer::Timeout,
+    view::{View, ViewMarker},
+    HTML_NS,
+};
+
+macro_rules! generate_dom_interface_impl {
+    ($dom_interface:ident, ()) => {
+        impl<I, U, D, T, A> $crate::interfaces::$dom_interface<T, A> for JsComponent<I, U, D, T, A>
+        where
+            I: Fn(&web_sys::HtmlElement, &JsValue) -> JsValue + 'static,
+            U: Fn(&JsValue, &JsValue) + 'static,
+            D: Fn(&JsValue) + 'static,
+        {
+        }
+    };
+}
+
+/// An external (non-xilem) JS component mounted in a `<div>`, see [`js_component`].
+pub struct JsComponent<I, U, D, T, A = ()> {
+    init: Rc<I>,
+    update: U,
+    destroy: Rc<D>,
+    props: serde_json::Value,
+    phantom: PhantomData<fn() -> (T, A)>,
+}
+
+/// A `<div>` container, in which an external JS component (e.g. a Leaflet map or a CodeMirror editor) is mounted.
+///
+/// - `init` creates the component with the container and the props, and returns its instance.
+///   It's called once the container is in the document (after the current build), so that it can be measured.
+/// - `update` is called with the instance and the new props, when the props (see [`JsComponent::props`]) changed.
+/// - `destroy` is called with the instance, when the view is removed.
+///
+/// The children of the container are owned by the component, xilem only manages its attributes (e.g. the size via `style`).
+///
+/// ```ignore
+/// js_component(
+///     |container, props| create_map(container, props),
+///     |map, props| set_map_view(map, props),
+///     |map| remove_map(map),
+/// )
+/// .props(&MapProps { lat: state.lat, lng: state.lng, zoom: state.zoom })
+/// .attr("style", "height: 400px")
+/// ```
+pub fn js_component<I, U, D, T, A>(init: I, update: U, destroy: D) -> JsComponent<I, U, D, T, A>
+where
+    I: Fn(&web_sys::HtmlElement, &JsValue) -> JsValue + 'static,
+    U: Fn(&JsValue, &JsValue) + 'static,
+    D: Fn(&JsValue) + 'static,
+{
+    JsComponent {
+        init: Rc::new(init),
+        update,
+        destroy: Rc::new(destroy),
+        props: serde_json::Value::Null,
+        phantom: PhantomData,
+    }
+}
+
+impl<I, U, D, T, A> JsComponent<I, U, D, T, A> {
+    /// The props passed to `init` and `update`, serialized as JSON.
+    ///
+    /// They're compared as JSON value, so `update` is only called when they changed.
+    /// When they can't be serialized, the error is logged, and `null` is passed instead.
+    pub fn props(mut self, props: &impl serde::Serialize) -> Self {
+        self.props = serde_json::to_value(props).unwrap_or_else(|err| {
+            web_sys::console::error_1(
+                &format!("Couldn't serialize the props of a `js_component`: {err}").into(),
+            );
+            serde_json::Value::Null
+        });
+        self
+    }
+}
+
+/// Shared with the (deferred) initialization, which uses the latest props.
+struct Mounted {
+    instance: Option<JsValue>,
+    props: JsValue,
+}
+
+pub struct JsComponentState {
+    props: ElementProps,
+    mounted: Rc<RefCell<Mounted>>,
+    destroy: Rc<dyn Fn(&JsValue)>,
+    pending_init: Option<Timeout>,
+}
+
+impl Drop for JsComponentState {
+    fn drop(&mut self) {
+        // The component is not initialized anymore, when it's removed before that
+        self.pending_init.take();
+        if let Some(instance) = self.mounted.borrow_mut().instance.take() {
+            (self.destroy)(&instance);
+        }
+    }
+}
+
+generate_dom_interface_impl!(HtmlDivElement, ());
+crate::interfaces::for_all_html_div_element_ancestors!(generate_dom_interface_impl, ());
+
+impl<I, U, D, T, A> ViewMarker for JsComponent<I, U, D, T, A> {}
+impl<I, U, D, T, A> Sealed for JsComponent<I, U, D, T, A> {}
+
+impl<I, U, D, T, A> View<T, A> for JsComponent<I, U, D, T, A>
+where
+    I: Fn(&web_sys::HtmlElement, &JsValue) -> JsValue + 'static,
+    U: Fn(&JsValue, &JsValue) + 'static,
+    D: Fn(&JsValue) + 'static,
+{
+    type State = JsComponentState;
+    type Element = web_sys::HtmlDivElement;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, (container, state)) = cx.with_new_id(|cx| {
+            let (el, props) = cx.build_element(HTML_NS, "div");
+            let container: web_sys::HtmlDivElement = el.unchecked_into();
+            let mounted = Rc::new(RefCell::new(Mounted {
+                instance: None,
+                props: json_to_js_value(&self.props),
+            }));
+            let pending_init = {
+                let container = container.clone();
+                let mounted = Rc::clone(&mounted);
+                let init = Rc::clone(&self.init);
+                Timeout::new(0, move || {
+                    let mut mounted = mounted.borrow_mut();
+                    let instance = init(&container, &mounted.props);
+                    mounted.instance = Some(instance);
+                })
+            };
+            let state = JsComponentState {
+                props,
+                mounted,
+                destroy: self.destroy.clone(),
+                pending_init: Some(pending_init),
+            };
+            (container, state)
+        });
+        (id, state, container)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        _id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let mut changed = cx.rebuild_element(element, &mut state.props);
+        state.destroy = self.destroy.clone();
+        if prev.props != self.props {
+            let mut mounted = state.mounted.borrow_mut();
+            mounted.props = json_to_js_value(&self.props);
+            // Otherwise the pending initialization uses the new props
+            if let Some(instance) = &mounted.instance {
+                (self.update)(instance, &mounted.props);
+            }
+            changed |= ChangeFlags::OTHER_CHANGE;
+        }
+        changed
+    }
+
+    fn message(
+        &self,
+        _id_path: &[Id],
+        _state: &mut Self::State,
+        message: Box<dyn Any>,
+        _app_state: &mut T,
+    ) -> MessageResult<A> {
+        MessageResult::Stale(message)
+    }
+}
@@ -0,0 +1,491 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Typed CSS flexbox and grid layouts, see [`flex`], [`grid`] and [`item`].
+//!
+//! The properties are compiled down to inline styles (like [`Element::style`]),
+//! so only properties which changed are updated in the DOM.
+//!
+//! ```ignore
+//! use xilem_web::layout::{self, Align, Justify, Track};
+//!
+//! layout::grid((
+//!     layout::item(el::header(title)).area("header"),
+//!     layout::item(el::nav(links)).area("nav"),
+//!     layout::item(el::main(content)).area("main"),
+//! ))
+//! .columns([Track::px(200), Track::fr(1)])
+//! .areas(["header header", "nav main"])
+//! .gap(px(16))
+//! .align_items(Align::Start)
+//! ```
+
+use std::{borrow::Cow, fmt::Write, marker::PhantomData};
+
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    elements::html,
+    interfaces::{sealed::Sealed, Element},
+    style::{IntoStyleValue, StyleValue},
+    ChangeFlags, Cx, View, ViewMarker, ViewSequence,
+};
+
+macro_rules! keyword_enum {
+    (
+        $(#[$attr:meta])*
+        $ident:ident { $($(#[$variant_attr:meta])* $variant:ident => $keyword:literal,)* }
+    ) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub enum $ident {
+            $($(#[$variant_attr])* $variant,)*
+        }
+
+        impl $ident {
+            /// The CSS keyword of this value.
+            pub fn as_str(self) -> &'static str {
+                match self {
+                    $($ident::$variant => $keyword,)*
+                }
+            }
+        }
+
+        impl IntoStyleValue for $ident {
+            fn into_style_value(self) -> Option<StyleValue> {
+                Some(StyleValue::String(self.as_str().into()))
+            }
+        }
+    };
+}
+
+keyword_enum! {
+    /// The distribution of space along an axis between and around items, e.g. for `justify-content`.
+    Justify {
+        Start => "start",
+        End => "end",
+        Center => "center",
+        SpaceBetween => "space-between",
+        SpaceAround => "space-around",
+        SpaceEvenly => "space-evenly",
+        Stretch => "stretch",
+    }
+}
+
+keyword_enum! {
+    /// The alignment of items within their line or cell, e.g. for `align-items`.
+    Align {
+        Start => "start",
+        End => "end",
+        Center => "center",
+        Stretch => "stretch",
+        Baseline => "baseline",
+    }
+}
+
+keyword_enum! {
+    /// The main axis of a [`flex`] layout.
+    FlexDirection {
+        Row => "row",
+        RowReverse => "row-reverse",
+        Column => "column",
+        ColumnReverse => "column-reverse",
+    }
+}
+
+keyword_enum! {
+    /// Whether the items of a [`flex`] layout wrap onto multiple lines.
+    FlexWrap {
+        NoWrap => "nowrap",
+        Wrap => "wrap",
+        WrapReverse => "wrap-reverse",
+    }
+}
+
+keyword_enum! {
+    /// How items without an explicit position are placed in a [`grid`] layout.
+    GridAutoFlow {
+        Row => "row",
+        Column => "column",
+        RowDense => "row dense",
+        ColumnDense => "column dense",
+    }
+}
+
+/// The size of a column or row of a [`grid`] layout.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Track {
+    /// A fraction of the remaining space, e.g. `1fr`.
+    Fr(f64),
+    /// A fixed length, e.g. created with [`px`](crate::style::px) or [`percent`](crate::style::percent).
+    Length(StyleValue),
+    Auto,
+    MinContent,
+    MaxContent,
+    /// A size between a minimum and a maximum, e.g. `minmax(100px, 1fr)`.
+    MinMax(Box<Track>, Box<Track>),
+    /// The tracks repeated `n` times, e.g. `repeat(3, 1fr)`.
+    Repeat(u16, Vec<Track>),
+    /// As many of the tracks as fit into the container, e.g. `repeat(auto-fill, minmax(200px, 1fr))`.
+    RepeatAutoFill(Vec<Track>),
+}
+
+impl Track {
+    pub fn fr(value: impl Into<f64>) -> Self {
+        Track::Fr(value.into())
+    }
+
+    pub fn px(value: impl Into<f64>) -> Self {
+        Track::Length(StyleValue::Px(value.into()))
+    }
+
+    pub fn minmax(min: Track, max: Track) -> Self {
+        Track::MinMax(Box::new(min), Box::new(max))
+    }
+
+    fn write_into(&self, buf: &mut String) {
+        let mut value_buf = String::new();
+        match self {
+            Track::Fr(n) => {
+                let _ = write!(
+                    buf,
+                    "{}fr",
+                    StyleValue::Number(*n).serialize_into(&mut value_buf)
+                );
+            }
+            Track::Length(value) => buf.push_str(value.serialize_into(&mut value_buf)),
+            Track::Auto => buf.push_str("auto"),
+            Track::MinContent => buf.push_str("min-content"),
+            Track::MaxContent => buf.push_str("max-content"),
+            Track::MinMax(min, max) => {
+                buf.push_str("minmax(");
+                min.write_into(buf);
+                buf.push_str(", ");
+                max.write_into(buf);
+                buf.push(')');
+            }
+            Track::Repeat(n, tracks) => {
+                let _ = write!(buf, "repeat({n}, ");
+                write_tracks(buf, tracks);
+                buf.push(')');
+            }
+            Track::RepeatAutoFill(tracks) => {
+                buf.push_str("repeat(auto-fill, ");
+                write_tracks(buf, tracks);
+                buf.push(')');
+            }
+        }
+    }
+}
+
+fn write_tracks(buf: &mut String, tracks: &[Track]) {
+    for (idx, track) in tracks.iter().enumerate() {
+        if idx > 0 {
+            buf.push(' ');
+        }
+        track.write_into(buf);
+    }
+}
+
+/// Marks a [`Layout`] as a flex container.
+pub struct Flex;
+/// Marks a [`Layout`] as a grid container.
+pub struct Grid;
+/// Marks a [`Layout`] as an item of a flex or grid container.
+pub struct Item;
+
+/// Implemented by the layouts which are containers, for the properties shared by flex and grid layouts.
+pub trait Container: Sealed {}
+impl Sealed for Flex {}
+impl Sealed for Grid {}
+impl Container for Flex {}
+impl Container for Grid {}
+
+/// Sets the typed layout properties of `K` as inline styles of the element, see [`flex`], [`grid`] and [`item`].
+pub struct Layout<E, T, A, K> {
+    element: E,
+    styles: Vec<(&'static str, StyleValue)>,
+    phantom: PhantomData<fn() -> (T, A, K)>,
+}
+
+impl<E, T, A, K> Layout<E, T, A, K> {
+    fn new(element: E, styles: Vec<(&'static str, StyleValue)>) -> Self {
+        Layout {
+            element,
+            styles,
+            phantom: PhantomData,
+        }
+    }
+
+    fn set(mut self, name: &'static str, value: impl IntoStyleValue) -> Self {
+        self.styles.retain(|(n, _)| *n != name);
+        if let Some(value) = value.into_style_value() {
+            self.styles.push((name, value));
+        }
+        self
+    }
+}
+
+/// A `<div>` with `display: flex`, the children can be configured with [`item`].
+pub fn flex<T, A, VS: ViewSequence<T, A>>(children: VS) -> Layout<html::Div<T, A, VS>, T, A, Flex> {
+    flex_container(html::div(children))
+}
+
+/// A `<div>` with `display: grid`, the children can be configured with [`item`].
+pub fn grid<T, A, VS: ViewSequence<T, A>>(children: VS) -> Layout<html::Div<T, A, VS>, T, A, Grid> {
+    grid_container(html::div(children))
+}
+
+/// Makes `element` (e.g. a `<ul>`) a flex container, see [`flex`].
+pub fn flex_container<E: Element<T, A>, T, A>(element: E) -> Layout<E, T, A, Flex> {
+    Layout::new(
+        element,
+        vec![("display", StyleValue::String("flex".into()))],
+    )
+}
+
+/// Makes `element` (e.g. a `<ul>`) a grid container, see [`grid`].
+pub fn grid_container<E: Element<T, A>, T, A>(element: E) -> Layout<E, T, A, Grid> {
+    Layout::new(
+        element,
+        vec![("display", StyleValue::String("grid".into()))],
+    )
+}
+
+/// Sets the properties of `element` as an item of a [`flex`] or [`grid`] container.
+pub fn item<E: Element<T, A>, T, A>(element: E) -> Layout<E, T, A, Item> {
+    Layout::new(element, vec![])
+}
+
+impl<E, T, A, K: Container> Layout<E, T, A, K> {
+    /// The space between rows and columns, e.g. `gap(px(8))`.
+    pub fn gap(self, gap: impl IntoStyleValue) -> Self {
+        self.set("gap", gap)
+    }
+
+    pub fn row_gap(self, gap: impl IntoStyleValue) -> Self {
+        self.set("row-gap", gap)
+    }
+
+    pub fn column_gap(self, gap: impl IntoStyleValue) -> Self {
+        self.set("column-gap", gap)
+    }
+
+    /// The distribution of the items along the main (flex) or inline (grid) axis.
+    pub fn justify_content(self, justify: Justify) -> Self {
+        self.set("justify-content", justify)
+    }
+
+    /// The distribution of the lines (flex) or tracks (grid) along the cross or block axis.
+    pub fn align_content(self, align: Justify) -> Self {
+        self.set("align-content", align)
+    }
+
+    /// The alignment of the items within their line (flex) or cell (grid).
+    pub fn align_items(self, align: Align) -> Self {
+        self.set("align-items", align)
+    }
+}
+
+impl<E, T, A> Layout<E, T, A, Flex> {
+    pub fn direction(self, direction: FlexDirection) -> Self {
+        self.set("flex-direction", direction)
+    }
+
+    pub fn wrap(self, wrap: FlexWrap) -> Self {
+        self.set("flex-wrap", wrap)
+    }
+}
+
+impl<E, T, A> Layout<E, T, A, Grid> {
+    /// The columns of the grid, e.g. `columns([Track::px(200), Track::fr(1)])`.
+    pub fn columns(self, tracks: impl IntoIterator<Item = Track>) -> Self {
+        let mut buf = String::new();
+        write_tracks(&mut buf, &tracks.into_iter().collect::<Vec<_>>());
+        self.set("grid-template-columns", buf)
+    }
+
+    /// The rows of the grid, e.g. `rows([Track::Auto, Track::fr(1), Track::Auto])`.
+    pub fn rows(self, tracks: impl IntoIterator<Item = Track>) -> Self {
+        let mut buf = String::new();
+        write_tracks(&mut buf, &tracks.into_iter().collect::<Vec<_>>());
+        self.set("grid-template-rows", buf)
+    }
+
+    /// Names the areas of the grid, one string per row with the names of its cells, e.g. `areas(["header header", "nav main"])`.
+    ///
+    /// Items are placed into an area with [`Layout::area`].
+    pub fn areas<'a>(self, rows: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut buf = String::new();
+        for row in rows {
+            if !buf.is_empty() {
+                buf.push(' ');
+            }
+            let _ = write!(buf, "\"{row}\"");
+        }
+        self.set("grid-template-areas", buf)
+    }
+
+    pub fn auto_flow(self, auto_flow: GridAutoFlow) -> Self {
+        self.set("grid-auto-flow", auto_flow)
+    }
+
+    /// The alignment of the items within their cell along the inline axis.
+    pub fn justify_items(self, justify: Align) -> Self {
+        self.set("justify-items", justify)
+    }
+}
+
+impl<E, T, A> Layout<E, T, A, Item> {
+    /// How much of the remaining space of a flex container this item takes.
+    pub fn grow(self, grow: impl Into<f64>) -> Self {
+        self.set("flex-grow", grow.into())
+    }
+
+    /// How much this item shrinks in a flex container, when there's not enough space.
+    pub fn shrink(self, shrink: impl Into<f64>) -> Self {
+        self.set("flex-shrink", shrink.into())
+    }
+
+    /// The initial size of this item in a flex container, e.g. `basis(percent(50))`.
+    pub fn basis(self, basis: impl IntoStyleValue) -> Self {
+        self.set("flex-basis", basis)
+    }
+
+    pub fn order(self, order: i32) -> Self {
+        self.set("order", order)
+    }
+
+    /// Overrides the `align-items` of the container for this item.
+    pub fn align_self(self, align: Align) -> Self {
+        self.set("align-self", align)
+    }
+
+    /// Overrides the `justify-items` of a grid container for this item.
+    pub fn justify_self(self, justify: Align) -> Self {
+        self.set("justify-self", justify)
+    }
+
+    /// Places this item into the named area of a grid container, see [`Layout::areas`].
+    pub fn area(self, area: impl Into<Cow<'static, str>>) -> Self {
+        self.set("grid-area", area.into())
+    }
+
+    /// Places this item starting at the (1-based, or negative from the end) column line of a grid container.
+    pub fn column_start(self, line: i16) -> Self {
+        self.set("grid-column-start", i32::from(line))
+    }
+
+    /// Spans this item over `n` columns of a grid container.
+    pub fn column_span(self, n: u16) -> Self {
+        self.set("grid-column-end", format!("span {n}"))
+    }
+
+    /// Places this item starting at the (1-based, or negative from the end) row line of a grid container.
+    pub fn row_start(self, line: i16) -> Self {
+        self.set("grid-row-start", i32::from(line))
+    }
+
+    /// Spans this item over `n` rows of a grid container.
+    pub fn row_span(self, n: u16) -> Self {
+        self.set("grid-row-end", format!("span {n}"))
+    }
+}
+
+impl<E, T, A, K> ViewMarker for Layout<E, T, A, K> {}
+impl<E, T, A, K> Sealed for Layout<E, T, A, K> {}
+
+impl<E: Element<T, A>, T, A, K> View<T, A> for Layout<E, T, A, K> {
+    type State = E::State;
+    type Element = E::Element;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        for (name, value) in &self.styles {
+            cx.add_style_to_element(&(*name).into(), &Some(value.clone()));
+        }
+        self.element.build(cx)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        for (name, value) in &self.styles {
+            cx.add_style_to_element(&(*name).into(), &Some(value.clone()));
+        }
+        self.element.rebuild(cx, &prev.element, id, state, element)
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Box<dyn std::any::Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        self.element.message(id_path, state, message, app_state)
+    }
+}
+
+crate::interfaces::impl_dom_interfaces_for_ty!(
+    Element,
+    Layout,
+    vars: <K,>,
+    vars_on_ty: <K,>,
+    bounds: {}
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::style::{percent, px};
+
+    fn tracks_to_string(tracks: &[Track]) -> String {
+        let mut buf = String::new();
+        write_tracks(&mut buf, tracks);
+        buf
+    }
+
+    #[test]
+    fn serializes_simple_tracks() {
+        assert_eq!(
+            tracks_to_string(&[
+                Track::px(200),
+                Track::fr(1),
+                Track::fr(1.5),
+                Track::Length(percent(25)),
+                Track::Auto,
+                Track::MinContent,
+                Track::MaxContent,
+            ]),
+            "200px 1fr 1.5fr 25% auto min-content max-content"
+        );
+        assert_eq!(tracks_to_string(&[]), "");
+    }
+
+    #[test]
+    fn serializes_nested_tracks() {
+        assert_eq!(
+            tracks_to_string(&[Track::minmax(Track::Length(px(100)), Track::fr(1))]),
+            "minmax(100px, 1fr)"
+        );
+        assert_eq!(
+            tracks_to_string(&[
+                Track::Repeat(3, vec![Track::fr(1), Track::px(8)]),
+                Track::Auto
+            ]),
+            "repeat(3, 1fr 8px) auto"
+        );
+        assert_eq!(
+            tracks_to_string(&[Track::RepeatAutoFill(vec![Track::minmax(
+                Track::px(200),
+                Track::fr(1)
+            )])]),
+            "repeat(auto-fill, minmax(200px, 1fr))"
+        );
+    }
+}
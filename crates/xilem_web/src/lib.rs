@@ -7,37 +7,144 @@
 
 use wasm_bindgen::JsCast;
 
+#[cfg(debug_assertions)]
+mod a11y;
+mod animate;
+mod animation_frame;
 mod app;
+pub mod aria;
 mod attribute;
 mod attribute_value;
+mod canvas;
+#[cfg(feature = "charts")]
+pub mod charts;
+mod class;
+#[cfg(feature = "code_block")]
+mod code_block;
 mod context;
+pub mod context_menu;
+pub mod controlled;
+mod css;
+#[cfg(feature = "json")]
+mod custom_event;
+pub mod devtools;
 mod diff;
+mod document_shell;
+mod drag_drop;
 pub mod elements;
+mod error_boundary;
 pub mod events;
+pub mod fetch;
+mod file_input;
+mod focus;
+mod form;
+pub mod gesture;
+pub mod head;
+mod hotkeys;
+pub mod i18n;
+mod image;
 pub mod interfaces;
+mod intersection_observer;
+pub mod intl;
+mod islands;
+#[cfg(feature = "json")]
+mod js_component;
+pub mod layout;
+#[cfg(feature = "markdown")]
+mod markdown;
+mod media_query;
+pub mod mutations;
 mod one_of;
 mod optional_action;
+mod overlay;
 mod pointer;
+#[cfg(feature = "profiling")]
+pub mod profiler;
+mod property;
+mod provide_context;
+pub mod recorder;
+mod responsive;
+mod scroll_restoration;
+pub mod skeleton;
+pub mod style;
+mod suspense;
 pub mod svg;
+mod timer;
+mod transition;
+mod truncate;
 mod vecmap;
 mod view;
 mod view_ext;
+pub mod web_socket;
+#[cfg(feature = "worker")]
+pub mod worker;
 
 pub use xilem_core::MessageResult;
 
+pub use animate::{animate, spring, Animate, AnimateState, Easing};
+pub use animation_frame::{on_animation_frame, OnAnimationFrame};
 pub use app::App;
+#[cfg(feature = "persist")]
+pub use app::StorageArea;
 pub use attribute::Attr;
 pub use attribute_value::{AttributeValue, IntoAttributeValue};
+pub use canvas::{
+    canvas_2d, canvas_surface, Canvas2d, Canvas2dState, CanvasSurface, CanvasSurfaceState,
+    SurfaceFrame,
+};
+pub use class::{ClassIf, Classes};
+#[cfg(feature = "code_block")]
+pub use code_block::{code_block, CodeBlock, CodeBlockState, CodeLanguage};
 pub use context::{ChangeFlags, Cx};
+pub use css::{Css, CssState};
+#[cfg(feature = "json")]
+pub use custom_event::{EmitCustomEvent, EmitCustomEventState};
+pub use document_shell::{document_shell, DocumentShell, DocumentShellState};
+pub use drag_drop::{Draggable, DraggableState, DropData, DropTarget, DropTargetState};
+pub use error_boundary::{
+    error_boundary, error_boundary_result, ErrorBoundary, ErrorBoundaryState, ResetErrorBoundary,
+};
+pub use file_input::{file_input, FileInput, FileInputState, FileProgress};
+pub use focus::{FocusTrap, FocusTrapState, FocusWhen, FocusWhenState};
+pub use form::{FormDataValue, FormFile, OnSubmitForm, OnSubmitFormState, TypedFormData};
+pub use hotkeys::{hotkeys, Hotkey, HotkeyPlatform, Hotkeys, HotkeysState};
+pub use image::{image_from_bytes, ImageFromBytes, ImageFromBytesState};
+pub use intersection_observer::{
+    IntersectionMsg, IntersectionOptions, OnIntersect, OnIntersectState, WhenVisible,
+    WhenVisibleState,
+};
+pub use islands::{island, islands, Island, IslandState, Islands, IslandsState};
+#[cfg(feature = "json")]
+pub use js_component::{js_component, JsComponent, JsComponentState};
+#[cfg(feature = "markdown")]
+pub use markdown::{markdown, Markdown, MarkdownState};
+pub use media_query::{
+    on_color_scheme, on_media_query, on_reduced_motion, ColorScheme, OnMediaQuery,
+    OnMediaQueryState,
+};
 pub use one_of::{
-    OneOf2, OneOf3, OneOf4, OneOf5, OneOf6, OneOf7, OneOf8, OneSeqOf2, OneSeqOf3, OneSeqOf4,
-    OneSeqOf5, OneSeqOf6, OneSeqOf7, OneSeqOf8,
+    cond, maybe, OneOf2, OneOf3, OneOf4, OneOf5, OneOf6, OneOf7, OneOf8, OneSeqOf2, OneSeqOf3,
+    OneSeqOf4, OneSeqOf5, OneSeqOf6, OneSeqOf7, OneSeqOf8,
 };
 pub use optional_action::{Action, OptionalAction};
+pub use overlay::{
+    modal_dialog, popover, CloseReason, ModalDialog, ModalDialogState, Popover, PopoverState,
+};
 pub use pointer::{Pointer, PointerDetails, PointerMsg};
+pub use property::{IntoPropertyValue, Prop, PropertyValue};
+pub use provide_context::{
+    provide_context, use_context, ProvideContext, UseContext, UseContextState,
+};
+pub use responsive::{responsive, Breakpoint, Breakpoints, Responsive, ResponsiveState};
+pub use scroll_restoration::{scroll_container, ScrollContainer, ScrollContainerState};
+pub use suspense::{suspense, Suspense, SuspenseState};
+pub use transition::Transition;
+pub use truncate::{Truncate, TruncateState};
 pub use view::{
-    memoize, static_view, Adapt, AdaptState, AdaptThunk, AnyView, BoxedView, ElementsSplice,
-    Memoize, MemoizeState, Pod, View, ViewMarker, ViewSequence,
+    adapt_state, adapt_state_option, hash_memoize, map_action, memoize, static_view, subscription,
+    Adapt, AdaptState, AdaptStateOption, AdaptThunk, AnyView, BoxedView, ElementsSplice,
+    HashMemoize, HashMemoizeState, MapAction, Memoize, MemoizeState, Pod, RcViewState,
+    Subscription, View, ViewMarker, ViewSequence,
 };
 pub use view_ext::ViewExt;
 
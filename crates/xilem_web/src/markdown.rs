@@ -0,0 +1,484 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Rendering markdown into DOM elements, see [`markdown`].
+//!
+//! The markdown is treated as untrusted: raw HTML is shown as text, and only relative, `http(s):` and `mailto:` urls
+//! are used for links and images (unless they're mapped by the app), so e.g. `javascript:` links are dropped.
+
+use std::{any::Any, borrow::Cow, cell::Cell, marker::PhantomData, rc::Rc};
+
+use gloo::events::{EventListener, EventListenerOptions};
+use pulldown_cmark::{Alignment, CodeBlockKind, Event, Options, Parser, Tag};
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    context::{ChangeFlags, Cx, ElementProps},
+    interfaces::sealed::Sealed,
+    view::{View, ViewMarker},
+    HTML_NS,
+};
+
+type MapUrl = Box<dyn Fn(&str) -> String>;
+type LinkClickHandler<T, A> = Box<dyn Fn(&mut T, String) -> A>;
+
+macro_rules! generate_dom_interface_impl {
+    ($dom_interface:ident, ()) => {
+        impl<T, A> $crate::interfaces::$dom_interface<T, A> for Markdown<T, A> {}
+    };
+}
+
+/// Markdown rendered into a `<div>`, see [`markdown`].
+pub struct Markdown<T, A = ()> {
+    source: Cow<'static, str>,
+    map_link: Option<MapUrl>,
+    map_image: Option<MapUrl>,
+    on_link_click: Option<LinkClickHandler<T, A>>,
+    phantom: PhantomData<fn() -> (T, A)>,
+}
+
+/// Renders the markdown `source` (CommonMark with tables, strikethrough and task lists) as elements in a `<div>`.
+///
+/// The elements are created directly (not via `innerHTML`), raw HTML within the markdown is shown as text.
+/// They're only created again, when `source` changed.
+///
+/// Links and images with other urls than relative, `http(s):` and `mailto:` ones are dropped (the link text is kept),
+/// unless [`Markdown::map_link`] or [`Markdown::map_image`] is used, which are responsible for that instead.
+///
+/// ```ignore
+/// markdown(state.readme.clone())
+///     .map_image(|src| format!("/assets/{src}"))
+///     .on_link_click(|state: &mut AppState, href| state.navigate(href))
+/// ```
+pub fn markdown<T, A>(source: impl Into<Cow<'static, str>>) -> Markdown<T, A> {
+    Markdown {
+        source: source.into(),
+        map_link: None,
+        map_image: None,
+        on_link_click: None,
+        phantom: PhantomData,
+    }
+}
+
+impl<T, A> Markdown<T, A> {
+    /// Maps the destination of links to their `href`, e.g. to resolve relative links.
+    ///
+    /// The destinations aren't filtered then (see [`markdown`]), so `map` should check them when the source isn't trusted.
+    /// Like [`Markdown::map_image`], it's only applied when the source changed.
+    pub fn map_link(mut self, map: impl Fn(&str) -> String + 'static) -> Self {
+        self.map_link = Some(Box::new(map));
+        self
+    }
+
+    /// Maps the source of images to their `src`, the same as [`Markdown::map_link`].
+    pub fn map_image(mut self, map: impl Fn(&str) -> String + 'static) -> Self {
+        self.map_image = Some(Box::new(map));
+        self
+    }
+
+    /// Handles (plain left) clicks on links with `handler` (called with the `href`), instead of navigating, e.g. for client-side routing.
+    pub fn on_link_click(mut self, handler: impl Fn(&mut T, String) -> A + 'static) -> Self {
+        self.on_link_click = Some(Box::new(handler));
+        self
+    }
+}
+
+/// Whether `url` is relative, or an `http:`, `https:` or `mailto:` url.
+///
+/// Whitespace and control characters are ignored, as browsers do when parsing urls (e.g. `java\tscript:`).
+fn is_allowed_url(url: &str) -> bool {
+    let url: String = url
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace() && !c.is_ascii_control())
+        .collect();
+    // A scheme ends with the first `:`, when it's in front of any path, query or fragment
+    match url.find([':', '/', '?', '#']) {
+        Some(idx) if url[idx..].starts_with(':') => {
+            let scheme = &url[..idx];
+            ["http", "https", "mailto"]
+                .iter()
+                .any(|allowed| scheme.eq_ignore_ascii_case(allowed))
+        }
+        _ => true,
+    }
+}
+
+/// The `<img>` which is currently rendered, its alt text is the text within the image tag.
+struct Image {
+    element: web_sys::Element,
+    alt: String,
+    /// The nesting of (ignored) tags within the alt text
+    depth: usize,
+}
+
+/// Builds the DOM of the parsed markdown.
+struct Renderer<'a, 'c> {
+    cx: &'c mut Cx,
+    map_link: Option<&'a dyn Fn(&str) -> String>,
+    map_image: Option<&'a dyn Fn(&str) -> String>,
+    /// The open elements, the first is the container
+    stack: Vec<web_sys::Element>,
+    /// The number of elements pushed on `stack` for each open tag
+    open_tags: Vec<usize>,
+    image: Option<Image>,
+    table_alignments: Vec<Alignment>,
+    table_cell: usize,
+    in_table_head: bool,
+}
+
+impl<'a, 'c> Renderer<'a, 'c> {
+    fn create(&mut self, name: &str) -> web_sys::Element {
+        let element = self.cx.create_element(HTML_NS, name);
+        let parent = self.stack.last().unwrap_throw();
+        self.cx.insert_node(parent, &element, None);
+        element
+    }
+
+    fn set_attr(&mut self, element: &web_sys::Element, name: &'static str, value: &str) {
+        self.cx
+            .set_attribute(element, name, Some(value.to_owned().into()));
+    }
+
+    /// Creates and opens the elements `names` (nested in each other), the innermost is returned.
+    fn open(&mut self, names: &[&str]) -> web_sys::Element {
+        for name in names {
+            let element = self.create(name);
+            self.stack.push(element);
+        }
+        self.open_tags.push(names.len());
+        self.stack.last().unwrap_throw().clone()
+    }
+
+    fn text(&mut self, text: &str) {
+        if let Some(image) = &mut self.image {
+            image.alt.push_str(text);
+            return;
+        }
+        let node = self.cx.create_text(text);
+        let parent = self.stack.last().unwrap_throw();
+        self.cx.insert_node(parent, &node, None);
+    }
+
+    /// An element `name` containing `text`, or just the text in the alt text of an image.
+    fn inline(&mut self, name: &str, text: &str) {
+        if self.image.is_none() {
+            self.open(&[name]);
+            self.text(text);
+            self.end();
+        } else {
+            self.text(text);
+        }
+    }
+
+    fn start(&mut self, tag: Tag<'_>) {
+        if let Some(image) = &mut self.image {
+            image.depth += 1;
+            return;
+        }
+        match tag {
+            Tag::Heading { level, .. } => {
+                let name = format!("h{}", level as u8);
+                self.open(&[name.as_str()]);
+            }
+            Tag::BlockQuote => {
+                self.open(&["blockquote"]);
+            }
+            Tag::CodeBlock(kind) => {
+                let code = self.open(&["pre", "code"]);
+                if let CodeBlockKind::Fenced(info) = kind {
+                    if let Some(language) = info.split_whitespace().next() {
+                        self.set_attr(&code, "class", &format!("language-{language}"));
+                    }
+                }
+            }
+            Tag::List(Some(start)) => {
+                let list = self.open(&["ol"]);
+                if start != 1 {
+                    self.set_attr(&list, "start", &start.to_string());
+                }
+            }
+            Tag::List(None) => {
+                self.open(&["ul"]);
+            }
+            Tag::Item => {
+                self.open(&["li"]);
+            }
+            Tag::Table(alignments) => {
+                self.table_alignments = alignments;
+                self.open(&["table"]);
+            }
+            Tag::TableHead => {
+                self.in_table_head = true;
+                self.table_cell = 0;
+                self.open(&["thead", "tr"]);
+            }
+            Tag::TableRow => {
+                self.table_cell = 0;
+                self.open(&["tr"]);
+            }
+            Tag::TableCell => {
+                let cell = self.open(&[if self.in_table_head { "th" } else { "td" }]);
+                let align = match self.table_alignments.get(self.table_cell) {
+                    Some(Alignment::Left) => Some("left"),
+                    Some(Alignment::Center) => Some("center"),
+                    Some(Alignment::Right) => Some("right"),
+                    Some(Alignment::None) | None => None,
+                };
+                if let Some(align) = align {
+                    self.set_attr(&cell, "style", &format!("text-align: {align}"));
+                }
+                self.table_cell += 1;
+            }
+            Tag::Emphasis => {
+                self.open(&["em"]);
+            }
+            Tag::Strong => {
+                self.open(&["strong"]);
+            }
+            Tag::Strikethrough => {
+                self.open(&["del"]);
+            }
+            Tag::Link {
+                dest_url, title, ..
+            } => {
+                let link = self.open(&["a"]);
+                let href = match self.map_link {
+                    Some(map_link) => Some(map_link(&dest_url)),
+                    None => is_allowed_url(&dest_url).then(|| dest_url.to_string()),
+                };
+                if let Some(href) = href {
+                    self.set_attr(&link, "href", &href);
+                }
+                if !title.is_empty() {
+                    self.set_attr(&link, "title", &title);
+                }
+            }
+            Tag::Image {
+                dest_url, title, ..
+            } => {
+                let element = self.create("img");
+                let src = match self.map_image {
+                    Some(map_image) => Some(map_image(&dest_url)),
+                    None => is_allowed_url(&dest_url).then(|| dest_url.to_string()),
+                };
+                if let Some(src) = src {
+                    self.set_attr(&element, "src", &src);
+                }
+                if !title.is_empty() {
+                    self.set_attr(&element, "title", &title);
+                }
+                self.image = Some(Image {
+                    element,
+                    alt: String::new(),
+                    depth: 0,
+                });
+            }
+            // Paragraphs, and the rest (e.g. footnote definitions) are shown as paragraph
+            _ => {
+                self.open(&["p"]);
+            }
+        }
+    }
+
+    fn end(&mut self) {
+        if let Some(image) = &mut self.image {
+            if image.depth > 0 {
+                image.depth -= 1;
+            } else {
+                let Image { element, alt, .. } = self.image.take().unwrap_throw();
+                self.set_attr(&element, "alt", &alt);
+            }
+            return;
+        }
+        let count = self.open_tags.pop().unwrap_or(0);
+        for _ in 0..count {
+            if let Some(element) = self.stack.pop() {
+                if element.tag_name().eq_ignore_ascii_case("thead") {
+                    self.in_table_head = false;
+                }
+            }
+        }
+    }
+
+    fn render(mut self, source: &str) {
+        let options =
+            Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+        for event in Parser::new_ext(source, options) {
+            match event {
+                Event::Start(tag) => self.start(tag),
+                Event::End(_) => self.end(),
+                Event::Code(code) => self.inline("code", &code),
+                Event::SoftBreak => self.text("\n"),
+                Event::HardBreak => {
+                    self.create("br");
+                }
+                Event::Rule => {
+                    self.create("hr");
+                }
+                Event::TaskListMarker(checked) => {
+                    let checkbox = self.create("input");
+                    self.set_attr(&checkbox, "type", "checkbox");
+                    self.set_attr(&checkbox, "disabled", "");
+                    if checked {
+                        self.set_attr(&checkbox, "checked", "");
+                    }
+                }
+                Event::FootnoteReference(label) => self.inline("sup", &label),
+                // Text, and raw HTML, which is shown as text as well
+                Event::Text(text) | Event::Html(text) | Event::InlineHtml(text) => {
+                    self.text(&text);
+                }
+                #[allow(unreachable_patterns)]
+                _ => {}
+            }
+        }
+    }
+}
+
+struct LinkClicked(String);
+
+pub struct MarkdownState {
+    props: ElementProps,
+    /// Whether link clicks are handled, see [`Markdown::on_link_click`]
+    handle_link_clicks: Rc<Cell<bool>>,
+    #[allow(unused)]
+    click_listener: EventListener,
+}
+
+generate_dom_interface_impl!(HtmlDivElement, ());
+crate::interfaces::for_all_html_div_element_ancestors!(generate_dom_interface_impl, ());
+
+impl<T, A> ViewMarker for Markdown<T, A> {}
+impl<T, A> Sealed for Markdown<T, A> {}
+
+impl<T, A> Markdown<T, A> {
+    fn render(&self, cx: &mut Cx, container: &web_sys::Element) {
+        cx.set_text(container, None);
+        Renderer {
+            cx,
+            map_link: self.map_link.as_deref(),
+            map_image: self.map_image.as_deref(),
+            stack: vec![container.clone()],
+            open_tags: Vec::new(),
+            image: None,
+            table_alignments: Vec::new(),
+            table_cell: 0,
+            in_table_head: false,
+        }
+        .render(&self.source);
+    }
+}
+
+impl<T, A> View<T, A> for Markdown<T, A> {
+    type State = MarkdownState;
+    type Element = web_sys::HtmlDivElement;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (el, props) = cx.build_element(HTML_NS, "div");
+        self.render(cx, &el);
+        let handle_link_clicks = Rc::new(Cell::new(self.on_link_click.is_some()));
+        let (id, click_listener) = cx.with_new_id(|cx| {
+            let thunk = cx.message_thunk();
+            let handle_link_clicks = Rc::clone(&handle_link_clicks);
+            EventListener::new_with_options(
+                &el,
+                "click",
+                EventListenerOptions::enable_prevent_default(),
+                move |event| {
+                    let event: &web_sys::MouseEvent = event.unchecked_ref();
+                    // Modified clicks (e.g. to open a new tab) are left to the browser
+                    if !handle_link_clicks.get()
+                        || event.button() != 0
+                        || event.ctrl_key()
+                        || event.meta_key()
+                        || event.shift_key()
+                        || event.alt_key()
+                    {
+                        return;
+                    }
+                    let href = event
+                        .target()
+                        .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+                        .and_then(|target| target.closest("a[href]").ok().flatten())
+                        .and_then(|link| link.get_attribute("href"));
+                    if let Some(href) = href {
+                        event.prevent_default();
+                        thunk.push_message(LinkClicked(href));
+                    }
+                },
+            )
+        });
+        let state = MarkdownState {
+            props,
+            handle_link_clicks,
+            click_listener,
+        };
+        (id, state, el.unchecked_into())
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        _id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let mut changed = cx.rebuild_element(element, &mut state.props);
+        state.handle_link_clicks.set(self.on_link_click.is_some());
+        if self.source != prev.source {
+            self.render(cx, element);
+            changed |= ChangeFlags::OTHER_CHANGE;
+        }
+        changed
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        _state: &mut Self::State,
+        message: Box<dyn Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        match (id_path, message.downcast::<LinkClicked>()) {
+            ([], Ok(link)) => match &self.on_link_click {
+                Some(on_link_click) => MessageResult::Action(on_link_click(app_state, link.0)),
+                None => MessageResult::Nop,
+            },
+            (_, Ok(link)) => MessageResult::Stale(link),
+            (_, Err(message)) => MessageResult::Stale(message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_allowed_url;
+
+    #[test]
+    fn allowed_urls() {
+        for url in [
+            "docs/intro.md",
+            "/assets/logo.png",
+            "#usage",
+            "?page=2",
+            "../a:b",
+            "https://example.com/a:b",
+            "HTTP://example.com",
+            "mailto:someone@example.com",
+        ] {
+            assert!(is_allowed_url(url), "{url}");
+        }
+        for url in [
+            "javascript:alert(1)",
+            " JavaScript:alert(1)",
+            "java\tscript:alert(1)",
+            "data:text/html,<script>alert(1)</script>",
+            "vbscript:msgbox",
+            "file:///etc/passwd",
+        ] {
+            assert!(!is_allowed_url(url), "{url}");
+        }
+    }
+}
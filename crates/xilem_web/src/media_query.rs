@@ -0,0 +1,168 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Subscriptions to media queries like `prefers-color-scheme`, see [`on_media_query`].
+
+use std::{any::Any, borrow::Cow, marker::PhantomData};
+
+use gloo::events::EventListener;
+use wasm_bindgen::UnwrapThrowExt;
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    context::{ChangeFlags, Cx},
+    timer::Timeout,
+    view::{View, ViewMarker},
+    OptionalAction,
+};
+
+type CowStr = Cow<'static, str>;
+
+/// The color scheme preferred by the user, see [`on_color_scheme`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ColorScheme {
+    #[default]
+    Light,
+    Dark,
+}
+
+/// Sent with whether the media query matches.
+struct MediaQueryMatches(bool);
+
+/// Calls the handler with whether a media query matches, see [`on_media_query`].
+pub struct OnMediaQuery<T, A, C> {
+    query: CowStr,
+    handler: C,
+    phantom: PhantomData<fn() -> (T, A)>,
+}
+
+/// Calls `handler` with whether the media `query` (e.g. `"(orientation: portrait)"`) matches,
+/// once after this view was built, and each time it changes, so that the app state can be kept in sync with it.
+///
+/// The listener is removed, when this view is removed from the view tree.
+pub fn on_media_query<T, A, C, OA>(query: impl Into<CowStr>, handler: C) -> OnMediaQuery<T, A, C>
+where
+    OA: OptionalAction<A>,
+    C: Fn(&mut T, bool) -> OA,
+{
+    OnMediaQuery {
+        query: query.into(),
+        handler,
+        phantom: PhantomData,
+    }
+}
+
+/// Calls `handler` with the color scheme preferred by the user (`prefers-color-scheme`), initially and when it changes.
+///
+/// ```ignore
+/// el::div((
+///     on_color_scheme(|state: &mut AppState, scheme| state.color_scheme = scheme),
+///     content(state),
+/// ))
+/// .class(if state.color_scheme == ColorScheme::Dark { "dark" } else { "light" })
+/// ```
+pub fn on_color_scheme<T, A, C, OA>(handler: C) -> OnMediaQuery<T, A, impl Fn(&mut T, bool) -> OA>
+where
+    OA: OptionalAction<A>,
+    C: Fn(&mut T, ColorScheme) -> OA,
+{
+    on_media_query(
+        "(prefers-color-scheme: dark)",
+        move |state: &mut T, dark| {
+            let scheme = if dark {
+                ColorScheme::Dark
+            } else {
+                ColorScheme::Light
+            };
+            handler(state, scheme)
+        },
+    )
+}
+
+/// Calls `handler` with whether the user prefers reduced motion (`prefers-reduced-motion`), initially and when it changes,
+/// e.g. to disable animations.
+pub fn on_reduced_motion<T, A, C, OA>(handler: C) -> OnMediaQuery<T, A, C>
+where
+    OA: OptionalAction<A>,
+    C: Fn(&mut T, bool) -> OA,
+{
+    on_media_query("(prefers-reduced-motion: reduce)", handler)
+}
+
+pub struct OnMediaQueryState {
+    // Retained, so that the listener is removed when dropped
+    #[allow(unused)]
+    listener: EventListener,
+    #[allow(unused)]
+    initial: Timeout,
+}
+
+fn subscribe(query: &str, cx: &Cx) -> OnMediaQueryState {
+    let query = web_sys::window()
+        .unwrap_throw()
+        .match_media(query)
+        .unwrap_throw()
+        .expect_throw("media query should be valid");
+    let thunk = cx.message_thunk();
+    let mql = query.clone();
+    let listener = EventListener::new(&query, "change", move |_| {
+        thunk.push_message(MediaQueryMatches(mql.matches()));
+    });
+    // Messages can't be handled while the view tree is (re)built, so the initial value is sent afterwards
+    let thunk = cx.message_thunk();
+    let initial = Timeout::new(0, move || {
+        thunk.push_message(MediaQueryMatches(query.matches()));
+    });
+    OnMediaQueryState { listener, initial }
+}
+
+impl<T, A, C> ViewMarker for OnMediaQuery<T, A, C> {}
+
+impl<T, A, C, OA> View<T, A> for OnMediaQuery<T, A, C>
+where
+    OA: OptionalAction<A>,
+    C: Fn(&mut T, bool) -> OA,
+{
+    type State = OnMediaQueryState;
+    type Element = web_sys::Comment;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, state) = cx.with_new_id(|cx| subscribe(&self.query, cx));
+        let el = cx.create_comment(&self.query);
+        (id, state, el)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        if prev.query != self.query {
+            *state = cx.with_id(*id, |cx| subscribe(&self.query, cx));
+            cx.set_text(element, Some(self.query.clone()));
+        }
+        ChangeFlags::empty()
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        _state: &mut Self::State,
+        message: Box<dyn Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        match id_path {
+            [] if message.downcast_ref::<MediaQueryMatches>().is_some() => {
+                let MediaQueryMatches(matches) = *message.downcast::<MediaQueryMatches>().unwrap();
+                match (self.handler)(app_state, matches).action() {
+                    Some(a) => MessageResult::Action(a),
+                    None => MessageResult::Nop,
+                }
+            }
+            _ => MessageResult::Stale(message),
+        }
+    }
+}
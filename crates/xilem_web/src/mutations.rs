@@ -0,0 +1,322 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The DOM mutations of a (re)build as a list of [`DomOp`]s, which is applied in one pass when the (re)build is done.
+//!
+//! Nodes are identified by a [`NodeId`]. The views still create their DOM nodes right away (as they hold on to them),
+//! but each new node is recorded with a create op, so the ops describe the whole tree, not only the changes of existing nodes.
+//!
+//! The ops are always applied to the DOM. An additional [`ApplyDomOps`] set via [`App::dom_applier`](crate::App::dom_applier)
+//! receives the same ops afterwards, e.g. to record or count them in tests, to render the tree to a string,
+//! or to mirror it somewhere else. The [`worker`](crate::worker) protocol consists of these ops as well.
+
+use std::{borrow::Cow, collections::HashMap};
+
+use wasm_bindgen::{intern, JsCast, JsValue, UnwrapThrowExt};
+
+use crate::PropertyValue;
+
+type CowStr = Cow<'static, str>;
+
+/// The (hidden) JS property of a DOM node, which stores its [`NodeId`].
+const NODE_ID_PROPERTY: &str = "__xilemNodeId";
+
+/// Identifies a DOM node in the [`DomOp`]s.
+///
+/// Nodes which weren't created via an op (e.g. the `<head>` of the document) get an id, when they're first referred to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "worker", derive(serde::Serialize, serde::Deserialize))]
+pub struct NodeId(pub u32);
+
+impl NodeId {
+    /// The element the app (or the worker) is mounted into.
+    pub const ROOT: NodeId = NodeId(0);
+}
+
+/// A single DOM mutation.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "worker",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "op", rename_all = "snake_case")
+)]
+pub enum DomOp {
+    CreateElement {
+        id: NodeId,
+        ns: CowStr,
+        tag: CowStr,
+    },
+    CreateText {
+        id: NodeId,
+        text: CowStr,
+    },
+    /// A comment, which is used as placeholder by views without a visible element.
+    CreateComment {
+        id: NodeId,
+        text: CowStr,
+    },
+    /// Inserts `child` in front of `before`, or at the end of `parent`, a child which is already in the DOM is moved.
+    Insert {
+        parent: NodeId,
+        child: NodeId,
+        before: Option<NodeId>,
+    },
+    /// Removes the node from the document, its id and the ids of its descendants are not valid anymore.
+    ///
+    /// In the DOM, the removal of an element with an [exit transition](crate::interfaces::Element::transition) is deferred until it ended.
+    Remove {
+        id: NodeId,
+    },
+    /// Sets the text of a text node, or replaces all the children of an element by `text` (`None` removes them).
+    SetText {
+        id: NodeId,
+        text: Option<CowStr>,
+    },
+    /// Sets the attribute to `value`, or removes it when it's `None`.
+    SetAttr {
+        id: NodeId,
+        name: CowStr,
+        value: Option<CowStr>,
+    },
+    /// Adds or removes a single class (via `classList`), without touching the other classes of the element.
+    ToggleClass {
+        id: NodeId,
+        class: CowStr,
+        present: bool,
+    },
+    /// Sets the (JS) property to `value`, or deletes it when it's `None`.
+    SetProp {
+        id: NodeId,
+        name: CowStr,
+        value: Option<PropertyValue>,
+    },
+    /// Sets the inline style property to `value`, or removes it when it's `None`.
+    SetStyle {
+        id: NodeId,
+        name: CowStr,
+        value: Option<CowStr>,
+    },
+    /// Sends the events of type `event` of the node back to the app.
+    ///
+    /// This is only used by the [`worker`](crate::worker) protocol, the views of this crate attach their listeners directly.
+    Listen {
+        id: NodeId,
+        event: CowStr,
+    },
+    Unlisten {
+        id: NodeId,
+        event: CowStr,
+    },
+}
+
+/// Applies the [`DomOp`]s of a (re)build, see [`App::dom_applier`](crate::App::dom_applier).
+pub trait ApplyDomOps {
+    /// Applies the ops, in the order they were emitted during the (re)build.
+    fn apply(&mut self, ops: &[DomOp]);
+}
+
+/// The ops of the current (re)build, which are recorded by the [`Cx`](crate::Cx), and the DOM nodes they refer to.
+#[derive(Default)]
+pub(crate) struct DomOps {
+    ops: Vec<DomOp>,
+    /// Only the nodes referred to by `ops` are kept here, so that removed nodes aren't retained.
+    nodes: HashMap<NodeId, web_sys::Node>,
+    last_id: u32,
+    applier: Option<Box<dyn ApplyDomOps>>,
+}
+
+impl DomOps {
+    /// The id of `node`, which is stored in the node itself, nodes without one get a new id.
+    pub(crate) fn node_id(&mut self, node: &web_sys::Node) -> NodeId {
+        let id = js_sys::Reflect::get(node, &JsValue::from_str(intern(NODE_ID_PROPERTY)))
+            .ok()
+            .and_then(|id| id.as_f64());
+        match id {
+            Some(id) => {
+                let id = NodeId(id as u32);
+                if !self.nodes.contains_key(&id) {
+                    self.nodes.insert(id, node.clone());
+                }
+                id
+            }
+            None => self.new_node_id(node),
+        }
+    }
+
+    /// Assigns a new id to `node`, which was just created.
+    pub(crate) fn new_node_id(&mut self, node: &web_sys::Node) -> NodeId {
+        self.last_id += 1;
+        let id = NodeId(self.last_id);
+        self.set_node_id(node, id);
+        id
+    }
+
+    /// Marks `root` as the element the app is mounted into, i.e. [`NodeId::ROOT`].
+    pub(crate) fn set_root(&mut self, root: &web_sys::Node) {
+        self.set_node_id(root, NodeId::ROOT);
+    }
+
+    fn set_node_id(&mut self, node: &web_sys::Node, id: NodeId) {
+        js_sys::Reflect::set(
+            node,
+            &JsValue::from_str(intern(NODE_ID_PROPERTY)),
+            &JsValue::from(id.0),
+        )
+        .unwrap_throw();
+        self.nodes.insert(id, node.clone());
+    }
+
+    pub(crate) fn push(&mut self, op: DomOp) {
+        self.ops.push(op);
+    }
+
+    pub(crate) fn set_applier(&mut self, applier: impl ApplyDomOps + 'static) {
+        self.applier = Some(Box::new(applier));
+    }
+
+    /// Applies the recorded ops to the DOM, and then passes them to the applier of the app.
+    pub(crate) fn flush(&mut self) {
+        if self.ops.is_empty() {
+            return;
+        }
+        let nodes = &self.nodes;
+        for op in &self.ops {
+            match op {
+                // The views have created the nodes already
+                DomOp::CreateElement { .. }
+                | DomOp::CreateText { .. }
+                | DomOp::CreateComment { .. } => {}
+                DomOp::Remove { id } => crate::transition::remove_node(&nodes[id]),
+                // The views attach their listeners directly
+                DomOp::Listen { .. } | DomOp::Unlisten { .. } => {}
+                op => apply_to_dom(op, |id| nodes.get(&id)),
+            }
+        }
+        if let Some(applier) = &mut self.applier {
+            applier.apply(&self.ops);
+        }
+        self.ops.clear();
+        self.nodes.clear();
+    }
+}
+
+/// Applies an op which inserts or changes a node to the DOM, `node` looks up the DOM node with an id.
+///
+/// Creating and removing nodes, and listening to events is left to the caller, as well as handling unknown ids.
+pub(crate) fn apply_to_dom<'a>(op: &DomOp, node: impl Fn(NodeId) -> Option<&'a web_sys::Node>) {
+    let element = |id| node(id).and_then(|node| node.dyn_ref::<web_sys::Element>());
+    match op {
+        DomOp::Insert {
+            parent,
+            child,
+            before,
+        } => {
+            let before = match before {
+                Some(before) => match node(*before) {
+                    Some(before) => Some(before),
+                    None => return,
+                },
+                None => None,
+            };
+            if let (Some(parent), Some(child)) = (node(*parent), node(*child)) {
+                parent.insert_before(child, before).unwrap_throw();
+            }
+        }
+        DomOp::SetText { id, text } => {
+            if let Some(node) = node(*id) {
+                node.set_text_content(text.as_deref());
+            }
+        }
+        DomOp::SetAttr { id, name, value } => {
+            if let Some(element) = element(*id) {
+                match value {
+                    Some(value) => set_attribute(element, name, value),
+                    None => remove_attribute(element, name),
+                }
+            }
+        }
+        DomOp::ToggleClass { id, class, present } => {
+            if let Some(element) = element(*id) {
+                let class_list = element.class_list();
+                if *present {
+                    class_list.add_1(intern(class)).unwrap_throw();
+                } else {
+                    class_list.remove_1(intern(class)).unwrap_throw();
+                }
+            }
+        }
+        DomOp::SetProp { id, name, value } => {
+            if let Some(element) = element(*id) {
+                match value {
+                    Some(value) => set_property(element, name, value),
+                    None => remove_property(element, name),
+                }
+            }
+        }
+        DomOp::SetStyle { id, name, value } => {
+            if let Some(element) = element(*id) {
+                let style = inline_style(element);
+                match value {
+                    Some(value) => style.set_property(intern(name), value).unwrap_throw(),
+                    None => {
+                        style.remove_property(intern(name)).unwrap_throw();
+                    }
+                }
+            }
+        }
+        DomOp::CreateElement { .. }
+        | DomOp::CreateText { .. }
+        | DomOp::CreateComment { .. }
+        | DomOp::Remove { .. }
+        | DomOp::Listen { .. }
+        | DomOp::Unlisten { .. } => {}
+    }
+}
+
+fn set_attribute(element: &web_sys::Element, name: &str, value: &str) {
+    // we have to special-case `value` because setting the value using `set_attribute`
+    // doesn't work after the value has been changed.
+    if name == "value" {
+        let element: &web_sys::HtmlInputElement = element.dyn_ref().unwrap_throw();
+        element.set_value(value);
+    } else if name == "checked" {
+        let element: &web_sys::HtmlInputElement = element.dyn_ref().unwrap_throw();
+        element.set_checked(true);
+    } else {
+        // Only the name is interned, the values (including whole class lists) are too diverse,
+        // every distinct value would stay in the intern cache forever.
+        // Single class names are interned when they're toggled via `classList` instead.
+        element.set_attribute(intern(name), value).unwrap_throw();
+    }
+}
+
+fn remove_attribute(element: &web_sys::Element, name: &str) {
+    // we have to special-case `checked` because setting the value using `set_attribute`
+    // doesn't work after the value has been changed.
+    if name == "checked" {
+        let element: &web_sys::HtmlInputElement = element.dyn_ref().unwrap_throw();
+        element.set_checked(false);
+    } else {
+        element.remove_attribute(intern(name)).unwrap_throw();
+    }
+}
+
+fn set_property(element: &web_sys::Element, name: &str, value: &PropertyValue) {
+    js_sys::Reflect::set(
+        element,
+        &JsValue::from_str(intern(name)),
+        &value.to_js_value(),
+    )
+    .unwrap_throw();
+}
+
+// Properties that are defined by the DOM interface of the element (like `checked`) can't be removed,
+// they keep their last value in that case.
+fn remove_property(element: &web_sys::Element, name: &str) {
+    js_sys::Reflect::delete_property(element, &JsValue::from_str(intern(name))).unwrap_throw();
+}
+
+fn inline_style(element: &web_sys::Element) -> web_sys::CssStyleDeclaration {
+    // Both HTML and SVG elements have a `style` property
+    element.unchecked_ref::<web_sys::HtmlElement>().style()
+}
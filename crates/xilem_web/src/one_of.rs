@@ -283,3 +283,20 @@ one_of_sequence! {
     /// This view sequence container can switch between eight view sequences.
     OneSeqOf8 { A, B, C, D, E, F, G, H }
 }
+
+/// Shows `if_true` when `flag` is set, and `if_false` otherwise,
+/// which is a shorthand for `if flag { OneOf2::A(if_true) } else { OneOf2::B(if_false) }`.
+///
+/// Note that both views are created, so [`maybe`] should be used when one of them is expensive to create.
+pub fn cond<A, B>(flag: bool, if_true: A, if_false: B) -> OneOf2<A, B> {
+    if flag {
+        OneOf2::A(if_true)
+    } else {
+        OneOf2::B(if_false)
+    }
+}
+
+/// Shows the view created by `view` only when `flag` is set (as `Option`, which is a view sequence).
+pub fn maybe<V>(flag: bool, view: impl FnOnce() -> V) -> Option<V> {
+    flag.then(view)
+}
@@ -0,0 +1,561 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Modal dialogs and popovers, see [`modal_dialog`] and [`popover`].
+
+use std::{any::Any, marker::PhantomData};
+
+use gloo::events::{EventListener, EventListenerOptions};
+use wasm_bindgen::JsCast;
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    context::{ChangeFlags, Cx, ElementProps},
+    focus::{create_tab_listener, RestoreFocus},
+    interfaces::sealed::Sealed,
+    style::IntoStyleValue,
+    timer::Timeout,
+    view::{DomNode, View, ViewMarker},
+    OptionalAction, HTML_NS,
+};
+
+/// Why the user wants to close a [`modal_dialog`] or a [`popover`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CloseReason {
+    /// The `Escape` key was pressed.
+    Escape,
+    /// The backdrop of the dialog, or outside of the popover (and its anchor) was clicked.
+    OutsideClick,
+    /// The dialog was closed by the page, e.g. by submitting a `<form method="dialog">` in it.
+    Closed,
+}
+
+/// Sent when the `<dialog>` was closed, no matter how.
+struct DialogClosed;
+
+/// Replaces the node `prev` with the (possibly recreated) node of a child view.
+fn replace_child_node(
+    cx: &mut Cx,
+    parent: &web_sys::Node,
+    prev: &web_sys::Node,
+    child: &impl DomNode,
+) {
+    let child = child.as_node_ref();
+    if !prev.is_same_node(Some(child)) {
+        cx.replace_node(parent, prev, child);
+    }
+}
+
+/// A modal `<dialog>`, see [`modal_dialog`].
+pub struct ModalDialog<V, T, A, F = fn(&mut T, CloseReason)> {
+    open: bool,
+    content: V,
+    on_close: Option<F>,
+    phantom: PhantomData<fn() -> (T, A)>,
+}
+
+/// A modal `<dialog>` with `content`, which is shown (via `showModal()`) as long as `open` is `true`.
+///
+/// While it's shown, the rest of the page is inert, and the focus is kept within the dialog,
+/// when it's closed, the focus is restored to the element that had it before.
+///
+/// The dialog doesn't close itself, instead [`ModalDialog::on_close`] is called with the [`CloseReason`],
+/// when the user presses `Escape` or clicks on the backdrop, so that the app state stays the single source of truth.
+/// When it's closed anyway (e.g. by a `<form method="dialog">`), `on_close` is called with [`CloseReason::Closed`].
+///
+/// ```ignore
+/// modal_dialog(state.confirm_delete, el::div((
+///     el::h2("Delete this file?"),
+///     el::button("Delete").on_click(|state: &mut AppState, _| state.delete()),
+///     el::button("Cancel").on_click(|state: &mut AppState, _| state.confirm_delete = false),
+/// )))
+/// .on_close(|state, _reason| state.confirm_delete = false)
+/// ```
+pub fn modal_dialog<V, T, A>(open: bool, content: V) -> ModalDialog<V, T, A> {
+    ModalDialog {
+        open,
+        content,
+        on_close: None,
+        phantom: PhantomData,
+    }
+}
+
+impl<V, T, A> ModalDialog<V, T, A> {
+    /// Calls `on_close`, when the user wants to close the dialog.
+    pub fn on_close<F, OA>(self, on_close: F) -> ModalDialog<V, T, A, F>
+    where
+        F: Fn(&mut T, CloseReason) -> OA,
+        OA: OptionalAction<A>,
+    {
+        ModalDialog {
+            open: self.open,
+            content: self.content,
+            on_close: Some(on_close),
+            phantom: PhantomData,
+        }
+    }
+}
+
+pub struct ModalDialogState<S, E> {
+    props: ElementProps,
+    child_id: Id,
+    child_state: S,
+    child_element: E,
+    // Retained, so that the listeners are removed when dropped
+    #[allow(unused)]
+    listeners: [EventListener; 4],
+    pending_show: Option<Timeout>,
+    #[allow(unused)]
+    restore_focus: Option<RestoreFocus>,
+}
+
+fn create_dialog_listeners(dialog: &web_sys::HtmlDialogElement, cx: &Cx) -> [EventListener; 4] {
+    let thunk = cx.message_thunk();
+    // Escape is handled by the browser by closing the dialog, which is prevented to keep it in sync with `open`
+    let cancel = EventListener::new_with_options(
+        dialog,
+        "cancel",
+        EventListenerOptions::enable_prevent_default(),
+        move |event| {
+            event.prevent_default();
+            thunk.push_message(CloseReason::Escape);
+        },
+    );
+    let thunk = cx.message_thunk();
+    let el = dialog.clone();
+    // Clicks on the backdrop target the dialog itself, but are outside of its box
+    let click = EventListener::new(dialog, "click", move |event| {
+        let event: &web_sys::MouseEvent = event.unchecked_ref();
+        let on_dialog = event
+            .target()
+            .and_then(|target| target.dyn_into::<web_sys::Node>().ok())
+            .is_some_and(|target| el.is_same_node(Some(&target)));
+        let rect = el.get_bounding_client_rect();
+        let (x, y) = (event.client_x() as f64, event.client_y() as f64);
+        let outside = x < rect.left() || x > rect.right() || y < rect.top() || y > rect.bottom();
+        if on_dialog && outside {
+            thunk.push_message(CloseReason::OutsideClick);
+        }
+    });
+    let thunk = cx.message_thunk();
+    let close = EventListener::new(dialog, "close", move |_| thunk.push_message(DialogClosed));
+    [cancel, click, close, create_tab_listener(dialog)]
+}
+
+impl<V, T, A, F> ModalDialog<V, T, A, F> {
+    /// Shows or closes `dialog` according to `open`.
+    fn sync_open<S, E>(
+        &self,
+        dialog: &web_sys::HtmlDialogElement,
+        state: &mut ModalDialogState<S, E>,
+    ) {
+        if self.open && !dialog.open() && state.pending_show.is_none() {
+            state.restore_focus = Some(RestoreFocus::active_element());
+            // The dialog has to be in the document to be shown, so this is done after the current (re)build
+            let dialog = dialog.clone();
+            state.pending_show = Some(Timeout::new(0, move || {
+                if dialog.is_connected() && !dialog.open() {
+                    let _ = dialog.show_modal();
+                }
+            }));
+        } else if !self.open {
+            state.pending_show = None;
+            if dialog.open() {
+                dialog.close();
+            }
+            state.restore_focus = None;
+        }
+    }
+}
+
+macro_rules! generate_dialog_dom_interface_impl {
+    ($dom_interface:ident, ()) => {
+        impl<V, T, A, F, OA> $crate::interfaces::$dom_interface<T, A> for ModalDialog<V, T, A, F>
+        where
+            V: View<T, A>,
+            F: Fn(&mut T, CloseReason) -> OA,
+            OA: OptionalAction<A>,
+        {
+        }
+    };
+}
+
+generate_dialog_dom_interface_impl!(HtmlDialogElement, ());
+crate::interfaces::for_all_html_dialog_element_ancestors!(generate_dialog_dom_interface_impl, ());
+
+impl<V, T, A, F> ViewMarker for ModalDialog<V, T, A, F> {}
+impl<V, T, A, F> Sealed for ModalDialog<V, T, A, F> {}
+
+impl<V, T, A, F, OA> View<T, A> for ModalDialog<V, T, A, F>
+where
+    V: View<T, A>,
+    F: Fn(&mut T, CloseReason) -> OA,
+    OA: OptionalAction<A>,
+{
+    type State = ModalDialogState<V::State, V::Element>;
+    type Element = web_sys::HtmlDialogElement;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, (dialog, state)) = cx.with_new_id(|cx| {
+            let (el, props) = cx.build_element(HTML_NS, "dialog");
+            let dialog: web_sys::HtmlDialogElement = el.unchecked_into();
+            let (child_id, child_state, child_element) = self.content.build(cx);
+            cx.insert_node(&dialog, child_element.as_node_ref(), None);
+            let mut state = ModalDialogState {
+                props,
+                child_id,
+                child_state,
+                child_element,
+                listeners: create_dialog_listeners(&dialog, cx),
+                pending_show: None,
+                restore_focus: None,
+            };
+            self.sync_open(&dialog, &mut state);
+            (dialog, state)
+        });
+        (id, state, dialog)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        cx.with_id(*id, |cx| {
+            let mut changed = cx.rebuild_element(element, &mut state.props);
+            let prev_child = state.child_element.as_node_ref().clone();
+            let child_changed = self.content.rebuild(
+                cx,
+                &prev.content,
+                &mut state.child_id,
+                &mut state.child_state,
+                &mut state.child_element,
+            );
+            if child_changed.contains(ChangeFlags::STRUCTURE) {
+                replace_child_node(cx, element, &prev_child, &state.child_element);
+            }
+            changed |= child_changed.difference(ChangeFlags::STRUCTURE);
+            self.sync_open(element, state);
+            changed
+        })
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Box<dyn Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        match id_path {
+            [] if message.downcast_ref::<CloseReason>().is_some() => {
+                let reason = *message.downcast::<CloseReason>().unwrap();
+                let Some(on_close) = &self.on_close else {
+                    return MessageResult::Nop;
+                };
+                match on_close(app_state, reason).action() {
+                    Some(a) => MessageResult::Action(a),
+                    None => MessageResult::Nop,
+                }
+            }
+            [] if message.downcast_ref::<DialogClosed>().is_some() => {
+                // The dialog is shown again by the next rebuild, when it should still be open
+                state.pending_show = None;
+                state.restore_focus = None;
+                let Some(on_close) = self.on_close.as_ref().filter(|_| self.open) else {
+                    return MessageResult::Nop;
+                };
+                match on_close(app_state, CloseReason::Closed).action() {
+                    Some(a) => MessageResult::Action(a),
+                    None => MessageResult::Nop,
+                }
+            }
+            [child_id, rest_path @ ..] if *child_id == state.child_id => {
+                self.content
+                    .message(rest_path, &mut state.child_state, message, app_state)
+            }
+            _ => MessageResult::Stale(message),
+        }
+    }
+}
+
+/// Content shown next to an anchor (e.g. a menu button), see [`popover`].
+pub struct Popover<V, P, T, A, F = fn(&mut T, CloseReason)> {
+    open: bool,
+    anchor: V,
+    content: P,
+    on_close: Option<F>,
+    phantom: PhantomData<fn() -> (T, A)>,
+}
+
+/// Shows `content` below `anchor` (via the Popover API) as long as `open` is `true`, e.g. for menus, pickers or tooltips.
+///
+/// The content is placed in the top layer, so it's not clipped by ancestors with e.g. `overflow: hidden`.
+/// It's positioned when it's opened, when the anchor moves while it's open (e.g. by scrolling), it's not repositioned.
+///
+/// Like [`modal_dialog`] the popover doesn't close itself, instead [`Popover::on_close`] is called,
+/// when the user presses `Escape`, or clicks outside of the popover and its anchor.
+///
+/// Both `anchor` and `content` are children of a `<div style="display: contents">`,
+/// which is the element of this view, the content is wrapped in a `<div popover class="xilem-popover">`.
+///
+/// ```ignore
+/// popover(
+///     state.menu_open,
+///     el::button("Options").on_click(|state: &mut AppState, _| state.menu_open = !state.menu_open),
+///     el::ul((el::li("Rename"), el::li("Delete"))),
+/// )
+/// .on_close(|state, _reason| state.menu_open = false)
+/// ```
+pub fn popover<V, P, T, A>(open: bool, anchor: V, content: P) -> Popover<V, P, T, A> {
+    Popover {
+        open,
+        anchor,
+        content,
+        on_close: None,
+        phantom: PhantomData,
+    }
+}
+
+impl<V, P, T, A> Popover<V, P, T, A> {
+    /// Calls `on_close`, when the user wants to close the popover.
+    pub fn on_close<F, OA>(self, on_close: F) -> Popover<V, P, T, A, F>
+    where
+        F: Fn(&mut T, CloseReason) -> OA,
+        OA: OptionalAction<A>,
+    {
+        Popover {
+            open: self.open,
+            anchor: self.anchor,
+            content: self.content,
+            on_close: Some(on_close),
+            phantom: PhantomData,
+        }
+    }
+}
+
+pub struct PopoverState<VS, VE, PS, PE> {
+    props: ElementProps,
+    anchor_id: Id,
+    anchor_state: VS,
+    anchor_element: VE,
+    content_id: Id,
+    content_state: PS,
+    content_element: PE,
+    popover: web_sys::HtmlElement,
+    // Retained, so that the listeners are removed when dropped
+    #[allow(unused)]
+    listeners: [EventListener; 2],
+    pending_show: Option<Timeout>,
+}
+
+/// Calls a method of the Popover API, which isn't available in `web_sys` yet.
+fn call_popover_method(popover: &web_sys::HtmlElement, method: &str) {
+    let method = js_sys::Reflect::get(popover, &method.into())
+        .ok()
+        .and_then(|method| method.dyn_into::<js_sys::Function>().ok());
+    match method {
+        Some(method) => {
+            let _ = method.call0(popover);
+        }
+        None => web_sys::console::warn_1(&"the Popover API isn't supported".into()),
+    }
+}
+
+fn is_popover_open(popover: &web_sys::HtmlElement) -> bool {
+    popover.matches(":popover-open").unwrap_or(false)
+}
+
+/// Places `popover` below the `anchor`.
+fn position_popover(popover: &web_sys::HtmlElement, anchor: &web_sys::Node) {
+    let Some(anchor) = anchor.dyn_ref::<web_sys::Element>() else {
+        return;
+    };
+    let rect = anchor.get_bounding_client_rect();
+    let style = popover.style();
+    let _ = style.set_property("inset", "auto");
+    let _ = style.set_property("margin", "0");
+    let _ = style.set_property("top", &format!("{}px", rect.bottom()));
+    let _ = style.set_property("left", &format!("{}px", rect.left()));
+}
+
+fn create_popover_listeners(
+    container: &web_sys::Element,
+    popover: &web_sys::HtmlElement,
+    cx: &Cx,
+) -> [EventListener; 2] {
+    let document = cx.document();
+    let thunk = cx.message_thunk();
+    let (container, popover_el) = (container.clone(), popover.clone());
+    let pointerdown = EventListener::new(document, "pointerdown", move |event| {
+        let inside = event
+            .target()
+            .and_then(|target| target.dyn_into::<web_sys::Node>().ok())
+            .is_some_and(|target| container.contains(Some(&target)));
+        if is_popover_open(&popover_el) && !inside {
+            thunk.push_message(CloseReason::OutsideClick);
+        }
+    });
+    let thunk = cx.message_thunk();
+    let popover = popover.clone();
+    let keydown = EventListener::new(document, "keydown", move |event| {
+        let event: &web_sys::KeyboardEvent = event.unchecked_ref();
+        if event.key() == "Escape" && is_popover_open(&popover) {
+            thunk.push_message(CloseReason::Escape);
+        }
+    });
+    [pointerdown, keydown]
+}
+
+impl<V, P, T, A, F> Popover<V, P, T, A, F> {
+    /// Shows or hides the popover according to `open`.
+    fn sync_open<VS, VE: DomNode, PS, PE>(&self, state: &mut PopoverState<VS, VE, PS, PE>) {
+        let popover = &state.popover;
+        if self.open && !is_popover_open(popover) && state.pending_show.is_none() {
+            // The popover has to be in the document to be shown (and positioned), so this is done after the current (re)build
+            let (popover, anchor) = (popover.clone(), state.anchor_element.as_node_ref().clone());
+            state.pending_show = Some(Timeout::new(0, move || {
+                if popover.is_connected() && !is_popover_open(&popover) {
+                    position_popover(&popover, &anchor);
+                    call_popover_method(&popover, "showPopover");
+                }
+            }));
+        } else if !self.open {
+            state.pending_show = None;
+            if is_popover_open(popover) {
+                call_popover_method(popover, "hidePopover");
+            }
+        }
+    }
+}
+
+macro_rules! generate_popover_dom_interface_impl {
+    ($dom_interface:ident, ()) => {
+        impl<V, P, T, A, F, OA> $crate::interfaces::$dom_interface<T, A> for Popover<V, P, T, A, F>
+        where
+            V: View<T, A>,
+            P: View<T, A>,
+            F: Fn(&mut T, CloseReason) -> OA,
+            OA: OptionalAction<A>,
+        {
+        }
+    };
+}
+
+generate_popover_dom_interface_impl!(HtmlDivElement, ());
+crate::interfaces::for_all_html_div_element_ancestors!(generate_popover_dom_interface_impl, ());
+
+impl<V, P, T, A, F> ViewMarker for Popover<V, P, T, A, F> {}
+impl<V, P, T, A, F> Sealed for Popover<V, P, T, A, F> {}
+
+impl<V, P, T, A, F, OA> View<T, A> for Popover<V, P, T, A, F>
+where
+    V: View<T, A>,
+    P: View<T, A>,
+    F: Fn(&mut T, CloseReason) -> OA,
+    OA: OptionalAction<A>,
+{
+    type State = PopoverState<V::State, V::Element, P::State, P::Element>;
+    type Element = web_sys::HtmlDivElement;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, (container, state)) = cx.with_new_id(|cx| {
+            cx.add_style_to_element(&"display".into(), &"contents".into_style_value());
+            let (container, props) = cx.build_element(HTML_NS, "div");
+            let (anchor_id, anchor_state, anchor_element) = self.anchor.build(cx);
+            let (content_id, content_state, content_element) = self.content.build(cx);
+            let popover: web_sys::HtmlElement = cx.create_element(HTML_NS, "div").unchecked_into();
+            cx.set_attribute(&popover, "popover", Some("manual".into()));
+            cx.set_attribute(&popover, "class", Some("xilem-popover".into()));
+            cx.insert_node(&popover, content_element.as_node_ref(), None);
+            cx.insert_node(&container, anchor_element.as_node_ref(), None);
+            cx.insert_node(&container, &popover, None);
+            let mut state = PopoverState {
+                props,
+                anchor_id,
+                anchor_state,
+                anchor_element,
+                content_id,
+                content_state,
+                content_element,
+                listeners: create_popover_listeners(&container, &popover, cx),
+                popover,
+                pending_show: None,
+            };
+            self.sync_open(&mut state);
+            (container.unchecked_into(), state)
+        });
+        (id, state, container)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        cx.with_id(*id, |cx| {
+            cx.add_style_to_element(&"display".into(), &"contents".into_style_value());
+            let mut changed = cx.rebuild_element(element, &mut state.props);
+            let prev_anchor = state.anchor_element.as_node_ref().clone();
+            let anchor_changed = self.anchor.rebuild(
+                cx,
+                &prev.anchor,
+                &mut state.anchor_id,
+                &mut state.anchor_state,
+                &mut state.anchor_element,
+            );
+            if anchor_changed.contains(ChangeFlags::STRUCTURE) {
+                replace_child_node(cx, element, &prev_anchor, &state.anchor_element);
+            }
+            let prev_content = state.content_element.as_node_ref().clone();
+            let content_changed = self.content.rebuild(
+                cx,
+                &prev.content,
+                &mut state.content_id,
+                &mut state.content_state,
+                &mut state.content_element,
+            );
+            if content_changed.contains(ChangeFlags::STRUCTURE) {
+                replace_child_node(cx, &state.popover, &prev_content, &state.content_element);
+            }
+            changed |= (anchor_changed | content_changed).difference(ChangeFlags::STRUCTURE);
+            self.sync_open(state);
+            changed
+        })
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Box<dyn Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        match id_path {
+            [] if message.downcast_ref::<CloseReason>().is_some() => {
+                let reason = *message.downcast::<CloseReason>().unwrap();
+                let Some(on_close) = &self.on_close else {
+                    return MessageResult::Nop;
+                };
+                match on_close(app_state, reason).action() {
+                    Some(a) => MessageResult::Action(a),
+                    None => MessageResult::Nop,
+                }
+            }
+            [anchor_id, rest_path @ ..] if *anchor_id == state.anchor_id => {
+                self.anchor
+                    .message(rest_path, &mut state.anchor_state, message, app_state)
+            }
+            [content_id, rest_path @ ..] if *content_id == state.content_id => self
+                .content
+                .message(rest_path, &mut state.content_state, message, app_state),
+            _ => MessageResult::Stale(message),
+        }
+    }
+}
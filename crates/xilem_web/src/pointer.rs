@@ -30,6 +30,8 @@ pub struct PointerState<S> {
     move_closure: Closure<dyn FnMut(PointerEvent)>,
     #[allow(unused)]
     up_closure: Closure<dyn FnMut(PointerEvent)>,
+    #[allow(unused)]
+    cancel_closure: Closure<dyn FnMut(PointerEvent)>,
     child_state: S,
 }
 
@@ -39,6 +41,8 @@ pub enum PointerMsg {
     Down(PointerDetails),
     Move(PointerDetails),
     Up(PointerDetails),
+    /// The pointer was canceled by the browser (e.g. when a touch turned into scrolling), a drag should be aborted.
+    Cancel(PointerDetails),
 }
 
 #[derive(Debug)]
@@ -48,19 +52,38 @@ pub struct PointerDetails {
     pub button: i16,
     pub x: f64,
     pub y: f64,
+    /// `"mouse"`, `"pen"` or `"touch"`.
+    pub pointer_type: String,
+    /// The normalized pressure between `0.0` and `1.0`, for devices without pressure support this is `0.5` while a button is pressed.
+    pub pressure: f32,
+    /// The angle in degrees between the Y-Z plane and the plane containing the pen and the Y axis, between `-90` and `90`.
+    pub tilt_x: i32,
+    /// The angle in degrees between the X-Z plane and the plane containing the pen and the X axis, between `-90` and `90`.
+    pub tilt_y: i32,
 }
 
 impl PointerDetails {
-    fn from_pointer_event(e: &PointerEvent) -> Self {
+    pub(crate) fn from_pointer_event(e: &PointerEvent) -> Self {
         PointerDetails {
             id: e.pointer_id(),
             button: e.button(),
             x: e.client_x() as f64,
             y: e.client_y() as f64,
+            pointer_type: e.pointer_type(),
+            pressure: e.pressure(),
+            tilt_x: e.tilt_x(),
+            tilt_y: e.tilt_y(),
         }
     }
 }
 
+fn release_pointer_capture(el: &web_sys::Element, pointer_id: i32) {
+    if el.has_pointer_capture(pointer_id) {
+        // The capture may already be lost (e.g. the element was removed), which isn't an error here
+        let _ = el.release_pointer_capture(pointer_id);
+    }
+}
+
 pub fn pointer<T, A, F: Fn(&mut T, PointerMsg), V: Element<T, A>>(
     child: V,
     callback: F,
@@ -111,17 +134,31 @@ impl<T, A, F: Fn(&mut T, PointerMsg) -> A, V: View<T, A>> View<T, A> for Pointer
         el.add_event_listener_with_callback("pointermove", move_closure.as_ref().unchecked_ref())
             .unwrap();
         let thunk = cx.with_id(id, |cx| cx.message_thunk());
+        let el_clone = el.clone();
         let up_closure = Closure::new(move |e: PointerEvent| {
+            release_pointer_capture(&el_clone, e.pointer_id());
             thunk.push_message(PointerMsg::Up(PointerDetails::from_pointer_event(&e)));
             e.prevent_default();
             e.stop_propagation();
         });
         el.add_event_listener_with_callback("pointerup", up_closure.as_ref().unchecked_ref())
             .unwrap();
+        let thunk = cx.with_id(id, |cx| cx.message_thunk());
+        let el_clone = el.clone();
+        let cancel_closure = Closure::new(move |e: PointerEvent| {
+            release_pointer_capture(&el_clone, e.pointer_id());
+            thunk.push_message(PointerMsg::Cancel(PointerDetails::from_pointer_event(&e)));
+        });
+        el.add_event_listener_with_callback(
+            "pointercancel",
+            cancel_closure.as_ref().unchecked_ref(),
+        )
+        .unwrap();
         let state = PointerState {
             down_closure,
             move_closure,
             up_closure,
+            cancel_closure,
             child_state,
         };
         (id, state, element)
@@ -0,0 +1,73 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Measuring the reconciliation pipeline of an [`App`](crate::App), see [`App::profiler`](crate::App::profiler).
+
+use wasm_bindgen::UnwrapThrowExt;
+
+use crate::ChangeFlags;
+
+/// The number of mutations of the DOM during a build or rebuild of the view tree.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DomMutations {
+    /// Attributes (including classes) which were set or removed.
+    pub attributes: usize,
+    /// Properties which were set or removed.
+    pub properties: usize,
+}
+
+/// Receives measurements of each stage of the app, all durations are in milliseconds.
+///
+/// All methods have an empty default implementation, so only the interesting stages need to be implemented,
+/// e.g. to show a performance HUD, or to forward them to `tracing`.
+#[allow(unused_variables)]
+pub trait Profiler {
+    /// Called after `app_logic` created the view tree.
+    fn app_logic(&mut self, duration: f64) {}
+
+    /// Called after the view tree was built initially.
+    fn build(&mut self, duration: f64, mutations: DomMutations) {}
+
+    /// Called after the view tree was rebuilt with the view tree of the last `app_logic` call.
+    fn rebuild(&mut self, duration: f64, changed: ChangeFlags, mutations: DomMutations) {}
+
+    /// Called after a message was handled by the view tree (which doesn't include the following rebuild).
+    fn message(&mut self, duration: f64) {}
+}
+
+/// A [`Profiler`] which logs all measurements to the browser console.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ConsoleProfiler;
+
+impl Profiler for ConsoleProfiler {
+    fn app_logic(&mut self, duration: f64) {
+        log(format!("app_logic: {duration:.2}ms"));
+    }
+
+    fn build(&mut self, duration: f64, mutations: DomMutations) {
+        log(format!("build: {duration:.2}ms, {mutations:?}"));
+    }
+
+    fn rebuild(&mut self, duration: f64, changed: ChangeFlags, mutations: DomMutations) {
+        log(format!(
+            "rebuild: {duration:.2}ms, {changed:?}, {mutations:?}"
+        ));
+    }
+
+    fn message(&mut self, duration: f64) {
+        log(format!("message: {duration:.2}ms"));
+    }
+}
+
+fn log(message: String) {
+    web_sys::console::debug_1(&message.into());
+}
+
+/// The current high resolution timestamp in milliseconds.
+pub(crate) fn now() -> f64 {
+    web_sys::window()
+        .unwrap_throw()
+        .performance()
+        .unwrap_throw()
+        .now()
+}
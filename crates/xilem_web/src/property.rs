@@ -0,0 +1,152 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::borrow::Cow;
+use std::marker::PhantomData;
+
+use wasm_bindgen::JsValue;
+use xilem_core::{Id, MessageResult};
+
+use crate::{interfaces::sealed::Sealed, ChangeFlags, Cx, View, ViewMarker};
+
+use super::interfaces::Element;
+
+type CowStr = std::borrow::Cow<'static, str>;
+
+/// The value of a (JS) property of an element, see [`Element::prop`].
+#[derive(PartialEq, Clone, Debug, PartialOrd)]
+#[cfg_attr(feature = "worker", derive(serde::Serialize, serde::Deserialize))]
+pub enum PropertyValue {
+    Bool(bool),
+    I32(i32),
+    U32(u32),
+    F32(f32),
+    F64(f64),
+    String(CowStr),
+}
+
+impl PropertyValue {
+    pub fn to_js_value(&self) -> JsValue {
+        match self {
+            PropertyValue::Bool(b) => JsValue::from_bool(*b),
+            PropertyValue::I32(n) => JsValue::from(*n),
+            PropertyValue::U32(n) => JsValue::from(*n),
+            PropertyValue::F32(n) => JsValue::from(*n),
+            PropertyValue::F64(n) => JsValue::from_f64(*n),
+            PropertyValue::String(s) => JsValue::from_str(s),
+        }
+    }
+}
+
+pub trait IntoPropertyValue: Sized {
+    fn into_prop_value(self) -> Option<PropertyValue>;
+}
+
+impl<T: IntoPropertyValue> IntoPropertyValue for Option<T> {
+    fn into_prop_value(self) -> Option<PropertyValue> {
+        if let Some(value) = self {
+            T::into_prop_value(value)
+        } else {
+            None
+        }
+    }
+}
+
+impl IntoPropertyValue for PropertyValue {
+    fn into_prop_value(self) -> Option<PropertyValue> {
+        Some(self)
+    }
+}
+
+// In contrast to attributes, `false` is a value on its own for properties (e.g. `checked`)
+impl IntoPropertyValue for bool {
+    fn into_prop_value(self) -> Option<PropertyValue> {
+        Some(PropertyValue::Bool(self))
+    }
+}
+
+impl IntoPropertyValue for i32 {
+    fn into_prop_value(self) -> Option<PropertyValue> {
+        Some(PropertyValue::I32(self))
+    }
+}
+
+impl IntoPropertyValue for u32 {
+    fn into_prop_value(self) -> Option<PropertyValue> {
+        Some(PropertyValue::U32(self))
+    }
+}
+
+impl IntoPropertyValue for f32 {
+    fn into_prop_value(self) -> Option<PropertyValue> {
+        Some(PropertyValue::F32(self))
+    }
+}
+
+impl IntoPropertyValue for f64 {
+    fn into_prop_value(self) -> Option<PropertyValue> {
+        Some(PropertyValue::F64(self))
+    }
+}
+
+impl IntoPropertyValue for String {
+    fn into_prop_value(self) -> Option<PropertyValue> {
+        Some(PropertyValue::String(self.into()))
+    }
+}
+
+impl IntoPropertyValue for CowStr {
+    fn into_prop_value(self) -> Option<PropertyValue> {
+        Some(PropertyValue::String(self))
+    }
+}
+
+impl IntoPropertyValue for &'static str {
+    fn into_prop_value(self) -> Option<PropertyValue> {
+        Some(PropertyValue::String(self.into()))
+    }
+}
+
+pub struct Prop<E, T, A> {
+    pub(crate) element: E,
+    pub(crate) name: Cow<'static, str>,
+    pub(crate) value: Option<PropertyValue>,
+    pub(crate) phantom: PhantomData<fn() -> (T, A)>,
+}
+
+impl<E, T, A> ViewMarker for Prop<E, T, A> {}
+impl<E, T, A> Sealed for Prop<E, T, A> {}
+
+impl<E: Element<T, A>, T, A> View<T, A> for Prop<E, T, A> {
+    type State = E::State;
+    type Element = E::Element;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        cx.add_prop_to_element(&self.name, &self.value);
+        self.element.build(cx)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        cx.add_prop_to_element(&self.name, &self.value);
+        self.element.rebuild(cx, &prev.element, id, state, element)
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Box<dyn std::any::Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        self.element.message(id_path, state, message, app_state)
+    }
+}
+
+crate::interfaces::impl_dom_interfaces_for_ty!(Element, Prop);
@@ -0,0 +1,145 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{any::Any, marker::PhantomData, rc::Rc};
+
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    context::{ChangeFlags, Cx},
+    view::{View, ViewMarker},
+};
+
+/// Provides a value to all descendants of the child view, see [`provide_context`].
+pub struct ProvideContext<C, V> {
+    value: Rc<C>,
+    child: V,
+}
+
+/// Provides `value` to all descendants of `child`, which can access it via [`use_context`] (or [`Cx::context`] in custom views),
+/// without passing it through each view function, e.g. a theme, the locale or information about the logged in user.
+///
+/// Values are looked up by their type, so a wrapper type should be used for e.g. a `String`.
+/// When multiple ancestors provide a value of the same type, the nearest one is used.
+///
+/// Note that descendants are only updated with a changed value, when they're rebuilt,
+/// which isn't the case e.g. within a [`memoize`](crate::memoize) view, which data didn't change
+/// (unlike with [`locale_provider`](crate::i18n::locale_provider), which rebuilds memoized views, when its catalog changed).
+pub fn provide_context<C: 'static, V>(value: C, child: V) -> ProvideContext<C, V> {
+    ProvideContext {
+        value: Rc::new(value),
+        child,
+    }
+}
+
+impl<C, V> ViewMarker for ProvideContext<C, V> {}
+
+impl<T, A, C: 'static, V: View<T, A>> View<T, A> for ProvideContext<C, V> {
+    type State = V::State;
+    type Element = V::Element;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let value: Rc<dyn Any> = self.value.clone();
+        cx.with_context(value, false, |cx| self.child.build(cx))
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let value: Rc<dyn Any> = self.value.clone();
+        // Values of any type can be provided, so it's unknown whether it changed
+        cx.with_context(value, false, |cx| {
+            self.child.rebuild(cx, &prev.child, id, state, element)
+        })
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Box<dyn Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        self.child.message(id_path, state, message, app_state)
+    }
+}
+
+/// A view created with a value provided by an ancestor, see [`use_context`].
+pub struct UseContext<C, F> {
+    child_cb: F,
+    phantom: PhantomData<fn() -> C>,
+}
+
+pub struct UseContextState<T, A, V: View<T, A>> {
+    view: V,
+    view_state: V::State,
+}
+
+/// Creates the view with the value of type `C`, which is provided by the nearest ancestor via [`provide_context`],
+/// or `None` if there's no such ancestor.
+///
+/// ```ignore
+/// struct Theme { accent: &'static str }
+///
+/// provide_context(Theme { accent: "teal" }, el::div((
+///     // possibly deeply nested in other views
+///     use_context(|theme: Option<&Theme>| {
+///         el::button("Ok").attr("style", format!("background: {}", theme.map_or("gray", |t| t.accent)))
+///     }),
+/// )))
+/// ```
+pub fn use_context<C: 'static, V, F: Fn(Option<&C>) -> V>(child_cb: F) -> UseContext<C, F> {
+    UseContext {
+        child_cb,
+        phantom: PhantomData,
+    }
+}
+
+impl<C, F> ViewMarker for UseContext<C, F> {}
+
+impl<T, A, C, V, F> View<T, A> for UseContext<C, F>
+where
+    C: 'static,
+    V: View<T, A>,
+    F: Fn(Option<&C>) -> V,
+{
+    type State = UseContextState<T, A, V>;
+    type Element = V::Element;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let view = (self.child_cb)(cx.context());
+        let (id, view_state, element) = view.build(cx);
+        (id, UseContextState { view, view_state }, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        _prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let view = (self.child_cb)(cx.context());
+        let changed = view.rebuild(cx, &state.view, id, &mut state.view_state, element);
+        state.view = view;
+        changed
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Box<dyn Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        state
+            .view
+            .message(id_path, &mut state.view_state, message, app_state)
+    }
+}
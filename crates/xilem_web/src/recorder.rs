@@ -0,0 +1,115 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Recording the messages of an [`App`](crate::App) and replaying them, see [`App::record`](crate::App::record).
+
+use std::any::Any;
+
+use xilem_core::{Id, IdPath};
+
+use crate::ChangeFlags;
+
+type Recreate = Box<dyn Fn() -> Box<dyn Any>>;
+
+/// A message which was handled by the app, see [`MessageRecorder::messages`].
+pub struct RecordedMessage {
+    pub id_path: IdPath,
+    /// Recreates the message body, `None` when its type isn't [replayable](MessageRecorder::replayable).
+    body: Option<Recreate>,
+    /// What changed in the rebuild after the message, which is shared with the preceding messages,
+    /// when they were handled within the same animation frame.
+    pub changed: Option<ChangeFlags>,
+}
+
+impl RecordedMessage {
+    /// Whether the message can be replayed with [`App::replay`](crate::App::replay).
+    pub fn is_replayable(&self) -> bool {
+        self.body.is_some()
+    }
+
+    pub(crate) fn recreate_body(&self) -> Option<Box<dyn Any>> {
+        self.body.as_ref().map(|recreate| recreate())
+    }
+}
+
+/// Records all messages of an app (with their id path), which can be replayed against a fresh app state,
+/// e.g. to reproduce a bug.
+///
+/// Message bodies are only retained, when their type is registered with [`MessageRecorder::replayable`],
+/// the common DOM events are registered by default.
+/// For stable id paths, both apps should use [`App::deterministic_ids`](crate::App::deterministic_ids) with the same seed.
+///
+/// ```ignore
+/// let recorder = Rc::new(RefCell::new(MessageRecorder::new().replayable::<MyMessage>()));
+/// App::new(AppState::default(), app_logic)
+///     .deterministic_ids(NonZeroU64::MIN)
+///     .record(recorder.clone())
+///     .run(&document_body());
+/// // later, e.g. from a debug button
+/// let app = App::new(AppState::default(), app_logic).deterministic_ids(NonZeroU64::MIN);
+/// app.clone().run(&debug_root);
+/// app.replay(recorder.borrow().messages());
+/// ```
+pub struct MessageRecorder {
+    messages: Vec<RecordedMessage>,
+    recreators: Vec<fn(&dyn Any) -> Option<Recreate>>,
+}
+
+impl MessageRecorder {
+    /// A recorder which can replay the common DOM events.
+    pub fn new() -> Self {
+        MessageRecorder {
+            messages: Vec::new(),
+            recreators: Vec::new(),
+        }
+        .replayable::<web_sys::Event>()
+        .replayable::<web_sys::MouseEvent>()
+        .replayable::<web_sys::PointerEvent>()
+        .replayable::<web_sys::KeyboardEvent>()
+        .replayable::<web_sys::InputEvent>()
+        .replayable::<web_sys::FocusEvent>()
+        .replayable::<web_sys::WheelEvent>()
+    }
+
+    /// Retains the messages of type `M`, so that they can be replayed.
+    pub fn replayable<M: Any + Clone>(mut self) -> Self {
+        self.recreators.push(|body| {
+            let body = body.downcast_ref::<M>()?.clone();
+            Some(Box::new(move || Box::new(body.clone())))
+        });
+        self
+    }
+
+    /// All messages which were recorded, in the order they were handled.
+    pub fn messages(&self) -> &[RecordedMessage] {
+        &self.messages
+    }
+
+    pub fn clear(&mut self) {
+        self.messages.clear();
+    }
+
+    pub(crate) fn record_message(&mut self, id_path: &[Id], body: &dyn Any) {
+        let body = self.recreators.iter().find_map(|recreator| recreator(body));
+        self.messages.push(RecordedMessage {
+            id_path: id_path.to_vec(),
+            body,
+            changed: None,
+        });
+    }
+
+    pub(crate) fn record_render(&mut self, changed: ChangeFlags) {
+        for message in self.messages.iter_mut().rev() {
+            if message.changed.is_some() {
+                break;
+            }
+            message.changed = Some(changed);
+        }
+    }
+}
+
+impl Default for MessageRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
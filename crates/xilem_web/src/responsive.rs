@@ -0,0 +1,221 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Switching views at viewport breakpoints, see [`responsive`].
+
+use std::any::Any;
+
+use gloo::events::EventListener;
+use wasm_bindgen::UnwrapThrowExt;
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    context::{ChangeFlags, Cx},
+    view::{View, ViewMarker},
+};
+
+/// The range of the viewport width, see [`responsive`] and [`Breakpoints`] for the widths.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Breakpoint {
+    /// Narrower than [`Breakpoints::sm`].
+    Xs,
+    Sm,
+    Md,
+    Lg,
+    Xl,
+}
+
+/// The minimum viewport widths in CSS pixels of each [`Breakpoint`] (except [`Breakpoint::Xs`]).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Breakpoints {
+    pub sm: f64,
+    pub md: f64,
+    pub lg: f64,
+    pub xl: f64,
+}
+
+impl Default for Breakpoints {
+    fn default() -> Self {
+        Breakpoints {
+            sm: 640.0,
+            md: 768.0,
+            lg: 1024.0,
+            xl: 1280.0,
+        }
+    }
+}
+
+/// Sent when one of the media queries changed.
+struct MediaQueryChanged;
+
+/// A view created with the current breakpoint of the viewport, see [`responsive`].
+pub struct Responsive<F> {
+    child_cb: F,
+    breakpoints: Breakpoints,
+}
+
+/// Creates the view with the current [`Breakpoint`] of the viewport width,
+/// and again when the viewport crosses a breakpoint, so that the view tree can switch its structure,
+/// e.g. between a sidebar and a bottom navigation.
+///
+/// The breakpoints are observed with `matchMedia` listeners, which are removed, when this view is removed.
+/// They can be configured with [`Responsive::breakpoints`].
+///
+/// ```ignore
+/// responsive(|breakpoint| {
+///     if breakpoint >= Breakpoint::Md {
+///         OneOf2::A(el::div((sidebar(), content())))
+///     } else {
+///         OneOf2::B(el::div((content(), bottom_navigation())))
+///     }
+/// })
+/// ```
+pub fn responsive<V, F: Fn(Breakpoint) -> V>(child_cb: F) -> Responsive<F> {
+    Responsive {
+        child_cb,
+        breakpoints: Breakpoints::default(),
+    }
+}
+
+impl<F> Responsive<F> {
+    /// Uses the viewport widths of `breakpoints` instead of [`Breakpoints::default`].
+    pub fn breakpoints(mut self, breakpoints: Breakpoints) -> Self {
+        self.breakpoints = breakpoints;
+        self
+    }
+}
+
+pub struct ResponsiveState<T, A, V: View<T, A>> {
+    breakpoint: Breakpoint,
+    queries: Vec<(Breakpoint, web_sys::MediaQueryList)>,
+    // Retained, so that the listeners are removed when dropped
+    #[allow(unused)]
+    listeners: Vec<EventListener>,
+    child_id: Id,
+    view: V,
+    view_state: V::State,
+}
+
+fn create_media_queries(
+    breakpoints: &Breakpoints,
+    cx: &Cx,
+) -> (
+    Vec<(Breakpoint, web_sys::MediaQueryList)>,
+    Vec<EventListener>,
+) {
+    let window = web_sys::window().unwrap_throw();
+    let queries: Vec<_> = [
+        (Breakpoint::Sm, breakpoints.sm),
+        (Breakpoint::Md, breakpoints.md),
+        (Breakpoint::Lg, breakpoints.lg),
+        (Breakpoint::Xl, breakpoints.xl),
+    ]
+    .into_iter()
+    .filter_map(|(breakpoint, min_width)| {
+        let query = window
+            .match_media(&format!("(min-width: {min_width}px)"))
+            .ok()
+            .flatten()?;
+        Some((breakpoint, query))
+    })
+    .collect();
+    let listeners = queries
+        .iter()
+        .map(|(_, query)| {
+            let thunk = cx.message_thunk();
+            EventListener::new(query, "change", move |_| {
+                thunk.push_message(MediaQueryChanged);
+            })
+        })
+        .collect();
+    (queries, listeners)
+}
+
+fn current_breakpoint(queries: &[(Breakpoint, web_sys::MediaQueryList)]) -> Breakpoint {
+    queries
+        .iter()
+        .rev()
+        .find(|(_, query)| query.matches())
+        .map_or(Breakpoint::Xs, |(breakpoint, _)| *breakpoint)
+}
+
+impl<F> ViewMarker for Responsive<F> {}
+
+impl<T, A, V, F> View<T, A> for Responsive<F>
+where
+    V: View<T, A>,
+    F: Fn(Breakpoint) -> V,
+{
+    type State = ResponsiveState<T, A, V>;
+    type Element = V::Element;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, (state, element)) = cx.with_new_id(|cx| {
+            let (queries, listeners) = create_media_queries(&self.breakpoints, cx);
+            let breakpoint = current_breakpoint(&queries);
+            let view = (self.child_cb)(breakpoint);
+            let (child_id, view_state, element) = view.build(cx);
+            let state = ResponsiveState {
+                breakpoint,
+                queries,
+                listeners,
+                child_id,
+                view,
+                view_state,
+            };
+            (state, element)
+        });
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        cx.with_id(*id, |cx| {
+            if prev.breakpoints != self.breakpoints {
+                (state.queries, state.listeners) = create_media_queries(&self.breakpoints, cx);
+                state.breakpoint = current_breakpoint(&state.queries);
+            }
+            let view = (self.child_cb)(state.breakpoint);
+            let changed = view.rebuild(
+                cx,
+                &state.view,
+                &mut state.child_id,
+                &mut state.view_state,
+                element,
+            );
+            state.view = view;
+            changed
+        })
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Box<dyn Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        match id_path {
+            [] if message.downcast_ref::<MediaQueryChanged>().is_some() => {
+                let breakpoint = current_breakpoint(&state.queries);
+                if breakpoint == state.breakpoint {
+                    return MessageResult::Nop;
+                }
+                state.breakpoint = breakpoint;
+                MessageResult::RequestRebuild
+            }
+            [child_id, rest_path @ ..] if *child_id == state.child_id => {
+                state
+                    .view
+                    .message(rest_path, &mut state.view_state, message, app_state)
+            }
+            _ => MessageResult::Stale(message),
+        }
+    }
+}
@@ -0,0 +1,262 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Restoring scroll positions on back/forward navigation,
+//! see [`App::scroll_restoration`](crate::App::scroll_restoration) and [`scroll_container`].
+
+use std::{borrow::Cow, cell::RefCell, collections::HashMap, marker::PhantomData};
+
+use gloo::events::EventListener;
+use wasm_bindgen::{JsCast, JsValue, UnwrapThrowExt};
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    interfaces::{sealed::Sealed, Element},
+    view::DomNode,
+    ChangeFlags, Cx, View, ViewMarker,
+};
+
+type CowStr = Cow<'static, str>;
+
+/// The property of `history.state` which identifies a history entry.
+const ENTRY_KEY_PROPERTY: &str = "__xilemScrollKey";
+
+/// What a scroll position was recorded for, the window is separate, so that it can't collide with any container name.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum ScrollTarget {
+    Window,
+    Container(CowStr),
+}
+
+#[derive(Default)]
+struct ScrollPositions {
+    enabled: bool,
+    next_key: u32,
+    /// The scroll positions of the window and the scroll containers, per history entry.
+    entries: HashMap<String, HashMap<ScrollTarget, (f64, f64)>>,
+    containers: HashMap<CowStr, web_sys::Element>,
+    restore_pending: bool,
+}
+
+thread_local! {
+    static SCROLL_POSITIONS: RefCell<ScrollPositions> = RefCell::new(ScrollPositions::default());
+}
+
+/// The key of the current history entry, which is added to `history.state` if necessary.
+///
+/// When the state can't be extended (because it's e.g. a string), the url is used instead.
+fn entry_key(next_key: &mut u32) -> String {
+    let window = web_sys::window().unwrap_throw();
+    let history = window.history().unwrap_throw();
+    let state = history.state().unwrap_or(JsValue::NULL);
+    if state.is_object() {
+        if let Some(key) = js_sys::Reflect::get(&state, &ENTRY_KEY_PROPERTY.into())
+            .ok()
+            .and_then(|key| key.as_string())
+        {
+            return key;
+        }
+    } else if !state.is_null() && !state.is_undefined() {
+        return window.location().href().unwrap_throw();
+    }
+    // The time avoids collisions with keys of entries from before a reload
+    let key = format!("{}-{next_key}", js_sys::Date::now());
+    *next_key += 1;
+    let state = if state.is_object() {
+        state
+    } else {
+        js_sys::Object::new().into()
+    };
+    js_sys::Reflect::set(&state, &ENTRY_KEY_PROPERTY.into(), &key.as_str().into()).unwrap_throw();
+    let _ = history.replace_state(&state, "");
+    key
+}
+
+fn record(target: ScrollTarget, position: (f64, f64)) {
+    SCROLL_POSITIONS.with(|positions| {
+        let mut positions = positions.borrow_mut();
+        if !positions.enabled {
+            return;
+        }
+        let key = entry_key(&mut positions.next_key);
+        positions
+            .entries
+            .entry(key)
+            .or_default()
+            .insert(target, position);
+    });
+}
+
+/// Takes over the scroll restoration from the browser, and records the scroll position of the window.
+pub(crate) fn enable() {
+    let window = web_sys::window().unwrap_throw();
+    let _ = window
+        .history()
+        .unwrap_throw()
+        .set_scroll_restoration(web_sys::ScrollRestoration::Manual);
+    SCROLL_POSITIONS.with(|positions| positions.borrow_mut().enabled = true);
+    let target = window.clone();
+    let listener = EventListener::new(&target, "scroll", move |_| {
+        let position = (
+            window.scroll_x().unwrap_or_default(),
+            window.scroll_y().unwrap_or_default(),
+        );
+        record(ScrollTarget::Window, position);
+    });
+    // Recorded as long as the app runs
+    listener.forget();
+}
+
+/// Restores the scroll positions of the current history entry after the next render, see [`restore_pending`].
+pub(crate) fn navigated() {
+    SCROLL_POSITIONS.with(|positions| positions.borrow_mut().restore_pending = true);
+}
+
+/// Restores the scroll positions of the current history entry, when it was navigated to since the last render.
+pub(crate) fn restore_pending() {
+    SCROLL_POSITIONS.with(|positions| {
+        let mut positions = positions.borrow_mut();
+        if !std::mem::take(&mut positions.restore_pending) {
+            return;
+        }
+        let key = entry_key(&mut positions.next_key);
+        let Some(entry) = positions.entries.get(&key) else {
+            return;
+        };
+        for (target, (x, y)) in entry {
+            match target {
+                ScrollTarget::Window => web_sys::window()
+                    .unwrap_throw()
+                    .scroll_to_with_x_and_y(*x, *y),
+                ScrollTarget::Container(name) => {
+                    if let Some(container) = positions.containers.get(name) {
+                        container.set_scroll_left(*x as i32);
+                        container.set_scroll_top(*y as i32);
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// Marks the element as scrollable pane, see [`scroll_container`].
+pub struct ScrollContainer<E, T, A> {
+    element: E,
+    name: CowStr,
+    phantom: PhantomData<fn() -> (T, A)>,
+}
+
+/// Records the scroll position of `element` (a scrollable pane, e.g. a sidebar with `overflow: auto`) under `name`,
+/// so that it's restored on back/forward navigation, when [`App::scroll_restoration`](crate::App::scroll_restoration) is enabled.
+///
+/// The `name` has to be unique among the scroll containers in the view tree, and stable across navigations.
+pub fn scroll_container<E: Element<T, A>, T, A>(
+    name: impl Into<CowStr>,
+    element: E,
+) -> ScrollContainer<E, T, A> {
+    ScrollContainer {
+        element,
+        name: name.into(),
+        phantom: PhantomData,
+    }
+}
+
+/// Unregisters the scroll container, when dropped.
+struct Registration {
+    name: CowStr,
+    element: web_sys::Element,
+    #[allow(unused)]
+    listener: EventListener,
+}
+
+impl Registration {
+    fn new(name: &CowStr, element: &web_sys::Element) -> Self {
+        SCROLL_POSITIONS.with(|positions| {
+            positions
+                .borrow_mut()
+                .containers
+                .insert(name.clone(), element.clone());
+        });
+        let (record_name, el) = (name.clone(), element.clone());
+        let listener = EventListener::new(element, "scroll", move |_| {
+            record(
+                ScrollTarget::Container(record_name.clone()),
+                (el.scroll_left() as f64, el.scroll_top() as f64),
+            );
+        });
+        Registration {
+            name: name.clone(),
+            element: element.clone(),
+            listener,
+        }
+    }
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        SCROLL_POSITIONS.with(|positions| {
+            let containers = &mut positions.borrow_mut().containers;
+            // Another element may have been registered with the same name in the meantime
+            if containers
+                .get(&self.name)
+                .is_some_and(|element| element.is_same_node(Some(&self.element)))
+            {
+                containers.remove(&self.name);
+            }
+        });
+    }
+}
+
+pub struct ScrollContainerState<S> {
+    child_state: S,
+    registration: Registration,
+}
+
+impl<E, T, A> ViewMarker for ScrollContainer<E, T, A> {}
+impl<E, T, A> Sealed for ScrollContainer<E, T, A> {}
+
+impl<E: Element<T, A>, T, A> View<T, A> for ScrollContainer<E, T, A> {
+    type State = ScrollContainerState<E::State>;
+    type Element = E::Element;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, child_state, element) = self.element.build(cx);
+        let registration = Registration::new(&self.name, element.as_node_ref().unchecked_ref());
+        let state = ScrollContainerState {
+            child_state,
+            registration,
+        };
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let changed = self
+            .element
+            .rebuild(cx, &prev.element, id, &mut state.child_state, element);
+        if prev.name != self.name || changed.contains(ChangeFlags::STRUCTURE) {
+            state.registration =
+                Registration::new(&self.name, element.as_node_ref().unchecked_ref());
+        }
+        changed
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Box<dyn std::any::Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        self.element
+            .message(id_path, &mut state.child_state, message, app_state)
+    }
+}
+
+crate::interfaces::impl_dom_interfaces_for_ty!(Element, ScrollContainer);
@@ -0,0 +1,178 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Placeholders with a shimmer animation, shown in place of content which is still loading.
+//!
+//! The styles are injected into the document `<head>` once, when the first skeleton is built.
+//! They can be overridden via the classes `xilem-skeleton`, `xilem-skeleton-avatar`, `xilem-skeleton-lines` and `xilem-skeleton-line`.
+
+use std::marker::PhantomData;
+
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    context::{ChangeFlags, Cx, ElementProps},
+    interfaces::sealed::Sealed,
+    view::{View, ViewMarker},
+    IntoAttributeValue, HTML_NS,
+};
+
+const STYLE_ID: &str = "xilem-skeleton-style";
+
+const STYLE: &str = "\
+.xilem-skeleton { display: flex; gap: 0.75em; }
+.xilem-skeleton-avatar { flex: none; width: 2.5em; height: 2.5em; border-radius: 50%; }
+.xilem-skeleton-lines { flex: 1; display: flex; flex-direction: column; gap: 0.5em; }
+.xilem-skeleton-line { height: 0.8em; border-radius: 0.25em; }
+.xilem-skeleton-avatar, .xilem-skeleton-line {
+  background: linear-gradient(90deg, #e2e2e2 25%, #f2f2f2 50%, #e2e2e2 75%);
+  background-size: 200% 100%;
+  animation: xilem-skeleton-shimmer 1.5s linear infinite;
+}
+@keyframes xilem-skeleton-shimmer { from { background-position: 200% 0; } to { background-position: -200% 0; } }
+@media (prefers-reduced-motion: reduce) {
+  .xilem-skeleton-avatar, .xilem-skeleton-line { animation: none; }
+}
+";
+
+macro_rules! generate_dom_interface_impl {
+    ($dom_interface:ident, ()) => {
+        impl<T, A> $crate::interfaces::$dom_interface<T, A> for Skeleton<T, A> {}
+    };
+}
+
+fn inject_style(document: &web_sys::Document) {
+    if document.get_element_by_id(STYLE_ID).is_some() {
+        return;
+    }
+    let style = document.create_element("style").unwrap_throw();
+    style.set_id(STYLE_ID);
+    style.set_text_content(Some(STYLE));
+    document
+        .head()
+        .unwrap_throw()
+        .append_child(&style)
+        .unwrap_throw();
+}
+
+fn add_attributes(cx: &mut Cx) {
+    cx.add_attr_to_element(&"class".into(), &"xilem-skeleton".into_attr_value());
+    cx.add_attr_to_element(&"aria-busy".into(), &"true".into_attr_value());
+}
+
+/// A placeholder for loading content, see [`skeleton`].
+pub struct Skeleton<T, A = ()> {
+    lines: u32,
+    avatar: bool,
+    width_pattern: Vec<u8>,
+    phantom: PhantomData<fn() -> (T, A)>,
+}
+
+/// A `<div>` with `lines` shimmering bars, and a round avatar placeholder in front of them when `avatar` is `true`.
+///
+/// The widths of the lines (in percent) are taken from `width_pattern`, which is repeated when there are more lines,
+/// e.g. `[100, 90, 60]` for a paragraph. An empty pattern results in full-width lines.
+/// Multiple skeletons (e.g. for a list) can be created with e.g. `vec![skeleton(2, true, [100, 60]); 5]`.
+pub fn skeleton<T, A>(
+    lines: u32,
+    avatar: bool,
+    width_pattern: impl IntoIterator<Item = u8>,
+) -> Skeleton<T, A> {
+    Skeleton {
+        lines,
+        avatar,
+        width_pattern: width_pattern.into_iter().collect(),
+        phantom: PhantomData,
+    }
+}
+
+impl<T, A> Clone for Skeleton<T, A> {
+    fn clone(&self) -> Self {
+        Skeleton {
+            lines: self.lines,
+            avatar: self.avatar,
+            width_pattern: self.width_pattern.clone(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T, A> Skeleton<T, A> {
+    fn append_placeholders(&self, cx: &mut Cx, element: &web_sys::Element) {
+        if self.avatar {
+            let avatar = cx.create_element(HTML_NS, "div");
+            cx.set_attribute(&avatar, "class", Some("xilem-skeleton-avatar".into()));
+            cx.insert_node(element, &avatar, None);
+        }
+        let lines = cx.create_element(HTML_NS, "div");
+        cx.set_attribute(&lines, "class", Some("xilem-skeleton-lines".into()));
+        for idx in 0..self.lines as usize {
+            let line = cx.create_element(HTML_NS, "div");
+            cx.set_attribute(&line, "class", Some("xilem-skeleton-line".into()));
+            if !self.width_pattern.is_empty() {
+                let width = self.width_pattern[idx % self.width_pattern.len()];
+                cx.set_attribute(&line, "style", Some(format!("width: {width}%").into()));
+            }
+            cx.insert_node(&lines, &line, None);
+        }
+        cx.insert_node(element, &lines, None);
+    }
+}
+
+generate_dom_interface_impl!(HtmlDivElement, ());
+crate::interfaces::for_all_html_div_element_ancestors!(generate_dom_interface_impl, ());
+
+impl<T, A> ViewMarker for Skeleton<T, A> {}
+impl<T, A> Sealed for Skeleton<T, A> {}
+
+impl<T, A> View<T, A> for Skeleton<T, A> {
+    type State = ElementProps;
+    type Element = web_sys::HtmlDivElement;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        inject_style(cx.document());
+        add_attributes(cx);
+        let (el, props) = cx.build_element(HTML_NS, "div");
+        self.append_placeholders(cx, &el);
+        let id = Id::next();
+
+        // Set the id used internally to the `data-debugid` attribute.
+        // This allows the user to see if an element has been re-created or only altered.
+        #[cfg(debug_assertions)]
+        cx.set_attribute(&el, "data-debugid", Some(id.to_raw().to_string().into()));
+
+        (id, props, el.unchecked_into())
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        _id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        add_attributes(cx);
+        let mut changed = cx.rebuild_element(element, state);
+        if prev.lines != self.lines
+            || prev.avatar != self.avatar
+            || prev.width_pattern != self.width_pattern
+        {
+            cx.set_text(element, None);
+            self.append_placeholders(cx, element);
+            changed |= ChangeFlags::OTHER_CHANGE;
+        }
+        changed
+    }
+
+    fn message(
+        &self,
+        _id_path: &[Id],
+        _state: &mut Self::State,
+        message: Box<dyn std::any::Any>,
+        _app_state: &mut T,
+    ) -> MessageResult<A> {
+        MessageResult::Stale(message)
+    }
+}
@@ -0,0 +1,187 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Typed inline styles of an element, see [`Element::style`](crate::interfaces::Element::style).
+
+use std::{fmt::Write, marker::PhantomData};
+
+use peniko::Color;
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    interfaces::{sealed::Sealed, Element},
+    ChangeFlags, Cx, View, ViewMarker,
+};
+
+type CowStr = std::borrow::Cow<'static, str>;
+
+/// The value of a CSS property, numbers are stored with their unit, and serialized only when the value changed.
+#[derive(PartialEq, Clone, Debug)]
+pub enum StyleValue {
+    /// A unitless number, e.g. for `opacity` or `flex-grow`.
+    Number(f64),
+    Px(f64),
+    Percent(f64),
+    Em(f64),
+    Rem(f64),
+    Auto,
+    Color(Color),
+    String(CowStr),
+}
+
+/// A length in pixels, e.g. `px(12)` is `12px`.
+pub fn px(value: impl Into<f64>) -> StyleValue {
+    StyleValue::Px(value.into())
+}
+
+/// A percentage, e.g. `percent(50)` is `50%`.
+pub fn percent(value: impl Into<f64>) -> StyleValue {
+    StyleValue::Percent(value.into())
+}
+
+/// A length relative to the font size of the element, e.g. `em(1.5)` is `1.5em`.
+pub fn em(value: impl Into<f64>) -> StyleValue {
+    StyleValue::Em(value.into())
+}
+
+/// A length relative to the font size of the root element, e.g. `rem(2)` is `2rem`.
+pub fn rem(value: impl Into<f64>) -> StyleValue {
+    StyleValue::Rem(value.into())
+}
+
+impl StyleValue {
+    pub fn serialize(&self) -> CowStr {
+        match self {
+            StyleValue::String(s) => s.clone(),
+            value => {
+                let mut buf = String::new();
+                value.serialize_into(&mut buf);
+                buf.into()
+            }
+        }
+    }
+
+    /// Writes the CSS representation of the value into `buf` (which is cleared before).
+    pub fn serialize_into<'a>(&'a self, buf: &'a mut String) -> &'a str {
+        buf.clear();
+        let (n, unit) = match self {
+            StyleValue::Number(n) => (*n, ""),
+            StyleValue::Px(n) => (*n, "px"),
+            StyleValue::Percent(n) => (*n, "%"),
+            StyleValue::Em(n) => (*n, "em"),
+            StyleValue::Rem(n) => (*n, "rem"),
+            StyleValue::Auto => return "auto",
+            StyleValue::Color(color) => {
+                let alpha = color.a as f64 / 255.0;
+                let _ = write!(buf, "rgba({}, {}, {}, {alpha})", color.r, color.g, color.b);
+                return buf;
+            }
+            StyleValue::String(s) => return s,
+        };
+        if n.fract() == 0.0 && n.abs() < 1e15 {
+            buf.push_str(itoa::Buffer::new().format(n as i64));
+        } else {
+            buf.push_str(ryu::Buffer::new().format(n));
+        }
+        buf.push_str(unit);
+        buf
+    }
+}
+
+pub trait IntoStyleValue: Sized {
+    fn into_style_value(self) -> Option<StyleValue>;
+}
+
+/// `None` removes the style, so e.g. `flag.then_some(px(4))` can be used for conditional styles.
+impl<T: IntoStyleValue> IntoStyleValue for Option<T> {
+    fn into_style_value(self) -> Option<StyleValue> {
+        self.and_then(T::into_style_value)
+    }
+}
+
+impl IntoStyleValue for StyleValue {
+    fn into_style_value(self) -> Option<StyleValue> {
+        Some(self)
+    }
+}
+
+impl IntoStyleValue for Color {
+    fn into_style_value(self) -> Option<StyleValue> {
+        Some(StyleValue::Color(self))
+    }
+}
+
+impl IntoStyleValue for f64 {
+    fn into_style_value(self) -> Option<StyleValue> {
+        Some(StyleValue::Number(self))
+    }
+}
+
+impl IntoStyleValue for i32 {
+    fn into_style_value(self) -> Option<StyleValue> {
+        Some(StyleValue::Number(self.into()))
+    }
+}
+
+impl IntoStyleValue for String {
+    fn into_style_value(self) -> Option<StyleValue> {
+        Some(StyleValue::String(self.into()))
+    }
+}
+
+impl IntoStyleValue for CowStr {
+    fn into_style_value(self) -> Option<StyleValue> {
+        Some(StyleValue::String(self))
+    }
+}
+
+impl IntoStyleValue for &'static str {
+    fn into_style_value(self) -> Option<StyleValue> {
+        Some(StyleValue::String(self.into()))
+    }
+}
+
+/// Sets an inline style of the element, see [`Element::style`].
+pub struct Style<E, T, A> {
+    pub(crate) element: E,
+    pub(crate) name: CowStr,
+    pub(crate) value: Option<StyleValue>,
+    pub(crate) phantom: PhantomData<fn() -> (T, A)>,
+}
+
+impl<E, T, A> ViewMarker for Style<E, T, A> {}
+impl<E, T, A> Sealed for Style<E, T, A> {}
+
+impl<E: Element<T, A>, T, A> View<T, A> for Style<E, T, A> {
+    type State = E::State;
+    type Element = E::Element;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        cx.add_style_to_element(&self.name, &self.value);
+        self.element.build(cx)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        cx.add_style_to_element(&self.name, &self.value);
+        self.element.rebuild(cx, &prev.element, id, state, element)
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Box<dyn std::any::Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        self.element.message(id_path, state, message, app_state)
+    }
+}
+
+crate::interfaces::impl_dom_interfaces_for_ty!(Element, Style);
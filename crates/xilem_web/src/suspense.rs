@@ -0,0 +1,142 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{future::Future, marker::PhantomData};
+
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    context::{ChangeFlags, Cx},
+    one_of::OneOf2,
+    view::{View, ViewMarker},
+};
+
+/// The message sent to the view when the future resolved.
+struct Resolved<V>(V);
+
+/// Shows a placeholder until the view of a future is available, see [`suspense`].
+pub struct Suspense<PV, F, T, A> {
+    pending: PV,
+    init_future: F,
+    phantom: PhantomData<fn() -> (T, A)>,
+}
+
+/// Shows the `pending` view until the future returned by `init_future` resolved to a view, which is then shown instead.
+///
+/// `init_future` is called once, when this view is built, i.e. the view isn't reloaded when e.g. the app state changes,
+/// this can be achieved by replacing the suspense view (e.g. via `OneOf`).
+/// The resolved view itself isn't rebuilt afterwards, similar to [`static_view`](crate::static_view),
+/// but its event handlers can of course modify the app state.
+///
+/// ```ignore
+/// suspense(el::p("Loading..."), || async {
+///     let user = load_user().await;
+///     el::p(format!("Hello {}", user.name))
+/// })
+/// ```
+pub fn suspense<PV, F, Fut, V, T, A>(pending: PV, init_future: F) -> Suspense<PV, F, T, A>
+where
+    PV: View<T, A>,
+    F: Fn() -> Fut,
+    Fut: Future<Output = V> + 'static,
+    V: View<T, A> + 'static,
+{
+    Suspense {
+        pending,
+        init_future,
+        phantom: PhantomData,
+    }
+}
+
+pub struct SuspenseState<PS, V, VS> {
+    child_id: Id,
+    /// The state of either the pending view, or the resolved view, which is retained for forwarding messages.
+    child: OneOf2<PS, (V, VS)>,
+    /// The resolved view, which is shown with the next rebuild.
+    resolved: Option<V>,
+}
+
+impl<PV, F, T, A> ViewMarker for Suspense<PV, F, T, A> {}
+
+impl<PV, F, Fut, V, T, A> View<T, A> for Suspense<PV, F, T, A>
+where
+    PV: View<T, A>,
+    F: Fn() -> Fut,
+    Fut: Future<Output = V> + 'static,
+    V: View<T, A> + 'static,
+{
+    type State = SuspenseState<PV::State, V, V::State>;
+    type Element = OneOf2<PV::Element, V::Element>;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, (state, element)) = cx.with_new_id(|cx| {
+            let future = (self.init_future)();
+            let thunk = cx.message_thunk();
+            wasm_bindgen_futures::spawn_local(async move {
+                thunk.push_message(Resolved(future.await));
+            });
+            let (child_id, child_state, element) = self.pending.build(cx);
+            let state = SuspenseState {
+                child_id,
+                child: OneOf2::A(child_state),
+                resolved: None,
+            };
+            (state, OneOf2::A(element))
+        });
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        cx.with_id(*id, |cx| {
+            if let Some(view) = state.resolved.take() {
+                let (child_id, child_state, child_element) = view.build(cx);
+                state.child_id = child_id;
+                state.child = OneOf2::B((view, child_state));
+                *element = OneOf2::B(child_element);
+                return ChangeFlags::STRUCTURE;
+            }
+            match (&mut state.child, element) {
+                (OneOf2::A(pending_state), OneOf2::A(pending_element)) => self.pending.rebuild(
+                    cx,
+                    &prev.pending,
+                    &mut state.child_id,
+                    pending_state,
+                    pending_element,
+                ),
+                _ => ChangeFlags::empty(),
+            }
+        })
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Box<dyn std::any::Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        match id_path {
+            [] if message.downcast_ref::<Resolved<V>>().is_some() => {
+                let Resolved(view) = *message.downcast().unwrap();
+                state.resolved = Some(view);
+                // The app is rebuilt after each message, which shows the resolved view
+                MessageResult::Nop
+            }
+            [child_id, rest @ ..] if *child_id == state.child_id => match &mut state.child {
+                OneOf2::A(pending_state) => {
+                    self.pending
+                        .message(rest, pending_state, message, app_state)
+                }
+                OneOf2::B((view, view_state)) => view.message(rest, view_state, message, app_state),
+            },
+            _ => MessageResult::Stale(message),
+        }
+    }
+}
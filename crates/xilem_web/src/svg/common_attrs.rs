@@ -2,9 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::rc::{Rc, Weak};
 use std::{any::Any, marker::PhantomData};
 
-use peniko::Brush;
+use peniko::{
+    kurbo::Point, Brush, Color, ColorStop, ColorStopsSource, Extend, Gradient, GradientKind, Image,
+};
+use wasm_bindgen::{Clamped, JsCast, UnwrapThrowExt};
 use xilem_core::{Id, MessageResult};
 
 use crate::{
@@ -14,7 +21,7 @@ use crate::{
         SvgRectElement, SvgTextContentElement, SvgTextElement, SvgTextPathElement,
         SvgTextPositioningElement, SvggElement, SvgtSpanElement,
     },
-    ChangeFlags, Cx, IntoAttributeValue, View, ViewMarker,
+    ChangeFlags, Cx, IntoAttributeValue, View, ViewMarker, SVG_NS,
 };
 
 pub struct Fill<V, T, A = ()> {
@@ -40,6 +47,34 @@ pub fn fill<T, A, V>(child: V, brush: impl Into<Brush>) -> Fill<V, T, A> {
     }
 }
 
+/// Fills `child` with a linear gradient from `start` to `end` (in the user space of the element).
+pub fn fill_linear_gradient<T, A, V>(
+    child: V,
+    start: impl Into<Point>,
+    end: impl Into<Point>,
+    stops: impl ColorStopsSource,
+) -> Fill<V, T, A> {
+    fill(child, Gradient::new_linear(start, end).with_stops(stops))
+}
+
+/// Fills `child` with a radial gradient around `center` (in the user space of the element).
+pub fn fill_radial_gradient<T, A, V>(
+    child: V,
+    center: impl Into<Point>,
+    radius: f32,
+    stops: impl ColorStopsSource,
+) -> Fill<V, T, A> {
+    fill(
+        child,
+        Gradient::new_radial(center, radius).with_stops(stops),
+    )
+}
+
+/// Fills `child` with `image`, which is tiled in the user space of the element, starting at the origin.
+pub fn fill_image<T, A, V>(child: V, image: Image) -> Fill<V, T, A> {
+    fill(child, image)
+}
+
 pub fn stroke<T, A, V>(
     child: V,
     brush: impl Into<Brush>,
@@ -53,16 +88,254 @@ pub fn stroke<T, A, V>(
     }
 }
 
-fn brush_to_string(brush: &Brush) -> String {
-    match brush {
-        Brush::Solid(color) => {
-            if color.a == 0 {
-                "none".into()
-            } else {
-                format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b)
+fn color_to_string(color: Color) -> String {
+    if color.a == 0 {
+        "none".into()
+    } else {
+        format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b)
+    }
+}
+
+/// The (hidden) `<svg>` element, which contains the gradients and image patterns of all `fill` and `stroke` views.
+const DEFS_ID: &str = "xilem-svg-gradients";
+
+/// A gradient or image pattern in the hidden `<svg>` of [`DEFS_ID`], which is shared by all views using the same brush.
+///
+/// It's removed from the document, when the last view using it was dropped (or uses another brush).
+pub(crate) struct SharedDef {
+    id: String,
+    element: web_sys::Element,
+}
+
+thread_local! {
+    static SHARED_DEFS: RefCell<HashMap<String, Weak<SharedDef>>> = RefCell::default();
+}
+
+impl Drop for SharedDef {
+    fn drop(&mut self) {
+        self.element.remove();
+        SHARED_DEFS.with(|defs| defs.borrow_mut().remove(&self.id));
+    }
+}
+
+/// The definition with `id`, which is created via `create` (and injected into the document) if it doesn't exist yet.
+fn shared_def(
+    document: &web_sys::Document,
+    id: String,
+    create: impl FnOnce(&web_sys::Document) -> web_sys::Element,
+) -> Rc<SharedDef> {
+    if let Some(def) = SHARED_DEFS.with(|defs| defs.borrow().get(&id).and_then(Weak::upgrade)) {
+        return def;
+    }
+    let defs = document.get_element_by_id(DEFS_ID).unwrap_or_else(|| {
+        let defs = document
+            .create_element_ns(Some(SVG_NS), "svg")
+            .unwrap_throw();
+        defs.set_id(DEFS_ID);
+        defs.set_attribute("aria-hidden", "true").unwrap_throw();
+        defs.set_attribute("style", "position: absolute; width: 0; height: 0")
+            .unwrap_throw();
+        document
+            .body()
+            .unwrap_throw()
+            .append_child(&defs)
+            .unwrap_throw();
+        defs
+    });
+    let element = create(document);
+    element.set_id(&id);
+    defs.append_child(&element).unwrap_throw();
+    let def = Rc::new(SharedDef {
+        id: id.clone(),
+        element,
+    });
+    SHARED_DEFS.with(|defs| defs.borrow_mut().insert(id, Rc::downgrade(&def)));
+    def
+}
+
+fn create_svg_element(
+    document: &web_sys::Document,
+    name: &str,
+    attributes: &[(&str, String)],
+) -> web_sys::Element {
+    let element = document
+        .create_element_ns(Some(SVG_NS), name)
+        .unwrap_throw();
+    for (name, value) in attributes {
+        element.set_attribute(name, value).unwrap_throw();
+    }
+    element
+}
+
+/// The attributes of the `<stop>` of a gradient.
+pub(crate) fn gradient_stop_attributes(stop: &ColorStop) -> [(&'static str, String); 3] {
+    let color = stop.color;
+    [
+        ("offset", stop.offset.to_string()),
+        (
+            "stop-color",
+            format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b),
+        ),
+        ("stop-opacity", (color.a as f32 / 255.0).to_string()),
+    ]
+}
+
+/// The tag and the attributes of the `<linearGradient>` or `<radialGradient>` of `gradient`, and the attributes of its `<stop>`s.
+///
+/// Returns `None` for sweep gradients, which aren't supported by SVG.
+pub(crate) fn gradient_definition(
+    gradient: &Gradient,
+) -> Option<(
+    &'static str,
+    Vec<(&'static str, String)>,
+    Vec<[(&'static str, String); 3]>,
+)> {
+    let (tag, mut attributes) = match gradient.kind {
+        GradientKind::Linear { start, end } => (
+            "linearGradient",
+            vec![
+                ("x1", start.x.to_string()),
+                ("y1", start.y.to_string()),
+                ("x2", end.x.to_string()),
+                ("y2", end.y.to_string()),
+            ],
+        ),
+        GradientKind::Radial {
+            start_center,
+            start_radius,
+            end_center,
+            end_radius,
+        } => (
+            "radialGradient",
+            vec![
+                ("fx", start_center.x.to_string()),
+                ("fy", start_center.y.to_string()),
+                ("fr", start_radius.to_string()),
+                ("cx", end_center.x.to_string()),
+                ("cy", end_center.y.to_string()),
+                ("r", end_radius.to_string()),
+            ],
+        ),
+        GradientKind::Sweep { .. } => return None,
+    };
+    let spread_method = match gradient.extend {
+        Extend::Pad => "pad",
+        Extend::Repeat => "repeat",
+        Extend::Reflect => "reflect",
+    };
+    attributes.push(("spreadMethod", spread_method.into()));
+    attributes.push(("gradientUnits", "userSpaceOnUse".into()));
+    let stops = gradient
+        .stops
+        .iter()
+        .map(gradient_stop_attributes)
+        .collect();
+    Some((tag, attributes, stops))
+}
+
+/// Injects `gradient` as definition into the document (unless it's already there), and returns a reference to it.
+///
+/// Gradients are deduplicated by their definition, so each distinct gradient is only injected once.
+/// Sweep gradients aren't supported by SVG, they fall back to their first color.
+fn gradient_to_string(cx: &Cx, gradient: &Gradient) -> (String, Option<Rc<SharedDef>>) {
+    let Some((tag, attributes, stops)) = gradient_definition(gradient) else {
+        let color = gradient
+            .stops
+            .first()
+            .map_or_else(|| "none".into(), |stop| color_to_string(stop.color));
+        return (color, None);
+    };
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    (tag, &attributes, &stops).hash(&mut hasher);
+    let id = format!("xilem-gradient-{:x}", hasher.finish());
+    let def = shared_def(cx.document(), id, |document| {
+        let element = create_svg_element(document, tag, &attributes);
+        for stop in &stops {
+            let stop = create_svg_element(document, "stop", stop);
+            element.append_child(&stop).unwrap_throw();
+        }
+        element
+    });
+    (format!("url(#{})", def.id), Some(def))
+}
+
+/// Encodes the pixels of `image` as PNG data url, via a canvas.
+fn image_data_url(document: &web_sys::Document, image: &Image) -> Option<String> {
+    let canvas: web_sys::HtmlCanvasElement =
+        document.create_element("canvas").ok()?.dyn_into().ok()?;
+    canvas.set_width(image.width);
+    canvas.set_height(image.height);
+    let context: web_sys::CanvasRenderingContext2d =
+        canvas.get_context("2d").ok()??.dyn_into().ok()?;
+    let pixels = web_sys::ImageData::new_with_u8_clamped_array_and_sh(
+        Clamped(image.data.data()),
+        image.width,
+        image.height,
+    )
+    .ok()?;
+    context.put_image_data(&pixels, 0.0, 0.0).ok()?;
+    canvas.to_data_url().ok()
+}
+
+/// Injects `image` as `<pattern>` (tiled in the user space of the element, starting at the origin) into the document,
+/// and returns a reference to it, the same as for gradients.
+fn image_to_string(cx: &Cx, image: &Image) -> (String, Option<Rc<SharedDef>>) {
+    // The pixels aren't hashed, the id of the blob identifies them
+    let id = format!(
+        "xilem-image-{:x}-{}x{}",
+        image.data.id(),
+        image.width,
+        image.height
+    );
+    let mut created = true;
+    let def = shared_def(cx.document(), id, |document| {
+        let (width, height) = (image.width.to_string(), image.height.to_string());
+        let pattern = create_svg_element(
+            document,
+            "pattern",
+            &[
+                ("patternUnits", "userSpaceOnUse".into()),
+                ("width", width.clone()),
+                ("height", height.clone()),
+            ],
+        );
+        match image_data_url(document, image) {
+            Some(url) => {
+                let image = create_svg_element(
+                    document,
+                    "image",
+                    &[("href", url), ("width", width), ("height", height)],
+                );
+                pattern.append_child(&image).unwrap_throw();
             }
+            None => created = false,
         }
-        _ => todo!("gradients not implemented"),
+        pattern
+    });
+    if !created {
+        web_sys::console::warn_1(&"Couldn't encode the image of an image brush".into());
+    }
+    (format!("url(#{})", def.id), Some(def))
+}
+
+/// The value of the `fill` or `stroke` attribute of a brush,
+/// with the definition of its gradient or image, which is retained as long as it's used.
+pub struct BrushAttr {
+    value: Cow<'static, str>,
+    #[allow(unused)]
+    def: Option<Rc<SharedDef>>,
+}
+
+/// Gradients and images are injected as definitions into the document, see [`gradient_to_string`].
+fn brush_attr(cx: &Cx, brush: &Brush) -> BrushAttr {
+    let (value, def) = match brush {
+        Brush::Solid(color) => (color_to_string(*color), None),
+        Brush::Gradient(gradient) => gradient_to_string(cx, gradient),
+        Brush::Image(image) => image_to_string(cx, image),
+    };
+    BrushAttr {
+        value: value.into(),
+        def,
     }
 }
 
@@ -90,14 +363,14 @@ impl<T, A, V> ViewMarker for Fill<V, T, A> {}
 impl<T, A, V> crate::interfaces::sealed::Sealed for Fill<V, T, A> {}
 
 impl<T, A, V: View<T, A>> View<T, A> for Fill<V, T, A> {
-    type State = (Cow<'static, str>, V::State);
+    type State = (BrushAttr, V::State);
     type Element = V::Element;
 
     fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
-        let brush_svg_repr = Cow::from(brush_to_string(&self.brush));
-        cx.add_attr_to_element(&"fill".into(), &brush_svg_repr.clone().into_attr_value());
+        let brush = brush_attr(cx, &self.brush);
+        cx.add_attr_to_element(&"fill".into(), &brush.value.clone().into_attr_value());
         let (id, child_state, element) = self.child.build(cx);
-        (id, (brush_svg_repr, child_state), element)
+        (id, (brush, child_state), element)
     }
 
     fn rebuild(
@@ -105,13 +378,14 @@ impl<T, A, V: View<T, A>> View<T, A> for Fill<V, T, A> {
         cx: &mut Cx,
         prev: &Self,
         id: &mut Id,
-        (brush_svg_repr, child_state): &mut Self::State,
+        (brush, child_state): &mut Self::State,
         element: &mut V::Element,
     ) -> ChangeFlags {
         if self.brush != prev.brush {
-            *brush_svg_repr = Cow::from(brush_to_string(&self.brush));
+            // The previous definition is only dropped afterwards, so it's kept when it's still used
+            *brush = brush_attr(cx, &self.brush);
         }
-        cx.add_attr_to_element(&"fill".into(), &brush_svg_repr.clone().into_attr_value());
+        cx.add_attr_to_element(&"fill".into(), &brush.value.clone().into_attr_value());
         self.child
             .rebuild(cx, &prev.child, id, child_state, element)
     }
@@ -152,15 +426,15 @@ impl<T, A, V> ViewMarker for Stroke<V, T, A> {}
 impl<T, A, V> crate::interfaces::sealed::Sealed for Stroke<V, T, A> {}
 
 impl<T, A, V: View<T, A>> View<T, A> for Stroke<V, T, A> {
-    type State = (Cow<'static, str>, V::State);
+    type State = (BrushAttr, V::State);
     type Element = V::Element;
 
     fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
-        let brush_svg_repr = Cow::from(brush_to_string(&self.brush));
-        cx.add_attr_to_element(&"stroke".into(), &brush_svg_repr.clone().into_attr_value());
+        let brush = brush_attr(cx, &self.brush);
+        cx.add_attr_to_element(&"stroke".into(), &brush.value.clone().into_attr_value());
         cx.add_attr_to_element(&"stroke-width".into(), &self.style.width.into_attr_value());
         let (id, child_state, element) = self.child.build(cx);
-        (id, (brush_svg_repr, child_state), element)
+        (id, (brush, child_state), element)
     }
 
     fn rebuild(
@@ -168,13 +442,13 @@ impl<T, A, V: View<T, A>> View<T, A> for Stroke<V, T, A> {
         cx: &mut Cx,
         prev: &Self,
         id: &mut Id,
-        (brush_svg_repr, child_state): &mut Self::State,
+        (brush, child_state): &mut Self::State,
         element: &mut V::Element,
     ) -> ChangeFlags {
         if self.brush != prev.brush {
-            *brush_svg_repr = Cow::from(brush_to_string(&self.brush));
+            *brush = brush_attr(cx, &self.brush);
         }
-        cx.add_attr_to_element(&"stroke".into(), &brush_svg_repr.clone().into_attr_value());
+        cx.add_attr_to_element(&"stroke".into(), &brush.value.clone().into_attr_value());
         cx.add_attr_to_element(&"stroke-width".into(), &self.style.width.into_attr_value());
         self.child
             .rebuild(cx, &prev.child, id, child_state, element)
@@ -0,0 +1,197 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Definitions (gradients, patterns, filters, ...), which are referenced by other SVG elements via their id.
+
+use std::{
+    fmt,
+    rc::Rc,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use peniko::{Gradient, GradientKind};
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    context::{ChangeFlags, Cx, ElementProps},
+    interfaces::sealed::Sealed,
+    svg::common_attrs::{gradient_definition, gradient_stop_attributes},
+    view::{View, ViewMarker},
+    AttributeValue, IntoAttributeValue, SVG_NS,
+};
+
+/// A generated, document-wide unique id of a definition, which is referenced via `url(#id)`.
+///
+/// It should be created once (e.g. stored in the app state), and set on the definition with
+/// [`SvgElement::def_id`](crate::interfaces::SvgElement::def_id) (or [`gradient`]),
+/// which then can be used with e.g. [`SvgGraphicsElement::fill_url`](crate::interfaces::SvgGraphicsElement::fill_url).
+///
+/// ```ignore
+/// let sky = DefId::new();
+/// svg((
+///     el::defs(gradient(&sky, Gradient::new_linear((0.0, 0.0), (0.0, 100.0)).with_stops([BLUE, WHITE]))),
+///     Rect::new(0.0, 0.0, 100.0, 100.0).fill_url(&sky),
+/// ))
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct DefId(Rc<str>);
+
+impl DefId {
+    pub fn new() -> Self {
+        static NEXT_DEF_ID: AtomicU32 = AtomicU32::new(0);
+        let id = NEXT_DEF_ID.fetch_add(1, Ordering::Relaxed);
+        DefId(format!("xilem-def-{id}").into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The reference to the definition, e.g. `url(#xilem-def-0)`, as it's used in `fill` or `filter`.
+    pub fn url(&self) -> String {
+        format!("url(#{})", self.0)
+    }
+}
+
+impl Default for DefId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for DefId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DefId({})", self.0)
+    }
+}
+
+/// The id itself, e.g. for the `href` of `<use>` the `#` has to be prepended.
+impl IntoAttributeValue for &DefId {
+    fn into_attr_value(self) -> Option<AttributeValue> {
+        Some(AttributeValue::String(self.0.to_string().into()))
+    }
+}
+
+/// A `<linearGradient>` or `<radialGradient>` definition, see [`gradient`].
+pub struct GradientDef {
+    id: DefId,
+    gradient: Gradient,
+}
+
+/// Defines `gradient` as `<linearGradient>` or `<radialGradient>` (in the user space of the referencing element) with the id `id`.
+///
+/// It has to be in a `<defs>` element, and is referenced via e.g. [`SvgGraphicsElement::fill_url`](crate::interfaces::SvgGraphicsElement::fill_url).
+/// In contrast to [`fill`](crate::svg::fill) with a gradient brush, the gradient is only defined once,
+/// and can be referenced by many elements, and its attributes (like `gradientTransform`) can be modified.
+///
+/// Sweep gradients aren't supported by SVG, they fall back to their first color, the same as for `fill`.
+pub fn gradient(id: &DefId, gradient: impl Into<Gradient>) -> GradientDef {
+    GradientDef {
+        id: id.clone(),
+        gradient: gradient.into(),
+    }
+}
+
+fn gradient_tag(gradient: &Gradient) -> &'static str {
+    match gradient.kind {
+        GradientKind::Radial { .. } => "radialGradient",
+        GradientKind::Linear { .. } | GradientKind::Sweep { .. } => "linearGradient",
+    }
+}
+
+type StopAttributes = [(&'static str, String); 3];
+
+/// The attributes of the gradient element and of its `<stop>`s, the same as for gradient brushes (see [`fill`](crate::svg::fill)).
+///
+/// Sweep gradients fall back to their first color, as linear gradient with a single stop.
+fn gradient_attributes(gradient: &Gradient) -> (Vec<(&'static str, String)>, Vec<StopAttributes>) {
+    match gradient_definition(gradient) {
+        Some((_, attributes, stops)) => (attributes, stops),
+        None => {
+            let stops = gradient.stops.first().map(gradient_stop_attributes);
+            (Vec::new(), stops.into_iter().collect())
+        }
+    }
+}
+
+impl GradientDef {
+    fn add_attrs(&self, cx: &mut Cx, attributes: Vec<(&'static str, String)>) {
+        cx.add_attr_to_element(&"id".into(), &(&self.id).into_attr_value());
+        for (name, value) in attributes {
+            cx.add_attr_to_element(&name.into(), &value.into_attr_value());
+        }
+    }
+}
+
+/// Replaces the `<stop>` children of `element` with `stops`.
+fn build_stops(cx: &mut Cx, element: &web_sys::Element, stops: Vec<StopAttributes>) {
+    cx.set_text(element, None);
+    for stop in stops {
+        let stop_el = cx.create_element(SVG_NS, "stop");
+        for (name, value) in stop {
+            cx.set_attribute(&stop_el, name, Some(value.into()));
+        }
+        cx.insert_node(element, &stop_el, None);
+    }
+}
+
+macro_rules! generate_dom_interface_impl {
+    ($dom_interface:ident, ()) => {
+        impl<T, A> $crate::interfaces::$dom_interface<T, A> for GradientDef {}
+    };
+}
+
+generate_dom_interface_impl!(SvgGradientElement, ());
+crate::interfaces::for_all_svg_gradient_element_ancestors!(generate_dom_interface_impl, ());
+
+impl ViewMarker for GradientDef {}
+impl Sealed for GradientDef {}
+
+impl<T, A> View<T, A> for GradientDef {
+    type State = ElementProps;
+    type Element = web_sys::Element;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (attributes, stops) = gradient_attributes(&self.gradient);
+        self.add_attrs(cx, attributes);
+        let (el, props) = cx.build_element(SVG_NS, gradient_tag(&self.gradient));
+        build_stops(cx, &el, stops);
+        (Id::next(), props, el)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        props: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        if gradient_tag(&self.gradient) != gradient_tag(&prev.gradient) {
+            // The kind of the gradient changed, so it's a different element
+            let (new_id, new_props, new_element) = <Self as View<T, A>>::build(self, cx);
+            *id = new_id;
+            *props = new_props;
+            *element = new_element;
+            return ChangeFlags::STRUCTURE;
+        }
+        let (attributes, stops) = gradient_attributes(&self.gradient);
+        self.add_attrs(cx, attributes);
+        let mut changed = cx.rebuild_element(element, props);
+        if stops != gradient_attributes(&prev.gradient).1 {
+            build_stops(cx, element, stops);
+            changed |= ChangeFlags::ATTRS;
+        }
+        changed
+    }
+
+    fn message(
+        &self,
+        _id_path: &[Id],
+        _state: &mut Self::State,
+        message: Box<dyn std::any::Any>,
+        _app_state: &mut T,
+    ) -> MessageResult<A> {
+        MessageResult::Stale(message)
+    }
+}
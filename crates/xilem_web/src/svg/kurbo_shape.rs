@@ -3,17 +3,16 @@
 
 //! Implementation of the View trait for various kurbo shapes.
 
-use peniko::kurbo::{BezPath, Circle, Line, Rect};
+use peniko::kurbo::{BezPath, Circle, Ellipse, Line, Rect, RoundedRect, Shape};
 use std::borrow::Cow;
 
 use xilem_core::{Id, MessageResult};
 
 use crate::{
-    context::{ChangeFlags, Cx},
+    context::{ChangeFlags, Cx, ElementProps},
     interfaces::sealed::Sealed,
-    vecmap::VecMap,
     view::{View, ViewMarker},
-    AttributeValue, IntoAttributeValue, SVG_NS,
+    IntoAttributeValue, SVG_NS,
 };
 
 macro_rules! generate_dom_interface_impl {
@@ -29,7 +28,7 @@ impl ViewMarker for Line {}
 impl Sealed for Line {}
 
 impl<T, A> View<T, A> for Line {
-    type State = VecMap<Cow<'static, str>, AttributeValue>;
+    type State = ElementProps;
     type Element = web_sys::Element;
 
     fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
@@ -37,9 +36,9 @@ impl<T, A> View<T, A> for Line {
         cx.add_attr_to_element(&"y1".into(), &self.p0.y.into_attr_value());
         cx.add_attr_to_element(&"x2".into(), &self.p1.x.into_attr_value());
         cx.add_attr_to_element(&"y2".into(), &self.p1.y.into_attr_value());
-        let (el, attributes) = cx.build_element(SVG_NS, "line");
+        let (el, props) = cx.build_element(SVG_NS, "line");
         let id = Id::next();
-        (id, attributes, el)
+        (id, props, el)
     }
 
     fn rebuild(
@@ -47,14 +46,14 @@ impl<T, A> View<T, A> for Line {
         cx: &mut Cx,
         _prev: &Self,
         _id: &mut Id,
-        attributes: &mut Self::State,
+        props: &mut Self::State,
         element: &mut Self::Element,
     ) -> ChangeFlags {
         cx.add_attr_to_element(&"x1".into(), &self.p0.x.into_attr_value());
         cx.add_attr_to_element(&"y1".into(), &self.p0.y.into_attr_value());
         cx.add_attr_to_element(&"x2".into(), &self.p1.x.into_attr_value());
         cx.add_attr_to_element(&"y2".into(), &self.p1.y.into_attr_value());
-        cx.rebuild_element(element, attributes)
+        cx.rebuild_element(element, props)
     }
 
     fn message(
@@ -75,7 +74,7 @@ impl ViewMarker for Rect {}
 impl Sealed for Rect {}
 
 impl<T, A> View<T, A> for Rect {
-    type State = VecMap<Cow<'static, str>, AttributeValue>;
+    type State = ElementProps;
     type Element = web_sys::Element;
 
     fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
@@ -84,9 +83,9 @@ impl<T, A> View<T, A> for Rect {
         let size = self.size();
         cx.add_attr_to_element(&"width".into(), &size.width.into_attr_value());
         cx.add_attr_to_element(&"height".into(), &size.height.into_attr_value());
-        let (el, attributes) = cx.build_element(SVG_NS, "rect");
+        let (el, props) = cx.build_element(SVG_NS, "rect");
         let id = Id::next();
-        (id, attributes, el)
+        (id, props, el)
     }
 
     fn rebuild(
@@ -94,7 +93,7 @@ impl<T, A> View<T, A> for Rect {
         cx: &mut Cx,
         _prev: &Self,
         _id: &mut Id,
-        attributes: &mut Self::State,
+        props: &mut Self::State,
         element: &mut Self::Element,
     ) -> ChangeFlags {
         cx.add_attr_to_element(&"x".into(), &self.x0.into_attr_value());
@@ -102,7 +101,7 @@ impl<T, A> View<T, A> for Rect {
         let size = self.size();
         cx.add_attr_to_element(&"width".into(), &size.width.into_attr_value());
         cx.add_attr_to_element(&"height".into(), &size.height.into_attr_value());
-        cx.rebuild_element(element, attributes)
+        cx.rebuild_element(element, props)
     }
 
     fn message(
@@ -123,16 +122,16 @@ impl ViewMarker for Circle {}
 impl Sealed for Circle {}
 
 impl<T, A> View<T, A> for Circle {
-    type State = VecMap<Cow<'static, str>, AttributeValue>;
+    type State = ElementProps;
     type Element = web_sys::Element;
 
     fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
         cx.add_attr_to_element(&"cx".into(), &self.center.x.into_attr_value());
         cx.add_attr_to_element(&"cy".into(), &self.center.y.into_attr_value());
         cx.add_attr_to_element(&"r".into(), &self.radius.into_attr_value());
-        let (el, attributes) = cx.build_element(SVG_NS, "circle");
+        let (el, props) = cx.build_element(SVG_NS, "circle");
         let id = Id::next();
-        (id, attributes, el)
+        (id, props, el)
     }
 
     fn rebuild(
@@ -140,13 +139,13 @@ impl<T, A> View<T, A> for Circle {
         cx: &mut Cx,
         _prev: &Self,
         _id: &mut Id,
-        attributes: &mut Self::State,
+        props: &mut Self::State,
         element: &mut Self::Element,
     ) -> ChangeFlags {
         cx.add_attr_to_element(&"cx".into(), &self.center.x.into_attr_value());
         cx.add_attr_to_element(&"cy".into(), &self.center.y.into_attr_value());
         cx.add_attr_to_element(&"r".into(), &self.radius.into_attr_value());
-        cx.rebuild_element(element, attributes)
+        cx.rebuild_element(element, props)
     }
 
     fn message(
@@ -167,15 +166,15 @@ impl ViewMarker for BezPath {}
 impl Sealed for BezPath {}
 
 impl<T, A> View<T, A> for BezPath {
-    type State = (Cow<'static, str>, VecMap<Cow<'static, str>, AttributeValue>);
+    type State = (Cow<'static, str>, ElementProps);
     type Element = web_sys::Element;
 
     fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
         let svg_repr = Cow::from(self.to_svg());
         cx.add_attr_to_element(&"d".into(), &svg_repr.clone().into_attr_value());
-        let (el, attributes) = cx.build_element(SVG_NS, "path");
+        let (el, props) = cx.build_element(SVG_NS, "path");
         let id = Id::next();
-        (id, (svg_repr, attributes), el)
+        (id, (svg_repr, props), el)
     }
 
     fn rebuild(
@@ -183,7 +182,7 @@ impl<T, A> View<T, A> for BezPath {
         cx: &mut Cx,
         prev: &Self,
         _id: &mut Id,
-        (svg_repr, attributes): &mut Self::State,
+        (svg_repr, props): &mut Self::State,
         element: &mut Self::Element,
     ) -> ChangeFlags {
         // slight optimization to avoid serialization/allocation
@@ -191,7 +190,7 @@ impl<T, A> View<T, A> for BezPath {
             *svg_repr = Cow::from(self.to_svg());
         }
         cx.add_attr_to_element(&"d".into(), &svg_repr.clone().into_attr_value());
-        cx.rebuild_element(element, attributes)
+        cx.rebuild_element(element, props)
     }
 
     fn message(
@@ -205,4 +204,107 @@ impl<T, A> View<T, A> for BezPath {
     }
 }
 
-// TODO: RoundedRect
+generate_dom_interface_impl!(SvgEllipseElement, (Ellipse));
+crate::interfaces::for_all_svg_ellipse_element_ancestors!(generate_dom_interface_impl, (Ellipse));
+
+impl ViewMarker for Ellipse {}
+impl Sealed for Ellipse {}
+
+fn add_ellipse_attrs(ellipse: &Ellipse, cx: &mut Cx) {
+    let center = ellipse.center();
+    let radii = ellipse.radii();
+    cx.add_attr_to_element(&"cx".into(), &center.x.into_attr_value());
+    cx.add_attr_to_element(&"cy".into(), &center.y.into_attr_value());
+    cx.add_attr_to_element(&"rx".into(), &radii.x.into_attr_value());
+    cx.add_attr_to_element(&"ry".into(), &radii.y.into_attr_value());
+    let rotation = ellipse.rotation();
+    if rotation != 0.0 {
+        let transform = format!(
+            "rotate({} {} {})",
+            rotation.to_degrees(),
+            center.x,
+            center.y
+        );
+        cx.add_attr_to_element(&"transform".into(), &transform.into_attr_value());
+    }
+}
+
+impl<T, A> View<T, A> for Ellipse {
+    type State = ElementProps;
+    type Element = web_sys::Element;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        add_ellipse_attrs(self, cx);
+        let (el, props) = cx.build_element(SVG_NS, "ellipse");
+        let id = Id::next();
+        (id, props, el)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        _prev: &Self,
+        _id: &mut Id,
+        props: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        add_ellipse_attrs(self, cx);
+        cx.rebuild_element(element, props)
+    }
+
+    fn message(
+        &self,
+        _id_path: &[Id],
+        _state: &mut Self::State,
+        message: Box<dyn std::any::Any>,
+        _app_state: &mut T,
+    ) -> MessageResult<A> {
+        MessageResult::Stale(message)
+    }
+}
+
+// SVG `<rect>` only supports the same radius for all corners, so a `<path>` is used instead
+generate_dom_interface_impl!(SvgPathElement, (RoundedRect));
+crate::interfaces::for_all_svg_path_element_ancestors!(generate_dom_interface_impl, (RoundedRect));
+
+impl ViewMarker for RoundedRect {}
+impl Sealed for RoundedRect {}
+
+impl<T, A> View<T, A> for RoundedRect {
+    type State = (Cow<'static, str>, ElementProps);
+    type Element = web_sys::Element;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let svg_repr = Cow::from(self.to_path(0.1).to_svg());
+        cx.add_attr_to_element(&"d".into(), &svg_repr.clone().into_attr_value());
+        let (el, props) = cx.build_element(SVG_NS, "path");
+        let id = Id::next();
+        (id, (svg_repr, props), el)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        _id: &mut Id,
+        (svg_repr, props): &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        // slight optimization to avoid serialization/allocation
+        if self != prev {
+            *svg_repr = Cow::from(self.to_path(0.1).to_svg());
+        }
+        cx.add_attr_to_element(&"d".into(), &svg_repr.clone().into_attr_value());
+        cx.rebuild_element(element, props)
+    }
+
+    fn message(
+        &self,
+        _id_path: &[Id],
+        _state: &mut Self::State,
+        message: Box<dyn std::any::Any>,
+        _app_state: &mut T,
+    ) -> MessageResult<A> {
+        MessageResult::Stale(message)
+    }
+}
@@ -1,6 +1,14 @@
 pub(crate) mod common_attrs;
+pub(crate) mod defs;
 pub(crate) mod kurbo_shape;
+pub(crate) mod viewport;
 
-pub use common_attrs::{fill, stroke, Fill, Stroke};
+pub use common_attrs::{
+    fill, fill_image, fill_linear_gradient, fill_radial_gradient, stroke, Fill, Stroke,
+};
+pub use defs::{gradient, DefId, GradientDef};
 pub use peniko;
 pub use peniko::kurbo;
+pub use viewport::{
+    svg_viewport, Align, AspectRatio, PanZoom, SvgViewport, SvgViewportState, ViewportPointerMsg,
+};
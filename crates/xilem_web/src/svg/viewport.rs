@@ -0,0 +1,576 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A root `<svg>` view, which manages the `viewBox`, maps pointer events into its user space, and optionally pans and zooms.
+
+use std::{any::Any, cell::Cell, marker::PhantomData, rc::Rc};
+
+use gloo::events::{EventListener, EventListenerOptions};
+use peniko::kurbo::{Affine, Point, Rect};
+use wasm_bindgen::JsCast;
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    context::{ChangeFlags, Cx, ElementProps},
+    elements::ChildrenSplice,
+    interfaces::sealed::Sealed,
+    pointer::PointerDetails,
+    view::{View, ViewMarker},
+    IntoAttributeValue, Pod, PointerMsg, ViewSequence, SVG_NS,
+};
+
+/// The alignment of the view box along one axis, see [`AspectRatio`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Align {
+    Min,
+    #[default]
+    Mid,
+    Max,
+}
+
+impl Align {
+    fn offset(self, space: f64) -> f64 {
+        match self {
+            Align::Min => 0.0,
+            Align::Mid => space / 2.0,
+            Align::Max => space,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Align::Min => "Min",
+            Align::Mid => "Mid",
+            Align::Max => "Max",
+        }
+    }
+}
+
+/// How the view box is fitted into the viewport, i.e. the `preserveAspectRatio` attribute.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AspectRatio {
+    /// The view box is stretched to fill the viewport (`none`).
+    Stretch,
+    /// The whole view box is visible, aligned by `x` and `y` (`meet`).
+    Meet { x: Align, y: Align },
+    /// The view box covers the whole viewport, aligned by `x` and `y`, the rest is clipped (`slice`).
+    Slice { x: Align, y: Align },
+}
+
+impl Default for AspectRatio {
+    fn default() -> Self {
+        AspectRatio::Meet {
+            x: Align::Mid,
+            y: Align::Mid,
+        }
+    }
+}
+
+impl AspectRatio {
+    fn to_attr_string(self) -> String {
+        match self {
+            AspectRatio::Stretch => "none".into(),
+            AspectRatio::Meet { x, y } => format!("x{}Y{} meet", x.as_str(), y.as_str()),
+            AspectRatio::Slice { x, y } => format!("x{}Y{} slice", x.as_str(), y.as_str()),
+        }
+    }
+}
+
+/// The transform from the user space of `view_box` into the coordinates of `viewport` (as it's defined by the SVG spec).
+pub(crate) fn view_box_transform(
+    viewport: Rect,
+    view_box: Rect,
+    aspect_ratio: AspectRatio,
+) -> Affine {
+    if view_box.width() <= 0.0 || view_box.height() <= 0.0 {
+        return Affine::IDENTITY;
+    }
+    let scale_x = viewport.width() / view_box.width();
+    let scale_y = viewport.height() / view_box.height();
+    let (scale_x, scale_y, align_x, align_y) = match aspect_ratio {
+        AspectRatio::Stretch => (scale_x, scale_y, Align::Min, Align::Min),
+        AspectRatio::Meet { x, y } => {
+            let scale = scale_x.min(scale_y);
+            (scale, scale, x, y)
+        }
+        AspectRatio::Slice { x, y } => {
+            let scale = scale_x.max(scale_y);
+            (scale, scale, x, y)
+        }
+    };
+    let translate_x = viewport.x0 - view_box.x0 * scale_x
+        + align_x.offset(viewport.width() - view_box.width() * scale_x);
+    let translate_y = viewport.y0 - view_box.y0 * scale_y
+        + align_y.offset(viewport.height() - view_box.height() * scale_y);
+    Affine::new([scale_x, 0.0, 0.0, scale_y, translate_x, translate_y])
+}
+
+/// A pointer event of an [`SvgViewport`], see [`SvgViewport::on_pointer`].
+#[derive(Debug)]
+pub struct ViewportPointerMsg {
+    /// The event with the client coordinates of the pointer.
+    pub msg: PointerMsg,
+    /// The position of the pointer in the user space (i.e. the coordinates of the view box).
+    pub position: Point,
+}
+
+/// Pan and zoom state of an [`SvgViewport`], which is stored in the app state, see [`SvgViewport::pan_zoom`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct PanZoom {
+    /// The currently visible view box, which should be passed to [`svg_viewport`].
+    pub view_box: Rect,
+    /// The smallest and largest width of the view box (i.e. the zoom limits).
+    pub width_range: (f64, f64),
+    drag: Option<Drag>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Drag {
+    pointer_id: i32,
+    start: Point,
+    start_view_box: Rect,
+    /// From client coordinates into the user space at the start of the drag
+    inverse_transform: Affine,
+}
+
+impl PanZoom {
+    pub fn new(view_box: Rect) -> Self {
+        PanZoom {
+            view_box,
+            width_range: (0.0, f64::INFINITY),
+            drag: None,
+        }
+    }
+
+    pub fn width_range(mut self, min: f64, max: f64) -> Self {
+        self.width_range = (min, max);
+        self
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.drag.is_some()
+    }
+
+    /// Zooms by `factor` (`> 1.0` zooms out), so that `center` (in user space) stays at the same position.
+    pub fn zoom_at(&mut self, center: Point, factor: f64) {
+        let (min, max) = self.width_range;
+        let width = (self.view_box.width() * factor).clamp(min, max);
+        let factor = width / self.view_box.width();
+        if !factor.is_finite() {
+            return;
+        }
+        let p0 = center + (self.view_box.origin() - center) * factor;
+        let p1 = center + (Point::new(self.view_box.x1, self.view_box.y1) - center) * factor;
+        self.view_box = Rect::from_points(p0, p1);
+    }
+
+    /// Pans by dragging with the primary button, `transform` is the current transform from user space into client coordinates.
+    fn handle_pointer(&mut self, msg: &PointerMsg, transform: Affine) {
+        match msg {
+            PointerMsg::Down(details) if details.button == 0 => {
+                self.drag = Some(Drag {
+                    pointer_id: details.id,
+                    start: Point::new(details.x, details.y),
+                    start_view_box: self.view_box,
+                    inverse_transform: transform.inverse(),
+                });
+            }
+            PointerMsg::Move(details) => {
+                if let Some(drag) = self.drag.as_ref().filter(|d| d.pointer_id == details.id) {
+                    // Relative to the start of the drag, so that it doesn't matter whether the view box
+                    // was already rebuilt after the previous move
+                    let delta = drag.inverse_transform * Point::new(details.x, details.y)
+                        - drag.inverse_transform * drag.start;
+                    self.view_box = drag.start_view_box - delta;
+                }
+            }
+            PointerMsg::Up(details) | PointerMsg::Cancel(details) => {
+                if self
+                    .drag
+                    .as_ref()
+                    .is_some_and(|d| d.pointer_id == details.id)
+                {
+                    self.drag = None;
+                }
+            }
+            PointerMsg::Down(_) => {}
+        }
+    }
+}
+
+enum ViewportEvent {
+    Pointer(PointerMsg),
+    Wheel { x: f64, y: f64, delta_y: f64 },
+}
+
+type PointerHandler<T, A> = Box<dyn Fn(&mut T, ViewportPointerMsg) -> A>;
+type PanZoomAccessor<T> = Box<dyn Fn(&mut T) -> &mut PanZoom>;
+
+/// An `<svg>` element with a managed `viewBox`, see [`svg_viewport`].
+pub struct SvgViewport<T, A, Children> {
+    view_box: Rect,
+    aspect_ratio: AspectRatio,
+    children: Children,
+    on_pointer: Option<PointerHandler<T, A>>,
+    pan_zoom: Option<PanZoomAccessor<T>>,
+    phantom: PhantomData<fn() -> (T, A)>,
+}
+
+/// An `<svg>` element showing `view_box` (in user space) of its children, which are e.g. kurbo shapes.
+///
+/// Pointer events are mapped into the user space (see [`SvgViewport::on_pointer`]),
+/// and with [`SvgViewport::pan_zoom`] the view box can be panned by dragging and zoomed with the mouse wheel.
+///
+/// ```ignore
+/// svg_viewport(state.pan_zoom.view_box, (Circle::new((0.0, 0.0), 10.0), Rect::new(20.0, 20.0, 40.0, 30.0)))
+///     .pan_zoom(|state: &mut AppState| &mut state.pan_zoom)
+///     .on_pointer(|state: &mut AppState, event| state.cursor = event.position)
+/// ```
+pub fn svg_viewport<T, A, Children>(
+    view_box: Rect,
+    children: Children,
+) -> SvgViewport<T, A, Children>
+where
+    Children: ViewSequence<T, A>,
+{
+    SvgViewport {
+        view_box,
+        aspect_ratio: AspectRatio::default(),
+        children,
+        on_pointer: None,
+        pan_zoom: None,
+        phantom: PhantomData,
+    }
+}
+
+impl<T, A, Children> SvgViewport<T, A, Children> {
+    /// Sets `preserveAspectRatio`, by default the view box is centered in the viewport ([`AspectRatio::Meet`]).
+    pub fn preserve_aspect_ratio(mut self, aspect_ratio: AspectRatio) -> Self {
+        self.aspect_ratio = aspect_ratio;
+        self
+    }
+
+    /// Calls `handler` with the pointer events on the viewport, with their position in user space.
+    pub fn on_pointer(
+        mut self,
+        handler: impl Fn(&mut T, ViewportPointerMsg) -> A + 'static,
+    ) -> Self {
+        self.on_pointer = Some(Box::new(handler));
+        self
+    }
+
+    /// Pans (by dragging) and zooms (with the mouse wheel) the [`PanZoom`] returned by `pan_zoom`,
+    /// its `view_box` should be the view box of this viewport.
+    pub fn pan_zoom(mut self, pan_zoom: impl Fn(&mut T) -> &mut PanZoom + 'static) -> Self {
+        self.pan_zoom = Some(Box::new(pan_zoom));
+        self
+    }
+}
+
+pub struct SvgViewportState<S> {
+    props: ElementProps,
+    children_states: S,
+    child_elements: Vec<Pod>,
+    scratch: Vec<Pod>,
+    element: web_sys::SvgsvgElement,
+    view_box: Rect,
+    aspect_ratio: AspectRatio,
+    handlers: Rc<Cell<Handlers>>,
+    #[allow(unused)]
+    listeners: [EventListener; 4],
+    /// Only attached with [`SvgViewport::pan_zoom`], as a non-passive wheel listener slows down scrolling
+    #[allow(unused)]
+    wheel_listener: Option<EventListener>,
+}
+
+impl<S> SvgViewportState<S> {
+    /// The current transform from `view_box` into client coordinates.
+    fn transform(&self, view_box: Rect) -> Affine {
+        let rect = self.element.get_bounding_client_rect();
+        let viewport = Rect::new(rect.left(), rect.top(), rect.right(), rect.bottom());
+        view_box_transform(viewport, view_box, self.aspect_ratio)
+    }
+}
+
+/// Which handlers are set, so that the events are only sent as messages when necessary.
+#[derive(Clone, Copy)]
+struct Handlers {
+    on_pointer: bool,
+    /// The pointer is captured
+    pan_zoom: bool,
+}
+
+fn install_listeners(
+    cx: &Cx,
+    element: &web_sys::SvgsvgElement,
+    handlers: &Rc<Cell<Handlers>>,
+) -> [EventListener; 4] {
+    let pointer_listener = |event_type: &'static str, to_msg: fn(PointerDetails) -> PointerMsg| {
+        let thunk = cx.message_thunk();
+        let capture_target = element.clone();
+        let handlers = Rc::clone(handlers);
+        EventListener::new_with_options(
+            element.as_ref(),
+            event_type,
+            EventListenerOptions::enable_prevent_default(),
+            move |event| {
+                let event: &web_sys::PointerEvent = event.unchecked_ref();
+                let Handlers {
+                    on_pointer,
+                    pan_zoom,
+                } = handlers.get();
+                if !on_pointer && !pan_zoom {
+                    return;
+                }
+                if pan_zoom {
+                    // Captured, so that dragging continues outside of the viewport
+                    if event_type == "pointerdown" && event.button() == 0 {
+                        let _ = capture_target.set_pointer_capture(event.pointer_id());
+                    }
+                    event.prevent_default();
+                }
+                let details = PointerDetails::from_pointer_event(event);
+                thunk.push_message(ViewportEvent::Pointer(to_msg(details)));
+            },
+        )
+    };
+    [
+        pointer_listener("pointerdown", PointerMsg::Down),
+        pointer_listener("pointermove", PointerMsg::Move),
+        pointer_listener("pointerup", PointerMsg::Up),
+        pointer_listener("pointercancel", PointerMsg::Cancel),
+    ]
+}
+
+/// Zooms with the wheel instead of scrolling the page.
+fn wheel_listener(cx: &Cx, element: &web_sys::SvgsvgElement) -> EventListener {
+    let thunk = cx.message_thunk();
+    EventListener::new_with_options(
+        element.as_ref(),
+        "wheel",
+        EventListenerOptions::enable_prevent_default(),
+        move |event| {
+            let event: &web_sys::WheelEvent = event.unchecked_ref();
+            event.prevent_default();
+            // Lines and pages are roughly converted into pixels
+            let delta_y = match event.delta_mode() {
+                web_sys::WheelEvent::DOM_DELTA_LINE => event.delta_y() * 16.0,
+                web_sys::WheelEvent::DOM_DELTA_PAGE => event.delta_y() * 400.0,
+                _ => event.delta_y(),
+            };
+            thunk.push_message(ViewportEvent::Wheel {
+                x: event.client_x() as f64,
+                y: event.client_y() as f64,
+                delta_y,
+            });
+        },
+    )
+}
+
+macro_rules! generate_dom_interface_impl {
+    ($dom_interface:ident, ()) => {
+        impl<T, A, Children> $crate::interfaces::$dom_interface<T, A>
+            for SvgViewport<T, A, Children>
+        where
+            Children: ViewSequence<T, A>,
+        {
+        }
+    };
+}
+
+generate_dom_interface_impl!(SvgsvgElement, ());
+crate::interfaces::for_all_svgsvg_element_ancestors!(generate_dom_interface_impl, ());
+
+impl<T, A, Children> ViewMarker for SvgViewport<T, A, Children> {}
+impl<T, A, Children> Sealed for SvgViewport<T, A, Children> {}
+
+impl<T, A, Children> SvgViewport<T, A, Children> {
+    fn handlers(&self) -> Handlers {
+        Handlers {
+            on_pointer: self.on_pointer.is_some(),
+            pan_zoom: self.pan_zoom.is_some(),
+        }
+    }
+
+    fn add_attrs(&self, cx: &mut Cx) {
+        let view_box = [
+            self.view_box.x0,
+            self.view_box.y0,
+            self.view_box.width(),
+            self.view_box.height(),
+        ];
+        cx.add_attr_to_element(&"viewBox".into(), &view_box.into_attr_value());
+        cx.add_attr_to_element(
+            &"preserveAspectRatio".into(),
+            &self.aspect_ratio.to_attr_string().into_attr_value(),
+        );
+    }
+}
+
+impl<T, A, Children> View<T, A> for SvgViewport<T, A, Children>
+where
+    Children: ViewSequence<T, A>,
+{
+    type State = SvgViewportState<Children::State>;
+    type Element = web_sys::SvgsvgElement;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        self.add_attrs(cx);
+        let (el, props) = cx.build_element(SVG_NS, "svg");
+        let element: web_sys::SvgsvgElement = el.unchecked_into();
+        let handlers = Rc::new(Cell::new(self.handlers()));
+        let mut child_elements = vec![];
+        let mut scratch = vec![];
+        let mut splice = ChildrenSplice::new(&mut child_elements, &mut scratch, &element);
+        let (id, (children_states, listeners, wheel_listener)) = cx.with_new_id(|cx| {
+            let listeners = install_listeners(cx, &element, &handlers);
+            let wheel_listener = self
+                .pan_zoom
+                .is_some()
+                .then(|| wheel_listener(cx, &element));
+            (
+                self.children.build(cx, &mut splice),
+                listeners,
+                wheel_listener,
+            )
+        });
+        debug_assert!(scratch.is_empty());
+        let state = SvgViewportState {
+            props,
+            children_states,
+            child_elements,
+            scratch,
+            element: element.clone(),
+            view_box: self.view_box,
+            aspect_ratio: self.aspect_ratio,
+            handlers,
+            listeners,
+            wheel_listener,
+        };
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        self.add_attrs(cx);
+        let mut changed = cx.rebuild_element(element, &mut state.props);
+        state.view_box = self.view_box;
+        state.aspect_ratio = self.aspect_ratio;
+        state.handlers.set(self.handlers());
+        if self.pan_zoom.is_some() != state.wheel_listener.is_some() {
+            state.wheel_listener = cx.with_id(*id, |cx| {
+                self.pan_zoom
+                    .is_some()
+                    .then(|| wheel_listener(cx, &state.element))
+            });
+            changed |= ChangeFlags::LISTENERS;
+        }
+        let mut splice =
+            ChildrenSplice::new(&mut state.child_elements, &mut state.scratch, element);
+        changed |= cx.with_id(*id, |cx| {
+            self.children
+                .rebuild(cx, &prev.children, &mut state.children_states, &mut splice)
+        });
+        debug_assert!(state.scratch.is_empty());
+        changed.remove(ChangeFlags::STRUCTURE);
+        changed
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Box<dyn Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        if !id_path.is_empty() {
+            return self
+                .children
+                .message(id_path, &mut state.children_states, message, app_state);
+        }
+        let event = match message.downcast::<ViewportEvent>() {
+            Ok(event) => event,
+            Err(message) => return MessageResult::Stale(message),
+        };
+        match *event {
+            ViewportEvent::Pointer(msg) => {
+                if let Some(pan_zoom) = &self.pan_zoom {
+                    // The view box of the app state may already be ahead of the rendered one (when there was no rebuild yet)
+                    let pan_zoom = pan_zoom(app_state);
+                    let transform = state.transform(pan_zoom.view_box);
+                    pan_zoom.handle_pointer(&msg, transform);
+                }
+                let transform = state.transform(state.view_box);
+                let details = match &msg {
+                    PointerMsg::Down(d)
+                    | PointerMsg::Move(d)
+                    | PointerMsg::Up(d)
+                    | PointerMsg::Cancel(d) => d,
+                };
+                let position = transform.inverse() * Point::new(details.x, details.y);
+                match &self.on_pointer {
+                    Some(on_pointer) => MessageResult::Action(on_pointer(
+                        app_state,
+                        ViewportPointerMsg { msg, position },
+                    )),
+                    None => MessageResult::Nop,
+                }
+            }
+            ViewportEvent::Wheel { x, y, delta_y } => {
+                if let Some(pan_zoom) = &self.pan_zoom {
+                    let pan_zoom = pan_zoom(app_state);
+                    let center = state.transform(pan_zoom.view_box).inverse() * Point::new(x, y);
+                    pan_zoom.zoom_at(center, (delta_y * 0.002).exp());
+                }
+                MessageResult::Nop
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn view_box_transform_meet_centers_the_view_box() {
+        let viewport = Rect::new(10.0, 20.0, 210.0, 120.0);
+        let view_box = Rect::new(0.0, 0.0, 10.0, 10.0);
+        let transform = view_box_transform(viewport, view_box, AspectRatio::default());
+        assert_eq!(transform * Point::new(0.0, 0.0), Point::new(60.0, 20.0));
+        assert_eq!(transform * Point::new(10.0, 10.0), Point::new(160.0, 120.0));
+        let inverse = transform.inverse() * Point::new(110.0, 70.0);
+        assert!((inverse - Point::new(5.0, 5.0)).hypot() < 1e-9);
+    }
+
+    #[test]
+    fn view_box_transform_slice_and_stretch() {
+        let viewport = Rect::new(0.0, 0.0, 200.0, 100.0);
+        let view_box = Rect::new(-5.0, -5.0, 5.0, 5.0);
+        let slice = AspectRatio::Slice {
+            x: Align::Min,
+            y: Align::Max,
+        };
+        let transform = view_box_transform(viewport, view_box, slice);
+        assert_eq!(transform * Point::new(-5.0, -5.0), Point::new(0.0, -100.0));
+        assert_eq!(transform * Point::new(5.0, 5.0), Point::new(200.0, 100.0));
+        let transform = view_box_transform(viewport, view_box, AspectRatio::Stretch);
+        assert_eq!(transform * Point::new(-5.0, -5.0), Point::new(0.0, 0.0));
+        assert_eq!(transform * Point::new(5.0, 5.0), Point::new(200.0, 100.0));
+    }
+
+    #[test]
+    fn zoom_at_keeps_the_center() {
+        let mut pan_zoom = PanZoom::new(Rect::new(0.0, 0.0, 100.0, 50.0)).width_range(10.0, 150.0);
+        pan_zoom.zoom_at(Point::new(50.0, 25.0), 0.5);
+        assert_eq!(pan_zoom.view_box, Rect::new(25.0, 12.5, 75.0, 37.5));
+        pan_zoom.zoom_at(Point::new(25.0, 12.5), 100.0);
+        assert_eq!(pan_zoom.view_box, Rect::new(25.0, 12.5, 175.0, 87.5));
+    }
+}
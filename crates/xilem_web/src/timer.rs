@@ -0,0 +1,93 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+use wasm_bindgen::{prelude::Closure, JsCast, UnwrapThrowExt};
+
+/// A `setTimeout` that is cleared when dropped, so it can be retained in view state.
+pub(crate) struct Timeout {
+    handle: i32,
+    #[allow(unused)]
+    closure: Closure<dyn FnMut()>,
+}
+
+impl Timeout {
+    pub(crate) fn new(ms: i32, f: impl FnMut() + 'static) -> Self {
+        let closure = Closure::<dyn FnMut()>::new(f);
+        let handle = web_sys::window()
+            .unwrap_throw()
+            .set_timeout_with_callback_and_timeout_and_arguments_0(
+                closure.as_ref().unchecked_ref(),
+                ms,
+            )
+            .unwrap_throw();
+        Timeout { handle, closure }
+    }
+}
+
+impl Drop for Timeout {
+    fn drop(&mut self) {
+        web_sys::window()
+            .unwrap_throw()
+            .clear_timeout_with_handle(self.handle);
+    }
+}
+
+/// Calls `f` with the timestamp of each animation frame (via `requestAnimationFrame`) until it's dropped,
+/// so it can be retained in view state.
+pub(crate) struct AnimationFrameLoop {
+    inner: Rc<AnimationFrameLoopInner>,
+}
+
+struct AnimationFrameLoopInner {
+    handle: Cell<i32>,
+    closure: RefCell<Option<Closure<dyn FnMut(f64)>>>,
+}
+
+impl AnimationFrameLoop {
+    pub(crate) fn new(mut f: impl FnMut(f64) + 'static) -> Self {
+        let inner = Rc::new(AnimationFrameLoopInner {
+            handle: Cell::new(0),
+            closure: RefCell::new(None),
+        });
+        let weak_inner = Rc::downgrade(&inner);
+        let closure = Closure::<dyn FnMut(f64)>::new(move |timestamp| {
+            f(timestamp);
+            // `f` may have dropped the loop (e.g. when a message caused a synchronous rebuild)
+            if let Some(inner) = weak_inner.upgrade() {
+                inner.request_frame();
+            }
+        });
+        *inner.closure.borrow_mut() = Some(closure);
+        inner.request_frame();
+        AnimationFrameLoop { inner }
+    }
+}
+
+impl AnimationFrameLoopInner {
+    fn request_frame(&self) {
+        if let Some(closure) = &*self.closure.borrow() {
+            let handle = web_sys::window()
+                .unwrap_throw()
+                .request_animation_frame(closure.as_ref().unchecked_ref())
+                .unwrap_throw();
+            self.handle.set(handle);
+        }
+    }
+}
+
+impl Drop for AnimationFrameLoop {
+    fn drop(&mut self) {
+        web_sys::window()
+            .unwrap_throw()
+            .cancel_animation_frame(self.inner.handle.get())
+            .unwrap_throw();
+        // The closure may currently be running, so it's dropped afterwards
+        let closure = self.inner.closure.borrow_mut().take();
+        wasm_bindgen_futures::spawn_local(async move { drop(closure) });
+    }
+}
@@ -0,0 +1,234 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{borrow::Cow, marker::PhantomData};
+
+use wasm_bindgen::{closure::Closure, JsCast, JsValue, UnwrapThrowExt};
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    interfaces::{sealed::Sealed, Element},
+    mutations::DomOp,
+    view::DomNode,
+    ChangeFlags, Cx, IntoAttributeValue, View, ViewMarker,
+};
+
+type CowStr = Cow<'static, str>;
+
+/// The class added when an element with this attribute is removed, the removal is deferred until the transition ended.
+const EXIT_CLASS_ATTR: &str = "data-xilem-exit";
+/// The maximum time (in ms) the removal is deferred, in case no `transitionend`/`animationend` event is fired.
+const EXIT_TIMEOUT_ATTR: &str = "data-xilem-exit-timeout";
+/// Marks an element, which was removed from the view tree, but is still in the DOM, until its exit transition ended.
+const EXITING_ATTR: &str = "data-xilem-exiting";
+
+const DEFAULT_TIMEOUT_MS: u32 = 1000;
+
+/// Whether there's a child of `parent` which has an exit transition (this includes the children which are exiting right now).
+pub(crate) fn has_exit_transition_child(parent: &web_sys::Node) -> bool {
+    parent
+        .dyn_ref::<web_sys::Element>()
+        .and_then(|parent| {
+            parent
+                .query_selector(&format!(":scope > [{EXIT_CLASS_ATTR}]"))
+                .unwrap_throw()
+        })
+        .is_some()
+}
+
+/// Calls `f` once, when `el` itself (not a descendant) fires `transitionend` or `animationend`, or at the latest after `timeout_ms`.
+fn on_transition_end(el: &web_sys::Element, timeout_ms: u32, f: impl FnOnce() + 'static) {
+    let mut f = Some(f);
+    let target = el.clone();
+    let callback = Closure::<dyn FnMut(JsValue)>::new(move |event: JsValue| {
+        if let Some(event) = event.dyn_ref::<web_sys::Event>() {
+            let is_target = event
+                .target()
+                .and_then(|t| t.dyn_into::<web_sys::Node>().ok())
+                .is_some_and(|t| t.is_same_node(Some(&*target)));
+            if !is_target {
+                return;
+            }
+        }
+        if let Some(f) = f.take() {
+            f();
+        }
+    })
+    // Freed by the garbage collector, when the element is gone
+    .into_js_value();
+    let callback: &js_sys::Function = callback.unchecked_ref();
+    el.add_event_listener_with_callback("transitionend", callback)
+        .unwrap_throw();
+    el.add_event_listener_with_callback("animationend", callback)
+        .unwrap_throw();
+    web_sys::window()
+        .unwrap_throw()
+        .set_timeout_with_callback_and_timeout_and_arguments_0(callback, timeout_ms as i32)
+        .unwrap_throw();
+}
+
+/// Removes `node` from its parent, or starts its exit transition, when it has one, see [`DomOp::Remove`](crate::mutations::DomOp::Remove).
+pub(crate) fn remove_node(node: &web_sys::Node) {
+    let Some(el) = node
+        .dyn_ref::<web_sys::Element>()
+        .filter(|el| el.has_attribute(EXIT_CLASS_ATTR) && !el.has_attribute(EXITING_ATTR))
+    else {
+        if let Some(parent) = node.parent_node() {
+            parent.remove_child(node).unwrap_throw();
+        }
+        return;
+    };
+    let exit_class = el.get_attribute(EXIT_CLASS_ATTR).unwrap_throw();
+    let timeout_ms = el
+        .get_attribute(EXIT_TIMEOUT_ATTR)
+        .and_then(|ms| ms.parse().ok())
+        .unwrap_or(DEFAULT_TIMEOUT_MS);
+    el.set_attribute(EXITING_ATTR, "").unwrap_throw();
+    el.class_list().add_1(&exit_class).unwrap_throw();
+    let exiting = el.clone();
+    on_transition_end(el, timeout_ms, move || exiting.remove());
+}
+
+/// Applies enter and exit transitions to the element, see [`Element::transition`].
+pub struct Transition<E, T, A> {
+    element: E,
+    enter: CowStr,
+    enter_active: CowStr,
+    exit: CowStr,
+    timeout_ms: u32,
+    phantom: PhantomData<fn() -> (T, A)>,
+}
+
+impl<E, T, A> Transition<E, T, A> {
+    pub fn new(element: E) -> Self {
+        Transition {
+            element,
+            enter: "enter".into(),
+            enter_active: "enter-active".into(),
+            exit: "exit".into(),
+            timeout_ms: DEFAULT_TIMEOUT_MS,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The class which is added when the element is inserted, and removed in the next frame, i.e. the start of the enter transition. (default = `"enter"`)
+    pub fn enter_class(mut self, class: impl Into<CowStr>) -> Self {
+        self.enter = class.into();
+        self
+    }
+
+    /// The class which is present during the whole enter transition, e.g. to define the `transition` property. (default = `"enter-active"`)
+    pub fn enter_active_class(mut self, class: impl Into<CowStr>) -> Self {
+        self.enter_active = class.into();
+        self
+    }
+
+    /// The class which is added when the element is removed, the element is removed from the DOM
+    /// when its transition or animation ended. (default = `"exit"`)
+    pub fn exit_class(mut self, class: impl Into<CowStr>) -> Self {
+        self.exit = class.into();
+        self
+    }
+
+    /// The maximum time, the removal of the element is deferred,
+    /// in case no `transitionend` or `animationend` event is fired (e.g. when there's no transition). (default = `1000`)
+    pub fn timeout(mut self, ms: u32) -> Self {
+        self.timeout_ms = ms;
+        self
+    }
+
+    fn add_exit_attributes(&self, cx: &mut Cx) {
+        cx.add_attr_to_element(
+            &EXIT_CLASS_ATTR.into(),
+            &self.exit.clone().into_attr_value(),
+        );
+        cx.add_attr_to_element(
+            &EXIT_TIMEOUT_ATTR.into(),
+            &self.timeout_ms.into_attr_value(),
+        );
+    }
+}
+
+/// Starts the enter transition after the element was rendered once with the `enter` class.
+fn start_enter(
+    cx: &mut Cx,
+    el: &web_sys::Element,
+    enter: &CowStr,
+    enter_active: &CowStr,
+    timeout_ms: u32,
+) {
+    // Added after the attributes of the element (which include the `class` attribute) were applied
+    let id = cx.node_id(el);
+    for class in [enter, enter_active] {
+        cx.push_dom_op(DomOp::ToggleClass {
+            id,
+            class: class.clone(),
+            present: true,
+        });
+    }
+    let (target, enter, enter_active) = (el.clone(), enter.clone(), enter_active.clone());
+    let window = web_sys::window().unwrap_throw();
+    // The styles of the first frame have to be applied, before the `enter` class is removed, hence two frames
+    let second_frame = Closure::once_into_js(move || {
+        let class_list = target.class_list();
+        class_list.remove_1(&enter).unwrap_throw();
+        let el = target.clone();
+        on_transition_end(&target, timeout_ms, move || {
+            el.class_list().remove_1(&enter_active).unwrap_throw();
+        });
+    });
+    let first_frame = Closure::once_into_js(move || {
+        web_sys::window()
+            .unwrap_throw()
+            .request_animation_frame(second_frame.unchecked_ref())
+            .unwrap_throw();
+    });
+    window
+        .request_animation_frame(first_frame.unchecked_ref())
+        .unwrap_throw();
+}
+
+impl<E, T, A> ViewMarker for Transition<E, T, A> {}
+impl<E, T, A> Sealed for Transition<E, T, A> {}
+
+impl<E: Element<T, A>, T, A> View<T, A> for Transition<E, T, A> {
+    type State = E::State;
+    type Element = E::Element;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        self.add_exit_attributes(cx);
+        let (id, state, element) = self.element.build(cx);
+        start_enter(
+            cx,
+            element.as_node_ref().unchecked_ref(),
+            &self.enter,
+            &self.enter_active,
+            self.timeout_ms,
+        );
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        self.add_exit_attributes(cx);
+        self.element.rebuild(cx, &prev.element, id, state, element)
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Box<dyn std::any::Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        self.element.message(id_path, state, message, app_state)
+    }
+}
+
+crate::interfaces::impl_dom_interfaces_for_ty!(Element, Transition);
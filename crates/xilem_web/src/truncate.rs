@@ -0,0 +1,148 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{borrow::Cow, cell::Cell, marker::PhantomData, rc::Rc};
+
+use gloo::events::EventListener;
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    interfaces::{sealed::Sealed, HtmlElement},
+    mutations::DomOp,
+    timer::Timeout,
+    view::DomNode,
+    ChangeFlags, Cx, View, ViewMarker,
+};
+
+type CowStr = Cow<'static, str>;
+
+/// Clamps the text of the element to a number of lines, see [`HtmlElement::truncate`].
+pub struct Truncate<E, T, A> {
+    element: E,
+    lines: u32,
+    phantom: PhantomData<fn() -> (T, A)>,
+}
+
+impl<E, T, A> Truncate<E, T, A> {
+    pub fn new(element: E, lines: u32) -> Self {
+        Truncate {
+            element,
+            lines,
+            phantom: PhantomData,
+        }
+    }
+}
+
+pub struct TruncateState<S> {
+    child_state: S,
+    /// Whether the `title` attribute was set by this view (and not by the user).
+    owns_title: Rc<Cell<bool>>,
+    #[allow(unused)]
+    measure: Timeout,
+    #[allow(unused)]
+    resize_listener: EventListener,
+}
+
+fn apply_line_clamp(cx: &mut Cx, el: &web_sys::HtmlElement, lines: u32) {
+    let id = cx.node_id(el);
+    for (name, value) in [
+        ("display", CowStr::from("-webkit-box")),
+        ("-webkit-box-orient", "vertical".into()),
+        ("-webkit-line-clamp", lines.to_string().into()),
+        ("overflow", "hidden".into()),
+    ] {
+        cx.push_dom_op(DomOp::SetStyle {
+            id,
+            name: name.into(),
+            value: Some(value),
+        });
+    }
+}
+
+/// Shows the full text as `title` when it's clamped, unless the title was set otherwise.
+fn update_title(el: &web_sys::HtmlElement, owns_title: &Cell<bool>) {
+    let overflows =
+        el.scroll_height() > el.client_height() || el.scroll_width() > el.client_width();
+    if overflows {
+        if owns_title.get() || !el.has_attribute("title") {
+            let text = el.text_content().unwrap_or_default();
+            el.set_attribute("title", text.trim()).unwrap_throw();
+            owns_title.set(true);
+        }
+    } else if owns_title.replace(false) {
+        el.remove_attribute("title").unwrap_throw();
+    }
+}
+
+/// The element has to be in the document to be measured, so this is done after the current (re)build.
+fn schedule_measure(el: &web_sys::HtmlElement, owns_title: &Rc<Cell<bool>>) -> Timeout {
+    let (el, owns_title) = (el.clone(), Rc::clone(owns_title));
+    Timeout::new(0, move || update_title(&el, &owns_title))
+}
+
+fn create_resize_listener(el: &web_sys::HtmlElement, owns_title: &Rc<Cell<bool>>) -> EventListener {
+    let (el, owns_title) = (el.clone(), Rc::clone(owns_title));
+    EventListener::new(&web_sys::window().unwrap_throw(), "resize", move |_| {
+        update_title(&el, &owns_title);
+    })
+}
+
+impl<E, T, A> ViewMarker for Truncate<E, T, A> {}
+impl<E, T, A> Sealed for Truncate<E, T, A> {}
+
+impl<E: HtmlElement<T, A>, T, A> View<T, A> for Truncate<E, T, A> {
+    type State = TruncateState<E::State>;
+    type Element = E::Element;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, child_state, element) = self.element.build(cx);
+        let el: &web_sys::HtmlElement = element.as_node_ref().unchecked_ref();
+        apply_line_clamp(cx, el, self.lines);
+        let owns_title = Rc::new(Cell::new(false));
+        let state = TruncateState {
+            child_state,
+            measure: schedule_measure(el, &owns_title),
+            resize_listener: create_resize_listener(el, &owns_title),
+            owns_title,
+        };
+        (id, state, element)
+    }
+
+    fn rebuild(
+        &self,
+        cx: &mut Cx,
+        prev: &Self,
+        id: &mut Id,
+        state: &mut Self::State,
+        element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let changed = self
+            .element
+            .rebuild(cx, &prev.element, id, &mut state.child_state, element);
+        let el: &web_sys::HtmlElement = element.as_node_ref().unchecked_ref();
+        if changed.contains(ChangeFlags::STRUCTURE) {
+            state.owns_title.set(false);
+            state.resize_listener = create_resize_listener(el, &state.owns_title);
+        }
+        if !changed.is_empty() || prev.lines != self.lines {
+            // A changed `style` attribute of the child would override the clamp otherwise
+            apply_line_clamp(cx, el, self.lines);
+            state.measure = schedule_measure(el, &state.owns_title);
+        }
+        changed
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        state: &mut Self::State,
+        message: Box<dyn std::any::Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        self.element
+            .message(id_path, &mut state.child_state, message, app_state)
+    }
+}
+
+crate::interfaces::impl_dom_interfaces_for_ty!(HtmlElement, Truncate);
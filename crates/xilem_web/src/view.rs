@@ -4,11 +4,11 @@
 //! Integration with xilem_core. This instantiates the View and related
 //! traits for DOM node generation.
 
-use std::{any::Any, borrow::Cow, ops::Deref};
+use std::{any::Any, borrow::Cow, ops::Deref, rc::Rc};
 
 use xilem_core::{Id, MessageResult};
 
-use crate::{context::Cx, ChangeFlags};
+use crate::{context::Cx, ChangeFlags, OptionalAction};
 
 pub(crate) mod sealed {
     pub trait Sealed {}
@@ -88,8 +88,21 @@ xilem_core::generate_view_trait! {View, DomNode, Cx, ChangeFlags;}
 xilem_core::generate_viewsequence_trait! {ViewSequence, View, ViewMarker, ElementsSplice, DomNode, Cx, ChangeFlags, Pod;}
 xilem_core::generate_anyview_trait! {AnyView, View, ViewMarker, Cx, ChangeFlags, AnyNode, BoxedView;}
 xilem_core::generate_memoize_view! {Memoize, MemoizeState, View, ViewMarker, Cx, ChangeFlags, static_view, memoize;}
+xilem_core::generate_hash_memoize_view! {HashMemoize, HashMemoizeState, View, ViewMarker, Cx, ChangeFlags, hash_memoize;}
+xilem_core::generate_rc_view! {RcViewState, View, ViewMarker, Cx, ChangeFlags, Rc;}
 xilem_core::generate_adapt_view! {View, Cx, ChangeFlags;}
 xilem_core::generate_adapt_state_view! {View, Cx, ChangeFlags;}
+xilem_core::generate_map_action_view! {View, Cx, ChangeFlags;}
+xilem_core::generate_subscription_view! {Subscription, subscription, View, ViewMarker, Cx, ChangeFlags, web_sys::Comment, subscription_placeholder, subscription_sender, OptionalAction}
+
+fn subscription_placeholder(cx: &mut Cx) -> web_sys::Comment {
+    cx.create_comment("subscription")
+}
+
+fn subscription_sender(cx: &Cx) -> Box<dyn Fn(Box<dyn Any>)> {
+    let thunk = cx.message_thunk();
+    Box::new(move |message| thunk.push_boxed_message(message))
+}
 
 // strings -> text nodes
 
@@ -100,21 +113,21 @@ macro_rules! impl_string_view {
             type State = ();
             type Element = web_sys::Text;
 
-            fn build(&self, _cx: &mut Cx) -> (Id, Self::State, Self::Element) {
-                (Id::next(), (), new_text(self))
+            fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+                (Id::next(), (), cx.create_text(self))
             }
 
             fn rebuild(
                 &self,
-                _cx: &mut Cx,
+                cx: &mut Cx,
                 prev: &Self,
                 _id: &mut Id,
                 _state: &mut Self::State,
                 element: &mut Self::Element,
             ) -> ChangeFlags {
-                if prev != self {
-                    element.set_data(self);
-                    ChangeFlags::OTHER_CHANGE
+                if text_changed(prev, self) {
+                    cx.set_text(element, Some(String::from(&**self).into()));
+                    ChangeFlags::TEXT
                 } else {
                     ChangeFlags::empty()
                 }
@@ -146,21 +159,21 @@ macro_rules! impl_to_string_view {
             type State = ();
             type Element = web_sys::Text;
 
-            fn build(&self, _cx: &mut Cx) -> (Id, Self::State, Self::Element) {
-                (Id::next(), (), new_text(&self.to_string()))
+            fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+                (Id::next(), (), cx.create_text(&self.to_string()))
             }
 
             fn rebuild(
                 &self,
-                _cx: &mut Cx,
+                cx: &mut Cx,
                 prev: &Self,
                 _id: &mut Id,
                 _state: &mut Self::State,
                 element: &mut Self::Element,
             ) -> ChangeFlags {
                 if prev != self {
-                    element.set_data(&self.to_string());
-                    ChangeFlags::OTHER_CHANGE
+                    cx.set_text(element, Some(self.to_string().into()));
+                    ChangeFlags::TEXT
                 } else {
                     ChangeFlags::empty()
                 }
@@ -194,6 +207,8 @@ impl_to_string_view!(u128);
 impl_to_string_view!(isize);
 impl_to_string_view!(usize);
 
-fn new_text(text: &str) -> web_sys::Text {
-    web_sys::Text::new_with_data(text).unwrap()
+/// Whether the text of a string view changed, constant strings (e.g. `&'static str` literals)
+/// are the same pointer in each rebuild, so they don't need to be compared.
+fn text_changed(prev: &str, new: &str) -> bool {
+    !std::ptr::eq(prev, new) && prev != new
 }
@@ -0,0 +1,358 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A WebSocket connection, which is owned by a view, see [`web_socket`].
+
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    marker::PhantomData,
+    rc::{Rc, Weak},
+};
+
+use wasm_bindgen::{prelude::Closure, JsCast, JsValue};
+use xilem_core::{Id, MessageResult};
+
+use crate::{
+    context::{ChangeFlags, Cx, MessageThunk},
+    timer::Timeout,
+    view::{View, ViewMarker},
+    OptionalAction,
+};
+
+type CowStr = Cow<'static, str>;
+
+/// A message received via a WebSocket.
+#[derive(Clone, Debug, PartialEq)]
+pub enum WebSocketMessage {
+    Text(String),
+    Binary(Vec<u8>),
+}
+
+/// Sends messages via the WebSocket, it's passed to the `on_open` handler of [`web_socket`] and can be retained in the app state.
+///
+/// After a reconnect, a new sender is passed to `on_open`.
+#[derive(Clone, Debug)]
+pub struct WebSocketSender(web_sys::WebSocket);
+
+impl WebSocketSender {
+    /// Whether the connection of this sender is (still) open.
+    pub fn is_open(&self) -> bool {
+        self.0.ready_state() == web_sys::WebSocket::OPEN
+    }
+
+    /// Sends a text message, returns `false` when the connection isn't open.
+    pub fn send_text(&self, text: &str) -> bool {
+        self.is_open() && self.0.send_with_str(text).is_ok()
+    }
+
+    /// Sends a binary message, returns `false` when the connection isn't open.
+    pub fn send_binary(&self, bytes: &[u8]) -> bool {
+        self.is_open() && self.0.send_with_u8_array(bytes).is_ok()
+    }
+}
+
+/// How long to wait before reconnecting, after the connection was closed, see [`WebSocket::reconnect`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Backoff {
+    initial_ms: u32,
+    max_ms: u32,
+    factor: u32,
+}
+
+impl Backoff {
+    /// Always waits `ms` before reconnecting.
+    pub fn constant(ms: u32) -> Self {
+        Backoff {
+            initial_ms: ms,
+            max_ms: ms,
+            factor: 1,
+        }
+    }
+
+    /// Waits `initial_ms` before the first attempt, and doubles the delay with each failed attempt, up to `max_ms`.
+    pub fn exponential(initial_ms: u32, max_ms: u32) -> Self {
+        Backoff {
+            initial_ms,
+            max_ms,
+            factor: 2,
+        }
+    }
+
+    fn delay_ms(&self, attempt: u32) -> u32 {
+        let factor = self.factor.saturating_pow(attempt);
+        self.initial_ms.saturating_mul(factor).min(self.max_ms)
+    }
+}
+
+/// The messages sent from the socket callbacks to the view.
+enum SocketEvent {
+    Open(web_sys::WebSocket),
+    Message(WebSocketMessage),
+    Error,
+}
+
+/// A `web_sys::WebSocket` with its callbacks, which is closed when dropped.
+struct Socket {
+    socket: web_sys::WebSocket,
+    // Closures are retained so they can be called by environment
+    #[allow(unused)]
+    callbacks: [Closure<dyn FnMut(JsValue)>; 4],
+}
+
+impl Socket {
+    fn new(
+        url: &str,
+        thunk: &Rc<MessageThunk>,
+        connection: Weak<RefCell<Connection>>,
+    ) -> Option<Self> {
+        let socket = match web_sys::WebSocket::new(url) {
+            Ok(socket) => socket,
+            Err(err) => {
+                web_sys::console::error_2(&"Could not open the WebSocket:".into(), &err);
+                return None;
+            }
+        };
+        socket.set_binary_type(web_sys::BinaryType::Arraybuffer);
+
+        let on_open = {
+            let (thunk, connection, socket) =
+                (Rc::clone(thunk), connection.clone(), socket.clone());
+            Closure::<dyn FnMut(JsValue)>::new(move |_| {
+                if let Some(connection) = connection.upgrade() {
+                    connection.borrow_mut().attempt = 0;
+                }
+                thunk.push_message(SocketEvent::Open(socket.clone()));
+            })
+        };
+        let on_message = {
+            let thunk = Rc::clone(thunk);
+            Closure::<dyn FnMut(JsValue)>::new(move |event: JsValue| {
+                let data = event.unchecked_into::<web_sys::MessageEvent>().data();
+                let message = match data.as_string() {
+                    Some(text) => WebSocketMessage::Text(text),
+                    None => WebSocketMessage::Binary(js_sys::Uint8Array::new(&data).to_vec()),
+                };
+                thunk.push_message(SocketEvent::Message(message));
+            })
+        };
+        let on_error = {
+            let thunk = Rc::clone(thunk);
+            Closure::<dyn FnMut(JsValue)>::new(move |_| thunk.push_message(SocketEvent::Error))
+        };
+        let on_close = Closure::<dyn FnMut(JsValue)>::new(move |_| {
+            if let Some(connection) = connection.upgrade() {
+                Connection::reconnect_after_backoff(&connection);
+            }
+        });
+
+        socket.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+        socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        socket.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+        socket.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+        Some(Socket {
+            socket,
+            callbacks: [on_open, on_message, on_error, on_close],
+        })
+    }
+}
+
+impl Drop for Socket {
+    fn drop(&mut self) {
+        // The callbacks are freed, so they must not be called anymore
+        self.socket.set_onopen(None);
+        self.socket.set_onmessage(None);
+        self.socket.set_onerror(None);
+        self.socket.set_onclose(None);
+        let _ = self.socket.close();
+    }
+}
+
+struct Connection {
+    url: CowStr,
+    backoff: Option<Backoff>,
+    /// The number of failed reconnection attempts, reset when the connection is open.
+    attempt: u32,
+    thunk: Rc<MessageThunk>,
+    socket: Option<Socket>,
+    reconnect_timeout: Option<Timeout>,
+}
+
+impl Connection {
+    fn connect(this: &Rc<RefCell<Self>>) {
+        let connection = &mut *this.borrow_mut();
+        // The previous socket (if any) is closed
+        connection.socket = Socket::new(&connection.url, &connection.thunk, Rc::downgrade(this));
+    }
+
+    fn reconnect(this: &Rc<RefCell<Self>>, delay_ms: u32) {
+        let weak = Rc::downgrade(this);
+        let timeout = Timeout::new(delay_ms as i32, move || {
+            if let Some(this) = weak.upgrade() {
+                Connection::connect(&this);
+            }
+        });
+        this.borrow_mut().reconnect_timeout = Some(timeout);
+    }
+
+    fn reconnect_after_backoff(this: &Rc<RefCell<Self>>) {
+        let delay_ms = {
+            let mut connection = this.borrow_mut();
+            let attempt = connection.attempt;
+            connection.attempt += 1;
+            connection.backoff.map(|backoff| backoff.delay_ms(attempt))
+        };
+        if let Some(delay_ms) = delay_ms {
+            Connection::reconnect(this, delay_ms);
+        }
+    }
+}
+
+/// A WebSocket connection, which is open as long as this view is in the view tree, see [`web_socket`].
+pub struct WebSocket<T, A, FM, FO, FE> {
+    url: CowStr,
+    on_message: FM,
+    on_open: FO,
+    on_error: FE,
+    backoff: Option<Backoff>,
+    phantom: PhantomData<fn() -> (T, A)>,
+}
+
+/// Opens a WebSocket connection to `url`, which is owned by this view, and closed when the view is removed from the view tree.
+///
+/// `on_message` is called with each received message, `on_open` with a [`WebSocketSender`] when the connection is open,
+/// and `on_error` when an error occurred (e.g. the connection was closed abnormally).
+/// When `url` changes, the connection is replaced with a new one.
+/// It doesn't reconnect after the connection was closed, unless [`WebSocket::reconnect`] is used.
+///
+/// The view itself is invisible, so it can be placed anywhere in the view tree, e.g. in a tuple with the rest of the UI:
+///
+/// ```ignore
+/// (
+///     web_socket(
+///         "wss://example.com/chat",
+///         |state: &mut AppState, msg| {
+///             if let WebSocketMessage::Text(text) = msg {
+///                 state.messages.push(text);
+///             }
+///         },
+///         |state: &mut AppState, sender| state.sender = Some(sender),
+///         |state: &mut AppState| state.sender = None,
+///     )
+///     .reconnect(Backoff::exponential(500, 30_000)),
+///     chat_view(state),
+/// )
+/// ```
+pub fn web_socket<T, A, FM, FO, FE, OM, OO, OE>(
+    url: impl Into<CowStr>,
+    on_message: FM,
+    on_open: FO,
+    on_error: FE,
+) -> WebSocket<T, A, FM, FO, FE>
+where
+    FM: Fn(&mut T, WebSocketMessage) -> OM,
+    FO: Fn(&mut T, WebSocketSender) -> OO,
+    FE: Fn(&mut T) -> OE,
+    OM: OptionalAction<A>,
+    OO: OptionalAction<A>,
+    OE: OptionalAction<A>,
+{
+    WebSocket {
+        url: url.into(),
+        on_message,
+        on_open,
+        on_error,
+        backoff: None,
+        phantom: PhantomData,
+    }
+}
+
+impl<T, A, FM, FO, FE> WebSocket<T, A, FM, FO, FE> {
+    /// Reconnects after the connection was closed, with a delay defined by `backoff`.
+    pub fn reconnect(mut self, backoff: Backoff) -> Self {
+        self.backoff = Some(backoff);
+        self
+    }
+}
+
+pub struct WebSocketState {
+    connection: Rc<RefCell<Connection>>,
+}
+
+impl<T, A, FM, FO, FE> ViewMarker for WebSocket<T, A, FM, FO, FE> {}
+
+impl<T, A, FM, FO, FE, OM, OO, OE> View<T, A> for WebSocket<T, A, FM, FO, FE>
+where
+    FM: Fn(&mut T, WebSocketMessage) -> OM,
+    FO: Fn(&mut T, WebSocketSender) -> OO,
+    FE: Fn(&mut T) -> OE,
+    OM: OptionalAction<A>,
+    OO: OptionalAction<A>,
+    OE: OptionalAction<A>,
+{
+    type State = WebSocketState;
+    type Element = web_sys::Comment;
+
+    fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+        let (id, connection) = cx.with_new_id(|cx| {
+            let connection = Rc::new(RefCell::new(Connection {
+                url: self.url.clone(),
+                backoff: self.backoff,
+                attempt: 0,
+                thunk: Rc::new(cx.message_thunk()),
+                socket: None,
+                reconnect_timeout: None,
+            }));
+            Connection::connect(&connection);
+            connection
+        });
+        let element = cx.create_comment("web_socket");
+        (id, WebSocketState { connection }, element)
+    }
+
+    fn rebuild(
+        &self,
+        _cx: &mut Cx,
+        prev: &Self,
+        _id: &mut Id,
+        state: &mut Self::State,
+        _element: &mut Self::Element,
+    ) -> ChangeFlags {
+        let mut connection = state.connection.borrow_mut();
+        connection.backoff = self.backoff;
+        if prev.url != self.url {
+            connection.url = self.url.clone();
+            connection.attempt = 0;
+            drop(connection);
+            // The rebuild may be caused by a message of the current socket, which therefore can't be closed synchronously
+            Connection::reconnect(&state.connection, 0);
+        }
+        ChangeFlags::empty()
+    }
+
+    fn message(
+        &self,
+        id_path: &[Id],
+        _state: &mut Self::State,
+        message: Box<dyn std::any::Any>,
+        app_state: &mut T,
+    ) -> MessageResult<A> {
+        if !id_path.is_empty() {
+            return MessageResult::Stale(message);
+        }
+        let action = match message.downcast::<SocketEvent>() {
+            Ok(event) => match *event {
+                SocketEvent::Open(socket) => {
+                    (self.on_open)(app_state, WebSocketSender(socket)).action()
+                }
+                SocketEvent::Message(message) => (self.on_message)(app_state, message).action(),
+                SocketEvent::Error => (self.on_error)(app_state).action(),
+            },
+            Err(message) => return MessageResult::Stale(message),
+        };
+        match action {
+            Some(action) => MessageResult::Action(action),
+            None => MessageResult::Nop,
+        }
+    }
+}
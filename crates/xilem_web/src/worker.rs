@@ -0,0 +1,314 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Experimental: running the app logic in a Web Worker, and applying the resulting DOM mutations on the main thread.
+//!
+//! The worker describes the DOM with a [`WorkerDom`], which batches [`Mutation`]s (i.e. [`DomOp`]s) and posts them (serialized as JSON)
+//! to the main thread, where they're applied by a [`MutationApplier`] (see [`spawn_worker`]).
+//! Events of nodes which are listened to are sent back to the worker as [`DomEvent`]s.
+//!
+//! The views of this crate create DOM nodes directly, so they can't be used within the worker (yet).
+
+use std::{borrow::Cow, cell::RefCell, collections::HashMap, rc::Rc};
+
+use gloo::events::EventListener;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{prelude::Closure, JsCast, UnwrapThrowExt};
+
+use crate::mutations::{apply_to_dom, DomOp};
+
+pub use crate::mutations::NodeId;
+
+/// A DOM mutation, sent from the worker to the main thread.
+///
+/// These are the same ops the views of an [`App`](crate::App) emit, see [`mutations`](crate::mutations).
+pub type Mutation = DomOp;
+
+/// An event of a node, sent from the main thread to the worker.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DomEvent {
+    pub target: NodeId,
+    /// The type of the event, e.g. `click`.
+    pub event: String,
+    /// The value of the target, when it's an `<input>`, `<textarea>` or `<select>`.
+    pub value: Option<String>,
+    /// The key of keyboard events.
+    pub key: Option<String>,
+}
+
+impl DomEvent {
+    fn new(target: NodeId, event: &web_sys::Event) -> Self {
+        let value = event.target().and_then(|target| {
+            if let Some(input) = target.dyn_ref::<web_sys::HtmlInputElement>() {
+                Some(input.value())
+            } else if let Some(textarea) = target.dyn_ref::<web_sys::HtmlTextAreaElement>() {
+                Some(textarea.value())
+            } else {
+                target
+                    .dyn_ref::<web_sys::HtmlSelectElement>()
+                    .map(|select| select.value())
+            }
+        });
+        DomEvent {
+            target,
+            event: event.type_(),
+            value,
+            key: event
+                .dyn_ref::<web_sys::KeyboardEvent>()
+                .map(|event| event.key()),
+        }
+    }
+}
+
+/// Applies [`Mutation`]s to the DOM on the main thread.
+pub struct MutationApplier {
+    document: web_sys::Document,
+    nodes: HashMap<NodeId, web_sys::Node>,
+    listeners: HashMap<(NodeId, String), EventListener>,
+    on_event: Rc<dyn Fn(DomEvent)>,
+}
+
+impl MutationApplier {
+    /// Creates an applier for mutations of the children of `root`, `on_event` is called with the events which are listened to.
+    pub fn new(root: &web_sys::Element, on_event: impl Fn(DomEvent) + 'static) -> Self {
+        let mut nodes = HashMap::new();
+        nodes.insert(NodeId::ROOT, root.clone().into());
+        MutationApplier {
+            document: crate::document(),
+            nodes,
+            listeners: HashMap::new(),
+            on_event: Rc::new(on_event),
+        }
+    }
+
+    /// The node with `id`, unknown ids (e.g. of already removed nodes) are logged, and the mutation is ignored.
+    fn node(&self, id: NodeId) -> Option<&web_sys::Node> {
+        let node = self.nodes.get(&id);
+        if node.is_none() {
+            web_sys::console::warn_1(
+                &format!("ignoring a mutation of the unknown node {}", id.0).into(),
+            );
+        }
+        node
+    }
+
+    /// Removes the node with `id` and all its descendants, which aren't valid anymore, with their listeners.
+    fn remove(&mut self, id: NodeId) {
+        if id == NodeId::ROOT {
+            web_sys::console::warn_1(&"ignoring the removal of the root node".into());
+            return;
+        }
+        let Some(node) = self.node(id).cloned() else {
+            return;
+        };
+        if let Some(parent) = node.parent_node() {
+            parent.remove_child(&node).unwrap_throw();
+        }
+        // `contains` is inclusive, so this also removes the node itself
+        self.nodes
+            .retain(|_, descendant| !node.contains(Some(descendant)));
+        let nodes = &self.nodes;
+        self.listeners.retain(|(id, _), _| nodes.contains_key(id));
+    }
+
+    /// Forgets the descendants of `element` (but not the element itself), with their listeners.
+    fn forget_descendants(&mut self, element: &web_sys::Element) {
+        let node: &web_sys::Node = element;
+        self.nodes
+            .retain(|_, descendant| *descendant == *node || !node.contains(Some(descendant)));
+        let nodes = &self.nodes;
+        self.listeners.retain(|(id, _), _| nodes.contains_key(id));
+    }
+
+    pub fn apply(&mut self, mutations: &[Mutation]) {
+        for mutation in mutations {
+            match mutation {
+                DomOp::CreateElement { id, ns, tag } => {
+                    let element = self
+                        .document
+                        .create_element_ns(Some(&**ns), tag)
+                        .unwrap_throw();
+                    self.nodes.insert(*id, element.into());
+                }
+                DomOp::CreateText { id, text } => {
+                    let text = self.document.create_text_node(text);
+                    self.nodes.insert(*id, text.into());
+                }
+                DomOp::CreateComment { id, text } => {
+                    let comment = self.document.create_comment(text);
+                    self.nodes.insert(*id, comment.into());
+                }
+                DomOp::Remove { id } => self.remove(*id),
+                DomOp::SetText { id, .. } => {
+                    // The children of an element are replaced, so their ids aren't valid anymore
+                    let element = self
+                        .nodes
+                        .get(id)
+                        .and_then(|node| node.dyn_ref::<web_sys::Element>());
+                    if let Some(element) = element.cloned() {
+                        self.forget_descendants(&element);
+                    }
+                    apply_to_dom(mutation, |id| self.node(id));
+                }
+                DomOp::Listen { id, event } => {
+                    let Some(node) = self.node(*id) else {
+                        continue;
+                    };
+                    let on_event = Rc::clone(&self.on_event);
+                    let target = *id;
+                    let listener = EventListener::new(node, event.to_string(), move |event| {
+                        on_event(DomEvent::new(target, event));
+                    });
+                    self.listeners.insert((*id, event.to_string()), listener);
+                }
+                DomOp::Unlisten { id, event } => {
+                    self.listeners.remove(&(*id, event.to_string()));
+                }
+                mutation => apply_to_dom(mutation, |id| self.node(id)),
+            }
+        }
+    }
+}
+
+impl crate::mutations::ApplyDomOps for MutationApplier {
+    fn apply(&mut self, ops: &[DomOp]) {
+        MutationApplier::apply(self, ops);
+    }
+}
+
+/// The main thread side of a worker, which is spawned by [`spawn_worker`].
+///
+/// The worker is terminated when this is dropped.
+pub struct WorkerHandle {
+    worker: web_sys::Worker,
+    #[allow(unused)]
+    on_message: Closure<dyn FnMut(web_sys::MessageEvent)>,
+}
+
+impl Drop for WorkerHandle {
+    fn drop(&mut self) {
+        self.worker.terminate();
+    }
+}
+
+/// Spawns the worker script at `url`, and applies the mutations it posts (via a [`WorkerDom`]) to the children of `root`.
+pub fn spawn_worker(url: &str, root: &web_sys::Element) -> WorkerHandle {
+    let worker = web_sys::Worker::new(url).unwrap_throw();
+    let applier = {
+        let worker = worker.clone();
+        MutationApplier::new(root, move |event| {
+            let event = serde_json::to_string(&event).unwrap_throw();
+            worker.post_message(&event.into()).unwrap_throw();
+        })
+    };
+    let applier = RefCell::new(applier);
+    let on_message =
+        Closure::<dyn FnMut(web_sys::MessageEvent)>::new(move |message: web_sys::MessageEvent| {
+            let Some(data) = message.data().as_string() else {
+                return;
+            };
+            match serde_json::from_str::<Vec<Mutation>>(&data) {
+                Ok(mutations) => applier.borrow_mut().apply(&mutations),
+                Err(err) => web_sys::console::error_1(
+                    &format!("Couldn't deserialize the mutations of the worker: {err}").into(),
+                ),
+            }
+        });
+    worker.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+    WorkerHandle { worker, on_message }
+}
+
+/// The worker side of the mutation protocol, which batches mutations until they're posted to the main thread via [`WorkerDom::flush`].
+pub struct WorkerDom {
+    next_id: u32,
+    mutations: Vec<Mutation>,
+}
+
+impl Default for WorkerDom {
+    fn default() -> Self {
+        WorkerDom {
+            next_id: NodeId::ROOT.0 + 1,
+            mutations: Vec::new(),
+        }
+    }
+}
+
+impl WorkerDom {
+    fn next_id(&mut self) -> NodeId {
+        let id = NodeId(self.next_id);
+        self.next_id += 1;
+        id
+    }
+
+    pub fn create_element(&mut self, tag: impl Into<Cow<'static, str>>) -> NodeId {
+        self.create_element_ns(crate::HTML_NS, tag)
+    }
+
+    pub fn create_element_ns(
+        &mut self,
+        ns: impl Into<Cow<'static, str>>,
+        tag: impl Into<Cow<'static, str>>,
+    ) -> NodeId {
+        let id = self.next_id();
+        self.mutations.push(DomOp::CreateElement {
+            id,
+            ns: ns.into(),
+            tag: tag.into(),
+        });
+        id
+    }
+
+    pub fn create_text(&mut self, text: impl Into<Cow<'static, str>>) -> NodeId {
+        let id = self.next_id();
+        self.mutations.push(DomOp::CreateText {
+            id,
+            text: text.into(),
+        });
+        id
+    }
+
+    /// Adds a mutation, which isn't covered by the other methods (e.g. [`DomOp::SetText`]).
+    pub fn push(&mut self, mutation: Mutation) {
+        self.mutations.push(mutation);
+    }
+
+    pub fn append(&mut self, parent: NodeId, child: NodeId) {
+        self.push(DomOp::Insert {
+            parent,
+            child,
+            before: None,
+        });
+    }
+
+    /// Posts the batched mutations to the main thread.
+    pub fn flush(&mut self) {
+        if self.mutations.is_empty() {
+            return;
+        }
+        let mutations = serde_json::to_string(&self.mutations).unwrap_throw();
+        self.mutations.clear();
+        worker_scope()
+            .post_message(&mutations.into())
+            .unwrap_throw();
+    }
+}
+
+fn worker_scope() -> web_sys::DedicatedWorkerGlobalScope {
+    js_sys::global().unchecked_into()
+}
+
+/// Calls `f` with the events sent by the main thread to this worker, the returned closure has to be retained.
+pub fn on_dom_event(f: impl Fn(DomEvent) + 'static) -> Closure<dyn FnMut(web_sys::MessageEvent)> {
+    let on_message =
+        Closure::<dyn FnMut(web_sys::MessageEvent)>::new(move |message: web_sys::MessageEvent| {
+            let event = message
+                .data()
+                .as_string()
+                .and_then(|data| serde_json::from_str(&data).ok());
+            if let Some(event) = event {
+                f(event);
+            }
+        });
+    worker_scope().set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+    on_message
+}
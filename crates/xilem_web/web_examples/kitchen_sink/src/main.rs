@@ -0,0 +1,226 @@
+//! An app which uses most of the features of xilem_web, one page per feature.
+
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use xilem_web::{
+    context_menu::context_menu,
+    document_body,
+    elements::html as el,
+    error_boundary_result,
+    fetch::{fetch_resource, FetchBody},
+    interfaces::*,
+    skeleton::skeleton,
+    suspense,
+    web_socket::{web_socket, Backoff, WebSocketMessage, WebSocketSender},
+    App, OneOf2, OneOf5, TypedFormData, View,
+};
+
+#[derive(Clone, Copy, Default, PartialEq)]
+enum Page {
+    #[default]
+    Forms,
+    Fetch,
+    WebSocket,
+    Dialog,
+    Misc,
+}
+
+const PAGES: [(Page, &str); 5] = [
+    (Page::Forms, "Forms"),
+    (Page::Fetch, "Fetch"),
+    (Page::WebSocket, "WebSocket"),
+    (Page::Dialog, "Dialog"),
+    (Page::Misc, "Misc"),
+];
+
+#[derive(Default)]
+struct AppState {
+    page: Page,
+    greeting: Option<String>,
+    reloads: u32,
+    fetched: Option<Result<String, String>>,
+    socket: Option<WebSocketSender>,
+    socket_log: Vec<String>,
+    dialog_open: bool,
+    number: String,
+    selected_menu_item: Option<usize>,
+}
+
+async fn sleep(ms: i32) {
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        web_sys::window()
+            .unwrap_throw()
+            .set_timeout_with_callback_and_timeout_and_arguments_0(&resolve, ms)
+            .unwrap_throw();
+    });
+    wasm_bindgen_futures::JsFuture::from(promise)
+        .await
+        .unwrap_throw();
+}
+
+fn input_value(event: &web_sys::Event) -> Option<String> {
+    event
+        .target()
+        .and_then(|t| t.dyn_into::<web_sys::HtmlInputElement>().ok())
+        .map(|input| input.value())
+}
+
+fn nav(state: &AppState) -> impl Element<AppState> {
+    let buttons: Vec<_> = PAGES
+        .iter()
+        .map(|&(page, label)| {
+            el::button(label)
+                .attr("aria-pressed", state.page == page)
+                .on_click(move |state: &mut AppState, _| state.page = page)
+        })
+        .collect();
+    el::nav(buttons)
+}
+
+fn forms_page(state: &AppState) -> impl Element<AppState> {
+    el::div((
+        el::form((
+            el::label((
+                "Name ",
+                el::input(()).attr("name", "name").attr("required", true),
+            )),
+            el::button("Greet").attr("type", "submit"),
+        ))
+        .on_submit_form(|state: &mut AppState, data: TypedFormData| {
+            state.greeting = data.text("name").map(|name| format!("Hello {name}!"));
+        }),
+        el::p(state.greeting.clone().unwrap_or_default()),
+    ))
+}
+
+fn fetch_page(state: &AppState) -> impl Element<AppState> {
+    let result = match &state.fetched {
+        None => "Loading...".to_string(),
+        Some(Ok(text)) => text.clone(),
+        Some(Err(err)) => format!("Error: {err}"),
+    };
+    el::div((
+        // The query string changes the url, which issues a new request
+        fetch_resource(
+            format!("index.html?reload={}", state.reloads),
+            |state: &mut AppState, result| {
+                state.fetched = Some(match result {
+                    Ok(FetchBody::Text(text)) => Ok(text),
+                    Ok(body) => Err(format!("unexpected body {body:?}")),
+                    Err(err) => Err(format!("{err:?}")),
+                });
+            },
+        ),
+        el::button("Reload").on_click(|state: &mut AppState, _| {
+            state.reloads += 1;
+            state.fetched = None;
+        }),
+        el::pre(result).truncate(8),
+    ))
+}
+
+fn web_socket_page(state: &AppState) -> impl Element<AppState> {
+    let log: Vec<_> = state
+        .socket_log
+        .iter()
+        .map(|line| el::li(line.clone()))
+        .collect();
+    el::div((
+        web_socket(
+            "wss://echo.websocket.org",
+            |state: &mut AppState, message| {
+                if let WebSocketMessage::Text(text) = message {
+                    state.socket_log.push(format!("< {text}"));
+                }
+            },
+            |state: &mut AppState, sender| {
+                state.socket_log.push("connected".into());
+                state.socket = Some(sender);
+            },
+            |state: &mut AppState| {
+                state.socket_log.push("connection error".into());
+                state.socket = None;
+            },
+        )
+        .reconnect(Backoff::exponential(500, 10_000)),
+        el::button("Send ping").on_click(|state: &mut AppState, _| {
+            if state.socket.as_ref().is_some_and(|s| s.send_text("ping")) {
+                state.socket_log.push("> ping".into());
+            }
+        }),
+        el::ul(log),
+    ))
+}
+
+fn dialog_page(state: &AppState) -> impl Element<AppState> {
+    let dialog = state.dialog_open.then(|| {
+        el::div((
+            el::h2("A modal dialog"),
+            el::p("Tab only cycles through the controls of this dialog."),
+            el::input(()).attr("aria-label", "Some input"),
+            el::button("Close").on_click(|state: &mut AppState, _| state.dialog_open = false),
+        ))
+        .attr("class", "dialog")
+        .attr("role", "dialog")
+        .attr("aria-modal", true)
+        .focus_trap()
+        .transition()
+    });
+    el::div((
+        el::button("Open dialog").on_click(|state: &mut AppState, _| state.dialog_open = true),
+        dialog,
+    ))
+}
+
+fn misc_page(state: &AppState) -> impl Element<AppState> {
+    let squared = state
+        .number
+        .parse::<u32>()
+        .map(|n| format!("{n} squared is {}", n.saturating_mul(n)));
+    el::div((
+        el::h3("Skeleton"),
+        skeleton(2, true, [100, 60]),
+        el::h3("Suspense"),
+        suspense(el::p("Waiting a second..."), || async {
+            sleep(1000).await;
+            el::p("Resolved after a second")
+        }),
+        el::h3("Error boundary"),
+        el::input(())
+            .attr("value", state.number.clone())
+            .attr("aria-label", "A number")
+            .on_input(|state: &mut AppState, event| {
+                if let Some(value) = input_value(&event) {
+                    state.number = value;
+                }
+            }),
+        error_boundary_result(squared, |error| {
+            el::p(format!("Not a valid number: {error}"))
+        }),
+        el::h3("Context menu"),
+        context_menu(
+            ["Copy", "Paste", "Delete"],
+            el::p("Right click here"),
+            |state: &mut AppState, idx| state.selected_menu_item = Some(idx),
+        ),
+        match state.selected_menu_item {
+            Some(idx) => OneOf2::A(el::p(format!("Selected item {}", idx + 1))),
+            None => OneOf2::B(el::p("Nothing selected yet")),
+        },
+    ))
+}
+
+fn app_logic(state: &mut AppState) -> impl View<AppState> {
+    let page = match state.page {
+        Page::Forms => OneOf5::A(forms_page(state)),
+        Page::Fetch => OneOf5::B(fetch_page(state)),
+        Page::WebSocket => OneOf5::C(web_socket_page(state)),
+        Page::Dialog => OneOf5::D(dialog_page(state)),
+        Page::Misc => OneOf5::E(misc_page(state)),
+    };
+    el::div((el::h1("Kitchen sink"), nav(state), el::main(page)))
+}
+
+pub fn main() {
+    console_error_panic_hook::set_once();
+    App::new(AppState::default(), app_logic).run(&document_body());
+}
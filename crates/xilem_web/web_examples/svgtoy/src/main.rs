@@ -44,7 +44,7 @@ impl GrabState {
                     *y = self.dy + e.y;
                 }
             }
-            PointerMsg::Up(e) => {
+            PointerMsg::Up(e) | PointerMsg::Cancel(e) => {
                 if self.id == e.id {
                     self.is_down = false;
                 }
@@ -4,7 +4,8 @@ use state::{AppState, Filter, Todo};
 
 use wasm_bindgen::JsCast;
 use xilem_web::{
-    elements::html as el, get_element_by_id, interfaces::*, Action, Adapt, App, MessageResult, View,
+    elements::html as el, get_element_by_id, interfaces::*, Action, Adapt, App, MessageResult,
+    StorageArea, View,
 };
 
 // All of these actions arise from within a `Todo`, but we need access to the full state to reduce
@@ -142,10 +143,7 @@ fn main_view(state: &mut AppState, should_display: bool) -> impl Element<AppStat
                     if let MessageResult::Action(action) = thunk.call(&mut data.todos[idx]) {
                         match action {
                             TodoAction::SetEditing(id) => data.start_editing(id),
-                            TodoAction::CommitEdit => {
-                                data.save();
-                                data.editing_id = None;
-                            }
+                            TodoAction::CommitEdit => data.editing_id = None,
                             TodoAction::CancelEditing => data.editing_id = None,
                             TodoAction::Destroy(id) => data.todos.retain(|todo| todo.id != id),
                         }
@@ -180,7 +178,7 @@ fn app_logic(state: &mut AppState) -> impl View<AppState> {
         .attr("class", "new-todo")
         .attr("placeholder", "What needs to be done?")
         .attr("value", state.new_todo.clone())
-        .attr("autofocus", true);
+        .autofocus();
     el::div((
         el::header((
             el::h1("TODOs"),
@@ -211,5 +209,7 @@ fn app_logic(state: &mut AppState) -> impl View<AppState> {
 pub fn main() {
     console_error_panic_hook::set_once();
     tracing_wasm::set_as_global_default();
-    App::new(AppState::load(), app_logic).run(&get_element_by_id("todoapp"));
+    App::new(AppState::default(), app_logic)
+        .persist("todomvc_persist", StorageArea::Local)
+        .run(&get_element_by_id("todoapp"));
 }
@@ -1,7 +1,4 @@
 use serde::{Deserialize, Serialize};
-use wasm_bindgen::UnwrapThrowExt;
-
-const KEY: &str = "todomvc_persist";
 
 #[derive(Default, Debug, Serialize, Deserialize)]
 pub struct AppState {
@@ -78,26 +75,6 @@ impl AppState {
             self.editing_id = Some(id);
         }
     }
-
-    /// Load the current state from local_storage, or use the default.
-    pub fn load() -> Self {
-        let Some(raw) = storage().get_item(KEY).unwrap_throw() else {
-            return Default::default();
-        };
-        match serde_json::from_str(&raw) {
-            Ok(todos) => todos,
-            Err(e) => {
-                tracing::error!("couldn't load existing todos: {e}");
-                Default::default()
-            }
-        }
-    }
-
-    /// Save the current state to local_storage
-    pub fn save(&self) {
-        let raw = serde_json::to_string(self).unwrap_throw();
-        storage().set_item(KEY, &raw).unwrap_throw();
-    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -133,11 +110,3 @@ pub enum Filter {
     Active,
     Completed,
 }
-
-fn storage() -> web_sys::Storage {
-    web_sys::window()
-        .unwrap_throw()
-        .local_storage()
-        .unwrap_throw()
-        .unwrap_throw()
-}
@@ -21,6 +21,7 @@ mod text;
 // mod use_state;
 mod linear_layout;
 mod list;
+mod one_of;
 mod switch;
 mod tree_structure_tracking;
 #[allow(clippy::module_inception)]
@@ -31,10 +32,14 @@ pub use xilem_core::{Id, IdPath, VecSplice};
 pub use button::button;
 pub use linear_layout::{h_stack, v_stack, LinearLayout};
 pub use list::{list, List};
+pub use one_of::{cond, maybe, OneOf2, OneOf3, OneOf4, OneOf5, OneOf6, OneOf7, OneOf8};
 pub use scroll_view::{scroll_view, ScrollView};
 pub use switch::switch;
 pub use tree_structure_tracking::TreeStructureSplice;
-pub use view::{Adapt, AdaptState, Cx, ElementsSplice, Memoize, View, ViewMarker, ViewSequence};
+pub use view::{
+    adapt_state, adapt_state_option, map_action, Adapt, AdaptState, AdaptStateOption, ArcViewState,
+    Cx, ElementsSplice, HashMemoize, MapAction, Memoize, View, ViewMarker, ViewSequence,
+};
 
 #[cfg(feature = "taffy")]
 mod taffy_layout;
@@ -0,0 +1,195 @@
+// Copyright 2024 the Xilem Authors.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::any::Any;
+
+use vello::{kurbo::Size, Scene};
+
+use crate::{
+    geometry::Axis,
+    view::{Id, ViewMarker},
+    widget::{
+        BoxConstraints, ChangeFlags, Event, EventCx, LayoutCx, LifeCycle, LifeCycleCx, PaintCx,
+        UpdateCx, Widget,
+    },
+    MessageResult,
+};
+
+use super::{Cx, View};
+
+macro_rules! one_of_view {
+    (
+        #[doc = $first_doc_line:literal]
+        $ident:ident { $( $vars:ident ),+ }
+    ) => {
+        #[doc = $first_doc_line]
+        ///
+        /// It is a statically-typed alternative to the type-erased `BoxedView`,
+        /// the widget of the current variant is used as element.
+        pub enum $ident<$($vars),+> {
+            $($vars($vars),)+
+        }
+
+        impl<$($vars),+> ViewMarker for $ident<$($vars),+> {}
+
+        impl<T, A, $($vars),+> View<T, A> for $ident<$($vars),+>
+        where
+            $($vars: View<T, A>,)+
+        {
+            type State = $ident<$($vars::State),+>;
+            type Element = $ident<$($vars::Element),+>;
+
+            fn build(&self, cx: &mut Cx) -> (Id, Self::State, Self::Element) {
+                match self {
+                    $(
+                        $ident::$vars(view) => {
+                            let (id, state, element) = view.build(cx);
+                            (id, $ident::$vars(state), $ident::$vars(element))
+                        }
+                    )+
+                }
+            }
+
+            fn rebuild(
+                &self,
+                cx: &mut Cx,
+                prev: &Self,
+                id: &mut Id,
+                state: &mut Self::State,
+                element: &mut Self::Element,
+            ) -> ChangeFlags {
+                match (prev, self, &mut *state, &mut *element) {
+                    $(
+                        // Variant is the same as before
+                        (
+                            $ident::$vars(prev_view),
+                            $ident::$vars(view),
+                            $ident::$vars(state),
+                            $ident::$vars(element),
+                        ) => view.rebuild(cx, prev_view, id, state, element),
+                    )+
+                    // Variant has changed
+                    _ => {
+                        let (new_id, new_state, new_element) = self.build(cx);
+                        *id = new_id;
+                        *state = new_state;
+                        *element = new_element;
+                        ChangeFlags::tree_structure()
+                    }
+                }
+            }
+
+            fn message(
+                &self,
+                id_path: &[Id],
+                state: &mut Self::State,
+                message: Box<dyn Any>,
+                app_state: &mut T,
+            ) -> MessageResult<A> {
+                match (self, state) {
+                    $(
+                        ($ident::$vars(view), $ident::$vars(state)) => {
+                            view.message(id_path, state, message, app_state)
+                        }
+                    )+
+                    _ => MessageResult::Stale(message),
+                }
+            }
+        }
+
+        impl<$($vars: Widget),+> Widget for $ident<$($vars),+> {
+            fn event(&mut self, cx: &mut EventCx, event: &Event) {
+                match self {
+                    $( $ident::$vars(widget) => widget.event(cx, event), )+
+                }
+            }
+
+            fn lifecycle(&mut self, cx: &mut LifeCycleCx, event: &LifeCycle) {
+                match self {
+                    $( $ident::$vars(widget) => widget.lifecycle(cx, event), )+
+                }
+            }
+
+            fn update(&mut self, cx: &mut UpdateCx) {
+                match self {
+                    $( $ident::$vars(widget) => widget.update(cx), )+
+                }
+            }
+
+            fn layout(&mut self, cx: &mut LayoutCx, bc: &BoxConstraints) -> Size {
+                match self {
+                    $( $ident::$vars(widget) => widget.layout(cx, bc), )+
+                }
+            }
+
+            fn compute_max_intrinsic(
+                &mut self,
+                axis: Axis,
+                cx: &mut LayoutCx,
+                bc: &BoxConstraints,
+            ) -> f64 {
+                match self {
+                    $( $ident::$vars(widget) => widget.compute_max_intrinsic(axis, cx, bc), )+
+                }
+            }
+
+            fn paint(&mut self, cx: &mut PaintCx, scene: &mut Scene) {
+                match self {
+                    $( $ident::$vars(widget) => widget.paint(cx, scene), )+
+                }
+            }
+        }
+    };
+}
+
+one_of_view! {
+    /// This view container can switch between two views.
+    OneOf2 { A, B }
+}
+
+one_of_view! {
+    /// This view container can switch between three views.
+    OneOf3 { A, B, C }
+}
+
+one_of_view! {
+    /// This view container can switch between four views.
+    OneOf4 { A, B, C, D }
+}
+
+one_of_view! {
+    /// This view container can switch between five views.
+    OneOf5 { A, B, C, D, E }
+}
+
+one_of_view! {
+    /// This view container can switch between six views.
+    OneOf6 { A, B, C, D, E, F }
+}
+
+one_of_view! {
+    /// This view container can switch between seven views.
+    OneOf7 { A, B, C, D, E, F, G }
+}
+
+one_of_view! {
+    /// This view container can switch between eight views.
+    OneOf8 { A, B, C, D, E, F, G, H }
+}
+
+/// Shows `if_true` when `flag` is set, and `if_false` otherwise,
+/// which is a shorthand for `if flag { OneOf2::A(if_true) } else { OneOf2::B(if_false) }`.
+///
+/// Note that both views are created, so [`maybe`] should be used when one of them is expensive to create.
+pub fn cond<A, B>(flag: bool, if_true: A, if_false: B) -> OneOf2<A, B> {
+    if flag {
+        OneOf2::A(if_true)
+    } else {
+        OneOf2::B(if_false)
+    }
+}
+
+/// Shows the view created by `view` only when `flag` is set (as `Option`, which is a view sequence).
+pub fn maybe<V>(flag: bool, view: impl FnOnce() -> V) -> Option<V> {
+    flag.then(view)
+}
@@ -27,8 +27,11 @@ xilem_core::generate_view_trait! {View, Widget, Cx, ChangeFlags; : Send}
 xilem_core::generate_viewsequence_trait! {ViewSequence, View, ViewMarker, ElementsSplice, Widget, Cx, ChangeFlags, Pod; : Send}
 xilem_core::generate_anyview_trait! {AnyView, View, ViewMarker, Cx, ChangeFlags, AnyWidget, BoxedView; + Send}
 xilem_core::generate_memoize_view! {Memoize, MemoizeState, View, ViewMarker, Cx, ChangeFlags, s, memoize; + Send}
+xilem_core::generate_hash_memoize_view! {HashMemoize, HashMemoizeState, View, ViewMarker, Cx, ChangeFlags, hash_memoize; + Send}
+xilem_core::generate_rc_view! {ArcViewState, View, ViewMarker, Cx, ChangeFlags, Arc; + Send + Sync}
 xilem_core::generate_adapt_view! {View, Cx, ChangeFlags; + Send}
 xilem_core::generate_adapt_state_view! {View, Cx, ChangeFlags; + Send}
+xilem_core::generate_map_action_view! {View, Cx, ChangeFlags; + Send}
 
 #[derive(Clone)]
 pub struct Cx {
@@ -78,6 +81,11 @@ impl Cx {
         &self.id_path
     }
 
+    /// Whether memoized views have to be rebuilt regardless of their data, which is never the case here.
+    pub fn memoize_invalidated(&self) -> bool {
+        false
+    }
+
     pub fn element_id_path_is_empty(&self) -> bool {
         self.element_id_path.is_empty()
     }